@@ -0,0 +1,255 @@
+use std::{any::TypeId, cell::RefCell, fmt, rc::Rc};
+
+use crate::{
+    api::{
+        contexts::{build_ctx::_BuildContext, render_ctx::RenderPhase},
+        media_query::{MediaQuery, MediaQueryData},
+    },
+    app::tree::WidgetNodeRef,
+    prelude::Size,
+};
+
+/// Key of a subtree whose rendered image can be captured at any time, e.g.
+/// from a callback, with [`capture_image`](CaptureKey::capture_image).
+///
+/// Key is attached to a widget by calling [`attach`](CaptureKey::attach) with
+/// its `BuildContext` (usually in [`WidgetState::mount`]). Captured subtree is
+/// made of that widget and all of its descendants.
+///
+/// [`WidgetState::mount`]: crate::prelude::WidgetState::mount
+#[derive(Clone, Default)]
+pub struct CaptureKey {
+    node: Rc<RefCell<Option<WidgetNodeRef>>>,
+}
+
+impl CaptureKey {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attaches this key to the widget of `ctx`, detaching it from the widget
+    /// it was attached to before.
+    pub fn attach<T>(&self, ctx: &_BuildContext<T>) {
+        *self.node.borrow_mut() = Some(ctx.node().clone());
+    }
+
+    /// Paints the attached subtree into an offscreen image, as of its last
+    /// layout, and returns its pixels.
+    ///
+    /// Image is painted at the device pixel ratio of the window, multiplied by
+    /// `scale` if it is set, so its size is the laid out size of the subtree
+    /// converted to physical pixels. Only the subtree itself is painted, on a
+    /// transparent background; parts of it painted outside of its bounds are
+    /// clipped.
+    ///
+    /// Returns an error if the key isn't attached to a mounted widget, or if
+    /// that widget wasn't laid out yet.
+    pub fn capture_image(&self, scale: Option<f64>) -> Result<CapturedImage, CaptureError> {
+        let node = match &*self.node.borrow() {
+            Some(node) if node.is_alive() => node.clone(),
+            _ => return Err(CaptureError::NotMounted),
+        };
+
+        let (size, offset) = {
+            let render_data = &node.borrow().render_data;
+
+            if !render_data.laid_out {
+                return Err(CaptureError::NotLaidOut);
+            }
+
+            // Offset from its parent during last paint.
+            let offset = match node.parent() {
+                Some(parent) => render_data.offset - parent.borrow().render_data.offset,
+                None => render_data.offset,
+            };
+
+            (render_data.size, offset)
+        };
+
+        let scale = device_pixel_ratio(&node) * scale.unwrap_or(1.);
+        let (width, height) = pixel_size(size, scale);
+
+        if width == 0 || height == 0 {
+            return Ok(CapturedImage {
+                width,
+                height,
+                pixels: Vec::new(),
+            });
+        }
+
+        let mut pixels = paint(&node, offset, width, height, scale)?;
+        unpremultiply(&mut pixels);
+
+        Ok(CapturedImage {
+            width,
+            height,
+            pixels,
+        })
+    }
+}
+
+/// Pixels of a captured image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedImage {
+    pub width: usize,
+    pub height: usize,
+    /// RGBA pixels (with straight alpha), row by row from the top left corner.
+    pub pixels: Vec<u8>,
+}
+
+/// Error returned by [`CaptureKey::capture_image`].
+#[derive(Debug, Clone)]
+pub enum CaptureError {
+    /// Key isn't attached to a widget or that widget was disposed.
+    NotMounted,
+    /// Widget wasn't laid out yet, so its size isn't known.
+    NotLaidOut,
+    /// Platform failed to paint the image.
+    Paint(String),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureError::NotMounted => write!(f, "capture key isn't attached to a mounted widget"),
+            CaptureError::NotLaidOut => write!(f, "captured widget wasn't laid out yet"),
+            CaptureError::Paint(e) => write!(f, "failed to paint the captured image: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+/// Device pixel ratio of the window `node` is mounted in.
+fn device_pixel_ratio(node: &WidgetNodeRef) -> f64 {
+    let mut node = Some(node.clone());
+
+    while let Some(n) = node {
+        if n.widget().unique_type_id() == TypeId::of::<MediaQuery>() {
+            if let Some(data) = n.borrow().state.downcast_ref::<MediaQueryData>() {
+                return data.device_pixel_ratio;
+            }
+        }
+
+        node = n.parent();
+    }
+
+    // Widget isn't mounted in a window, e.g. during tests.
+    1.
+}
+
+/// Size in physical pixels of an image of `size` painted at `scale`.
+fn pixel_size(size: Size, scale: f64) -> (usize, usize) {
+    let to_pixels = |length: f64| (length * scale).ceil().max(0.) as usize;
+
+    (to_pixels(size.width), to_pixels(size.height))
+}
+
+/// Paints `node` into a bitmap, returning its premultiplied RGBA pixels.
+#[cfg(not(feature = "miri"))]
+fn paint(
+    node: &WidgetNodeRef,
+    offset: crate::prelude::Offset,
+    width: usize,
+    height: usize,
+    scale: f64,
+) -> Result<Vec<u8>, CaptureError> {
+    use druid_shell::piet::{kurbo::Affine, Device, ImageFormat, RenderContext};
+
+    use crate::api::contexts::render_ctx::AnyRenderContext;
+
+    let error = |e: druid_shell::piet::Error| CaptureError::Paint(e.to_string());
+
+    let mut device = Device::new().map_err(error)?;
+    let mut target = device.bitmap_target(width, height, scale).map_err(error)?;
+
+    {
+        let mut piet = target.render_context();
+
+        // Subtree is painted at the offset of its last paint (so that its
+        // widgets keep the positions they receive events at), and moved to the
+        // origin of the image instead.
+        piet.transform(Affine::translate((-offset.x, -offset.y)));
+
+        RenderPhase::Paint.run(|| AnyRenderContext::new(node.clone()).paint(&mut piet, &offset));
+
+        piet.finish().map_err(error)?;
+    }
+
+    let mut pixels = vec![0; width * height * 4];
+    target
+        .copy_raw_pixels(ImageFormat::RgbaPremul, &mut pixels)
+        .map_err(error)?;
+
+    Ok(pixels)
+}
+
+#[cfg(feature = "miri")]
+fn paint(
+    _: &WidgetNodeRef,
+    _: crate::prelude::Offset,
+    _: usize,
+    _: usize,
+    _: f64,
+) -> Result<Vec<u8>, CaptureError> {
+    Err(CaptureError::Paint(String::from(
+        "images can't be captured with feature `miri` enabled",
+    )))
+}
+
+/// Converts premultiplied RGBA `pixels` to straight alpha.
+fn unpremultiply(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u32;
+
+        if alpha != 0 && alpha != 255 {
+            for channel in pixel[..3].iter_mut() {
+                *channel = ((*channel as u32 * 255 + alpha / 2) / alpha).min(255) as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::{api::IntoWidgetPtr, app::tree::WidgetTree};
+
+    #[test]
+    fn image_size_is_rounded_up_to_whole_pixels() {
+        assert_eq!(pixel_size(Size::new(10., 5.), 2.), (20, 10));
+        assert_eq!(pixel_size(Size::new(10.2, 0.), 1.5), (16, 0));
+    }
+
+    #[test]
+    fn pixels_are_unpremultiplied() {
+        let mut pixels = [128, 64, 0, 128, 10, 20, 30, 255, 0, 0, 0, 0];
+        unpremultiply(&mut pixels);
+
+        assert_eq!(pixels, [255, 128, 0, 128, 10, 20, 30, 255, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn subtree_must_be_mounted_and_laid_out() {
+        let key = CaptureKey::new();
+        assert!(matches!(
+            key.capture_image(None),
+            Err(CaptureError::NotMounted)
+        ));
+
+        let mut tree = WidgetTree::new(().into_widget_ptr());
+        *key.node.borrow_mut() = Some(tree.get_root());
+
+        assert!(matches!(
+            key.capture_image(None),
+            Err(CaptureError::NotLaidOut)
+        ));
+
+        drop(tree);
+        assert!(matches!(
+            key.capture_image(None),
+            Err(CaptureError::NotMounted)
+        ));
+    }
+}
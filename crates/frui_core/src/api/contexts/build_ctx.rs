@@ -1,7 +1,14 @@
-use crate::{app::tree::WidgetNodeRef, prelude::InheritedWidget};
+use crate::{
+    api::{
+        implementors::WidgetDerive, listenable::Listener, notifications::Notification,
+        state_handle::StateHandle,
+    },
+    app::{runner::handler::schedule_frame, tree::WidgetNodeRef},
+    prelude::{InheritedWidget, Size},
+};
 
 use std::{
-    any::Any,
+    any::{Any, TypeId},
     cell::{Ref, RefMut},
     marker::PhantomData,
     ops::{Deref, DerefMut},
@@ -33,6 +40,58 @@ pub trait WidgetState: Sized {
     fn unmount<'a>(&'a self, ctx: BuildContext<'a, Self>) {
         let _ = ctx;
     }
+
+    /// Called when the configuration of this mounted widget was replaced with
+    /// a new one (which wasn't structurally equal to it), right before the
+    /// widget is built with it. `self` is the new configuration.
+    ///
+    /// The widget is also unmounted before and mounted again after that,
+    /// but unlike in `mount`, state set up for the previous configuration
+    /// (like a registered callback) is still there, so it can be updated
+    /// instead of set up from scratch.
+    ///
+    /// Accessing `state_mut` of the provided `BuildContext` will not cause a
+    /// rebuild of this widget to be scheduled.
+    fn did_update_widget<'a>(&'a self, ctx: BuildContext<'a, Self>) {
+        let _ = ctx;
+    }
+
+    /// Called after the first build, once dependencies on inherited widgets
+    /// were registered, and then whenever an inherited widget this widget
+    /// depends on changes, right before the rebuild caused by that change.
+    ///
+    /// Use it for side effects which should happen when a dependency changes.
+    /// Since a rebuild is already coming, accessing `state_mut` of the provided
+    /// `BuildContext` will not cause another rebuild to be scheduled.
+    fn did_change_dependencies<'a>(&'a self, ctx: BuildContext<'a, Self>) {
+        let _ = ctx;
+    }
+
+    /// Called exactly once, right after the first frame this widget was built
+    /// for is laid out and painted, i.e. once its initial state and layout
+    /// exist. Unlike `mount`, which runs before the widget is built, this runs
+    /// only once per mounted widget, and not again when its configuration is
+    /// updated. Widgets removed before that frame are never called.
+    ///
+    /// Accessing `state_mut` of the provided `BuildContext` schedules a rebuild
+    /// of this widget, like it does in event handlers.
+    fn after_first_build<'a>(&'a self, ctx: BuildContext<'a, Self>) {
+        let _ = ctx;
+    }
+
+    /// Called exactly once when the widget is permanently removed from the
+    /// tree, right after its last `unmount`. Descendants of this widget are
+    /// disposed before it.
+    ///
+    /// This happens both when the widget itself is removed and when it's torn
+    /// down together with its ancestor. The widget is never mounted again, so
+    /// this is the place to release resources held in its state.
+    ///
+    /// Accessing `state_mut` of the provided `BuildContext` will not cause a
+    /// rebuild of this widget to be scheduled.
+    fn dispose<'a>(&'a self, ctx: BuildContext<'a, Self>) {
+        let _ = ctx;
+    }
 }
 
 // `BuildContext` is borrowed to make it so that closures don't take ownership
@@ -47,6 +106,10 @@ pub struct _BuildContext<'a, T> {
 }
 
 impl<'a, T> _BuildContext<'a, T> {
+    pub(crate) fn node(&self) -> &WidgetNodeRef {
+        &self.node
+    }
+
     pub fn state(&self) -> StateGuard<T::State>
     where
         T: WidgetState,
@@ -57,10 +120,26 @@ impl<'a, T> _BuildContext<'a, T> {
         }
     }
 
+    /// Widget state mutably. Schedules a rebuild of this widget.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if called during the build of this widget, since
+    /// the scheduled rebuild would cause it to be rebuilt endlessly. Use
+    /// [`is_building`](Self::is_building) to check for that.
+    #[track_caller]
     pub fn state_mut(&self) -> StateGuardMut<T::State>
     where
         T: WidgetState,
     {
+        if cfg!(debug_assertions) && self.is_building() {
+            panic!(
+                "`state_mut` was called during the build of `{}`, which would cause it \
+                to be rebuilt endlessly (mutate the state in a callback instead)",
+                self.node.widget().debug_name_short(),
+            );
+        }
+
         if !STATE_UPDATE_SUPRESSED.load(Ordering::SeqCst) {
             self.node.mark_dirty();
         }
@@ -71,6 +150,107 @@ impl<'a, T> _BuildContext<'a, T> {
         }
     }
 
+    /// Schedules layout and paint of this widget for the next frame, without
+    /// rebuilding it.
+    ///
+    /// Use it when something this widget's layout depends on changed, but its
+    /// children would be built the same, e.g. an animated value kept in a
+    /// `Cell` of the state and read during layout. Mutating the state through
+    /// [`state_mut`](Self::state_mut) rebuilds this widget instead.
+    pub fn mark_needs_layout(&self) {
+        self.node.mark_needs_layout_up_to_boundary();
+
+        schedule_frame();
+    }
+
+    #[deprecated = "use `mark_needs_layout`, which discards the cached layout"]
+    pub fn invalidate_layout_cache(&self) {
+        self.mark_needs_layout();
+    }
+
+    /// Listener rebuilding this widget, to be added to a
+    /// [`Listenable`](crate::api::listenable::Listenable) this widget reads.
+    pub fn listener(&self) -> Listener {
+        Listener::new(self.node.clone())
+    }
+
+    /// Handle to the state of this widget which can be sent to other threads,
+    /// e.g. to store the result of a computation running in the background.
+    ///
+    /// Closures passed to [`StateHandle::update`] run on the UI thread, see
+    /// [`StateHandle`].
+    pub fn state_handle(&self) -> StateHandle<T::State>
+    where
+        T: WidgetState,
+    {
+        StateHandle::new(self.node.clone())
+    }
+
+    /// Whether this widget is currently being built.
+    ///
+    /// This is `false` in closures created during the build, unless they are
+    /// called before the build finishes.
+    pub fn is_building(&self) -> bool {
+        self.node.borrow().building
+    }
+
+    /// Number of ancestors of this widget. Root widget has depth `0`.
+    pub fn depth(&self) -> usize {
+        self.node.depth()
+    }
+
+    /// Path to this widget in the tree, made of short type names of this widget
+    /// and its ancestors joined with `/`, e.g. `App/Center/Text`. For the root
+    /// widget it's just its own type name.
+    ///
+    /// It walks the ancestors each time it's called, so it's meant for logging
+    /// and debugging.
+    pub fn debug_path(&self) -> String {
+        self.node.debug_path()
+    }
+
+    /// Id of this widget for correlating logs across frames.
+    ///
+    /// Unlike a key, it's assigned by the framework once the widget is mounted.
+    /// It stays the same across rebuilds of this widget, even if its
+    /// configuration changes, and differs once the widget is built from scratch
+    /// in the same place (e.g. because the type of the widget changed).
+    pub fn debug_id(&self) -> u64 {
+        self.node.debug_id()
+    }
+
+    /// Describes the subtree starting at this widget, one widget per line with
+    /// its type name, [`debug_id`](Self::debug_id) and the size from its last
+    /// layout. Children are indented under their parent.
+    ///
+    /// ```text
+    /// Column #12 size (800.0, 600.0)
+    ///   Text #13 size (42.0, 16.0)
+    /// ```
+    pub fn dump_tree(&self) -> String {
+        self.node.dump_tree()
+    }
+
+    /// Size of the closest ancestor of type `W`, as computed during its last
+    /// layout. Returns `None` if there is no such ancestor or if it wasn't laid
+    /// out yet.
+    ///
+    /// During build this is the size from the previous frame, since layout runs
+    /// after build.
+    pub fn find_ancestor_size<W: WidgetDerive>(&self) -> Option<Size> {
+        self.node
+            .find_ancestor_size(TypeId::of::<W::UniqueTypeId>())
+    }
+
+    /// Dispatches `notification` to ancestors of this widget, nearest first,
+    /// until one of them stops it.
+    ///
+    /// Notification is delivered immediately, even during the build. Listeners
+    /// shouldn't assume that this widget was already laid out.
+    pub fn dispatch_notification<N: Notification>(&self, notification: N) {
+        self.node.dispatch_notification(&notification);
+    }
+
     /// This method registers the widget of this `BuildContext` as a dependency of
     /// the closest `InheritedWidget` ancestor of type `W` in the tree. It then
     /// returns the state of that inherited widget or `None` if inherited ancestor
@@ -89,6 +269,19 @@ impl<'a, T> _BuildContext<'a, T> {
             _p: PhantomData,
         })
     }
+
+    /// Rebuilds every widget which depends on this `InheritedWidget`.
+    ///
+    /// Use it in [`WidgetState::did_update_widget`] of inherited widgets whose
+    /// state mirrors their configuration, once the state was updated to the new
+    /// configuration. Mutating the state through `state_mut` rebuilds only this
+    /// widget.
+    pub fn notify_dependents(&self)
+    where
+        T: InheritedWidget,
+    {
+        self.node.mark_dependent_widgets_as_dirty();
+    }
 }
 
 pub struct StateGuard<'a, T: 'static> {
@@ -200,6 +393,10 @@ mod sealed {
         fn create_state(&self) -> Box<dyn Any>;
         fn mount(&self, build_ctx: &Context);
         fn unmount(&self, build_ctx: &Context);
+        fn did_update_widget(&self, build_ctx: &Context);
+        fn did_change_dependencies(&self, build_ctx: &Context);
+        fn after_first_build(&self, build_ctx: &Context);
+        fn dispose(&self, build_ctx: &Context);
     }
 
     impl<T> WidgetStateOS for T {
@@ -214,6 +411,10 @@ mod sealed {
 
         default fn mount(&self, _ctx: &Context) {}
         default fn unmount(&self, _ctx: &Context) {}
+        default fn did_update_widget(&self, _ctx: &Context) {}
+        default fn did_change_dependencies(&self, _ctx: &Context) {}
+        default fn after_first_build(&self, _ctx: &Context) {}
+        default fn dispose(&self, _ctx: &Context) {}
     }
 
     impl<T: super::WidgetState> WidgetStateOS for T {
@@ -236,5 +437,29 @@ mod sealed {
 
             T::unmount(&self, ctx)
         }
+
+        fn did_update_widget(&self, ctx: &Context) {
+            let ctx = unsafe { std::mem::transmute::<&Context, &_BuildContext<T>>(ctx) };
+
+            T::did_update_widget(self, ctx)
+        }
+
+        fn did_change_dependencies(&self, ctx: &Context) {
+            let ctx = unsafe { std::mem::transmute::<&Context, &_BuildContext<T>>(ctx) };
+
+            T::did_change_dependencies(self, ctx)
+        }
+
+        fn after_first_build(&self, ctx: &Context) {
+            let ctx = unsafe { std::mem::transmute::<&Context, &_BuildContext<T>>(ctx) };
+
+            T::after_first_build(self, ctx)
+        }
+
+        fn dispose(&self, ctx: &Context) {
+            let ctx = unsafe { std::mem::transmute::<&Context, &_BuildContext<T>>(ctx) };
+
+            T::dispose(self, ctx)
+        }
     }
 }
@@ -1,17 +1,29 @@
-use std::ops::{Sub, SubAssign};
+use std::ops::{Add, Sub, SubAssign};
 use std::{
-    cell::{Ref, RefMut},
+    any::TypeId,
+    cell::{Cell, Ref, RefMut},
     marker::PhantomData,
     ops::{AddAssign, Deref, DerefMut},
     sync::atomic::Ordering,
 };
 
-use druid_shell::{kurbo::Point, IdleToken};
+use druid_shell::{
+    kurbo::{Affine, Point, Rect},
+    piet::{FontFamily, RenderContext as _},
+};
 
 use crate::{
-    api::events::Event,
+    api::{
+        events::{Event, PointerBehavior},
+        fonts,
+        implementors::WidgetDerive,
+        layers, lifecycle,
+        notifications::Notification,
+        paint::PaintContextExt,
+    },
     app::{
-        runner::{handler::APP_HANDLE, PaintContext},
+        listeners::pointer,
+        runner::{handler::schedule_frame, PaintContext},
         tree::WidgetNodeRef,
     },
     prelude::{MultiChildWidget, SingleChildWidget, WidgetState},
@@ -23,6 +35,12 @@ pub struct Offset {
     pub y: f64,
 }
 
+impl Offset {
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite()
+    }
+}
+
 impl From<Offset> for Point {
     fn from(offset: Offset) -> Self {
         Point {
@@ -32,6 +50,28 @@ impl From<Offset> for Point {
     }
 }
 
+impl Add for Offset {
+    type Output = Offset;
+
+    fn add(self, rhs: Offset) -> Offset {
+        Offset {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl Sub for Offset {
+    type Output = Offset;
+
+    fn sub(self, rhs: Offset) -> Offset {
+        Offset {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
 impl From<&Offset> for Point {
     fn from(offset: &Offset) -> Self {
         Point {
@@ -55,6 +95,10 @@ impl Size {
     pub fn aspect_ratio(&self) -> f64 {
         self.width / self.height
     }
+
+    pub fn is_finite(&self) -> bool {
+        self.width.is_finite() && self.height.is_finite()
+    }
 }
 
 impl From<druid_shell::kurbo::Size> for Size {
@@ -166,6 +210,22 @@ impl Constraints {
         }
     }
 
+    /// These constraints clamped to `constraints`, i.e. their intersection.
+    ///
+    /// On an axis where they don't overlap, the result is tight to the bound of
+    /// `constraints` closest to these.
+    pub fn enforce(&self, constraints: Constraints) -> Self {
+        let width = |w: f64| w.clamp(constraints.min_width, constraints.max_width);
+        let height = |h: f64| h.clamp(constraints.min_height, constraints.max_height);
+
+        Self {
+            min_width: width(self.min_width),
+            max_width: width(self.max_width),
+            min_height: height(self.min_height),
+            max_height: height(self.max_height),
+        }
+    }
+
     pub fn tight(size: Size) -> Self {
         Self {
             min_width: size.width,
@@ -278,6 +338,31 @@ impl Constraints {
         self.min_height >= f64::INFINITY
     }
 
+    /// Whether minimal extents are non-negative and don't exceed the maximal
+    /// ones, i.e. whether some size satisfies these constraints.
+    pub fn is_normalized(&self) -> bool {
+        (0.0..=self.max_width).contains(&self.min_width)
+            && (0.0..=self.max_height).contains(&self.min_height)
+    }
+
+    /// These constraints with negative extents raised to zero and minimal
+    /// extents lowered to the maximal ones.
+    ///
+    /// Inverted constraints (e.g. deflated by insets larger than the space
+    /// available) thus become tight to the maximal extent, or to zero if that
+    /// extent is negative.
+    pub fn normalize(&self) -> Self {
+        let max_width = self.max_width.max(0.);
+        let max_height = self.max_height.max(0.);
+
+        Self {
+            min_width: self.min_width.clamp(0., max_width),
+            max_width,
+            min_height: self.min_height.clamp(0., max_height),
+            max_height,
+        }
+    }
+
     pub fn is_satisfied_by(&self, size: Size) -> bool {
         (self.min_width..=self.max_width).contains(&size.width)
             && (self.min_height..=self.max_height).contains(&size.height)
@@ -301,6 +386,13 @@ pub trait RenderState {
     fn create_state(&self) -> Self::State;
 }
 
+/// Marker trait which opts a widget out of the debug check of its layout.
+///
+/// In debug builds, the size returned from `layout` of every widget must satisfy
+/// the constraints passed to it, otherwise Frui panics. Implement this trait
+/// only for widgets that genuinely need to violate their constraints.
+pub trait SkipConstraintsCheck {}
+
 pub type RenderContext<'a, T> = &'a mut _RenderContext<'a, T>;
 
 pub struct _RenderContext<'a, T> {
@@ -360,14 +452,129 @@ impl<'a, T> _RenderContext<'a, T> {
         })
     }
 
+    /// Schedules layout and paint of this widget for the next frame, without
+    /// rebuilding it.
+    ///
+    /// Layout of widgets is cached until their configuration or state changes.
+    /// Call this whenever layout of this widget depends on anything else, e.g.
+    /// on its render state changed during an event, or on something the
+    /// framework doesn't track, like a result cached in the render state.
+    /// Unlike `wstate_mut`, which rebuilds this widget and its descendants,
+    /// this only lays out this widget and its ancestors up to the closest
+    /// relayout boundary (a widget with tight constraints, whose size can't
+    /// change). So a widget laid out within tight constraints is laid out
+    /// again on its own, and its parent keeps its layout.
+    ///
+    /// Widget whose layout stays the same and which only paints differently
+    /// should call [`mark_needs_paint`](Self::mark_needs_paint) instead.
+    ///
+    /// Like with [`mark_needs_paint`](Self::mark_needs_paint), layout requested
+    /// during paint is deferred while the application isn't resumed.
+    pub fn mark_needs_layout(&mut self) {
+        self.ctx.node.mark_needs_layout_up_to_boundary();
+
+        if RenderPhase::current() == RenderPhase::Paint {
+            lifecycle::schedule_animation_frame();
+        } else {
+            schedule_frame();
+        }
+    }
+
+    #[deprecated = "use `mark_needs_layout`, or `mark_needs_paint` if layout doesn't change"]
     pub fn schedule_layout(&mut self) {
-        APP_HANDLE.with(|handle| {
-            handle
-                .borrow_mut()
-                .as_mut()
-                .expect("APP_HANDLE wasn't set")
-                .schedule_idle(IdleToken::new(0));
-        });
+        self.mark_needs_layout();
+    }
+
+    #[deprecated = "use `mark_needs_layout`, which discards the cached layout"]
+    pub fn invalidate_layout_cache(&mut self) {
+        self.mark_needs_layout();
+    }
+
+    /// Lays out this widget again once `family` is loaded, if it isn't loaded
+    /// yet. Call it from `layout` of widgets measuring text with `family`, see
+    /// [`fonts`](crate::api::fonts).
+    pub fn depend_on_font(&mut self, family: &FontFamily) {
+        fonts::depend_on_font(&self.ctx.node, family);
+    }
+
+    /// Schedules paint of the next frame, reusing cached layout of this widget.
+    ///
+    /// This is useful for widgets whose paint depends on something that doesn't
+    /// affect their layout, e.g. on an external clock. When no widget needs to
+    /// be rebuilt or laid out, the next frame only paints.
+    ///
+    /// Called during paint, it's deferred while the application isn't
+    /// [`Resumed`](crate::api::lifecycle::AppLifecycleState::Resumed), which
+    /// pauses animations.
+    pub fn mark_needs_paint(&mut self) {
+        self.ctx.node.mark_needs_paint();
+    }
+
+    /// Captures the pointer, so that following pointer move and up events are
+    /// delivered to this widget even if the pointer leaves its bounds (or the
+    /// window, as far as the platform allows) or an ancestor doesn't pass them
+    /// to its children.
+    ///
+    /// Call this on pointer down starting a drag. Capture is released on
+    /// pointer up, when this widget is unmounted, or when the window loses
+    /// focus, in which case a pointer up event is delivered to this widget.
+    ///
+    /// Events delivered only because of the capture aren't transformed by the
+    /// ancestors, so their positions are in coordinates of the window.
+    pub fn capture_pointer(&mut self) {
+        pointer::capture(&self.ctx.node);
+    }
+
+    /// Sets how this widget and its descendants take part in the dispatch of
+    /// pointer events. Persists until changed, typically set during layout.
+    pub fn set_pointer_behavior(&mut self, behavior: PointerBehavior) {
+        self.ctx.node.borrow_mut().render_data.pointer_behavior = behavior;
+    }
+
+    /// Releases the pointer captured by this widget.
+    pub fn release_pointer(&mut self) {
+        pointer::release(&self.ctx.node);
+    }
+
+    pub fn has_pointer_capture(&self) -> bool {
+        pointer::is_captured_by(&self.ctx.node)
+    }
+
+    /// Schedules a rebuild of this widget for the next frame.
+    ///
+    /// This is useful for widgets whose build output depends on something other
+    /// than their state, e.g. on the current value of an animation.
+    pub fn schedule_rebuild(&mut self) {
+        self.ctx.node.mark_dirty();
+    }
+
+    /// Stores the offset of the child at `index` from the origin of this
+    /// widget, read back with [`child_offset`](Self::child_offset) during
+    /// paint. Can only be called during layout.
+    ///
+    /// Use [`ChildContext::set_offset`] when iterating over children.
+    #[track_caller]
+    pub fn set_child_offset(&mut self, index: usize, offset: Offset) {
+        self.nth_child(index).set_offset(offset);
+    }
+
+    /// Offset of the child at `index` from the origin of this widget, as set
+    /// during layout with [`set_child_offset`](Self::set_child_offset).
+    ///
+    /// See [`ChildContext::offset`].
+    #[track_caller]
+    pub fn child_offset(&mut self, index: usize) -> Offset {
+        self.nth_child(index).offset()
+    }
+
+    #[track_caller]
+    fn nth_child(&mut self, index: usize) -> ChildContext<'_> {
+        let len = self.ctx.node.children().len();
+
+        self.ctx
+            .children()
+            .nth(index)
+            .unwrap_or_else(|| panic!("child index {index} out of range of {len} children"))
     }
 
     //
@@ -398,6 +605,30 @@ impl<'a, T> _RenderContext<'a, T> {
         self.ctx.node.borrow().render_data.offset
     }
 
+    /// Id of this widget for correlating logs across frames, see
+    /// [`_BuildContext::debug_id`](crate::api::contexts::build_ctx::_BuildContext::debug_id).
+    pub fn debug_id(&self) -> u64 {
+        self.ctx.node.debug_id()
+    }
+
+    /// Size of the closest ancestor of type `W`, as computed during its last
+    /// layout. Returns `None` if there is no such ancestor or if it wasn't laid
+    /// out yet.
+    ///
+    /// During layout this is the size from the previous frame, since ancestors
+    /// finish their layout after their descendants.
+    pub fn find_ancestor_size<W: WidgetDerive>(&self) -> Option<Size> {
+        self.ctx
+            .node
+            .find_ancestor_size(TypeId::of::<W::UniqueTypeId>())
+    }
+
+    /// Dispatches `notification` to ancestors of this widget, nearest first,
+    /// until one of them stops it.
+    pub fn dispatch_notification<N: Notification>(&self, notification: N) {
+        self.ctx.node.dispatch_notification(&notification);
+    }
+
     pub fn point_in_layout_bounds(&self, point: Point) -> bool {
         let Offset { x: o_x, y: o_y } = self.offset();
         let Point { x, y } = point;
@@ -412,8 +643,102 @@ impl<'a, T> _RenderContext<'a, T> {
     }
 }
 
+pub(crate) mod layout_guard {
+    use std::cell::Cell;
+
+    thread_local! {
+        static MAX_LAYOUTS_PER_FRAME: Cell<usize> = const { Cell::new(10) };
+        /// Number of the current layout pass.
+        static LAYOUT_PASS: Cell<u64> = const { Cell::new(0) };
+    }
+
+    pub fn max_layouts_per_frame() -> usize {
+        MAX_LAYOUTS_PER_FRAME.with(|max| max.get())
+    }
+
+    pub fn set_max_layouts_per_frame(max: usize) {
+        MAX_LAYOUTS_PER_FRAME.with(|m| m.set(max));
+    }
+
+    pub fn begin_pass() {
+        LAYOUT_PASS.with(|pass| pass.set(pass.get() + 1));
+    }
+
+    /// Counts a layout of a widget, given the layout pass it was last laid out
+    /// in and how many times. Returns `Some` if that widget was already laid
+    /// out the maximum number of times during the current pass, with `true` the
+    /// first time it happens.
+    ///
+    /// Only layouts with constraints differing from the previous ones are
+    /// counted, since laying out again with the same ones (e.g. after a
+    /// descendant changed) can't oscillate.
+    pub fn count(layout_pass: &mut (u64, usize), constraints_changed: bool) -> Option<bool> {
+        let current = LAYOUT_PASS.with(|pass| pass.get());
+
+        if layout_pass.0 != current {
+            *layout_pass = (current, 0);
+        }
+
+        if !constraints_changed {
+            return None;
+        }
+
+        let max = max_layouts_per_frame();
+        layout_pass.1 += 1;
+
+        match layout_pass.1 > max {
+            true => Some(layout_pass.1 == max + 1),
+            false => None,
+        }
+    }
+}
+
+/// Phase of a frame that is currently running.
+///
+/// Used to make sure that widgets lay out their children only during layout
+/// and paint them only during paint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RenderPhase {
+    Idle,
+    Layout,
+    Paint,
+}
+
+thread_local! {
+    static RENDER_PHASE: Cell<RenderPhase> = const { Cell::new(RenderPhase::Idle) };
+}
+
+impl RenderPhase {
+    pub(crate) fn current() -> RenderPhase {
+        RENDER_PHASE.with(|phase| phase.get())
+    }
+
+    /// Runs `f` with the current phase set to `self`.
+    pub(crate) fn run<R>(self, f: impl FnOnce() -> R) -> R {
+        let previous = RENDER_PHASE.with(|phase| phase.replace(self));
+        let r = f();
+        RENDER_PHASE.with(|phase| phase.set(previous));
+        r
+    }
+
+    #[track_caller]
+    fn assert_current(expected: RenderPhase, method: &str) {
+        if cfg!(debug_assertions) {
+            let phase = RENDER_PHASE.with(|phase| phase.get());
+
+            assert!(
+                phase == expected,
+                "`ChildContext::{method}` can only be called during {expected:?} phase, \
+                but it was called during {phase:?} phase",
+            );
+        }
+    }
+}
+
 pub struct ChildContext<'a> {
     ctx: AnyRenderContext,
+    /// Index of this child among the children of its parent.
+    index: usize,
     _p: PhantomData<&'a ()>,
 }
 
@@ -448,12 +773,86 @@ impl<'a> ChildContext<'a> {
         }))
     }
 
+    /// Stores the offset of this child from the origin of its parent, read
+    /// back with [`offset`](Self::offset) during paint. Can only be called
+    /// during layout.
+    #[track_caller]
+    pub fn set_offset(&mut self, offset: Offset) {
+        RenderPhase::assert_current(RenderPhase::Layout, "set_offset");
+
+        self.ctx.node.borrow_mut().render_data.parent_offset = Some(offset);
+    }
+
+    /// Offset of this child from the origin of its parent, as set during
+    /// layout with [`set_offset`](Self::set_offset).
+    ///
+    /// Child which wasn't positioned is at the origin of its parent, which is
+    /// reported once in debug builds.
+    pub fn offset(&self) -> Offset {
+        let mut node = self.ctx.node.borrow_mut();
+
+        match node.render_data.parent_offset {
+            Some(offset) => offset,
+            None => {
+                node.render_data.parent_offset = Some(Offset::default());
+                drop(node);
+
+                if cfg!(debug_assertions) {
+                    diagnostic!(
+                        warn,
+                        "layout",
+                        "offset of `{}` was read before its parent set it during layout, \
+                        it's placed at the origin of its parent",
+                        self.ctx.node.widget().debug_name_short(),
+                    );
+                }
+
+                Offset::default()
+            }
+        }
+    }
+
+    /// Lays out this child. Can only be called during layout.
+    #[track_caller]
     pub fn layout(&mut self, constraints: Constraints) -> Size {
+        RenderPhase::assert_current(RenderPhase::Layout, "layout");
+
+        if let Some(parent) = self.ctx.node.parent() {
+            let laid_out = &mut parent.borrow_mut().render_data.laid_out_children;
+
+            if laid_out.last() != Some(&self.index) {
+                laid_out.push(self.index);
+            }
+        }
+
         self.ctx.layout(constraints.clone())
     }
 
+    /// Paints this child at its [`offset`](Self::offset) from the origin of its
+    /// parent. Can only be called during paint.
+    ///
+    /// Child is painted in its own coordinates, i.e. with the canvas translated
+    /// to its origin. Child which wasn't positioned during layout is painted
+    /// at the origin of its parent.
     #[track_caller]
-    pub fn paint(&mut self, canvas: &mut PaintContext, offset: &Offset) {
+    pub fn paint(&mut self, canvas: &mut PaintContext) {
+        RenderPhase::assert_current(RenderPhase::Paint, "paint");
+
+        let offset = self.ctx.node.borrow().render_data.parent_offset;
+        self.ctx.paint(canvas, &offset.unwrap_or_default())
+    }
+
+    /// Paints this child at `offset` from the origin of its parent, instead of
+    /// the offset set during layout. Can only be called during paint.
+    ///
+    /// Meant for widgets which position their children only once they are
+    /// painted (e.g. by the current scroll position). Others should position
+    /// them with [`set_offset`](Self::set_offset) and use
+    /// [`paint`](Self::paint).
+    #[track_caller]
+    pub fn paint_at(&mut self, canvas: &mut PaintContext, offset: &Offset) {
+        RenderPhase::assert_current(RenderPhase::Paint, "paint_at");
+
         self.ctx.paint(canvas, offset)
     }
 
@@ -481,6 +880,7 @@ impl AnyRenderContext {
 
         ChildContext {
             ctx: AnyRenderContext::new(crate::app::tree::WidgetNode::node_ref(child_node)),
+            index: 0,
             _p: PhantomData,
         }
     }
@@ -493,15 +893,93 @@ impl AnyRenderContext {
     }
 
     pub(crate) fn layout(&mut self, constraints: Constraints) -> Size {
+        let constraints = match constraints.is_normalized() {
+            true => constraints,
+            false => {
+                diagnostic!(
+                    warn,
+                    "layout",
+                    "`{}` was laid out with invalid constraints {:?} (minimal extent is \
+                    negative or larger than the maximal one), laying it out with {:?} instead",
+                    self.node.widget().debug_name_short(),
+                    constraints,
+                    constraints.normalize(),
+                );
+
+                constraints.normalize()
+            }
+        };
+
+        // Neither this widget nor its descendants changed since they were laid
+        // out with the same constraints.
+        if let Some(size) = self.node.borrow().render_data.cached_size(constraints) {
+            return size;
+        }
+
         let widget = self.node.widget().clone();
+
+        let guard = {
+            let render_data = &mut self.node.borrow_mut().render_data;
+            let constraints_changed = render_data.constraints != constraints;
+            layout_guard::count(&mut render_data.layout_pass, constraints_changed)
+        };
+
+        if let Some(first) = guard {
+            // Layout is oscillating (e.g. a parent sizing itself by a child
+            // which depends on that parent), reuse its last result.
+            let render_data = &self.node.borrow().render_data;
+
+            if first {
+                diagnostic!(
+                    warn,
+                    "layout",
+                    "`{}` was laid out more than {} times during a single frame with \
+                    differing constraints (last {:?}, now {:?}), reusing its last size {:?}",
+                    widget.debug_name_short(),
+                    layout_guard::max_layouts_per_frame(),
+                    render_data.constraints,
+                    constraints,
+                    render_data.size,
+                );
+            }
+
+            return render_data.size;
+        }
+
+        self.node.borrow_mut().render_data.laid_out_children.clear();
+
         let size = widget.layout(self, constraints);
 
         if cfg!(debug_assertions) {
-            if size > constraints.max() {
-                if widget.debug_name_short() != "DebugContainer" {
-                    log::warn!("`{}` overflowed", widget.debug_name_short());
-                }
+            if let Some(axis) = infinite_unbounded_axis(size, constraints) {
+                panic!(
+                    "`{}` returned infinite {axis} from layout with constraints {:?}, which \
+                    don't bound its {axis}. It probably sizes itself to the available space, \
+                    which is unbounded there (e.g. within a `Scroll` or a `Row`). Wrap it in a \
+                    widget giving it a finite {axis}, e.g. a `Container` with a {axis}.",
+                    widget.debug_name_short(),
+                    constraints,
+                );
             }
+
+            assert!(
+                size.is_finite() && size.width >= 0. && size.height >= 0.,
+                "`{}` returned invalid size {:?} from layout with constraints {:?} \
+                (size must be finite and non-negative)",
+                widget.debug_name_short(),
+                size,
+                constraints,
+            );
+        }
+
+        if cfg!(debug_assertions) && !widget.skip_constraints_check() {
+            assert!(
+                constraints.is_satisfied_by(size),
+                "`{}` returned size {:?} which doesn't satisfy its constraints {:?}",
+                widget.debug_name_short(),
+                size,
+                constraints,
+            );
         }
 
         let render_data = &mut self.node.borrow_mut().render_data;
@@ -509,27 +987,118 @@ impl AnyRenderContext {
         render_data.size = size;
         render_data.laid_out = true;
         render_data.constraints = constraints;
+        render_data.needs_layout = false;
+
+        // Children can be laid out in any order (e.g. flexible ones last), but
+        // they are hit tested in the order they are painted in.
+        render_data.laid_out_children.sort_unstable();
+        render_data.laid_out_children.dedup();
 
         size
     }
 
+    /// Paints this widget at `offset` from the origin of its parent, with the
+    /// canvas translated to its own origin.
     pub(crate) fn paint(&mut self, piet: &mut PaintContext, offset: &Offset) {
+        // Rebuild of this widget was deferred by the build budget, but it can't
+        // be painted stale.
+        if self.node.borrow().dirty {
+            self.rebuild_before_paint();
+        }
+
         assert!(
             self.node.borrow().render_data.laid_out,
             "child was not laid out before paint"
         );
 
-        // This should probably be calculated during layout probably.
-        self.node.borrow_mut().render_data.offset = offset.clone();
+        if cfg!(debug_assertions) {
+            let render_data = &self.node.borrow().render_data;
+
+            assert!(
+                offset.is_finite(),
+                "`{}` was painted at invalid offset {:?} (its size is {:?}, laid out \
+                with constraints {:?})",
+                self.node.widget().debug_name_short(),
+                offset,
+                render_data.size,
+                render_data.constraints,
+            );
+        }
+
+        let Some(mut piet) = piet.save_guard() else {
+            return;
+        };
+
+        piet.transform(Affine::translate((offset.x, offset.y)));
+
+        let origin = match self.node.parent() {
+            Some(parent) => parent.borrow().render_data.offset + *offset,
+            None => *offset,
+        };
+
+        {
+            let render_data = &mut self.node.borrow_mut().render_data;
+            render_data.offset = origin;
+            render_data.paint_transform = piet.current_transform();
+            render_data.painted_pass = crate::app::tree::paint_pass();
+        }
+
+        let widget = self.node.widget().clone();
+        let bounds = Rect::from_origin_size(Point::ORIGIN, self.node.borrow().render_data.size);
+
+        layers::with_painter(widget.debug_name_short(), bounds, || {
+            widget.paint(self, &mut piet)
+        });
+    }
+
+    /// Rebuilds this widget and lays it out again with its last constraints.
+    ///
+    /// Its parent already placed it according to its previous size, so if
+    /// that size changed, the parent is laid out again during the next frame.
+    fn rebuild_before_paint(&mut self) {
+        let previous_size = self.node.borrow().render_data.size;
+        let constraints = self.node.borrow().render_data.constraints;
 
-        self.node.widget().clone().paint(self, piet, offset);
+        RenderPhase::Idle.run(|| self.node.update_subtree());
+        self.node.borrow_mut().render_data.needs_layout = true;
+
+        let size = RenderPhase::Layout.run(|| self.layout(constraints));
+
+        if size != previous_size {
+            self.node.mark_needs_layout();
+            schedule_frame();
+        }
     }
 
     pub(crate) fn handle_event(&mut self, event: &Event) {
+        let behavior = self.node.borrow().render_data.pointer_behavior;
+
+        if event.pointer_pos().is_some() && behavior == PointerBehavior::Ignore {
+            return;
+        }
+
+        if !pointer::is_delivered_to(&self.node) {
+            return;
+        }
+
+        pointer::mark_received(&self.node);
+
         self.node.widget().clone().handle_event(self, event);
     }
 }
 
+/// Axis (`"width"` or `"height"`) along which `size` is infinite while
+/// `constraints` are unbounded, which is the likely cause of that.
+fn infinite_unbounded_axis(size: Size, constraints: Constraints) -> Option<&'static str> {
+    if size.width.is_infinite() && constraints.max_width.is_infinite() {
+        Some("width")
+    } else if size.height.is_infinite() && constraints.max_height.is_infinite() {
+        Some("height")
+    } else {
+        None
+    }
+}
+
 pub struct ChildContextIter<'a> {
     child_idx: usize,
     parent: &'a WidgetNodeRef,
@@ -554,12 +1123,21 @@ impl<'a> Iterator for ChildContextIter<'a> {
 
         Some(ChildContext {
             ctx: AnyRenderContext::new(crate::app::tree::WidgetNode::node_ref(next_child)),
+            index: self.child_idx - 1,
             _p: PhantomData,
         })
     }
+
+    // Skipping children doesn't visit them, so that widgets which lay out or
+    // paint only some of many children (e.g. the visible ones) don't pay for
+    // the rest.
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.child_idx = self.child_idx.saturating_add(n);
+        self.next()
+    }
 }
 
-pub(crate) use sealed::RenderStateOS;
+pub(crate) use sealed::{RenderStateOS, SkipConstraintsCheckOS};
 
 use super::build_ctx::STATE_UPDATE_SUPRESSED;
 
@@ -581,4 +1159,20 @@ mod sealed {
             Box::new(T::create_state(&self))
         }
     }
+
+    pub trait SkipConstraintsCheckOS {
+        fn skip_constraints_check(&self) -> bool;
+    }
+
+    impl<T> SkipConstraintsCheckOS for T {
+        default fn skip_constraints_check(&self) -> bool {
+            false
+        }
+    }
+
+    impl<T: super::SkipConstraintsCheck> SkipConstraintsCheckOS for T {
+        fn skip_constraints_check(&self) -> bool {
+            true
+        }
+    }
 }
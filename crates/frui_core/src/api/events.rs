@@ -1,7 +1,7 @@
 //! Following events implementation is a bad prototype.
 
 use druid_shell::{
-    kurbo::{Rect, Shape, Vec2},
+    kurbo::{Affine, Point, Rect, Shape, Vec2},
     MouseEvent,
 };
 
@@ -17,6 +17,30 @@ pub enum Event {
 }
 
 impl Event {
+    /// Position of the pointer, if this is a pointer event.
+    pub fn pointer_pos(&self) -> Option<Point> {
+        match self {
+            Event::MouseDown(e)
+            | Event::MouseUp(e)
+            | Event::MouseMove(e)
+            | Event::MouseWheel(e) => Some(e.pos),
+        }
+    }
+
+    /// Maps the pointer position of this event through `transform`.
+    pub fn transform(&self, transform: Affine) -> Event {
+        let mut event = self.clone();
+
+        match &mut event {
+            Event::MouseDown(mouse_event)
+            | Event::MouseUp(mouse_event)
+            | Event::MouseMove(mouse_event)
+            | Event::MouseWheel(mouse_event) => mouse_event.pos = transform * mouse_event.pos,
+        }
+
+        event
+    }
+
     pub fn transform_scroll(&self, offset: Vec2, viewport: Rect) -> Option<Event> {
         match self {
             Event::MouseDown(mouse_event) => {
@@ -59,6 +83,21 @@ impl Event {
     }
 }
 
+/// How a widget and its descendants take part in the dispatch of pointer
+/// events. Set with [`RenderContext::set_pointer_behavior`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PointerBehavior {
+    /// Pointer events are delivered as usual.
+    #[default]
+    Pass,
+    /// Neither the widget nor its descendants receive pointer events.
+    Ignore,
+    /// The widget receives pointer events, but its descendants don't. Pointer
+    /// events within its bounds aren't delivered to widgets behind it either,
+    /// that is to widgets visited before it, other than its ancestors.
+    Absorb,
+}
+
 pub trait WidgetEvent: Sized {
     fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool;
 }
@@ -67,7 +106,7 @@ pub(crate) use sealed::WidgetEventOS;
 
 mod sealed {
     use super::Event;
-    use crate::api::contexts::render_ctx::{AnyRenderContext, _RenderContext};
+    use crate::api::contexts::render_ctx::{_RenderContext, AnyRenderContext};
 
     /// `OS` stands for "object safe".
     pub trait WidgetEventOS {
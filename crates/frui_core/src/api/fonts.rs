@@ -0,0 +1,198 @@
+//! Fonts loaded while the application runs.
+//!
+//! Text laid out with a font family which isn't loaded yet is measured with a
+//! fallback font. Widgets laying out text call
+//! [`RenderContext::depend_on_font`](super::contexts::render_ctx::_RenderContext::depend_on_font),
+//! so that once that family is loaded (see [`load_font`] and [`font_loaded`]),
+//! the layout cache of just those widgets is invalidated and they are
+//! measured again during the next frame.
+//!
+//! ```no_run
+//! # extern crate frui_core as frui;
+//! # use frui::api::fonts;
+//! # fn load(data: Vec<u8>) -> Result<(), frui::druid_shell::piet::Error> {
+//! // E.g. in a future spawned at startup, once the font file is read:
+//! let family = fonts::load_font(&data)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
+
+use druid_shell::piet::{self, FontFamily, Text};
+
+use crate::app::{runner::handler::schedule_frame, tree::WidgetNodeRef, TEXT_FACTORY};
+
+/// Number of nodes depending on a font above which dead nodes are removed.
+const MIN_PRUNE_LEN: usize = 16;
+
+struct Fonts {
+    loaded: HashSet<String>,
+    /// Nodes laid out with each family which wasn't loaded yet.
+    dependents: HashMap<String, Dependents>,
+}
+
+struct Dependents {
+    nodes: HashSet<WidgetNodeRef>,
+    /// Length past which dead nodes are removed, so that nodes of a family
+    /// which never loads don't pile up.
+    prune_at: usize,
+}
+
+thread_local! {
+    static FONTS: RefCell<Fonts> = RefCell::new(Fonts {
+        loaded: HashSet::new(),
+        dependents: HashMap::new(),
+    });
+}
+
+/// Loads a font from the contents of its file (e.g. TrueType or OpenType),
+/// returning its family. Layout of widgets which used that family is
+/// invalidated.
+///
+/// Must be called on the UI thread, after the window was created.
+pub fn load_font(data: &[u8]) -> Result<FontFamily, piet::Error> {
+    let family = TEXT_FACTORY.with(|f| f.get().load_font(data))?;
+    font_loaded(&family);
+
+    Ok(family)
+}
+
+/// Invalidates layout of widgets which used `family` before it was loaded,
+/// e.g. for a font installed on the system while the application runs.
+/// [`load_font`] calls this already.
+pub fn font_loaded(family: &FontFamily) {
+    let dependents = FONTS.with(|fonts| {
+        let mut fonts = fonts.borrow_mut();
+        fonts.loaded.insert(family.name().to_owned());
+        fonts.dependents.remove(family.name())
+    });
+
+    if let Some(dependents) = dependents {
+        for node in dependents.nodes.into_iter().filter(|n| n.is_alive()) {
+            node.mark_needs_layout_up_to_boundary();
+        }
+
+        schedule_frame();
+    }
+}
+
+/// Whether `family` was loaded with [`load_font`] or [`font_loaded`].
+pub fn is_font_loaded(family: &FontFamily) -> bool {
+    FONTS.with(|fonts| fonts.borrow().loaded.contains(family.name()))
+}
+
+/// Registers `node` to be laid out again once `family` is loaded.
+pub(crate) fn depend_on_font(node: &WidgetNodeRef, family: &FontFamily) {
+    // Generic families are provided by the platform.
+    if family.is_generic() {
+        return;
+    }
+
+    FONTS.with(|fonts| {
+        let fonts = &mut *fonts.borrow_mut();
+
+        if fonts.loaded.contains(family.name()) {
+            return;
+        }
+
+        let dependents = fonts
+            .dependents
+            .entry(family.name().to_owned())
+            .or_insert_with(|| Dependents {
+                nodes: HashSet::new(),
+                prune_at: MIN_PRUNE_LEN,
+            });
+
+        if dependents.nodes.insert(node.clone()) && dependents.nodes.len() > dependents.prune_at {
+            dependents.nodes.retain(|n| n.is_alive());
+            dependents.prune_at = MIN_PRUNE_LEN.max(dependents.nodes.len() * 2);
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use std::{any::TypeId, cell::Cell, rc::Rc};
+
+    use super::*;
+
+    use crate::{
+        api::{
+            implementors::{leaf::LeafWidget, WidgetDerive},
+            Widget, WidgetKind, WidgetPtr,
+        },
+        app::tree::WidgetTree,
+        prelude::{Constraints, PaintContext, RenderContext, Size},
+    };
+
+    /// Stands in for text, which is wider once laid out with its font.
+    struct Label {
+        family: FontFamily,
+        layouts: Rc<Cell<usize>>,
+    }
+
+    impl Widget for Label {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Label>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::Leaf(self)
+        }
+    }
+
+    impl WidgetDerive for Label {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = Label;
+    }
+
+    impl LeafWidget for Label {
+        fn layout(&self, ctx: RenderContext<Self>, _: Constraints) -> Size {
+            self.layouts.set(self.layouts.get() + 1);
+            ctx.depend_on_font(&self.family);
+
+            match is_font_loaded(&self.family) {
+                true => Size::new(20., 10.),
+                false => Size::new(10., 10.),
+            }
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    #[test]
+    fn text_is_laid_out_again_once_its_font_loads() {
+        let family = FontFamily::new_unchecked("Loaded Later");
+        let layouts = Rc::new(Cell::new(0));
+
+        let label = Label {
+            family: family.clone(),
+            layouts: layouts.clone(),
+        };
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(label)));
+        let constraints = Constraints::loose(Size::new(100., 100.));
+        let size = |tree: &mut WidgetTree| tree.get_root().borrow().render_data.size;
+
+        tree.layout(constraints);
+        assert_eq!(size(&mut tree), Size::new(10., 10.));
+
+        // Other fonts don't invalidate the layout.
+        font_loaded(&FontFamily::new_unchecked("Unrelated"));
+        tree.layout(constraints);
+        assert_eq!(layouts.get(), 1);
+
+        font_loaded(&family);
+        tree.layout(constraints);
+        assert_eq!(layouts.get(), 2);
+        assert_eq!(size(&mut tree), Size::new(20., 10.));
+
+        // Loaded font isn't tracked anymore.
+        FONTS.with(|fonts| assert!(fonts.borrow().dependents.is_empty()));
+    }
+}
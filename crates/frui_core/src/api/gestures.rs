@@ -0,0 +1,594 @@
+//! Gesture arena, which decides which of the recognizers interested in a
+//! pointer gesture (from pointer down to pointer up) gets to handle it.
+//!
+//! Recognizers enter the arena on pointer down with [`GestureArenaEntry::enter`]
+//! and then either claim the gesture with [`GestureArenaEntry::accept`], which
+//! rejects every other member, or leave it with [`GestureArenaEntry::reject`].
+//! Arena is also resolved by the framework:
+//!
+//! - Once pointer down was dispatched, a member which entered the arena alone
+//!   wins it.
+//! - Once all but one member left the arena, the remaining one wins it.
+//! - Once pointer up was dispatched, the member which entered the arena last
+//!   wins it if it is still unresolved. Since events are delivered to widgets
+//!   before their descendants, that is usually the innermost one.
+//!
+//! So exactly one member wins each gesture. Members don't get notified of the
+//! result, instead they check their [`GestureState`] when handling the
+//! following events.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use druid_shell::kurbo::{Point, Vec2};
+
+use super::events::Event;
+
+/// Distance the pointer can move from where it was pressed, before a tap turns
+/// into a drag.
+pub const GESTURE_SLOP: f64 = 8.;
+
+thread_local! {
+    /// Arena of the current gesture, if any.
+    static ARENA: RefCell<Option<GestureArena>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GestureState {
+    /// Arena wasn't resolved yet.
+    Possible,
+    /// This member won the arena.
+    Accepted,
+    /// This member lost the arena or left it.
+    Rejected,
+}
+
+/// Membership of a recognizer in the gesture arena of the current gesture.
+#[derive(Debug)]
+pub struct GestureArenaEntry {
+    state: Rc<Cell<GestureState>>,
+}
+
+impl GestureArenaEntry {
+    /// Enters the arena of the gesture started by the currently dispatched
+    /// pointer down.
+    ///
+    /// If no pointer down is being dispatched, or the arena was already won,
+    /// returned entry is rejected.
+    pub fn enter() -> Self {
+        let state = Rc::new(Cell::new(GestureState::Rejected));
+
+        ARENA.with(|arena| {
+            if let Some(arena) = arena.borrow_mut().as_mut() {
+                arena.add(state.clone());
+            }
+        });
+
+        GestureArenaEntry { state }
+    }
+
+    pub fn state(&self) -> GestureState {
+        self.state.get()
+    }
+
+    /// Claims the gesture, rejecting every other member of the arena.
+    ///
+    /// Does nothing if the arena was already resolved, so [`state`] tells
+    /// whether the gesture was claimed.
+    ///
+    /// [`state`]: Self::state
+    pub fn accept(&self) {
+        self.with_arena(|arena| arena.accept(&self.state));
+    }
+
+    /// Leaves the arena. Its winner leaves it too, but then no one else wins.
+    pub fn reject(&self) {
+        self.with_arena(|arena| arena.reject(&self.state));
+    }
+
+    fn with_arena(&self, f: impl FnOnce(&mut GestureArena)) {
+        ARENA.with(|arena| match arena.borrow_mut().as_mut() {
+            Some(arena) if arena.contains(&self.state) => f(arena),
+            // Arena of this entry was already swept.
+            _ => {
+                if self.state.get() == GestureState::Possible {
+                    self.state.set(GestureState::Rejected);
+                }
+            }
+        });
+    }
+}
+
+struct GestureArena {
+    members: Vec<Rc<Cell<GestureState>>>,
+    /// Whether pointer down is still being dispatched.
+    is_open: bool,
+    is_resolved: bool,
+}
+
+impl GestureArena {
+    fn new() -> Self {
+        GestureArena {
+            members: Vec::new(),
+            is_open: true,
+            is_resolved: false,
+        }
+    }
+
+    fn contains(&self, member: &Rc<Cell<GestureState>>) -> bool {
+        self.members.iter().any(|m| Rc::ptr_eq(m, member))
+    }
+
+    fn add(&mut self, member: Rc<Cell<GestureState>>) {
+        if !self.is_resolved {
+            member.set(GestureState::Possible);
+        }
+
+        self.members.push(member);
+    }
+
+    fn accept(&mut self, member: &Rc<Cell<GestureState>>) {
+        if self.is_resolved {
+            return;
+        }
+
+        for m in &self.members {
+            m.set(match Rc::ptr_eq(m, member) {
+                true => GestureState::Accepted,
+                false => GestureState::Rejected,
+            });
+        }
+
+        self.is_resolved = true;
+    }
+
+    fn reject(&mut self, member: &Rc<Cell<GestureState>>) {
+        member.set(GestureState::Rejected);
+
+        if !self.is_resolved && !self.is_open {
+            self.resolve_last_possible();
+        }
+    }
+
+    fn close(&mut self) {
+        self.is_open = false;
+
+        if !self.is_resolved {
+            self.resolve_last_possible();
+        }
+    }
+
+    /// Resolves the arena if at most one member is still in it.
+    fn resolve_last_possible(&mut self) {
+        let possible = self.possible().cloned().collect::<Vec<_>>();
+
+        match possible.as_slice() {
+            [winner] => self.accept(winner),
+            [] => self.is_resolved = true,
+            _ => {}
+        }
+    }
+
+    /// Resolves the arena in favor of the member which entered it last.
+    fn sweep(&mut self) {
+        if let Some(winner) = self.possible().last().cloned() {
+            self.accept(&winner);
+        }
+
+        self.is_resolved = true;
+    }
+
+    fn possible(&self) -> impl Iterator<Item = &Rc<Cell<GestureState>>> {
+        self.members
+            .iter()
+            .filter(|m| m.get() == GestureState::Possible)
+    }
+}
+
+/// Opens the arena of a new gesture when pointer down is dispatched, rejecting
+/// members of the previous one.
+pub(crate) fn before_dispatch(event: &Event) {
+    if let Event::MouseDown(_) = event {
+        ARENA.with(|arena| {
+            if let Some(arena) = arena.borrow_mut().take() {
+                for m in arena.possible() {
+                    m.set(GestureState::Rejected);
+                }
+            }
+
+            *arena.borrow_mut() = Some(GestureArena::new());
+        });
+    }
+}
+
+/// Closes the arena once pointer down was dispatched and sweeps it once pointer
+/// up was dispatched.
+pub(crate) fn after_dispatch(event: &Event) {
+    ARENA.with(|arena| {
+        let mut arena = arena.borrow_mut();
+
+        match event {
+            Event::MouseDown(_) => {
+                if let Some(arena) = arena.as_mut() {
+                    arena.close();
+                }
+            }
+            Event::MouseUp(_) => {
+                if let Some(mut arena) = arena.take() {
+                    arena.sweep();
+                }
+            }
+            _ => {}
+        }
+    });
+}
+
+/// Recognizes taps: presses released without moving the pointer further than
+/// [`GESTURE_SLOP`].
+#[derive(Debug, Default)]
+pub struct TapGestureRecognizer {
+    /// Entry and the position of the tracked press.
+    tracked: Option<(GestureArenaEntry, Point)>,
+}
+
+impl TapGestureRecognizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts tracking a press at `pos`. Call this on pointer down within the
+    /// widget, while the pointer down is being dispatched.
+    pub fn add_pointer(&mut self, pos: Point) {
+        self.tracked = Some((GestureArenaEntry::enter(), pos));
+    }
+
+    /// Returns `true` if `event` completes a tap which this recognizer won.
+    ///
+    /// Tap is claimed on pointer up, unless another recognizer (e.g. of a
+    /// drag) claimed the gesture earlier.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        let Some((entry, down)) = &self.tracked else {
+            return false;
+        };
+
+        match event {
+            Event::MouseMove(e) => {
+                if (e.pos - *down).hypot() > GESTURE_SLOP {
+                    entry.reject();
+                    self.tracked = None;
+                }
+
+                false
+            }
+            Event::MouseUp(_) => {
+                entry.accept();
+                let is_tap = entry.state() == GestureState::Accepted;
+                self.tracked = None;
+
+                is_tap
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a press is tracked which may still turn out to be a tap.
+    pub fn is_tracking(&self) -> bool {
+        self.tracked
+            .as_ref()
+            .is_some_and(|(entry, _)| entry.state() != GestureState::Rejected)
+    }
+}
+
+/// Samples older than that (relative to the newest one) don't affect the
+/// estimated velocity.
+const VELOCITY_HORIZON: Duration = Duration::from_millis(100);
+
+/// Pointer which didn't move for longer than that is assumed to have stopped.
+const ASSUME_STOPPED: Duration = Duration::from_millis(40);
+
+const MAX_VELOCITY_SAMPLES: usize = 20;
+
+/// Estimates velocity of the pointer from its recent positions, e.g. to keep
+/// scrolling after a drag was released.
+#[derive(Debug, Clone, Default)]
+pub struct VelocityTracker {
+    samples: VecDeque<(Instant, Point)>,
+}
+
+impl VelocityTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that the pointer was at `pos` at `time`.
+    pub fn add_position(&mut self, time: Instant, pos: Point) {
+        self.samples.push_back((time, pos));
+
+        while self.samples.len() > MAX_VELOCITY_SAMPLES
+            || self
+                .samples
+                .front()
+                .is_some_and(|(t, _)| time.saturating_duration_since(*t) > VELOCITY_HORIZON)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Velocity of the pointer in pixels per second as of `now`, fitted to the
+    /// recent positions with least squares.
+    ///
+    /// Velocity is zero if there are too few positions, or if the pointer
+    /// didn't move for a while before `now`.
+    pub fn velocity(&self, now: Instant) -> Vec2 {
+        let Some(&(last_time, _)) = self.samples.back() else {
+            return Vec2::ZERO;
+        };
+
+        if now.saturating_duration_since(last_time) > ASSUME_STOPPED || self.samples.len() < 2 {
+            return Vec2::ZERO;
+        }
+
+        // Seconds before the last sample.
+        let time = |t: Instant| -(last_time.duration_since(t).as_secs_f64());
+
+        let n = self.samples.len() as f64;
+        let mean_t = self.samples.iter().map(|(t, _)| time(*t)).sum::<f64>() / n;
+        let mean_pos = self
+            .samples
+            .iter()
+            .fold(Vec2::ZERO, |sum, (_, p)| sum + p.to_vec2())
+            / n;
+
+        let (mut covariance, mut variance) = (Vec2::ZERO, 0.);
+
+        for (t, pos) in &self.samples {
+            let dt = time(*t) - mean_t;
+
+            covariance += (pos.to_vec2() - mean_pos) * dt;
+            variance += dt * dt;
+        }
+
+        if variance < 1e-12 {
+            return Vec2::ZERO;
+        }
+
+        covariance / variance
+    }
+
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+}
+
+/// Progress of a drag won by a [`DragGestureRecognizer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DragEvent {
+    /// Pointer moved by `delta`.
+    Update { delta: Vec2 },
+    /// Pointer was released, moving at `velocity` in pixels per second.
+    End { velocity: Vec2 },
+}
+
+/// Recognizes drags: presses moved further than [`GESTURE_SLOP`].
+#[derive(Debug, Default)]
+pub struct DragGestureRecognizer {
+    tracked: Option<TrackedDrag>,
+}
+
+#[derive(Debug)]
+struct TrackedDrag {
+    entry: GestureArenaEntry,
+    last_pos: Point,
+    /// Movement since the press, until the drag was claimed.
+    pending: Vec2,
+    velocity: VelocityTracker,
+}
+
+impl DragGestureRecognizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts tracking a press at `pos`. Call this on pointer down within the
+    /// widget, while the pointer down is being dispatched.
+    pub fn add_pointer(&mut self, pos: Point) {
+        let mut velocity = VelocityTracker::new();
+        velocity.add_position(Instant::now(), pos);
+
+        self.tracked = Some(TrackedDrag {
+            entry: GestureArenaEntry::enter(),
+            last_pos: pos,
+            pending: Vec2::ZERO,
+            velocity,
+        });
+    }
+
+    /// Returns how the drag which this recognizer won progressed with `event`.
+    ///
+    /// Drag is claimed once the pointer moves further than [`GESTURE_SLOP`],
+    /// at which point the whole movement since the press is returned.
+    pub fn handle_event(&mut self, event: &Event) -> Option<DragEvent> {
+        let drag = self.tracked.as_mut()?;
+        let now = Instant::now();
+
+        match event {
+            Event::MouseMove(e) => {
+                let delta = e.pos - drag.last_pos;
+                drag.last_pos = e.pos;
+                drag.velocity.add_position(now, e.pos);
+
+                match drag.entry.state() {
+                    GestureState::Accepted => Some(DragEvent::Update { delta }),
+                    GestureState::Possible => {
+                        drag.pending += delta;
+
+                        if drag.pending.hypot() > GESTURE_SLOP {
+                            drag.entry.accept();
+                        }
+
+                        match drag.entry.state() {
+                            GestureState::Accepted => Some(DragEvent::Update {
+                                delta: std::mem::take(&mut drag.pending),
+                            }),
+                            _ => None,
+                        }
+                    }
+                    GestureState::Rejected => {
+                        self.tracked = None;
+                        None
+                    }
+                }
+            }
+            Event::MouseUp(_) => {
+                let drag = self.tracked.take()?;
+
+                if drag.entry.state() == GestureState::Accepted {
+                    Some(DragEvent::End {
+                        velocity: drag.velocity.velocity(now),
+                    })
+                } else {
+                    drag.entry.reject();
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a drag won by this recognizer is in progress.
+    pub fn is_dragging(&self) -> bool {
+        self.tracked
+            .as_ref()
+            .is_some_and(|drag| drag.entry.state() == GestureState::Accepted)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use druid_shell::{Modifiers, MouseButton, MouseButtons, MouseEvent};
+
+    use super::*;
+
+    fn mouse_event(pos: Point) -> MouseEvent {
+        MouseEvent {
+            pos,
+            buttons: MouseButtons::new(),
+            mods: Modifiers::empty(),
+            count: 1,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        }
+    }
+
+    /// Presses at the origin, moves the pointer by `movement` and releases it
+    /// over a tap recognizer inside of a drag recognizer, which receives events
+    /// first. Returns whether a tap was recognized and the dragged distance.
+    fn tap_inside_drag(movement: Vec2) -> (bool, Vec2) {
+        let mut drag = DragGestureRecognizer::new();
+        let mut tap = TapGestureRecognizer::new();
+
+        let (mut tapped, mut dragged) = (false, Vec2::ZERO);
+
+        let mut dispatch = |event: Event| {
+            before_dispatch(&event);
+
+            if let Event::MouseDown(e) = &event {
+                drag.add_pointer(e.pos);
+                tap.add_pointer(e.pos);
+            }
+
+            if let Some(DragEvent::Update { delta }) = drag.handle_event(&event) {
+                dragged += delta;
+            }
+            tapped |= tap.handle_event(&event);
+
+            after_dispatch(&event);
+        };
+
+        dispatch(Event::MouseDown(mouse_event(Point::ZERO)));
+        dispatch(Event::MouseMove(mouse_event((movement / 2.).to_point())));
+        dispatch(Event::MouseMove(mouse_event(movement.to_point())));
+        dispatch(Event::MouseUp(mouse_event(movement.to_point())));
+
+        (tapped, dragged)
+    }
+
+    #[test]
+    fn tap_inside_scrollable_is_chosen_by_movement() {
+        // Small movement is a tap.
+        assert_eq!(tap_inside_drag(Vec2::new(0., 3.)), (true, Vec2::ZERO));
+
+        // Larger one is a drag, of the whole distance.
+        assert_eq!(
+            tap_inside_drag(Vec2::new(0., 30.)),
+            (false, Vec2::new(0., 30.))
+        );
+    }
+
+    #[test]
+    fn velocity_is_estimated_from_recent_positions() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        let mut tracker = VelocityTracker::new();
+
+        // Old samples moving the other way are outside of the horizon.
+        tracker.add_position(at(0), Point::new(500., 0.));
+
+        for n in 1..=10 {
+            tracker.add_position(at(200 + n * 10), Point::new(n as f64 * 10., 0.));
+        }
+
+        let velocity = tracker.velocity(at(300));
+        assert!((velocity.x - 1000.).abs() < 1e-6, "{velocity:?}");
+        assert!(velocity.y.abs() < 1e-6);
+
+        // Pointer stopped before its release.
+        assert_eq!(tracker.velocity(at(400)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn exactly_one_member_wins() {
+        let event = Event::MouseDown(mouse_event(Point::ZERO));
+
+        // Lone member wins once pointer down was dispatched.
+        before_dispatch(&event);
+        let alone = GestureArenaEntry::enter();
+        assert_eq!(alone.state(), GestureState::Possible);
+        after_dispatch(&event);
+        assert_eq!(alone.state(), GestureState::Accepted);
+
+        // New gesture, last member standing wins.
+        before_dispatch(&event);
+        let (first, second, third) = (
+            GestureArenaEntry::enter(),
+            GestureArenaEntry::enter(),
+            GestureArenaEntry::enter(),
+        );
+        after_dispatch(&event);
+        first.reject();
+        assert_eq!(second.state(), GestureState::Possible);
+        third.reject();
+        assert_eq!(second.state(), GestureState::Accepted);
+
+        // Once claimed, the arena can't be claimed again.
+        first.accept();
+        assert_eq!(first.state(), GestureState::Rejected);
+
+        // Unresolved arena is won by the last member once swept.
+        before_dispatch(&event);
+        let (outer, inner) = (GestureArenaEntry::enter(), GestureArenaEntry::enter());
+        after_dispatch(&event);
+        after_dispatch(&Event::MouseUp(mouse_event(Point::ZERO)));
+        assert_eq!(outer.state(), GestureState::Rejected);
+        assert_eq!(inner.state(), GestureState::Accepted);
+
+        // Entries outside of pointer down are rejected.
+        assert_eq!(GestureArenaEntry::enter().state(), GestureState::Rejected);
+    }
+}
@@ -13,14 +13,15 @@ mod sealed {
         api::{
             contexts::{
                 build_ctx::WidgetStateOS,
-                render_ctx::{AnyRenderContext, RenderStateOS},
+                render_ctx::{AnyRenderContext, RenderStateOS, SkipConstraintsCheckOS},
             },
             events::WidgetEventOS,
             local_key::WidgetLocalKey,
+            notifications::WidgetNotificationOS,
             structural_eq::StructuralEqOS,
             AnyExt, IntoWidgetPtr, WidgetDebug, WidgetPtr, WidgetUniqueType,
         },
-        prelude::{Constraints, Offset, PaintContext, Size},
+        prelude::{Constraints, PaintContext, Size},
     };
 
     use super::InheritedWidget;
@@ -34,6 +35,8 @@ mod sealed {
         + WidgetUniqueType
         + WidgetDebug
         + WidgetEventOS
+        + SkipConstraintsCheckOS
+        + WidgetNotificationOS
         + AnyExt
     {
         fn inherited_key(&self) -> TypeId;
@@ -42,12 +45,7 @@ mod sealed {
 
         fn layout<'w>(&self, ctx: &'w mut AnyRenderContext, constraints: Constraints) -> Size;
 
-        fn paint<'w>(
-            &'w self,
-            ctx: &'w mut AnyRenderContext,
-            canvas: &mut PaintContext,
-            offset: &Offset,
-        );
+        fn paint<'w>(&'w self, ctx: &'w mut AnyRenderContext, canvas: &mut PaintContext);
     }
 
     impl<T: InheritedWidget> InheritedWidgetOS for T {
@@ -63,13 +61,8 @@ mod sealed {
             ctx.child().layout(constraints)
         }
 
-        fn paint<'w>(
-            &'w self,
-            ctx: &'w mut AnyRenderContext,
-            canvas: &mut PaintContext,
-            offset: &Offset,
-        ) {
-            ctx.child().paint(canvas, offset)
+        fn paint<'w>(&'w self, ctx: &'w mut AnyRenderContext, canvas: &mut PaintContext) {
+            ctx.child().paint(canvas)
         }
     }
 }
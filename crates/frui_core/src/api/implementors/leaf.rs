@@ -1,9 +1,9 @@
-use crate::prelude::{Constraints, Offset, PaintContext, RenderContext, Size};
+use crate::prelude::{Constraints, PaintContext, RenderContext, Size};
 
 pub trait LeafWidget: WidgetDerive + Sized {
     fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size;
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, offset: &Offset);
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext);
 }
 
 pub(crate) use sealed::LeafWidgetOS;
@@ -15,14 +15,17 @@ mod sealed {
         api::{
             contexts::{
                 build_ctx::WidgetStateOS,
-                render_ctx::{AnyRenderContext, RenderStateOS, _RenderContext},
+                render_ctx::{
+                    _RenderContext, AnyRenderContext, RenderStateOS, SkipConstraintsCheckOS,
+                },
             },
             events::WidgetEventOS,
             local_key::WidgetLocalKey,
+            notifications::WidgetNotificationOS,
             structural_eq::StructuralEqOS,
             AnyExt, WidgetDebug, WidgetUniqueType,
         },
-        prelude::{Constraints, Offset, PaintContext, Size},
+        prelude::{Constraints, PaintContext, Size},
     };
 
     /// `OS` stands for "object safe".
@@ -34,16 +37,13 @@ mod sealed {
         + WidgetUniqueType
         + WidgetDebug
         + WidgetEventOS
+        + SkipConstraintsCheckOS
+        + WidgetNotificationOS
         + AnyExt
     {
         fn layout<'a>(&self, ctx: &'a mut AnyRenderContext, constraints: Constraints) -> Size;
 
-        fn paint<'a>(
-            &self,
-            ctx: &'a mut AnyRenderContext,
-            canvas: &mut PaintContext,
-            offset: &Offset,
-        );
+        fn paint<'a>(&self, ctx: &'a mut AnyRenderContext, canvas: &mut PaintContext);
     }
 
     impl<T: super::LeafWidget> LeafWidgetOS for T {
@@ -53,15 +53,10 @@ mod sealed {
             T::layout(&self, ctx, constraints)
         }
 
-        fn paint<'a>(
-            &self,
-            ctx: &'a mut AnyRenderContext,
-            canvas: &mut PaintContext,
-            offset: &Offset,
-        ) {
+        fn paint<'a>(&self, ctx: &'a mut AnyRenderContext, canvas: &mut PaintContext) {
             let ctx = &mut <_RenderContext<T>>::new(ctx);
 
-            T::paint(&self, ctx, canvas, offset)
+            T::paint(&self, ctx, canvas)
         }
     }
 }
@@ -1,6 +1,6 @@
 use crate::{
     api::contexts::build_ctx::BuildContext,
-    prelude::{Constraints, Offset, PaintContext, RenderContext, Size},
+    prelude::{Constraints, PaintContext, RenderContext, Size},
 };
 
 pub trait MultiChildWidget: WidgetDerive + Sized {
@@ -8,7 +8,7 @@ pub trait MultiChildWidget: WidgetDerive + Sized {
 
     fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size;
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, offset: &Offset);
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext);
 }
 
 pub(crate) use sealed::MultiChildWidgetOS;
@@ -19,16 +19,19 @@ mod sealed {
     use crate::{
         api::{
             contexts::{
-                build_ctx::{WidgetStateOS, _BuildContext},
-                render_ctx::{AnyRenderContext, RenderStateOS, _RenderContext},
+                build_ctx::{_BuildContext, WidgetStateOS},
+                render_ctx::{
+                    _RenderContext, AnyRenderContext, RenderStateOS, SkipConstraintsCheckOS,
+                },
                 Context,
             },
             events::WidgetEventOS,
             local_key::WidgetLocalKey,
+            notifications::WidgetNotificationOS,
             structural_eq::StructuralEqOS,
             AnyExt, IntoWidgetPtr, WidgetDebug, WidgetPtr, WidgetUniqueType,
         },
-        prelude::{Constraints, Offset, PaintContext, Size},
+        prelude::{Constraints, PaintContext, Size},
     };
 
     /// `OS` stands for "object safe".
@@ -40,18 +43,15 @@ mod sealed {
         + WidgetUniqueType
         + WidgetDebug
         + WidgetEventOS
+        + SkipConstraintsCheckOS
+        + WidgetNotificationOS
         + AnyExt
     {
         fn build<'w>(&'w self, ctx: &'w Context) -> Vec<WidgetPtr<'w>>;
 
         fn layout<'w>(&self, ctx: &'w mut AnyRenderContext, constraints: Constraints) -> Size;
 
-        fn paint<'w>(
-            &self,
-            ctx: &'w mut AnyRenderContext,
-            canvas: &mut PaintContext,
-            offset: &Offset,
-        );
+        fn paint<'w>(&self, ctx: &'w mut AnyRenderContext, canvas: &mut PaintContext);
     }
 
     impl<T: super::MultiChildWidget> MultiChildWidgetOS for T {
@@ -70,15 +70,10 @@ mod sealed {
             T::layout(&self, ctx, constraints)
         }
 
-        fn paint<'w>(
-            &self,
-            ctx: &'w mut AnyRenderContext,
-            canvas: &mut PaintContext,
-            offset: &Offset,
-        ) {
+        fn paint<'w>(&self, ctx: &'w mut AnyRenderContext, canvas: &mut PaintContext) {
             let ctx = &mut <_RenderContext<T>>::new(ctx);
 
-            T::paint(&self, ctx, canvas, offset);
+            T::paint(&self, ctx, canvas);
         }
     }
 }
@@ -1,6 +1,6 @@
 use crate::{
     api::contexts::build_ctx::BuildContext,
-    prelude::{Constraints, Offset, PaintContext, RenderContext, Size},
+    prelude::{Constraints, PaintContext, RenderContext, Size},
 };
 
 pub trait SingleChildWidget: WidgetDerive + Sized {
@@ -10,8 +10,8 @@ pub trait SingleChildWidget: WidgetDerive + Sized {
         ctx.child().layout(constraints)
     }
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, offset: &Offset) {
-        ctx.child().paint(canvas, offset)
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
     }
 }
 
@@ -23,16 +23,19 @@ mod sealed {
     use crate::{
         api::{
             contexts::{
-                build_ctx::{WidgetStateOS, _BuildContext},
-                render_ctx::{AnyRenderContext, RenderStateOS, _RenderContext},
+                build_ctx::{_BuildContext, WidgetStateOS},
+                render_ctx::{
+                    _RenderContext, AnyRenderContext, RenderStateOS, SkipConstraintsCheckOS,
+                },
                 Context,
             },
             events::WidgetEventOS,
             local_key::WidgetLocalKey,
+            notifications::WidgetNotificationOS,
             structural_eq::StructuralEqOS,
             AnyExt, IntoWidgetPtr, WidgetDebug, WidgetPtr, WidgetUniqueType,
         },
-        prelude::{Constraints, Offset, PaintContext, Size},
+        prelude::{Constraints, PaintContext, Size},
     };
 
     /// `OS` stands for "object safe".
@@ -44,18 +47,15 @@ mod sealed {
         + WidgetUniqueType
         + WidgetDebug
         + WidgetEventOS
+        + SkipConstraintsCheckOS
+        + WidgetNotificationOS
         + AnyExt
     {
         fn build<'w>(&'w self, ctx: &'w Context) -> WidgetPtr<'w>;
 
         fn layout<'w>(&self, ctx: &'w mut AnyRenderContext, constraints: Constraints) -> Size;
 
-        fn paint<'w>(
-            &self,
-            ctx: &'w mut AnyRenderContext,
-            canvas: &mut PaintContext,
-            offset: &Offset,
-        );
+        fn paint<'w>(&self, ctx: &'w mut AnyRenderContext, canvas: &mut PaintContext);
     }
 
     impl<T: super::SingleChildWidget> SingleChildWidgetOS for T {
@@ -71,15 +71,10 @@ mod sealed {
             T::layout(&self, ctx, constraints)
         }
 
-        fn paint<'w>(
-            &self,
-            ctx: &'w mut AnyRenderContext,
-            canvas: &mut PaintContext,
-            offset: &Offset,
-        ) {
+        fn paint<'w>(&self, ctx: &'w mut AnyRenderContext, canvas: &mut PaintContext) {
             let ctx = &mut <_RenderContext<T>>::new(ctx);
 
-            T::paint(&self, ctx, canvas, offset);
+            T::paint(&self, ctx, canvas);
         }
     }
 }
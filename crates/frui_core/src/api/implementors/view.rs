@@ -12,16 +12,17 @@ mod sealed {
     use crate::{
         api::{
             contexts::{
-                build_ctx::{WidgetStateOS, _BuildContext},
-                render_ctx::AnyRenderContext,
+                build_ctx::{_BuildContext, WidgetStateOS},
+                render_ctx::{AnyRenderContext, SkipConstraintsCheckOS},
                 Context,
             },
             events::WidgetEventOS,
             local_key::WidgetLocalKey,
+            notifications::WidgetNotificationOS,
             structural_eq::StructuralEqOS,
             AnyExt, IntoWidgetPtr, WidgetDebug, WidgetPtr, WidgetUniqueType,
         },
-        prelude::{Constraints, Offset, PaintContext, Size},
+        prelude::{Constraints, PaintContext, Size},
     };
 
     /// `OS` stands for "object safe".
@@ -32,18 +33,15 @@ mod sealed {
         + WidgetUniqueType
         + WidgetDebug
         + WidgetEventOS
+        + SkipConstraintsCheckOS
+        + WidgetNotificationOS
         + AnyExt
     {
         fn build<'w>(&'w self, ctx: &'w Context) -> WidgetPtr<'w>;
 
         fn layout<'w>(&self, ctx: &'w mut AnyRenderContext, constraints: Constraints) -> Size;
 
-        fn paint<'w>(
-            &'w self,
-            ctx: &'w mut AnyRenderContext,
-            canvas: &mut PaintContext,
-            offset: &Offset,
-        );
+        fn paint<'w>(&'w self, ctx: &'w mut AnyRenderContext, canvas: &mut PaintContext);
     }
 
     impl<T: super::ViewWidget> ViewWidgetOS for T {
@@ -57,13 +55,8 @@ mod sealed {
             ctx.child().layout(constraints)
         }
 
-        fn paint<'w>(
-            &'w self,
-            ctx: &'w mut AnyRenderContext,
-            canvas: &mut PaintContext,
-            offset: &Offset,
-        ) {
-            ctx.child().paint(canvas, offset)
+        fn paint<'w>(&'w self, ctx: &'w mut AnyRenderContext, canvas: &mut PaintContext) {
+            ctx.child().paint(canvas)
         }
     }
 }
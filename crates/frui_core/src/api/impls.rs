@@ -1,12 +1,20 @@
-use std::ops::Deref;
+use std::{ops::Deref, rc::Rc};
 
 use crate::{
-    api::{implementors::leaf::LeafWidget, Widget, WidgetKind},
+    api::{implementors::leaf::LeafWidget, local_key::Key, Widget, WidgetKind},
+    macro_exports::StructuralEqImpl,
     prelude::*,
 };
 
-use super::implementors::WidgetDerive;
+use super::{implementors::WidgetDerive, AnyExt};
 
+/// Type erases widgets, so that widgets of different types can be stored
+/// together (e.g. in a `Vec<Box<dyn Widget>>` of children).
+///
+/// Boxed widget is reconciled by the type of the widget inside of it: it keeps
+/// its state while that type stays the same, and is replaced otherwise. Two
+/// boxed widgets are structurally equal if the widgets inside are of the same
+/// type and equal.
 pub trait BoxedWidget: Widget + Sized {
     /// Convenience method used to type erase and box a widget.
     fn boxed<'a>(self) -> Box<dyn Widget + 'a>
@@ -33,6 +41,40 @@ impl<T: Widget> BoxedWidget for Box<T> {
     }
 }
 
+unsafe impl<'a> StructuralEqImpl for Box<dyn Widget + 'a> {
+    const EQ_ENABLED: bool = false;
+
+    fn eq(&self, other: &Self) -> bool {
+        eq_dyn(&**self, &**other)
+    }
+}
+
+/// Compares widgets of unknown types, which are equal only if their types are.
+fn eq_dyn(a: &dyn Widget, b: &dyn Widget) -> bool {
+    fn same_type(a: &dyn AnyExt, b: &dyn AnyExt) -> bool {
+        AnyExt::type_id(a) == AnyExt::type_id(b)
+    }
+
+    match (a.kind(), b.kind()) {
+        (WidgetKind::View(a), WidgetKind::View(b)) => {
+            same_type(a.as_any_ext(), b.as_any_ext()) && a.eq(b.as_any_ext())
+        }
+        (WidgetKind::Inherited(a), WidgetKind::Inherited(b)) => {
+            same_type(a.as_any_ext(), b.as_any_ext()) && a.eq(b.as_any_ext())
+        }
+        (WidgetKind::Leaf(a), WidgetKind::Leaf(b)) => {
+            same_type(a.as_any_ext(), b.as_any_ext()) && a.eq(b.as_any_ext())
+        }
+        (WidgetKind::SingleChild(a), WidgetKind::SingleChild(b)) => {
+            same_type(a.as_any_ext(), b.as_any_ext()) && a.eq(b.as_any_ext())
+        }
+        (WidgetKind::MultiChild(a), WidgetKind::MultiChild(b)) => {
+            same_type(a.as_any_ext(), b.as_any_ext()) && a.eq(b.as_any_ext())
+        }
+        _ => false,
+    }
+}
+
 //
 // Implementations
 //
@@ -45,6 +87,10 @@ impl<T: Widget> Widget for &T {
     fn kind(&self) -> WidgetKind {
         T::kind(&self)
     }
+
+    fn key(&self) -> Option<Key<'_>> {
+        T::key(self)
+    }
 }
 
 impl<T: Widget> Widget for &mut T {
@@ -55,6 +101,10 @@ impl<T: Widget> Widget for &mut T {
     fn kind(&self) -> WidgetKind {
         T::kind(&self)
     }
+
+    fn key(&self) -> Option<Key<'_>> {
+        T::key(self)
+    }
 }
 
 impl<'a> Widget for &'a dyn Widget {
@@ -65,6 +115,10 @@ impl<'a> Widget for &'a dyn Widget {
     fn kind(&self) -> WidgetKind {
         self.deref().kind()
     }
+
+    fn key(&self) -> Option<Key<'_>> {
+        (**self).key()
+    }
 }
 
 impl<'a> Widget for Box<dyn Widget + 'a> {
@@ -75,6 +129,10 @@ impl<'a> Widget for Box<dyn Widget + 'a> {
     fn kind(&self) -> WidgetKind {
         self.deref().kind()
     }
+
+    fn key(&self) -> Option<Key<'_>> {
+        self.deref().key()
+    }
 }
 
 impl<T: Widget> Widget for Box<T> {
@@ -85,6 +143,38 @@ impl<T: Widget> Widget for Box<T> {
     fn kind(&self) -> WidgetKind {
         self.deref().kind()
     }
+
+    fn key(&self) -> Option<Key<'_>> {
+        self.deref().key()
+    }
+}
+
+impl<'a> Widget for Rc<dyn Widget + 'a> {
+    fn unique_type(&self) -> std::any::TypeId {
+        self.deref().unique_type()
+    }
+
+    fn kind(&self) -> WidgetKind {
+        self.deref().kind()
+    }
+
+    fn key(&self) -> Option<Key<'_>> {
+        self.deref().key()
+    }
+}
+
+impl<T: Widget> Widget for Rc<T> {
+    fn unique_type(&self) -> std::any::TypeId {
+        self.deref().unique_type()
+    }
+
+    fn kind(&self) -> WidgetKind {
+        self.deref().kind()
+    }
+
+    fn key(&self) -> Option<Key<'_>> {
+        self.deref().key()
+    }
 }
 
 impl Widget for () {
@@ -104,9 +194,64 @@ impl WidgetDerive for () {
 }
 
 impl LeafWidget for () {
-    fn layout(&self, _: RenderContext<Self>, _: Constraints) -> Size {
-        Size::default()
+    fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+        constraints.smallest()
+    }
+
+    fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+}
+
+#[cfg(test)]
+mod test {
+    use std::any::TypeId;
+
+    use super::*;
+    use crate::macro_exports::StructuralEq;
+
+    struct Label(u32);
+
+    impl Widget for Label {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Label>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::Leaf(self)
+        }
+    }
+
+    impl WidgetDerive for Label {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = Label;
     }
 
-    fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext, _: &Offset) {}
+    unsafe impl StructuralEqImpl for Label {
+        const EQ_ENABLED: bool = true;
+
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl LeafWidget for Label {
+        fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+            constraints.smallest()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    #[test]
+    fn boxed_widgets_are_equal_if_inner_widgets_are() {
+        let eq = |a: Box<dyn Widget>, b: Box<dyn Widget>| StructuralEq::eq(&a, &b);
+
+        assert!(eq(Label(1).boxed(), Label(1).boxed()));
+        assert!(!eq(Label(1).boxed(), Label(2).boxed()));
+        assert!(eq(().boxed(), ().boxed()));
+
+        // Widgets of different types are never equal.
+        assert!(!eq(Label(0).boxed(), ().boxed()));
+        assert!(!eq(().boxed(), Label(0).boxed()));
+    }
 }
@@ -0,0 +1,222 @@
+//! Introspection of layers created during paint.
+//!
+//! In debug builds, every clip, opacity layer and transform applied through
+//! [`PaintContextExt`](super::paint::PaintContextExt) is recorded along with
+//! the widget which painted it, so that the layers of the last painted frame
+//! can be dumped with [`dump_layers`].
+
+use std::{cell::RefCell, fmt::Write};
+
+use druid_shell::kurbo::{Affine, Rect};
+
+/// Painter of layers created outside of widget paint methods.
+const UNKNOWN_PAINTER: &str = "<unknown>";
+
+thread_local! {
+    static RECORDER: RefCell<Recorder> = RefCell::new(Recorder::default());
+}
+
+/// Kind of a layer created during paint.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LayerKind {
+    /// Paint clipped to the bounds of the layer.
+    Clip,
+    /// Paint composited onto its parent with the given opacity.
+    Opacity(f64),
+    /// Paint transformed with the given transform.
+    Transform(Affine),
+    /// Paint whose pixels were filtered before it was composited onto its
+    /// parent.
+    Filter,
+}
+
+#[derive(Debug, Clone)]
+struct Layer {
+    kind: LayerKind,
+    /// Short name of the widget which created this layer.
+    painter: &'static str,
+    /// Bounds in the coordinates of the parent layer.
+    bounds: Rect,
+    /// Transform of the canvas once this layer was created.
+    transform: Affine,
+    parent: Option<usize>,
+}
+
+#[derive(Default)]
+struct Recorder {
+    /// Layers of the frame being painted, in paint order.
+    layers: Vec<Layer>,
+    /// Indices of layers which weren't closed yet.
+    open: Vec<usize>,
+    /// Layers of the last painted frame.
+    last_frame: Vec<Layer>,
+    /// Widget being painted, along with its bounds.
+    painter: Option<(&'static str, Rect)>,
+}
+
+/// Records layers created during `paint` as the layers of a new frame.
+pub(crate) fn record_frame(paint: impl FnOnce()) {
+    if !cfg!(debug_assertions) {
+        return paint();
+    }
+
+    RECORDER.with(|r| {
+        let mut r = r.borrow_mut();
+        r.layers.clear();
+        r.open.clear();
+    });
+
+    paint();
+
+    RECORDER.with(|r| {
+        let r = &mut *r.borrow_mut();
+        std::mem::swap(&mut r.layers, &mut r.last_frame);
+    });
+}
+
+/// Runs `paint`, attributing layers created during it to the widget `name`
+/// painted within `bounds`.
+pub(crate) fn with_painter<R>(name: &'static str, bounds: Rect, paint: impl FnOnce() -> R) -> R {
+    if !cfg!(debug_assertions) {
+        return paint();
+    }
+
+    let previous = RECORDER.with(|r| r.borrow_mut().painter.replace((name, bounds)));
+    let r = paint();
+    RECORDER.with(|r| r.borrow_mut().painter = previous);
+    r
+}
+
+/// Records a layer of `kind`, which stays open (i.e. parent of layers created
+/// after it) until the returned guard is dropped.
+///
+/// Layers without their own bounds (like transforms) use `None`, in which case
+/// the bounds of the widget being painted are used.
+pub(crate) fn push(kind: LayerKind, bounds: Option<Rect>, transform: Affine) -> LayerGuard {
+    if !cfg!(debug_assertions) {
+        return LayerGuard { recorded: false };
+    }
+
+    RECORDER.with(|r| {
+        let r = &mut *r.borrow_mut();
+
+        let (painter, painter_bounds) = r.painter.unwrap_or((UNKNOWN_PAINTER, Rect::ZERO));
+
+        r.layers.push(Layer {
+            kind,
+            painter,
+            bounds: bounds.unwrap_or(painter_bounds),
+            transform,
+            parent: r.open.last().copied(),
+        });
+        r.open.push(r.layers.len() - 1);
+    });
+
+    LayerGuard { recorded: true }
+}
+
+/// Closes the layer which created it once dropped.
+pub(crate) struct LayerGuard {
+    recorded: bool,
+}
+
+impl Drop for LayerGuard {
+    fn drop(&mut self) {
+        if self.recorded {
+            RECORDER.with(|r| r.borrow_mut().open.pop());
+        }
+    }
+}
+
+/// Describes layers created while painting the last frame, in paint order.
+///
+/// Each layer is listed with its index, kind, the widget which created it, its
+/// bounds (in the coordinates of its parent) and the transform of the canvas
+/// it was painted with. Children are indented under their parent layer.
+///
+/// ```text
+/// #0 clip by `Scroll` bounds (0.0, 0.0)-(800.0, 600.0) transform [..]
+///   #1 opacity 0.5 by `Opacity` bounds (0.0, 0.0)-(100.0, 40.0) transform [..]
+/// ```
+///
+/// Returns an empty string in release builds, where layers aren't recorded.
+pub fn dump_layers() -> String {
+    RECORDER.with(|r| describe(&r.borrow().last_frame))
+}
+
+fn describe(layers: &[Layer]) -> String {
+    let mut depths = Vec::with_capacity(layers.len());
+    let mut dump = String::new();
+
+    for (n, layer) in layers.iter().enumerate() {
+        let depth = match layer.parent {
+            Some(parent) => depths[parent] + 1,
+            None => 0,
+        };
+        depths.push(depth);
+
+        let kind = match layer.kind {
+            LayerKind::Clip => "clip".to_string(),
+            LayerKind::Opacity(opacity) => format!("opacity {opacity}"),
+            LayerKind::Transform(transform) => format!("transform {:?}", transform.as_coeffs()),
+            LayerKind::Filter => "filter".to_string(),
+        };
+
+        let Rect { x0, y0, x1, y1 } = layer.bounds;
+
+        let _ = writeln!(
+            dump,
+            "{:indent$}#{n} {kind} by `{}` bounds ({x0:?}, {y0:?})-({x1:?}, {y1:?}) \
+            transform {:?}",
+            "",
+            layer.painter,
+            layer.transform.as_coeffs(),
+            indent = depth * 2,
+        );
+    }
+
+    dump
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn layers_are_dumped_in_paint_order_under_their_parents() {
+        let bounds = Rect::new(0., 0., 100., 50.);
+
+        record_frame(|| {
+            with_painter("Scroll", bounds, || {
+                let _clip = push(LayerKind::Clip, Some(bounds), Affine::default());
+
+                with_painter("Transform", bounds, || {
+                    let _transform = push(
+                        LayerKind::Transform(Affine::scale(2.)),
+                        None,
+                        Affine::scale(2.),
+                    );
+                });
+
+                let _opacity = push(LayerKind::Opacity(0.5), Some(bounds), Affine::default());
+            });
+        });
+
+        let identity = "[1.0, 0.0, 0.0, 1.0, 0.0, 0.0]";
+        let scale = "[2.0, 0.0, 0.0, 2.0, 0.0, 0.0]";
+        let bounds = "bounds (0.0, 0.0)-(100.0, 50.0)";
+
+        assert_eq!(
+            dump_layers(),
+            format!(
+                "#0 clip by `Scroll` {bounds} transform {identity}\n  \
+                #1 transform {scale} by `Transform` {bounds} transform {scale}\n  \
+                #2 opacity 0.5 by `Scroll` {bounds} transform {identity}\n"
+            )
+        );
+
+        // Next frame replaces layers of the previous one.
+        record_frame(|| {});
+        assert_eq!(dump_layers(), "");
+    }
+}
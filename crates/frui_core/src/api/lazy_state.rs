@@ -0,0 +1,107 @@
+use once_cell::unsync::OnceCell;
+
+/// State of an [`InheritedWidget`](crate::prelude::InheritedWidget) whose
+/// value is expensive to compute and read only by some of its dependents.
+///
+/// Value is computed from `inputs` on first [`get`](Self::get) and memoized
+/// until the inputs change, so every dependent reads the same value until
+/// then. Changing the inputs through
+/// [`InheritedState::as_mut`](crate::prelude::InheritedState::as_mut)
+/// rebuilds dependents, which then recompute the value on the next access.
+///
+/// ```
+/// # #![feature(type_alias_impl_trait)]
+/// # extern crate frui_core as frui;
+/// # use frui::prelude::*;
+/// # struct Palette;
+/// # fn derive_palette(_: &Color) -> Palette { Palette }
+/// #[derive(InheritedWidget)]
+/// struct Theme<W: Widget> {
+///     primary: Color,
+///     child: W,
+/// }
+///
+/// impl<W: Widget> WidgetState for Theme<W> {
+///     type State = LazyState<Color, Palette>;
+///
+///     fn create_state(&self) -> Self::State {
+///         LazyState::new(self.primary.clone(), derive_palette)
+///     }
+/// }
+/// # impl<W: Widget> InheritedWidget for Theme<W> {
+/// #     fn child<'w>(&'w self) -> &'w Self::Widget<'w> {
+/// #         &self.child
+/// #     }
+/// # }
+/// ```
+pub struct LazyState<I, T> {
+    inputs: I,
+    compute: Box<dyn Fn(&I) -> T>,
+    value: OnceCell<T>,
+}
+
+impl<I, T> LazyState<I, T> {
+    pub fn new(inputs: I, compute: impl Fn(&I) -> T + 'static) -> Self {
+        Self {
+            inputs,
+            compute: Box::new(compute),
+            value: OnceCell::new(),
+        }
+    }
+
+    /// Value computed from the current inputs, computing it if it wasn't
+    /// computed since the inputs last changed.
+    pub fn get(&self) -> &T {
+        self.value.get_or_init(|| (self.compute)(&self.inputs))
+    }
+
+    pub fn inputs(&self) -> &I {
+        &self.inputs
+    }
+
+    /// Replaces the inputs, discarding the memoized value.
+    pub fn set_inputs(&mut self, inputs: I) {
+        self.inputs = inputs;
+        self.value = OnceCell::new();
+    }
+
+    /// Updates the inputs in place, discarding the memoized value.
+    pub fn update_inputs(&mut self, f: impl FnOnce(&mut I)) {
+        f(&mut self.inputs);
+        self.value = OnceCell::new();
+    }
+
+    /// Whether the value for the current inputs was already computed.
+    pub fn is_computed(&self) -> bool {
+        self.value.get().is_some()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    #[test]
+    fn value_is_computed_once_per_inputs() {
+        let computed = Rc::new(Cell::new(0));
+
+        let mut state = LazyState::new(2, {
+            let computed = computed.clone();
+            move |n: &i32| {
+                computed.set(computed.get() + 1);
+                n * n
+            }
+        });
+
+        assert!(!state.is_computed());
+        assert_eq!((*state.get(), *state.get()), (4, 4));
+        assert_eq!(computed.get(), 1);
+
+        state.set_inputs(3);
+        assert!(!state.is_computed());
+        assert_eq!(*state.get(), 9);
+        assert_eq!(computed.get(), 2);
+    }
+}
@@ -0,0 +1,161 @@
+//! State of the application as a whole, derived from the focus of its windows.
+//!
+//! While the application isn't [`Resumed`](AppLifecycleState::Resumed), the
+//! clock animations are driven by ([`animation_now`]) is stopped and frames
+//! requested during paint (i.e. next frames of animations) are deferred until
+//! it is resumed. Animations then continue from where they stopped.
+
+use std::{
+    cell::{Cell, RefCell},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    api::listenable::ValueNotifier,
+    app::runner::handler::{schedule_frame, WindowId},
+};
+
+/// State of the application, see [`app_lifecycle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AppLifecycleState {
+    /// One of the windows of the application is focused.
+    #[default]
+    Resumed,
+    /// Windows of the application are visible, but none of them is focused.
+    Inactive,
+    /// No window of the application is visible, e.g. all of them were
+    /// minimized.
+    Paused,
+}
+
+thread_local! {
+    static LIFECYCLE: ValueNotifier<AppLifecycleState> = ValueNotifier::default();
+
+    /// State of each open window which received a focus or size event.
+    static WINDOWS: RefCell<Vec<(WindowId, AppLifecycleState)>> = const { RefCell::new(Vec::new()) };
+
+    static CLOCK: RefCell<AnimationClock> = const {
+        RefCell::new(AnimationClock {
+            stopped_at: None,
+            stopped_for: Duration::ZERO,
+        })
+    };
+
+    /// Whether a frame was requested during paint while the application wasn't
+    /// resumed.
+    static DEFERRED_FRAME: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Current state of the application, which notifies its listeners whenever it
+/// changes.
+///
+/// Widgets can observe it by adding their
+/// [`listener`](crate::api::contexts::build_ctx::_BuildContext::listener) to
+/// it, e.g. with `ListenableBuilder`, to be rebuilt once it changes.
+pub fn app_lifecycle() -> ValueNotifier<AppLifecycleState> {
+    LIFECYCLE.with(|lifecycle| lifecycle.clone())
+}
+
+/// Current time of the clock animations should be driven by.
+///
+/// It's [`Instant::now`], except that the clock stops while the application
+/// isn't [`Resumed`](AppLifecycleState::Resumed). Time which passed meanwhile
+/// is skipped, so that an animation doesn't jump ahead once it's resumed.
+pub fn animation_now() -> Instant {
+    CLOCK.with(|clock| clock.borrow().now())
+}
+
+/// Records `state` of the window `id`, or removes the window if it's `None`,
+/// updating the state of the application.
+pub(crate) fn set_window_state(id: WindowId, state: Option<AppLifecycleState>) {
+    let app_state = WINDOWS.with(|windows| {
+        let mut windows = windows.borrow_mut();
+        windows.retain(|(window, _)| *window != id);
+
+        if let Some(state) = state {
+            windows.push((id, state));
+        }
+
+        // Application is as active as its most active window.
+        windows
+            .iter()
+            .map(|(_, state)| *state)
+            .min_by_key(|state| *state as u8)
+            .unwrap_or_default()
+    });
+
+    set_state(app_state);
+}
+
+fn set_state(state: AppLifecycleState) {
+    CLOCK.with(|clock| {
+        let mut clock = clock.borrow_mut();
+
+        match state {
+            AppLifecycleState::Resumed => clock.start(),
+            _ => clock.stop(),
+        }
+    });
+
+    if state == AppLifecycleState::Resumed && DEFERRED_FRAME.with(|f| f.replace(false)) {
+        schedule_frame();
+    }
+
+    LIFECYCLE.with(|lifecycle| lifecycle.set(state));
+}
+
+/// Schedules a frame requested during paint, unless the application isn't
+/// resumed, in which case it's scheduled once it is.
+pub(crate) fn schedule_animation_frame() {
+    if LIFECYCLE.with(|lifecycle| lifecycle.get()) == AppLifecycleState::Resumed {
+        schedule_frame();
+    } else {
+        DEFERRED_FRAME.with(|f| f.set(true));
+    }
+}
+
+struct AnimationClock {
+    /// Real time at which the clock was stopped, if it is.
+    stopped_at: Option<Instant>,
+    /// Total time the clock was stopped for.
+    stopped_for: Duration,
+}
+
+impl AnimationClock {
+    fn now(&self) -> Instant {
+        self.stopped_at.unwrap_or_else(Instant::now) - self.stopped_for
+    }
+
+    fn stop(&mut self) {
+        self.stopped_at.get_or_insert_with(Instant::now);
+    }
+
+    fn start(&mut self) {
+        if let Some(stopped_at) = self.stopped_at.take() {
+            self.stopped_for += stopped_at.elapsed();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn animation_clock_stops_while_not_resumed() {
+        let mut clock = AnimationClock {
+            stopped_at: None,
+            stopped_for: Duration::ZERO,
+        };
+
+        clock.stop();
+        let stopped = clock.now();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(clock.now(), stopped);
+
+        // Time passed while stopped is skipped.
+        clock.start();
+        assert!(clock.now() - stopped < Duration::from_millis(5));
+        assert!(clock.now() >= stopped);
+    }
+}
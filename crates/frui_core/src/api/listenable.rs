@@ -0,0 +1,221 @@
+use std::{
+    cell::{Ref, RefCell},
+    fmt,
+    rc::Rc,
+};
+
+use crate::app::tree::WidgetNodeRef;
+
+/// Widget which is rebuilt whenever a [`Listenable`] it was added to notifies
+/// its listeners, obtained through `BuildContext::listener`.
+///
+/// Listener of a widget which was removed from the tree is ignored, but it
+/// should still be removed from its listenables (e.g. in `unmount`), so that
+/// they don't keep it forever.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Listener {
+    node: WidgetNodeRef,
+}
+
+impl Listener {
+    pub(crate) fn new(node: WidgetNodeRef) -> Self {
+        Self { node }
+    }
+
+    /// Schedules a rebuild of the widget of this listener.
+    pub fn notify(&self) {
+        if self.node.is_alive() {
+            self.node.mark_dirty();
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.node.is_alive()
+    }
+}
+
+/// Object which rebuilds its listeners when it changes (see, for example,
+/// `ListenableBuilder` of `frui_widgets`).
+pub trait Listenable {
+    /// Adds `listener`, unless it was already added.
+    fn add_listener(&self, listener: Listener);
+
+    fn remove_listener(&self, listener: &Listener);
+}
+
+/// Listeners of a [`Listenable`], shared by its clones.
+#[derive(Clone, Default)]
+pub struct Listeners {
+    listeners: Rc<RefCell<Vec<Listener>>>,
+}
+
+impl Listeners {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `listener`, unless it was already added.
+    pub fn add(&self, listener: Listener) {
+        let mut listeners = self.listeners.borrow_mut();
+
+        if !listeners.contains(&listener) {
+            listeners.push(listener);
+        }
+    }
+
+    pub fn remove(&self, listener: &Listener) {
+        self.listeners.borrow_mut().retain(|l| l != listener);
+    }
+
+    /// Rebuilds every listener, forgetting the ones of removed widgets.
+    pub fn notify(&self) {
+        let mut listeners = self.listeners.borrow_mut();
+
+        listeners.retain(Listener::is_alive);
+
+        for listener in listeners.iter() {
+            listener.notify();
+        }
+    }
+
+    /// Removes every listener.
+    pub fn clear(&self) {
+        self.listeners.borrow_mut().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.listeners.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.listeners.borrow().is_empty()
+    }
+}
+
+impl fmt::Debug for Listeners {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Listeners")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl Listenable for Listeners {
+    fn add_listener(&self, listener: Listener) {
+        self.add(listener)
+    }
+
+    fn remove_listener(&self, listener: &Listener) {
+        self.remove(listener)
+    }
+}
+
+/// Value which notifies its listeners whenever it changes. Clones share the
+/// same value and listeners.
+#[derive(Clone, Default)]
+pub struct ValueNotifier<T> {
+    value: Rc<RefCell<T>>,
+    listeners: Listeners,
+}
+
+impl<T> ValueNotifier<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Rc::new(RefCell::new(value)),
+            listeners: Listeners::new(),
+        }
+    }
+
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.value.borrow().clone()
+    }
+
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.value.borrow()
+    }
+
+    /// Replaces the value, notifying listeners if it isn't equal to the
+    /// previous one.
+    pub fn set(&self, value: T)
+    where
+        T: PartialEq,
+    {
+        let changed = *self.value.borrow() != value;
+
+        if changed {
+            *self.value.borrow_mut() = value;
+            self.listeners.notify();
+        }
+    }
+
+    /// Changes the value in place, always notifying listeners.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.value.borrow_mut());
+        self.listeners.notify();
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ValueNotifier<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ValueNotifier")
+            .field(&*self.value.borrow())
+            .finish()
+    }
+}
+
+impl<T> Listenable for ValueNotifier<T> {
+    fn add_listener(&self, listener: Listener) {
+        self.listeners.add(listener)
+    }
+
+    fn remove_listener(&self, listener: &Listener) {
+        self.listeners.remove(listener)
+    }
+}
+
+impl<L: Listenable> Listenable for &L {
+    fn add_listener(&self, listener: Listener) {
+        (*self).add_listener(listener)
+    }
+
+    fn remove_listener(&self, listener: &Listener) {
+        (*self).remove_listener(listener)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::{api::IntoWidgetPtr, app::tree::WidgetTree};
+
+    #[test]
+    fn listeners_are_rebuilt_on_change() {
+        let notifier = ValueNotifier::new(0);
+
+        let mut tree = WidgetTree::new(().into_widget_ptr());
+        let listener = Listener::new(tree.get_root());
+
+        notifier.add_listener(listener.clone());
+        notifier.add_listener(listener.clone());
+        assert_eq!(notifier.listeners.len(), 1);
+
+        // Setting an equal value doesn't notify.
+        notifier.set(0);
+        assert!(!tree.get_root().borrow().dirty);
+
+        notifier.set(1);
+        assert!(tree.get_root().borrow().dirty);
+        assert_eq!(notifier.get(), 1);
+
+        // Listeners of removed widgets are forgotten.
+        drop(tree);
+        notifier.update(|value| *value += 1);
+        assert!(notifier.listeners.is_empty());
+
+        notifier.remove_listener(&listener);
+    }
+}
@@ -6,6 +6,7 @@ use super::{implementors::WidgetDerive, Widget, WidgetKind};
 
 /// LocalKey is a widget that allows you to annotate the key for a `child`
 /// widget.
+#[derive(Clone)]
 pub struct LocalKey<K: 'static + PartialEq, W: Widget> {
     pub key: K,
     pub child: W,
@@ -34,7 +35,10 @@ impl<K: 'static + PartialEq, W: Widget> Widget for LocalKey<K, W> {
 }
 
 impl<K: 'static + PartialEq, W: Widget> WidgetDerive for LocalKey<K, W> {
-    type Widget<'a> = &'a W where Self: 'a;
+    type Widget<'a>
+        = &'a W
+    where
+        Self: 'a;
 
     type UniqueTypeId = LocalKeyUniqueTypeId;
 }
@@ -46,31 +50,41 @@ impl<K: 'static + PartialEq, W: Widget> SingleChildWidget for LocalKey<K, W> {
 }
 
 //
-// LocalKeyAny
+// Key
 
-pub struct LocalKeyAny<'a> {
+/// Type-erased key of a widget, see [`Widget::key`].
+///
+/// Keys of different types are never equal.
+#[derive(Clone, Copy)]
+pub struct Key<'a> {
     key: &'a dyn PartialEqAny,
 }
 
-impl PartialEq for LocalKeyAny<'_> {
+impl<'a> Key<'a> {
+    pub fn new<K: 'static + PartialEq>(key: &'a K) -> Self {
+        Self { key }
+    }
+}
+
+impl PartialEq for Key<'_> {
     fn eq(&self, other: &Self) -> bool {
         self.key.eq(other.key)
     }
 }
 
 pub trait WidgetLocalKey {
-    fn local_key(&self) -> Option<LocalKeyAny>;
+    fn local_key(&self) -> Option<Key>;
 }
 
 impl<T> WidgetLocalKey for T {
-    default fn local_key(&self) -> Option<LocalKeyAny> {
+    default fn local_key(&self) -> Option<Key> {
         None
     }
 }
 
 impl<K: 'static + PartialEq, W: Widget> WidgetLocalKey for LocalKey<K, W> {
-    fn local_key(&self) -> Option<LocalKeyAny> {
-        Some(LocalKeyAny { key: &self.key })
+    fn local_key(&self) -> Option<Key> {
+        Some(Key { key: &self.key })
     }
 }
 
@@ -0,0 +1,116 @@
+use crate::{
+    api::{implementors::WidgetDerive, Widget, WidgetKind},
+    prelude::{BuildContext, InheritedWidget, Size, WidgetState},
+};
+
+/// Information about the window a widget is displayed in.
+///
+/// Layout and paint happen in logical pixels (display points), independently
+/// of the device pixel ratio of the window. The ratio is applied once, to the
+/// transform of the root canvas, so a widget 100 logical pixels wide covers
+/// 200 physical pixels at the ratio of 2.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MediaQueryData {
+    /// Size of the window's drawing area, in logical pixels.
+    pub size: Size,
+    /// Number of physical pixels per one logical pixel.
+    pub device_pixel_ratio: f64,
+}
+
+impl Default for MediaQueryData {
+    fn default() -> Self {
+        Self {
+            size: Size::default(),
+            device_pixel_ratio: 1.,
+        }
+    }
+}
+
+impl MediaQueryData {
+    /// Converts `size` in logical pixels to physical pixels.
+    pub fn to_physical(&self, size: Size) -> Size {
+        Size::new(
+            size.width * self.device_pixel_ratio,
+            size.height * self.device_pixel_ratio,
+        )
+    }
+
+    /// Converts `size` in physical pixels to logical pixels.
+    pub fn to_logical(&self, size: Size) -> Size {
+        Size::new(
+            size.width / self.device_pixel_ratio,
+            size.height / self.device_pixel_ratio,
+        )
+    }
+}
+
+/// Root of the widget tree of every window, providing its [`MediaQueryData`].
+pub struct MediaQuery {
+    data: MediaQueryData,
+    child: Box<dyn Widget>,
+}
+
+impl MediaQuery {
+    pub(crate) fn new(data: MediaQueryData, child: Box<dyn Widget>) -> Self {
+        Self { data, child }
+    }
+
+    /// Returns media query data of the window, rebuilding the widget of `ctx`
+    /// whenever it changes.
+    pub fn of<'a, T>(ctx: BuildContext<'a, T>) -> MediaQueryData {
+        match ctx.depend_on_inherited_widget::<Self>() {
+            Some(state) => *state.as_ref(),
+            // Widget isn't mounted in a window, e.g. during tests.
+            None => MediaQueryData::default(),
+        }
+    }
+}
+
+impl Widget for MediaQuery {
+    fn unique_type(&self) -> std::any::TypeId {
+        std::any::TypeId::of::<MediaQuery>()
+    }
+
+    fn kind(&self) -> WidgetKind<'_> {
+        WidgetKind::Inherited(self)
+    }
+}
+
+impl WidgetDerive for MediaQuery {
+    type Widget<'a> = Box<dyn Widget>;
+
+    type UniqueTypeId = MediaQuery;
+}
+
+impl WidgetState for MediaQuery {
+    type State = MediaQueryData;
+
+    fn create_state(&self) -> Self::State {
+        self.data
+    }
+}
+
+impl InheritedWidget for MediaQuery {
+    fn child<'w>(&'w self) -> &'w Self::Widget<'w> {
+        &self.child
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn logical_pixels_are_scaled_by_device_pixel_ratio() {
+        let data = MediaQueryData {
+            size: Size::new(800., 600.),
+            device_pixel_ratio: 2.,
+        };
+
+        assert_eq!(
+            data.to_physical(Size::new(100., 50.)),
+            Size::new(200., 100.)
+        );
+        assert_eq!(data.to_logical(Size::new(200., 100.)), Size::new(100., 50.));
+    }
+}
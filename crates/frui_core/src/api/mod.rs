@@ -1,6 +1,9 @@
 use std::any::{Any, TypeId};
 
-use crate::prelude::{Constraints, Offset, PaintContext, Size};
+use crate::{
+    app::listeners::with_registrar,
+    prelude::{Constraints, PaintContext, Size},
+};
 
 use self::{
     contexts::{build_ctx::STATE_UPDATE_SUPRESSED, render_ctx::AnyRenderContext, Context},
@@ -9,14 +12,27 @@ use self::{
         inherited::InheritedWidgetOS, leaf::LeafWidgetOS, multi::MultiChildWidgetOS,
         single::SingleChildWidgetOS, view::ViewWidgetOS,
     },
-    local_key::LocalKeyAny,
+    local_key::Key,
 };
 
+pub mod capture;
 pub mod contexts;
 pub mod events;
+pub mod fonts;
+pub mod gestures;
 pub mod implementors;
 pub mod impls;
+pub mod layers;
+pub mod lazy_state;
+pub mod lifecycle;
+pub mod listenable;
 pub mod local_key;
+pub mod media_query;
+pub mod notifications;
+pub mod paint;
+pub mod prewarm;
+pub mod signal;
+pub mod state_handle;
 pub mod structural_eq;
 
 pub trait Widget: WidgetDebug {
@@ -26,6 +42,20 @@ pub trait Widget: WidgetDebug {
     fn unique_type(&self) -> TypeId;
 
     fn kind(&self) -> WidgetKind;
+
+    /// Key identifying this widget among its siblings, or `None` if it should
+    /// be identified by its position.
+    ///
+    /// When a widget is rebuilt, each of its new children is matched with one
+    /// of the old children, whose state it then keeps (if their types match).
+    /// Keyed children are matched with the old child of an equal key, wherever
+    /// that child was. Children without a key are matched with old children
+    /// without a key, in order.
+    ///
+    /// Defaults to the key of [`LocalKey`](local_key::LocalKey).
+    fn key(&self) -> Option<Key<'_>> {
+        self.kind().local_key()
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -37,6 +67,18 @@ pub enum WidgetKind<'a> {
     MultiChild(&'a (dyn MultiChildWidgetOS + 'a)),
 }
 
+impl<'a> WidgetKind<'a> {
+    fn local_key(self) -> Option<Key<'a>> {
+        match self {
+            WidgetKind::View(w) => w.local_key(),
+            WidgetKind::Leaf(w) => w.local_key(),
+            WidgetKind::MultiChild(w) => w.local_key(),
+            WidgetKind::SingleChild(w) => w.local_key(),
+            WidgetKind::Inherited(w) => w.local_key(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct WidgetPtr<'a> {
     /// Reference to the exact type of this widget. Used to properly dispatch methods.
@@ -44,6 +86,9 @@ pub struct WidgetPtr<'a> {
 
     /// Whether this pointer references or owns a widget. Used to properly drop it.
     pub(crate) owned: Option<*mut (dyn Widget + 'a)>,
+
+    /// Key of this widget, see [`Widget::key`].
+    pub(crate) key: Option<Key<'a>>,
 }
 
 impl<'a> WidgetPtr<'a> {
@@ -51,6 +96,7 @@ impl<'a> WidgetPtr<'a> {
         Self {
             kind: widget.kind(),
             owned: None,
+            key: widget.key(),
         }
     }
 
@@ -66,6 +112,7 @@ impl<'a> WidgetPtr<'a> {
         unsafe {
             Self {
                 kind: std::mem::transmute::<WidgetKind, WidgetKind>(widget.kind()),
+                key: std::mem::transmute::<Option<Key>, Option<Key>>(widget.key()),
                 owned: Some(Box::into_raw(widget)),
             }
         }
@@ -133,31 +180,27 @@ impl<'a> WidgetPtr<'a> {
         }
     }
 
-    pub fn paint<'b>(
-        &self,
-        render_ctx: &'b mut AnyRenderContext,
-        piet: &mut PaintContext,
-        offset: &Offset,
-    ) {
+    pub fn paint<'b>(&self, render_ctx: &'b mut AnyRenderContext, piet: &mut PaintContext) {
         match self.kind {
-            WidgetKind::View(w) => w.paint(render_ctx, piet, offset),
-            WidgetKind::Leaf(w) => w.paint(render_ctx, piet, offset),
-            WidgetKind::MultiChild(w) => w.paint(render_ctx, piet, offset),
-            WidgetKind::SingleChild(w) => w.paint(render_ctx, piet, offset),
-            WidgetKind::Inherited(w) => w.paint(render_ctx, piet, offset),
+            WidgetKind::View(w) => w.paint(render_ctx, piet),
+            WidgetKind::Leaf(w) => w.paint(render_ctx, piet),
+            WidgetKind::MultiChild(w) => w.paint(render_ctx, piet),
+            WidgetKind::SingleChild(w) => w.paint(render_ctx, piet),
+            WidgetKind::Inherited(w) => w.paint(render_ctx, piet),
         }
     }
 
     pub fn mount(&self, build_ctx: &Context) {
         STATE_UPDATE_SUPRESSED.store(true, std::sync::atomic::Ordering::SeqCst);
 
-        match self.kind {
+        // Listeners are usually registered here.
+        with_registrar(self.debug_name_short(), || match self.kind {
             WidgetKind::View(w) => w.mount(build_ctx),
             WidgetKind::Leaf(w) => w.mount(build_ctx),
             WidgetKind::MultiChild(w) => w.mount(build_ctx),
             WidgetKind::SingleChild(w) => w.mount(build_ctx),
             WidgetKind::Inherited(w) => w.mount(build_ctx),
-        }
+        });
 
         STATE_UPDATE_SUPRESSED.store(false, std::sync::atomic::Ordering::SeqCst);
     }
@@ -176,6 +219,60 @@ impl<'a> WidgetPtr<'a> {
         STATE_UPDATE_SUPRESSED.store(false, std::sync::atomic::Ordering::SeqCst);
     }
 
+    pub fn did_update_widget(&self, build_ctx: &Context) {
+        STATE_UPDATE_SUPRESSED.store(true, std::sync::atomic::Ordering::SeqCst);
+
+        // Listeners are usually registered here.
+        with_registrar(self.debug_name_short(), || match self.kind {
+            WidgetKind::View(w) => w.did_update_widget(build_ctx),
+            WidgetKind::Leaf(w) => w.did_update_widget(build_ctx),
+            WidgetKind::MultiChild(w) => w.did_update_widget(build_ctx),
+            WidgetKind::SingleChild(w) => w.did_update_widget(build_ctx),
+            WidgetKind::Inherited(w) => w.did_update_widget(build_ctx),
+        });
+
+        STATE_UPDATE_SUPRESSED.store(false, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    pub fn did_change_dependencies(&self, build_ctx: &Context) {
+        STATE_UPDATE_SUPRESSED.store(true, std::sync::atomic::Ordering::SeqCst);
+
+        match self.kind {
+            WidgetKind::View(w) => w.did_change_dependencies(build_ctx),
+            WidgetKind::Leaf(w) => w.did_change_dependencies(build_ctx),
+            WidgetKind::MultiChild(w) => w.did_change_dependencies(build_ctx),
+            WidgetKind::SingleChild(w) => w.did_change_dependencies(build_ctx),
+            WidgetKind::Inherited(w) => w.did_change_dependencies(build_ctx),
+        }
+
+        STATE_UPDATE_SUPRESSED.store(false, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    pub fn after_first_build(&self, build_ctx: &Context) {
+        // State updates aren't suppressed, so that they schedule a rebuild.
+        with_registrar(self.debug_name_short(), || match self.kind {
+            WidgetKind::View(w) => w.after_first_build(build_ctx),
+            WidgetKind::Leaf(w) => w.after_first_build(build_ctx),
+            WidgetKind::MultiChild(w) => w.after_first_build(build_ctx),
+            WidgetKind::SingleChild(w) => w.after_first_build(build_ctx),
+            WidgetKind::Inherited(w) => w.after_first_build(build_ctx),
+        });
+    }
+
+    pub fn dispose(&self, build_ctx: &Context) {
+        STATE_UPDATE_SUPRESSED.store(true, std::sync::atomic::Ordering::SeqCst);
+
+        match self.kind {
+            WidgetKind::View(w) => w.dispose(build_ctx),
+            WidgetKind::Leaf(w) => w.dispose(build_ctx),
+            WidgetKind::MultiChild(w) => w.dispose(build_ctx),
+            WidgetKind::SingleChild(w) => w.dispose(build_ctx),
+            WidgetKind::Inherited(w) => w.dispose(build_ctx),
+        }
+
+        STATE_UPDATE_SUPRESSED.store(false, std::sync::atomic::Ordering::SeqCst);
+    }
+
     /// Returned `bool` indicates whether the event was consumed.
     ///
     /// # Note
@@ -241,20 +338,14 @@ impl<'a> WidgetPtr<'a> {
     }
 
     pub fn has_key(&self) -> bool {
-        self.local_key().is_some()
+        self.key.is_some()
     }
 
-    pub fn local_key(&self) -> Option<LocalKeyAny<'a>> {
-        match self.kind {
-            WidgetKind::View(w) => w.local_key(),
-            WidgetKind::Leaf(w) => w.local_key(),
-            WidgetKind::MultiChild(w) => w.local_key(),
-            WidgetKind::SingleChild(w) => w.local_key(),
-            WidgetKind::Inherited(w) => w.local_key(),
-        }
+    pub fn key(&self) -> Option<Key<'a>> {
+        self.key
     }
 
-    fn unique_type_id(&self) -> TypeId {
+    pub(crate) fn unique_type_id(&self) -> TypeId {
         match self.kind {
             WidgetKind::View(w) => w.unique_type(),
             WidgetKind::Leaf(w) => w.unique_type(),
@@ -294,6 +385,27 @@ impl<'a> WidgetPtr<'a> {
         }
     }
 
+    /// Returned `bool` indicates whether the notification was stopped.
+    pub(crate) fn handle_notification(&self, notification: &dyn Any) -> bool {
+        match self.kind {
+            WidgetKind::View(w) => w.handle_notification(notification),
+            WidgetKind::Leaf(w) => w.handle_notification(notification),
+            WidgetKind::SingleChild(w) => w.handle_notification(notification),
+            WidgetKind::MultiChild(w) => w.handle_notification(notification),
+            WidgetKind::Inherited(w) => w.handle_notification(notification),
+        }
+    }
+
+    pub fn skip_constraints_check(&self) -> bool {
+        match self.kind {
+            WidgetKind::View(w) => w.skip_constraints_check(),
+            WidgetKind::Leaf(w) => w.skip_constraints_check(),
+            WidgetKind::SingleChild(w) => w.skip_constraints_check(),
+            WidgetKind::MultiChild(w) => w.skip_constraints_check(),
+            WidgetKind::Inherited(w) => w.skip_constraints_check(),
+        }
+    }
+
     pub fn inherited_key(&self) -> TypeId {
         match self.kind {
             WidgetKind::Inherited(w) => w.inherited_key(),
@@ -305,7 +417,7 @@ impl<'a> WidgetPtr<'a> {
         self.owned.is_none()
     }
 
-    fn widget_ptr(&self) -> *const () {
+    pub(crate) fn widget_ptr(&self) -> *const () {
         match self.kind {
             WidgetKind::View(w) => w as *const _ as *const (),
             WidgetKind::Inherited(w) => w as *const _ as *const (),
@@ -415,6 +527,8 @@ mod any_ext {
     pub trait AnyExt: AsAny {
         fn type_id(&self) -> TypeId;
 
+        fn type_name(&self) -> &'static str;
+
         /// Helper function.
         fn as_any_ext<'a>(&'a self) -> &'a (dyn AnyExt + 'a);
     }
@@ -424,6 +538,10 @@ mod any_ext {
             get_type_id::<T>()
         }
 
+        fn type_name(&self) -> &'static str {
+            std::any::type_name::<T>()
+        }
+
         fn as_any_ext<'a>(&'a self) -> &'a dyn AnyExt {
             self
         }
@@ -0,0 +1,40 @@
+//! Notifications bubbling up the widget tree.
+
+use std::any::Any;
+
+/// A message that widgets can dispatch to their ancestors.
+///
+/// Notification is dispatched using `dispatch_notification` of a build or a
+/// render context. It bubbles up the tree, starting at the nearest ancestor of
+/// the dispatching widget, until some ancestor stops it.
+pub trait Notification: Any {}
+
+/// Implemented by widgets that listen to notifications of their descendants.
+pub trait WidgetNotification: Sized {
+    /// Returned `bool` indicates whether the notification should stop bubbling
+    /// further up the tree.
+    fn handle_notification(&self, notification: &dyn Any) -> bool;
+}
+
+pub(crate) use sealed::WidgetNotificationOS;
+
+mod sealed {
+    use std::any::Any;
+
+    /// `OS` stands for "object safe".
+    pub trait WidgetNotificationOS {
+        fn handle_notification(&self, notification: &dyn Any) -> bool;
+    }
+
+    impl<T> WidgetNotificationOS for T {
+        default fn handle_notification(&self, _: &dyn Any) -> bool {
+            false
+        }
+    }
+
+    impl<T: super::WidgetNotification> WidgetNotificationOS for T {
+        fn handle_notification(&self, notification: &dyn Any) -> bool {
+            T::handle_notification(self, notification)
+        }
+    }
+}
@@ -0,0 +1,406 @@
+//! Helpers for painting parts of a widget into a clip, a separate layer or with
+//! a transform.
+//!
+//! All of them save the state of the canvas before calling the provided closure and
+//! restore it afterwards, also when the closure panics, so that a widget can't
+//! leave a clip or a transform applied to the paint of its siblings. Widgets
+//! changing the canvas state in other ways should do so through a
+//! [`CanvasGuard`] for the same reason.
+
+use std::{
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use druid_shell::{
+    kurbo::{Affine, Rect, Shape},
+    piet::RenderContext,
+};
+
+use crate::prelude::PaintContext;
+
+use super::layers::{self, LayerKind};
+
+/// How a source color (the one being painted) is combined with the
+/// destination color (the one already painted).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    Clear,
+    Src,
+    Dst,
+    SrcOver,
+    DstOver,
+    SrcIn,
+    DstIn,
+    SrcOut,
+    DstOut,
+    SrcATop,
+    DstATop,
+    Xor,
+    Multiply,
+    Screen,
+}
+
+/// Clipping and compositing helpers of [`PaintContext`].
+pub trait PaintContextExt {
+    /// Paints with `paint`, clipped to `shape`.
+    fn with_clip(&mut self, shape: impl Shape, paint: impl FnOnce(&mut PaintContext));
+
+    /// Paints with `paint` into a layer clipped to `bounds`, which is then
+    /// composited onto this canvas with `opacity` using `blend_mode`.
+    ///
+    /// Unlike painting every shape with a reduced opacity, shapes painted into
+    /// a layer don't show through each other. Layers can be nested, and the
+    /// `PaintContext` passed to `paint` may be a different one than this, so
+    /// `paint` should only paint into the provided one.
+    ///
+    /// # Note
+    ///
+    /// Piet doesn't support blend modes, so only [`BlendMode::SrcOver`] (the
+    /// usual painting over) and [`BlendMode::Dst`] (which doesn't paint the
+    /// layer at all) are applied. Any other mode is painted as
+    /// [`BlendMode::SrcOver`], and a warning is logged once.
+    ///
+    /// Layer with `opacity` below `1.0` is painted offscreen at the resolution
+    /// of this canvas. If that fails, it is painted directly instead, as if its
+    /// opacity was `1.0`.
+    fn with_layer(
+        &mut self,
+        bounds: Rect,
+        opacity: f64,
+        blend_mode: BlendMode,
+        paint: impl FnOnce(&mut PaintContext),
+    );
+
+    /// Paints with `paint` offscreen into a layer clipped to `bounds`, whose
+    /// premultiplied RGBA pixels are changed by `filter` before the layer is
+    /// drawn onto this canvas. Rows of pixels are stored top to bottom, four
+    /// bytes per pixel.
+    ///
+    /// Like with [`with_layer`](Self::with_layer), the layer is painted at the
+    /// resolution of this canvas. Returns `false` if painting offscreen failed
+    /// (logging an error), in which case `paint` is painted directly onto this
+    /// canvas, unfiltered, unless it already painted into the layer.
+    fn with_filter(
+        &mut self,
+        bounds: Rect,
+        filter: impl FnOnce(&mut [u8]),
+        paint: impl FnOnce(&mut PaintContext),
+    ) -> bool;
+
+    /// Paints with `paint`, transformed with `transform`.
+    fn with_transform(&mut self, transform: Affine, paint: impl FnOnce(&mut PaintContext));
+
+    /// Saves the state of this canvas, which is restored once the returned
+    /// guard is dropped. Returns `None` (logging an error) if saving failed.
+    ///
+    /// ```
+    /// # extern crate frui_core as frui;
+    /// # use frui::prelude::*;
+    /// # fn paint<T: SingleChildWidget>(ctx: RenderContext<T>, canvas: &mut PaintContext, angle: f64) {
+    /// let Some(mut canvas) = canvas.save_guard() else {
+    ///     return;
+    /// };
+    ///
+    /// canvas.transform(Affine::rotate(angle));
+    /// ctx.child().paint(&mut canvas);
+    /// // Restored here, so that siblings aren't rotated.
+    /// # }
+    /// ```
+    fn save_guard(&mut self) -> Option<CanvasGuard<'_, Self>>
+    where
+        Self: RenderContext + Sized;
+}
+
+impl PaintContextExt for PaintContext<'_> {
+    fn with_clip(&mut self, shape: impl Shape, paint: impl FnOnce(&mut PaintContext)) {
+        let Some(mut canvas) = CanvasGuard::save(self) else {
+            return;
+        };
+
+        let bounds = shape.bounding_box();
+        canvas.clip(shape);
+
+        let _layer = layers::push(LayerKind::Clip, Some(bounds), canvas.current_transform());
+
+        paint(&mut canvas);
+    }
+
+    fn with_layer(
+        &mut self,
+        bounds: Rect,
+        opacity: f64,
+        blend_mode: BlendMode,
+        paint: impl FnOnce(&mut PaintContext),
+    ) {
+        let opacity = opacity.clamp(0., 1.);
+
+        if opacity == 0. || !paints_layer(blend_mode) {
+            return;
+        }
+
+        let Some(mut canvas) = CanvasGuard::save(self) else {
+            return;
+        };
+
+        canvas.clip(bounds);
+
+        let _layer = layers::push(
+            LayerKind::Opacity(opacity),
+            Some(bounds),
+            canvas.current_transform(),
+        );
+
+        if opacity == 1. {
+            return paint(&mut canvas);
+        }
+
+        layer::paint(
+            &mut canvas,
+            bounds,
+            |pixels| apply_opacity(pixels, opacity),
+            paint,
+        );
+    }
+
+    fn with_filter(
+        &mut self,
+        bounds: Rect,
+        filter: impl FnOnce(&mut [u8]),
+        paint: impl FnOnce(&mut PaintContext),
+    ) -> bool {
+        let Some(mut canvas) = CanvasGuard::save(self) else {
+            return false;
+        };
+
+        canvas.clip(bounds);
+
+        let _layer = layers::push(LayerKind::Filter, Some(bounds), canvas.current_transform());
+
+        layer::paint(&mut canvas, bounds, filter, paint)
+    }
+
+    fn with_transform(&mut self, transform: Affine, paint: impl FnOnce(&mut PaintContext)) {
+        let Some(mut canvas) = CanvasGuard::save(self) else {
+            return;
+        };
+
+        canvas.transform(transform);
+
+        let _layer = layers::push(
+            LayerKind::Transform(transform),
+            None,
+            canvas.current_transform(),
+        );
+
+        paint(&mut canvas);
+    }
+
+    fn save_guard(&mut self) -> Option<CanvasGuard<'_, Self>> {
+        CanvasGuard::save(self)
+    }
+}
+
+/// Whether a layer composited with `blend_mode` is painted at all, logging a
+/// warning (once) if `blend_mode` isn't supported.
+fn paints_layer(blend_mode: BlendMode) -> bool {
+    static WARNED: AtomicBool = AtomicBool::new(false);
+
+    match blend_mode {
+        BlendMode::SrcOver => true,
+        BlendMode::Dst => false,
+        blend_mode => {
+            if !WARNED.swap(true, Ordering::Relaxed) {
+                diagnostic!(
+                    warn,
+                    "paint",
+                    "{blend_mode:?} is not supported by the render backend, painting layer with \
+                    BlendMode::SrcOver"
+                );
+            }
+
+            true
+        }
+    }
+}
+
+/// Saved state (transform and clip) of a canvas, restored once this guard is
+/// dropped, also while unwinding from a panic. Obtained through
+/// [`PaintContextExt::save_guard`], and dereferences to the canvas.
+///
+/// In debug builds, Frui panics at the end of paint if the canvas was saved
+/// more times than restored.
+pub struct CanvasGuard<'a, C: RenderContext> {
+    canvas: &'a mut C,
+}
+
+impl<'a, C: RenderContext> CanvasGuard<'a, C> {
+    fn save(canvas: &'a mut C) -> Option<Self> {
+        match canvas.save() {
+            Ok(()) => Some(CanvasGuard { canvas }),
+            Err(e) => {
+                diagnostic!(error, "paint", "saving render context failed: {e:?}");
+                None
+            }
+        }
+    }
+}
+
+impl<C: RenderContext> Deref for CanvasGuard<'_, C> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        self.canvas
+    }
+}
+
+impl<C: RenderContext> DerefMut for CanvasGuard<'_, C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.canvas
+    }
+}
+
+impl<C: RenderContext> Drop for CanvasGuard<'_, C> {
+    fn drop(&mut self) {
+        if let Err(e) = self.canvas.restore() {
+            diagnostic!(error, "paint", "restoring render context failed: {e:?}");
+        }
+    }
+}
+
+/// Restores saves of `canvas` which weren't restored, returning their number.
+pub(crate) fn restore_unbalanced(canvas: &mut impl RenderContext) -> usize {
+    let mut unbalanced = 0;
+
+    while canvas.restore().is_ok() {
+        unbalanced += 1;
+    }
+
+    unbalanced
+}
+
+/// Size in pixels of a layer covering `bounds` painted with `transform`, and
+/// the scale of pixels to units of `bounds`.
+fn layer_size(bounds: Rect, transform: Affine) -> (usize, usize, f64) {
+    let [a, b, c, d, _, _] = transform.as_coeffs();
+    let scale = (a * d - b * c).abs().sqrt();
+
+    let to_pixels = |length: f64| (length * scale).ceil().max(0.) as usize;
+
+    (to_pixels(bounds.width()), to_pixels(bounds.height()), scale)
+}
+
+/// Multiplies premultiplied RGBA `pixels` by `opacity`.
+fn apply_opacity(pixels: &mut [u8], opacity: f64) {
+    for channel in pixels {
+        *channel = (*channel as f64 * opacity).round() as u8;
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+mod layer {
+    use druid_shell::piet::{Device, Error, ImageFormat, InterpolationMode};
+
+    use super::*;
+
+    /// Paints `paint` offscreen and draws the result into `bounds` of `canvas`
+    /// once its pixels are changed by `filter`. Returns `false` if that failed.
+    pub(super) fn paint(
+        canvas: &mut PaintContext,
+        bounds: Rect,
+        filter: impl FnOnce(&mut [u8]),
+        paint: impl FnOnce(&mut PaintContext),
+    ) -> bool {
+        let (width, height, scale) = layer_size(bounds, canvas.current_transform());
+
+        if width == 0 || height == 0 {
+            return true;
+        }
+
+        let mut paint = Some(paint);
+
+        let result = (|| -> Result<(), Error> {
+            let mut device = Device::new()?;
+            let mut target = device.bitmap_target(width, height, scale)?;
+
+            {
+                let mut layer = target.render_context();
+                layer.transform(Affine::translate(-bounds.origin().to_vec2()));
+
+                (paint.take().unwrap())(&mut layer);
+
+                layer.finish()?;
+            }
+
+            let mut pixels = vec![0; width * height * 4];
+            target.copy_raw_pixels(ImageFormat::RgbaPremul, &mut pixels)?;
+            filter(&mut pixels);
+
+            let image = canvas.make_image(width, height, &pixels, ImageFormat::RgbaPremul)?;
+            canvas.draw_image(&image, bounds, InterpolationMode::Bilinear);
+
+            Ok(())
+        })();
+
+        if let Err(e) = result {
+            diagnostic!(error, "paint", "painting offscreen layer failed: {e:?}");
+
+            // Layer couldn't be painted before `paint` was called.
+            if let Some(paint) = paint {
+                paint(canvas);
+            }
+
+            return false;
+        }
+
+        true
+    }
+}
+
+#[cfg(feature = "miri")]
+mod layer {
+    use super::*;
+
+    pub(super) fn paint(
+        canvas: &mut PaintContext,
+        _: Rect,
+        _: impl FnOnce(&mut [u8]),
+        paint: impl FnOnce(&mut PaintContext),
+    ) -> bool {
+        paint(canvas);
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn layer_matches_resolution_of_canvas() {
+        let bounds = Rect::new(10., 10., 20.5, 30.);
+
+        assert_eq!(layer_size(bounds, Affine::default()), (11, 20, 1.));
+        assert_eq!(layer_size(bounds, Affine::scale(2.)), (21, 40, 2.));
+
+        // Translation and rotation don't change the resolution.
+        let (width, height, _) = layer_size(
+            bounds,
+            Affine::translate((5., 5.)) * Affine::rotate(1.) * Affine::scale(2.),
+        );
+        assert_eq!((width, height), (21, 40));
+
+        assert_eq!(layer_size(bounds, Affine::scale(0.)).0, 0);
+    }
+
+    #[test]
+    fn opacity_scales_premultiplied_channels() {
+        let mut pixels = [255, 128, 0, 255, 10, 20, 30, 40];
+        apply_opacity(&mut pixels, 0.5);
+
+        assert_eq!(pixels, [128, 64, 0, 128, 5, 10, 15, 20]);
+
+        assert!(paints_layer(BlendMode::SrcOver));
+        assert!(!paints_layer(BlendMode::Dst));
+    }
+}
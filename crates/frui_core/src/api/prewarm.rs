@@ -0,0 +1,271 @@
+use std::{
+    any::TypeId,
+    cell::{RefCell, UnsafeCell},
+    rc::Rc,
+};
+
+use crate::{
+    api::{
+        contexts::render_ctx::{layout_guard, AnyRenderContext, RenderPhase},
+        implementors::WidgetDerive,
+        structural_eq::StructuralEqImpl,
+        WidgetPtr,
+    },
+    app::tree::{WidgetNode, WidgetTree},
+    prelude::{BuildContext, Constraints, SingleChildWidget, Widget, WidgetKind},
+};
+
+/// Subtree built ahead of time, before it is shown, e.g. the next screen of a
+/// navigation, so that showing it doesn't have to wait for it to be built.
+///
+/// [`new`](PrewarmedSubtree::new) builds and mounts the subtree right away,
+/// detached from any widget tree, so widgets in it run their
+/// [`WidgetState::mount`] (and can start loading data) early. It can also be
+/// laid out ahead of time with [`layout`](PrewarmedSubtree::layout). Detached
+/// subtree is never painted and doesn't receive events.
+///
+/// Subtree is attached by building `PrewarmedSubtree` (or a clone of it) as a
+/// widget. Attached subtree keeps the state of every of its widgets, which are
+/// neither mounted nor built again, and keeps its layout too if it receives the
+/// same constraints. From then on it behaves like any other subtree, and it is
+/// disposed once its parent stops building it. Subtree can be attached only
+/// once; building it again after it was disposed builds it anew.
+///
+/// While detached, subtree sees no inherited widgets, like [`MediaQuery`].
+/// Widgets which didn't find an inherited widget they looked up are rebuilt
+/// when the subtree is attached.
+///
+/// Subtree which is never attached is disposed when the last clone of its
+/// `PrewarmedSubtree` is dropped.
+///
+/// [`WidgetState::mount`]: crate::prelude::WidgetState::mount
+/// [`MediaQuery`]: crate::prelude::MediaQuery
+#[derive(Clone)]
+pub struct PrewarmedSubtree {
+    widget: Rc<dyn Widget>,
+    /// Subtree which wasn't attached yet, made of the node of this widget and
+    /// its descendants.
+    detached: Rc<RefCell<Option<WidgetTree>>>,
+}
+
+impl PrewarmedSubtree {
+    /// Builds and mounts the subtree of `widget`.
+    pub fn new(widget: impl Widget + 'static) -> Self {
+        let widget: Rc<dyn Widget> = Rc::new(widget);
+
+        // Node of the subtree is configured by a clone which doesn't own it.
+        let root = PrewarmedSubtree {
+            widget: widget.clone(),
+            detached: Rc::default(),
+        };
+
+        PrewarmedSubtree {
+            widget,
+            detached: Rc::new(RefCell::new(Some(WidgetTree::new(WidgetPtr::from_owned(
+                Box::new(root),
+            ))))),
+        }
+    }
+
+    /// Lays out the detached subtree with `constraints`. Does nothing if the
+    /// subtree was already attached.
+    pub fn layout(&self, constraints: Constraints) {
+        if let Some(tree) = &mut *self.detached.borrow_mut() {
+            let root = tree.get_root();
+
+            RenderPhase::Layout.run(|| {
+                layout_guard::begin_pass();
+                AnyRenderContext::new(root).layout(constraints);
+            });
+        }
+    }
+
+    /// Whether the subtree is still detached, i.e. it wasn't attached yet.
+    pub fn is_detached(&self) -> bool {
+        self.detached.borrow().is_some()
+    }
+
+    /// Takes the detached subtree of `widget` if it is a `PrewarmedSubtree`
+    /// which wasn't attached yet.
+    pub(crate) fn take_detached(widget: &WidgetPtr) -> Option<UnsafeCell<Box<WidgetNode>>> {
+        if widget.unique_type_id() != TypeId::of::<PrewarmedSubtree>() {
+            return None;
+        }
+
+        // Safety: Only `PrewarmedSubtree` has its unique type id.
+        let this = unsafe { &*(widget.widget_ptr() as *const PrewarmedSubtree) };
+
+        let mut tree = this.detached.borrow_mut().take()?;
+
+        Some(tree.take_root())
+    }
+}
+
+impl Widget for PrewarmedSubtree {
+    fn unique_type(&self) -> TypeId {
+        TypeId::of::<PrewarmedSubtree>()
+    }
+
+    fn kind(&self) -> WidgetKind<'_> {
+        WidgetKind::SingleChild(self)
+    }
+}
+
+impl WidgetDerive for PrewarmedSubtree {
+    type Widget<'a> = &'a dyn Widget;
+
+    type UniqueTypeId = PrewarmedSubtree;
+}
+
+impl SingleChildWidget for PrewarmedSubtree {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &*self.widget
+    }
+}
+
+/// Clones of the same `PrewarmedSubtree` are equal, so that its attached
+/// subtree isn't rebuilt every time its parent builds it.
+unsafe impl StructuralEqImpl for PrewarmedSubtree {
+    const EQ_ENABLED: bool = true;
+
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(
+            Rc::as_ptr(&self.widget) as *const (),
+            Rc::as_ptr(&other.widget) as *const (),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+
+    use crate::{
+        api::{contexts::build_ctx::WidgetState, implementors::leaf::LeafWidget},
+        prelude::{PaintContext, RenderContext, Size},
+    };
+
+    /// Counts how many times it was mounted and laid out.
+    struct Counter {
+        mounts: Rc<Cell<usize>>,
+        layouts: Rc<Cell<usize>>,
+    }
+
+    impl Widget for Counter {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Counter>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::Leaf(self)
+        }
+    }
+
+    impl WidgetDerive for Counter {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = Counter;
+    }
+
+    impl WidgetState for Counter {
+        type State = ();
+
+        fn create_state(&self) -> Self::State {}
+
+        fn mount<'a>(&'a self, _: BuildContext<'a, Self>) {
+            self.mounts.set(self.mounts.get() + 1);
+        }
+    }
+
+    impl LeafWidget for Counter {
+        fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+            self.layouts.set(self.layouts.get() + 1);
+            constraints.biggest()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    /// Builds `subtree` once `shown` is set.
+    struct Host {
+        subtree: PrewarmedSubtree,
+        shown: Rc<Cell<bool>>,
+    }
+
+    impl Widget for Host {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Host>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::SingleChild(self)
+        }
+    }
+
+    impl WidgetDerive for Host {
+        type Widget<'a> = &'a dyn Widget;
+
+        type UniqueTypeId = Host;
+    }
+
+    impl SingleChildWidget for Host {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+            if self.shown.get() {
+                &self.subtree
+            } else {
+                &()
+            }
+        }
+
+        fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+            ctx.child().layout(constraints)
+        }
+    }
+
+    #[test]
+    fn attached_subtree_is_not_mounted_again() {
+        let (mounts, layouts) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+        let constraints = Constraints::tight(Size::new(100., 100.));
+
+        let subtree = PrewarmedSubtree::new(Counter {
+            mounts: mounts.clone(),
+            layouts: layouts.clone(),
+        });
+
+        subtree.layout(constraints);
+        assert_eq!((mounts.get(), layouts.get()), (1, 1));
+
+        let shown = Rc::new(Cell::new(false));
+        let host = Host {
+            subtree: subtree.clone(),
+            shown: shown.clone(),
+        };
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(host)));
+
+        shown.set(true);
+        tree.get_root().update_subtree();
+        assert!(!subtree.is_detached());
+
+        // Layout from before the subtree was attached is reused.
+        tree.layout(constraints);
+        assert_eq!((mounts.get(), layouts.get()), (1, 1));
+
+        let child = WidgetNode::node_ref(&tree.get_root().children()[0]);
+        assert_eq!(child.depth(), 1);
+        assert_eq!(child.debug_path(), "Host/PrewarmedSubtree");
+
+        // Building it again reuses the attached subtree.
+        tree.get_root().update_subtree();
+        assert_eq!(mounts.get(), 1);
+
+        // Once disposed, it is built anew.
+        shown.set(false);
+        tree.get_root().update_subtree();
+        shown.set(true);
+        tree.get_root().update_subtree();
+        assert_eq!(mounts.get(), 2);
+    }
+}
@@ -0,0 +1,422 @@
+//! Signals, values which rebuild exactly the widgets reading them.
+//!
+//! Unlike the state of a widget, which rebuilds that widget whenever it's
+//! mutated, a signal keeps track of widgets which read it during their
+//! `build` and rebuilds only those once it's set:
+//!
+//! ```
+//! # extern crate frui_core as frui;
+//! # use frui::prelude::*;
+//! let (count, set_count) = create_signal(0);
+//!
+//! // Widget reading `count.get()` in its build is rebuilt by:
+//! set_count.update(|count| *count += 1);
+//! ```
+//!
+//! Reading a signal anywhere else (e.g. in layout or an event handler)
+//! doesn't make the widget depend on it. Dependencies are collected again
+//! during every build, so a widget which stops reading a signal is rebuilt by
+//! it at most once more.
+//!
+//! A signal lives as long as its getters and setters do. Kept in the state of
+//! a widget, it is disposed along with that widget once it's unmounted.
+//! Widgets which read it are forgotten once they're removed from the tree.
+//!
+//! Values derived from signals can be cached with [`create_memo`].
+
+use std::{
+    cell::{Cell, Ref, RefCell},
+    fmt,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    api::listenable::{Listener, Listeners},
+    app::tree,
+};
+
+thread_local! {
+    /// Memo whose value is being computed.
+    static COMPUTING: RefCell<Option<Weak<dyn Invalidate>>> = const { RefCell::new(None) };
+}
+
+/// Memo which can be told its dependencies changed.
+trait Invalidate {
+    fn invalidate(&self);
+}
+
+/// Widgets and memos which read a signal (or a memo) since it last changed.
+#[derive(Default)]
+struct Dependents {
+    nodes: Listeners,
+    memos: RefCell<Vec<Weak<dyn Invalidate>>>,
+}
+
+impl Dependents {
+    /// Makes the memo being computed or otherwise the widget being built
+    /// depend on this value.
+    fn track(&self) {
+        if let Some(memo) = COMPUTING.with(|m| m.borrow().clone()) {
+            let mut memos = self.memos.borrow_mut();
+
+            if !memos.iter().any(|m| m.ptr_eq(&memo)) {
+                memos.push(memo);
+            }
+        } else if let Some(node) = tree::building_node() {
+            self.nodes.add(Listener::new(node));
+        }
+    }
+
+    /// Rebuilds widgets and invalidates memos depending on this value, which
+    /// register again if they still read it.
+    fn notify(&self) {
+        self.nodes.notify();
+        self.nodes.clear();
+
+        let memos = std::mem::take(&mut *self.memos.borrow_mut());
+
+        for memo in memos.iter().filter_map(Weak::upgrade) {
+            memo.invalidate();
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.nodes.len() + self.memos.borrow().len()
+    }
+}
+
+struct SignalInner<T> {
+    value: RefCell<T>,
+    dependents: Dependents,
+}
+
+/// Creates a signal holding `initial` value, returning its getter and setter.
+/// See [`signal`](self).
+pub fn create_signal<T>(initial: T) -> (ReadSignal<T>, WriteSignal<T>) {
+    let inner = Rc::new(SignalInner {
+        value: RefCell::new(initial),
+        dependents: Dependents::default(),
+    });
+
+    (
+        ReadSignal {
+            inner: inner.clone(),
+        },
+        WriteSignal { inner },
+    )
+}
+
+/// Getter of a signal created with [`create_signal`]. Clones read the same
+/// signal.
+pub struct ReadSignal<T> {
+    inner: Rc<SignalInner<T>>,
+}
+
+impl<T> ReadSignal<T> {
+    /// Current value of this signal. Read during a build, that widget is
+    /// rebuilt once the value changes.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Calls `f` with the current value, see [`get`](Self::get).
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.inner.dependents.track();
+        f(&self.inner.value.borrow())
+    }
+
+    /// Current value of this signal, without making the widget being built
+    /// depend on it.
+    pub fn get_untracked(&self) -> Ref<'_, T> {
+        self.inner.value.borrow()
+    }
+}
+
+impl<T> Clone for ReadSignal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ReadSignal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ReadSignal")
+            .field(&*self.inner.value.borrow())
+            .finish()
+    }
+}
+
+/// Setter of a signal created with [`create_signal`]. Clones set the same
+/// signal.
+pub struct WriteSignal<T> {
+    inner: Rc<SignalInner<T>>,
+}
+
+impl<T> WriteSignal<T> {
+    /// Replaces the value, rebuilding widgets which read it if it isn't equal
+    /// to the previous one.
+    pub fn set(&self, value: T)
+    where
+        T: PartialEq,
+    {
+        let changed = *self.inner.value.borrow() != value;
+
+        if changed {
+            *self.inner.value.borrow_mut() = value;
+            self.inner.dependents.notify();
+        }
+    }
+
+    /// Changes the value in place, always rebuilding widgets which read it.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.inner.value.borrow_mut());
+        self.inner.dependents.notify();
+    }
+}
+
+impl<T> Clone for WriteSignal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for WriteSignal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WriteSignal")
+            .field("dependents", &self.inner.dependents.len())
+            .finish()
+    }
+}
+
+/// Creates a memo caching the value returned by `compute`.
+///
+/// Signals and memos read by `compute` are tracked the same way as those read
+/// during a build. Once any of them changes, the memo rebuilds widgets (and
+/// invalidates memos) which read it, and calls `compute` again next time it's
+/// read. Until then, reads return the cached value:
+///
+/// ```
+/// # extern crate frui_core as frui;
+/// # use frui::prelude::*;
+/// # let ((price, _), (quantity, _)) = (create_signal(2.5), create_signal(4));
+/// let total = create_memo(move || price.get() * quantity.get() as f64);
+/// ```
+///
+/// Like a signal, a memo lives as long as its clones do.
+pub fn create_memo<T: 'static>(compute: impl Fn() -> T + 'static) -> Memo<T> {
+    Memo {
+        inner: Rc::new(MemoInner {
+            compute: Box::new(compute),
+            value: RefCell::new(None),
+            stale: Cell::new(true),
+            dependents: Dependents::default(),
+        }),
+    }
+}
+
+struct MemoInner<T> {
+    compute: Box<dyn Fn() -> T>,
+    value: RefCell<Option<T>>,
+    /// Whether a dependency changed since the value was computed.
+    stale: Cell<bool>,
+    dependents: Dependents,
+}
+
+impl<T> Invalidate for MemoInner<T> {
+    fn invalidate(&self) {
+        if !self.stale.replace(true) {
+            self.dependents.notify();
+        }
+    }
+}
+
+/// Value derived from signals or other memos, see [`create_memo`]. Clones read
+/// the same memo.
+pub struct Memo<T> {
+    inner: Rc<MemoInner<T>>,
+}
+
+impl<T: 'static> Memo<T> {
+    /// Current value of this memo, computing it if any of its dependencies
+    /// changed. Read during a build, that widget is rebuilt once it changes.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Calls `f` with the current value, see [`get`](Self::get).
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.inner.dependents.track();
+
+        if self.inner.stale.get() {
+            let weak = Rc::downgrade(&self.inner) as Weak<dyn Invalidate>;
+
+            let previous = COMPUTING.with(|m| m.replace(Some(weak)));
+            let value = (self.inner.compute)();
+            COMPUTING.with(|m| *m.borrow_mut() = previous);
+
+            *self.inner.value.borrow_mut() = Some(value);
+            self.inner.stale.set(false);
+        }
+
+        f(self.inner.value.borrow().as_ref().unwrap())
+    }
+}
+
+impl<T> Clone for Memo<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Memo<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Memo")
+            .field(&*self.inner.value.borrow())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::any::TypeId;
+
+    use super::*;
+
+    use crate::{
+        api::{
+            contexts::build_ctx::BuildContext,
+            implementors::{view::ViewWidget, WidgetDerive},
+            Widget, WidgetKind, WidgetPtr,
+        },
+        app::tree::WidgetTree,
+    };
+
+    /// Widget calling its closure during build.
+    struct Reader(Box<dyn Fn()>);
+
+    impl Widget for Reader {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Reader>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::View(self)
+        }
+    }
+
+    impl WidgetDerive for Reader {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = Reader;
+    }
+
+    impl ViewWidget for Reader {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+            (self.0)();
+        }
+    }
+
+    #[test]
+    fn setting_signal_rebuilds_only_widgets_reading_it_in_build() {
+        let (count, set_count) = create_signal(0);
+
+        // Read outside of any build.
+        assert_eq!(count.get(), 0);
+        assert_eq!(count.inner.dependents.len(), 0);
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(Reader(Box::new({
+            let count = count.clone();
+            move || {
+                count.get();
+            }
+        })))));
+        let root = tree.get_root();
+        root.borrow_mut().dirty = false;
+        assert_eq!(count.inner.dependents.len(), 1);
+
+        // Equal value doesn't rebuild anything.
+        set_count.set(0);
+        assert!(!root.borrow().dirty);
+
+        set_count.set(1);
+        assert!(root.borrow().dirty);
+        assert_eq!(count.get_untracked().clone(), 1);
+
+        // Dependency is registered again by the next build.
+        assert_eq!(count.inner.dependents.len(), 0);
+        root.update_subtree();
+        assert_eq!(count.inner.dependents.len(), 1);
+
+        // Removed widget isn't rebuilt anymore.
+        drop(tree);
+        set_count.update(|count| *count += 1);
+        assert_eq!(count.inner.dependents.len(), 0);
+    }
+
+    #[test]
+    fn memo_is_computed_only_once_its_dependencies_change() {
+        let (a, set_a) = create_signal(1);
+        let (b, set_b) = create_signal(2);
+        let (_, set_unrelated) = create_signal(0);
+
+        let computed = Rc::new(Cell::new(0));
+
+        let sum = create_memo({
+            let computed = computed.clone();
+            move || {
+                computed.set(computed.get() + 1);
+                a.get() + b.get()
+            }
+        });
+
+        let doubled = create_memo({
+            let sum = sum.clone();
+            move || sum.get() * 2
+        });
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(Reader(Box::new({
+            let doubled = doubled.clone();
+            move || {
+                doubled.get();
+            }
+        })))));
+        let root = tree.get_root();
+        root.borrow_mut().dirty = false;
+        assert_eq!(computed.get(), 1);
+
+        // Cached until a dependency changes.
+        assert_eq!(doubled.get(), 6);
+        set_unrelated.set(1);
+        assert_eq!(doubled.get(), 6);
+        assert_eq!(computed.get(), 1);
+        assert!(!root.borrow().dirty);
+
+        // Invalidates memo depending on the memo, and widget depending on it.
+        set_b.set(3);
+        assert!(root.borrow().dirty);
+        assert_eq!(computed.get(), 1);
+
+        assert_eq!(doubled.get(), 8);
+        assert_eq!(sum.get(), 4);
+        assert_eq!(computed.get(), 2);
+
+        set_a.update(|a| *a += 1);
+        set_b.set(4);
+        assert_eq!(doubled.get(), 12);
+        assert_eq!(computed.get(), 3);
+
+        drop((doubled, tree));
+    }
+}
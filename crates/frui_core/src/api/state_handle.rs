@@ -0,0 +1,260 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+
+use crate::app::{executor::spawn_local, tree::WidgetNodeRef};
+
+type Update<S> = Box<dyn FnOnce(&mut S) + Send>;
+
+/// Handle to the state of a widget which can be sent to other threads, obtained
+/// through `BuildContext::state_handle`.
+///
+/// Updates don't touch the state directly. They are posted to the run loop and
+/// applied on the UI thread, rebuilding the widget. Updates posted once the
+/// widget was removed from the tree are dropped without being applied.
+///
+/// ```
+/// # extern crate frui_core as frui;
+/// # use frui::prelude::*;
+/// # struct Primes { primes: Vec<u64> }
+/// # fn compute_primes() -> Vec<u64> { vec![2, 3, 5] }
+/// # fn build<T: WidgetState<State = Primes>>(ctx: BuildContext<T>) {
+/// let handle = ctx.state_handle();
+///
+/// std::thread::spawn(move || {
+///     let primes = compute_primes();
+///     handle.update(move |state| state.primes = primes);
+/// });
+/// # }
+/// ```
+pub struct StateHandle<S: 'static> {
+    channel: Arc<Channel<S>>,
+}
+
+impl<S: 'static> StateHandle<S> {
+    /// Creates a handle to the state of `node`, which has to be of type `S`.
+    pub(crate) fn new(node: WidgetNodeRef) -> Self {
+        let channel = Arc::new(Channel {
+            updates: Mutex::new(Vec::new()),
+            waker: Mutex::new(None),
+            handles: AtomicUsize::new(1),
+            closed: AtomicBool::new(false),
+        });
+
+        spawn_local(Receiver {
+            node,
+            channel: channel.clone(),
+        })
+        .detach();
+
+        Self { channel }
+    }
+
+    /// Posts `f` to be called with the state on the UI thread, after which the
+    /// widget is rebuilt.
+    ///
+    /// Closure is dropped without being called if the widget is removed from
+    /// the tree before the run loop gets to it.
+    pub fn update(&self, f: impl FnOnce(&mut S) + Send + 'static) {
+        if self.channel.closed.load(Ordering::SeqCst) {
+            return;
+        }
+
+        self.channel.updates.lock().unwrap().push(Box::new(f));
+        self.channel.wake();
+    }
+
+    /// Whether updates posted through this handle may still be applied, i.e.
+    /// the widget wasn't found to be removed from the tree yet.
+    pub fn is_alive(&self) -> bool {
+        !self.channel.closed.load(Ordering::SeqCst)
+    }
+}
+
+impl<S: 'static> Clone for StateHandle<S> {
+    fn clone(&self) -> Self {
+        self.channel.handles.fetch_add(1, Ordering::SeqCst);
+
+        Self {
+            channel: self.channel.clone(),
+        }
+    }
+}
+
+impl<S: 'static> Drop for StateHandle<S> {
+    fn drop(&mut self) {
+        // Receiver is woken to finish once there are no handles left.
+        if self.channel.handles.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.channel.wake();
+        }
+    }
+}
+
+/// Updates shared by handles and the receiver applying them.
+struct Channel<S> {
+    updates: Mutex<Vec<Update<S>>>,
+    /// Waker of the receiver.
+    waker: Mutex<Option<Waker>>,
+    /// Number of handles, once it reaches zero no updates can be posted.
+    handles: AtomicUsize,
+    /// Set once the receiver finished, after which updates are dropped.
+    closed: AtomicBool,
+}
+
+impl<S> Channel<S> {
+    fn wake(&self) {
+        if let Some(waker) = self.waker.lock().unwrap().as_ref() {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+/// Task applying updates to the state of `node` on the UI thread.
+struct Receiver<S: 'static> {
+    node: WidgetNodeRef,
+    channel: Arc<Channel<S>>,
+}
+
+impl<S: 'static> Future for Receiver<S> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if !self.node.is_alive() {
+            return Poll::Ready(());
+        }
+
+        // Waker is stored before draining updates, so that an update posted
+        // meanwhile wakes this task again.
+        *self.channel.waker.lock().unwrap() = Some(cx.waker().clone());
+
+        let updates = std::mem::take(&mut *self.channel.updates.lock().unwrap());
+
+        if !updates.is_empty() {
+            let mut node = self.node.borrow_mut();
+
+            // Node is reused only by widgets with the same state type.
+            if let Some(state) = node.state.downcast_mut::<S>() {
+                for update in updates {
+                    update(state);
+                }
+            }
+
+            drop(node);
+            self.node.mark_dirty();
+        }
+
+        if self.channel.handles.load(Ordering::SeqCst) == 0 {
+            return Poll::Ready(());
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<S: 'static> Drop for Receiver<S> {
+    fn drop(&mut self) {
+        self.channel.closed.store(true, Ordering::SeqCst);
+        self.channel.updates.lock().unwrap().clear();
+        self.channel.waker.lock().unwrap().take();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::any::TypeId;
+
+    use super::*;
+
+    use crate::{
+        api::{
+            contexts::{
+                build_ctx::WidgetState,
+                render_ctx::{Constraints, RenderContext, Size},
+            },
+            implementors::{leaf::LeafWidget, WidgetDerive},
+            Widget, WidgetKind, WidgetPtr,
+        },
+        app::{executor, runner::PaintContext, tree::WidgetTree},
+    };
+
+    struct Counter;
+
+    impl Widget for Counter {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Counter>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::Leaf(self)
+        }
+    }
+
+    impl WidgetDerive for Counter {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = Counter;
+    }
+
+    impl WidgetState for Counter {
+        type State = usize;
+
+        fn create_state(&self) -> Self::State {
+            0
+        }
+    }
+
+    impl LeafWidget for Counter {
+        fn layout(&self, _: RenderContext<Self>, _: Constraints) -> Size {
+            Size::default()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    fn count(tree: &mut WidgetTree) -> usize {
+        *tree
+            .get_root()
+            .borrow()
+            .state
+            .downcast_ref::<usize>()
+            .unwrap()
+    }
+
+    #[test]
+    fn updates_are_applied_on_ui_thread_while_mounted() {
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(Counter)));
+        let handle = StateHandle::<usize>::new(tree.get_root());
+
+        executor::poll_woken();
+        tree.get_root().borrow_mut().dirty = false;
+
+        let worker = handle.clone();
+        std::thread::spawn(move || {
+            worker.update(|count| *count += 1);
+            worker.update(|count| *count += 2);
+        })
+        .join()
+        .unwrap();
+
+        // Nothing is applied until the run loop polls the receiver.
+        assert_eq!(count(&mut tree), 0);
+
+        executor::poll_woken();
+        assert_eq!(count(&mut tree), 3);
+        assert!(tree.get_root().borrow().dirty);
+
+        // Updates posted after the widget was removed are dropped.
+        drop(tree);
+        handle.update(|_| unreachable!());
+        executor::poll_woken();
+
+        assert!(!handle.is_alive());
+        handle.update(|_| unreachable!());
+    }
+}
@@ -1,7 +1,7 @@
 mod structural_eq;
 
 pub(crate) use sealed::StructuralEqOS;
-pub use structural_eq::{StructuralEq, StructuralEqImpl};
+pub use structural_eq::{assert_static, PtrEq, StructuralEq, StructuralEqImpl};
 
 mod sealed {
     use crate::{api::AnyExt, macro_exports::StructuralEq};
@@ -29,8 +29,12 @@ mod sealed {
                 match other.downcast_ref::<T>() {
                     Some(other) => <T as StructuralEq>::eq(self, other),
                     None => {
-                        eprintln!(
-                            "WidgetEqOS: can't compare widgets of different types. This is a bug."
+                        diagnostic!(
+                            warn,
+                            "structural_eq",
+                            "can't compare widgets of different types ({} and {}). This is a bug.",
+                            std::any::type_name::<T>(),
+                            other.type_name(),
                         );
                         false
                     }
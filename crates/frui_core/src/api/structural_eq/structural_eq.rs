@@ -166,6 +166,39 @@ unsafe impl<T: Immutable + StructuralEq + ?Sized> StructuralEqImpl for Arc<T> {
     }
 }
 
+//
+// Field attributes
+//
+
+/// ## Warning
+///
+/// This is internal trait and shouldn't be implemented manually.
+///
+/// ## About
+///
+/// Used to compare fields annotated with `#[structural_eq(ptr)]` by their
+/// pointers. It's implemented only for [`Rc`] and [`Arc`].
+pub trait PtrEq {
+    fn ptr_eq(&self, other: &Self) -> bool;
+}
+
+impl<T: ?Sized> PtrEq for Rc<T> {
+    fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(self, other)
+    }
+}
+
+impl<T: ?Sized> PtrEq for Arc<T> {
+    fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(self, other)
+    }
+}
+
+/// Used to make sure fields annotated with `#[structural_eq(skip)]` or
+/// `#[structural_eq(ptr)]` don't borrow anything. Otherwise a reused widget
+/// configuration could reference memory of an already dropped ancestor.
+pub fn assert_static<T: 'static>(_: &T) {}
+
 //
 // Primitives and built-in types
 //
@@ -214,3 +247,51 @@ impl_eq_for_primitives! {
     i8 i16 i32 i64 i128 isize
     f32 f64 char bool ()
 }
+
+// Tuples are compared element by element, so that widgets with a tuple of
+// children (see `WidgetList` of `frui_widgets`) can be compared without boxing
+// those children.
+macro_rules! impl_eq_for_tuples {
+    ($($($t:ident $n:tt),+;)*) => ($(
+        unsafe impl<$($t: StructuralEq),+> StructuralEqImpl for ($($t,)+) {
+            const EQ_ENABLED: bool = $($t::EQ_ENABLED)&&+;
+
+            fn eq(&self, other: &Self) -> bool {
+                $(StructuralEq::eq(&self.$n, &other.$n))&&+
+            }
+        }
+    )*)
+}
+
+impl_eq_for_tuples! {
+    A 0;
+    A 0, B 1;
+    A 0, B 1, C 2;
+    A 0, B 1, C 2, D 3;
+    A 0, B 1, C 2, D 3, E 4;
+    A 0, B 1, C 2, D 3, E 4, F 5;
+    A 0, B 1, C 2, D 3, E 4, F 5, G 6;
+    A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7;
+    A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8;
+    A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9;
+    A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10;
+    A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tuples_are_compared_by_elements() {
+        assert!(StructuralEq::eq(&(1u8, (), true), &(1u8, (), true)));
+        assert!(!StructuralEq::eq(&(1u8, (), true), &(1u8, (), false)));
+
+        // Elements which can't be compared make the whole tuple incomparable.
+        let enabled = [
+            <(u8, (), bool) as StructuralEq>::EQ_ENABLED,
+            <(u8, String) as StructuralEq>::EQ_ENABLED,
+        ];
+        assert_eq!(enabled, [true, false]);
+    }
+}
@@ -0,0 +1,309 @@
+//! Executor of futures driven by the run loop of the application.
+//!
+//! Futures are polled on the UI thread, so they don't have to be `Send` and can
+//! access widgets' state through shared handles. They can still be woken from
+//! any thread (e.g. by a channel whose other end is in a worker thread), which
+//! schedules polling of the woken tasks on the UI thread.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Wake, Waker},
+};
+
+use druid_shell::IdleToken;
+
+use super::runner::{handler::WindowId, IdleHandle};
+
+/// Token of idle callbacks scheduled to poll woken tasks.
+pub(crate) const POLL_TOKEN: IdleToken = IdleToken::new(1);
+
+type LocalFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+thread_local! {
+    static EXECUTOR: Executor = Executor::new();
+}
+
+struct Executor {
+    tasks: RefCell<HashMap<u64, LocalFuture>>,
+    next_id: Cell<u64>,
+    /// Task which is being polled and whether it was cancelled meanwhile.
+    polling: Cell<Option<(u64, bool)>>,
+    queue: Arc<WakeQueue>,
+}
+
+impl Executor {
+    fn new() -> Self {
+        Self {
+            tasks: RefCell::new(HashMap::new()),
+            next_id: Cell::new(0),
+            polling: Cell::new(None),
+            queue: Arc::new(WakeQueue {
+                woken: Mutex::new(Vec::new()),
+                idle_handles: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Polls every task woken so far once. Tasks woken while polling (e.g.
+    /// one which wakes itself) are polled by the idle callback scheduled by
+    /// their waker, so that they can't starve the run loop.
+    fn poll_woken(&self) {
+        let woken = std::mem::take(&mut *self.queue.woken.lock().unwrap());
+
+        for id in woken {
+            // Task is taken out while polled, so that it can spawn or cancel
+            // other tasks (and itself).
+            let mut task = match self.tasks.borrow_mut().remove(&id) {
+                Some(task) => task,
+                None => continue,
+            };
+
+            let waker = Waker::from(Arc::new(TaskWaker {
+                id,
+                queue: self.queue.clone(),
+            }));
+
+            self.polling.set(Some((id, false)));
+            let poll = task.as_mut().poll(&mut Context::from_waker(&waker));
+            let (_, cancelled) = self.polling.take().unwrap();
+
+            if poll.is_pending() && !cancelled {
+                self.tasks.borrow_mut().insert(id, task);
+            }
+        }
+    }
+}
+
+/// Tasks which were woken and should be polled, shared with their wakers.
+struct WakeQueue {
+    woken: Mutex<Vec<u64>>,
+    /// Idle handles of open windows, any of which can be used to wake up the
+    /// run loop.
+    idle_handles: Mutex<Vec<(WindowId, IdleHandle)>>,
+}
+
+impl WakeQueue {
+    fn wake(&self, id: u64) {
+        let mut woken = self.woken.lock().unwrap();
+
+        if !woken.contains(&id) {
+            woken.push(id);
+        }
+
+        drop(woken);
+
+        if let Some((_, handle)) = self.idle_handles.lock().unwrap().first_mut() {
+            handle.schedule_idle(POLL_TOKEN);
+        }
+    }
+}
+
+struct TaskWaker {
+    id: u64,
+    queue: Arc<WakeQueue>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.queue.wake(self.id);
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.queue.wake(self.id);
+    }
+}
+
+/// Handle to a future spawned with [`spawn_local`]. Dropping it cancels the
+/// future, dropping it without polling it any further.
+#[must_use = "dropping a `Task` cancels it, use `detach` to let it run"]
+pub struct Task {
+    id: u64,
+}
+
+impl Task {
+    /// Lets the future run to completion without keeping its handle.
+    pub fn detach(self) {
+        std::mem::forget(self);
+    }
+
+    /// Whether the future is still running, i.e. it neither completed nor
+    /// was cancelled.
+    pub fn is_running(&self) -> bool {
+        EXECUTOR.with(|executor| {
+            executor.tasks.borrow().contains_key(&self.id)
+                || matches!(executor.polling.get(), Some((id, false)) if id == self.id)
+        })
+    }
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        // Executor is gone if the thread is being torn down.
+        let _ = EXECUTOR.try_with(|executor| {
+            if let Some((id, _)) = executor.polling.get().filter(|(id, _)| *id == self.id) {
+                executor.polling.set(Some((id, true)));
+                return;
+            }
+
+            // Future is dropped outside of the borrow, since it may own other
+            // tasks.
+            let future = executor.tasks.borrow_mut().remove(&self.id);
+            drop(future);
+        });
+    }
+}
+
+/// Runs `future` on the UI thread, polling it from the run loop whenever it
+/// is woken. It is first polled during the next iteration of the run loop,
+/// not from within this call.
+pub fn spawn_local(future: impl Future<Output = ()> + 'static) -> Task {
+    EXECUTOR.with(|executor| {
+        let id = executor.next_id.get();
+        executor.next_id.set(id + 1);
+
+        executor.tasks.borrow_mut().insert(id, Box::pin(future));
+        executor.queue.wake(id);
+
+        Task { id }
+    })
+}
+
+/// Polls tasks which were woken before this call.
+pub(crate) fn poll_woken() {
+    EXECUTOR.with(|executor| executor.poll_woken());
+}
+
+/// Lets the executor wake up the run loop through `handle`.
+pub(crate) fn add_idle_handle(window: WindowId, handle: IdleHandle) {
+    EXECUTOR.with(|executor| {
+        let mut handles = executor.queue.idle_handles.lock().unwrap();
+        handles.push((window, handle));
+
+        // Tasks spawned before any window was open.
+        if !executor.queue.woken.lock().unwrap().is_empty() {
+            handles[0].1.schedule_idle(POLL_TOKEN);
+        }
+    });
+}
+
+pub(crate) fn remove_idle_handle(window: WindowId) {
+    EXECUTOR.with(|executor| {
+        let mut handles = executor.queue.idle_handles.lock().unwrap();
+        handles.retain(|(id, _)| *id != window);
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use std::{rc::Rc, task::Poll};
+
+    use super::*;
+
+    /// Future which is pending until `ready` is set, remembering its waker.
+    struct Gate {
+        ready: Rc<Cell<bool>>,
+        waker: Rc<RefCell<Option<Waker>>>,
+    }
+
+    impl Future for Gate {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            match self.ready.get() {
+                true => Poll::Ready(()),
+                false => {
+                    *self.waker.borrow_mut() = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tasks_are_polled_once_woken_until_done_or_cancelled() {
+        let ready = Rc::new(Cell::new(false));
+        let waker = Rc::new(RefCell::new(None::<Waker>));
+        let done = Rc::new(Cell::new(0));
+
+        let spawn = || {
+            let (gate, done) = (
+                Gate {
+                    ready: ready.clone(),
+                    waker: waker.clone(),
+                },
+                done.clone(),
+            );
+
+            spawn_local(async move {
+                gate.await;
+                done.set(done.get() + 1);
+            })
+        };
+
+        let task = spawn();
+
+        // Not polled from within `spawn_local`.
+        assert!(waker.borrow().is_none());
+
+        poll_woken();
+        assert!(task.is_running());
+
+        // Waking from another thread.
+        ready.set(true);
+        let sent = waker.borrow().clone().unwrap();
+        std::thread::spawn(move || sent.wake()).join().unwrap();
+
+        poll_woken();
+        assert_eq!(done.get(), 1);
+        assert!(!task.is_running());
+
+        // Cancelled task is dropped without completing.
+        ready.set(false);
+        let task = spawn();
+        poll_woken();
+        drop(task);
+
+        ready.set(true);
+        waker.borrow().as_ref().unwrap().wake_by_ref();
+        poll_woken();
+        assert_eq!(done.get(), 1);
+    }
+
+    /// Future which wakes itself every time it is polled.
+    struct Spin {
+        polls: Rc<Cell<usize>>,
+    }
+
+    impl Future for Spin {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            self.polls.set(self.polls.get() + 1);
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn self_waking_task_is_polled_once_per_callback() {
+        let polls = Rc::new(Cell::new(0));
+        let task = spawn_local(Spin {
+            polls: polls.clone(),
+        });
+
+        poll_woken();
+        assert_eq!(polls.get(), 1);
+
+        // Woken again for the next callback.
+        poll_woken();
+        assert_eq!(polls.get(), 2);
+
+        drop(task);
+        poll_woken();
+        assert_eq!(polls.get(), 2);
+    }
+}
@@ -0,0 +1,308 @@
+//! Dispatch of copy, cut and paste shortcuts to the focused clipboard target.
+//!
+//! Shortcuts are recognized here, once for all widgets, and only the focused
+//! target receives them. Key events of recognized shortcuts are not delivered
+//! to keyboard event listeners while some target is focused.
+
+use std::cell::RefCell;
+
+use druid_shell::{KbKey, KeyEvent, Modifiers};
+
+/// Receiver of clipboard shortcuts, usually an editable or selectable widget.
+pub trait ClipboardTarget {
+    /// Called on Ctrl+C (Cmd+C on macOS).
+    fn copy(&self) {}
+
+    /// Called on Ctrl+X (Cmd+X on macOS).
+    fn cut(&self) {}
+
+    /// Called on Ctrl+V (Cmd+V on macOS) with the text from the clipboard. Not
+    /// called if the clipboard doesn't contain text.
+    fn paste(&self, text: &str) {
+        let _ = text;
+    }
+}
+
+/// Platform shortcut of a clipboard command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardShortcut {
+    Copy,
+    Cut,
+    Paste,
+}
+
+impl ClipboardShortcut {
+    /// Recognizes Cmd+C, Cmd+X and Cmd+V on macOS. On other platforms,
+    /// recognizes Ctrl+C, Ctrl+X and Ctrl+V, as well as Ctrl+Insert,
+    /// Shift+Delete and Shift+Insert.
+    pub fn from_key_event(event: &KeyEvent) -> Option<Self> {
+        let mods =
+            event.mods & (Modifiers::SHIFT | Modifiers::CONTROL | Modifiers::ALT | Modifiers::META);
+
+        let command = if cfg!(target_os = "macos") {
+            Modifiers::META
+        } else {
+            Modifiers::CONTROL
+        };
+
+        if mods == command {
+            match &event.key {
+                KbKey::Character(c) if c.eq_ignore_ascii_case("c") => Some(Self::Copy),
+                KbKey::Character(c) if c.eq_ignore_ascii_case("x") => Some(Self::Cut),
+                KbKey::Character(c) if c.eq_ignore_ascii_case("v") => Some(Self::Paste),
+                KbKey::Insert if !cfg!(target_os = "macos") => Some(Self::Copy),
+                _ => None,
+            }
+        } else if mods == Modifiers::SHIFT && !cfg!(target_os = "macos") {
+            match &event.key {
+                KbKey::Delete => Some(Self::Cut),
+                KbKey::Insert => Some(Self::Paste),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClipboardTargetKey(usize);
+
+/// Registered clipboard targets, at most one of which is focused.
+pub struct ClipboardTargets {
+    targets: Vec<Option<*const dyn ClipboardTarget>>,
+    /// Widget which registered each of `targets`.
+    registrars: Vec<&'static str>,
+    focused: Option<usize>,
+}
+
+impl ClipboardTargets {
+    /// Registers a clipboard target. It receives shortcuts only once focused.
+    ///
+    /// # Safety
+    ///
+    /// Value `target` points to must live until [`unregister`](Self::unregister)
+    /// or [`update`](Self::update) is called with the returned key.
+    pub unsafe fn register<'a>(
+        &mut self,
+        target: *const (dyn ClipboardTarget + 'a),
+    ) -> ClipboardTargetKey {
+        self.targets.push(Some(std::mem::transmute::<
+            *const (dyn ClipboardTarget + 'a),
+            *const dyn ClipboardTarget,
+        >(target)));
+        self.registrars.push(super::registrar());
+
+        ClipboardTargetKey(self.targets.len() - 1)
+    }
+
+    /// Replaces the target registered with `key`, keeping its focus.
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`register`](Self::register).
+    pub unsafe fn update<'a>(
+        &mut self,
+        key: &ClipboardTargetKey,
+        target: *const (dyn ClipboardTarget + 'a),
+    ) {
+        let slot = &mut self.targets[key.0];
+
+        assert!(slot.is_some(), "target of {key:?} was unregistered");
+
+        *slot = Some(std::mem::transmute::<
+            *const (dyn ClipboardTarget + 'a),
+            *const dyn ClipboardTarget,
+        >(target));
+    }
+
+    pub fn unregister(&mut self, key: &ClipboardTargetKey) {
+        self.unfocus(key);
+        self.targets[key.0] = None;
+
+        while let Some(None) = self.targets.last() {
+            self.targets.pop();
+            self.registrars.pop();
+        }
+    }
+
+    /// Focuses the target registered with `key`, unfocusing the previous one.
+    pub fn focus(&mut self, key: &ClipboardTargetKey) {
+        self.focused = Some(key.0);
+    }
+
+    /// Unfocuses the target registered with `key`, if it is focused.
+    pub fn unfocus(&mut self, key: &ClipboardTargetKey) {
+        if self.is_focused(key) {
+            self.focused = None;
+        }
+    }
+
+    pub fn is_focused(&self, key: &ClipboardTargetKey) -> bool {
+        self.focused == Some(key.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.targets.iter().filter(|t| t.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // Trailing unregistered targets are popped.
+        self.targets.is_empty()
+    }
+
+    /// Keys of registered targets, along with the type names of widgets which
+    /// registered them (known only in debug builds).
+    pub fn registered(&self) -> impl Iterator<Item = (ClipboardTargetKey, &'static str)> + '_ {
+        self.targets
+            .iter()
+            .zip(&self.registrars)
+            .enumerate()
+            .filter(|(_, (target, _))| target.is_some())
+            .map(|(n, (_, registrar))| (ClipboardTargetKey(n), *registrar))
+    }
+
+    fn focused(&self) -> Option<*const dyn ClipboardTarget> {
+        self.focused.and_then(|idx| self.targets[idx])
+    }
+}
+
+thread_local! {
+    pub static CLIPBOARD_TARGETS: RefCell<ClipboardTargets> = const {
+        RefCell::new(ClipboardTargets {
+            targets: Vec::new(),
+            registrars: Vec::new(),
+            focused: None,
+        })
+    };
+}
+
+/// Dispatches the clipboard shortcut of `event` to the focused target,
+/// returning whether it was dispatched.
+pub(crate) fn dispatch(event: &KeyEvent) -> bool {
+    dispatch_with(event, || {
+        druid_shell::Application::try_global().and_then(|app| app.clipboard().get_string())
+    })
+}
+
+fn dispatch_with(event: &KeyEvent, read_clipboard: impl FnOnce() -> Option<String>) -> bool {
+    let shortcut = match ClipboardShortcut::from_key_event(event) {
+        Some(shortcut) => shortcut,
+        None => return false,
+    };
+
+    // Borrow is released before calling the target, which may (un)focus
+    // itself or other targets.
+    let target = match CLIPBOARD_TARGETS.with(|targets| targets.borrow().focused()) {
+        Some(target) => target,
+        None => return false,
+    };
+
+    // Safety: `target` is valid as ensured by registrars to `ClipboardTargets`.
+    let target = unsafe { &*target };
+
+    match shortcut {
+        ClipboardShortcut::Copy => target.copy(),
+        ClipboardShortcut::Cut => target.cut(),
+        ClipboardShortcut::Paste => {
+            if let Some(text) = read_clipboard() {
+                target.paste(&text);
+            }
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn shortcut(key: KbKey, mods: Modifiers) -> KeyEvent {
+        let mut event = KeyEvent::default();
+        event.key = key;
+        event.mods = mods;
+        event
+    }
+
+    fn command() -> Modifiers {
+        if cfg!(target_os = "macos") {
+            Modifiers::META
+        } else {
+            Modifiers::CONTROL
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder(RefCell<Vec<String>>);
+
+    impl ClipboardTarget for Recorder {
+        fn copy(&self) {
+            self.0.borrow_mut().push("copy".into());
+        }
+
+        fn paste(&self, text: &str) {
+            self.0.borrow_mut().push(format!("paste {text}"));
+        }
+    }
+
+    #[test]
+    fn shortcuts_are_recognized_with_command_modifier() {
+        let key = |c: &str| KbKey::Character(c.into());
+
+        assert_eq!(
+            ClipboardShortcut::from_key_event(&shortcut(key("c"), command())),
+            Some(ClipboardShortcut::Copy)
+        );
+        assert_eq!(
+            ClipboardShortcut::from_key_event(&shortcut(
+                key("X"),
+                command() | Modifiers::CAPS_LOCK
+            )),
+            Some(ClipboardShortcut::Cut)
+        );
+        assert_eq!(
+            ClipboardShortcut::from_key_event(&shortcut(key("v"), command() | Modifiers::SHIFT)),
+            None
+        );
+        assert_eq!(
+            ClipboardShortcut::from_key_event(&shortcut(key("v"), Modifiers::empty())),
+            None
+        );
+    }
+
+    #[test]
+    fn only_focused_target_receives_shortcuts() {
+        let (first, second) = (Recorder::default(), Recorder::default());
+        let copy = shortcut(KbKey::Character("c".into()), command());
+        let paste = shortcut(KbKey::Character("v".into()), command());
+
+        let (first_key, second_key) = CLIPBOARD_TARGETS.with(|targets| unsafe {
+            let mut targets = targets.borrow_mut();
+            (targets.register(&first), targets.register(&second))
+        });
+
+        // Nothing is focused yet.
+        assert!(!dispatch_with(&copy, || None));
+
+        CLIPBOARD_TARGETS.with(|targets| targets.borrow_mut().focus(&second_key));
+        assert!(dispatch_with(&copy, || None));
+        assert!(dispatch_with(&paste, || Some("text".into())));
+
+        // Empty clipboard is not pasted, but the shortcut is still consumed.
+        assert!(dispatch_with(&paste, || None));
+
+        assert!(first.0.borrow().is_empty());
+        assert_eq!(*second.0.borrow(), ["copy", "paste text"]);
+
+        // Unregistered target loses focus.
+        CLIPBOARD_TARGETS.with(|targets| {
+            let mut targets = targets.borrow_mut();
+            targets.unregister(&second_key);
+            targets.unregister(&first_key);
+            assert!(targets.is_empty());
+        });
+
+        assert!(!dispatch_with(&copy, || None));
+    }
+}
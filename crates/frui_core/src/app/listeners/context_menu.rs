@@ -0,0 +1,206 @@
+//! Context menus opened by widgets (usually `ContextMenuRegion` of
+//! `frui_widgets`).
+//!
+//! Menus are native pop-up menus of the platform, which also places them on
+//! screen, handles their submenus and keyboard navigation, and dismisses them
+//! once the pointer is pressed outside of them or Escape is pressed. At most
+//! one menu is open at a time. Once an item of it is selected, the widget which
+//! opened it is notified with the id of that item.
+
+use std::cell::RefCell;
+
+use druid_shell::kurbo::Point;
+
+pub use druid_shell::Menu;
+
+use crate::app::runner::WindowHandle;
+
+/// Widget which opened a context menu.
+pub trait ContextMenuOwner {
+    /// Called once the item with `id` was selected in the menu of this owner.
+    fn item_selected(&self, id: u32);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextMenuKey(u64);
+
+struct OpenMenu {
+    key: ContextMenuKey,
+    owner: *const dyn ContextMenuOwner,
+}
+
+/// Context menu which is open, if any.
+pub struct ContextMenus {
+    open: Option<OpenMenu>,
+    /// Menu to show once the event which opened it is handled, along with its
+    /// position in the window.
+    pending: Option<(Menu, Point)>,
+    next_key: u64,
+}
+
+impl ContextMenus {
+    /// Opens `menu` at `pos` (in the coordinates of the window), closing the
+    /// menu opened before. Menu is shown once the current event is handled.
+    ///
+    /// # Safety
+    ///
+    /// Value `owner` points to must live until [`close`](Self::close) or
+    /// [`update`](Self::update) is called with the returned key.
+    pub unsafe fn open<'a>(
+        &mut self,
+        owner: *const (dyn ContextMenuOwner + 'a),
+        menu: Menu,
+        pos: Point,
+    ) -> ContextMenuKey {
+        let key = ContextMenuKey(self.next_key);
+        self.next_key += 1;
+
+        self.open = Some(OpenMenu {
+            key,
+            owner: std::mem::transmute::<
+                *const (dyn ContextMenuOwner + 'a),
+                *const dyn ContextMenuOwner,
+            >(owner),
+        });
+        self.pending = Some((menu, pos));
+
+        key
+    }
+
+    /// Replaces the owner of the menu opened with `key`, if it's still open.
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`open`](Self::open).
+    pub unsafe fn update<'a>(
+        &mut self,
+        key: &ContextMenuKey,
+        owner: *const (dyn ContextMenuOwner + 'a),
+    ) {
+        if let Some(open) = self.open.as_mut().filter(|open| open.key == *key) {
+            open.owner = std::mem::transmute::<
+                *const (dyn ContextMenuOwner + 'a),
+                *const dyn ContextMenuOwner,
+            >(owner);
+        }
+    }
+
+    /// Forgets the menu opened with `key`, so that its owner isn't notified
+    /// about its items anymore.
+    ///
+    /// Menu which is already displayed by the platform can't be closed, but
+    /// selecting its items does nothing.
+    pub fn close(&mut self, key: &ContextMenuKey) {
+        if self.is_open(key) {
+            self.open = None;
+            self.pending = None;
+        }
+    }
+
+    /// Whether a menu was opened while handling the current event.
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Whether the menu opened with `key` is the open menu. Menu dismissed by
+    /// the platform without selecting anything is considered open until
+    /// another menu is opened.
+    pub fn is_open(&self, key: &ContextMenuKey) -> bool {
+        self.open.as_ref().is_some_and(|open| open.key == *key)
+    }
+}
+
+thread_local! {
+    pub static CONTEXT_MENUS: RefCell<ContextMenus> = const {
+        RefCell::new(ContextMenus {
+            open: None,
+            pending: None,
+            next_key: 0,
+        })
+    };
+}
+
+/// Shows a menu opened while handling the last event in the window of
+/// `handle`.
+pub(crate) fn show_pending(handle: &WindowHandle) {
+    let pending = CONTEXT_MENUS.with(|menus| menus.borrow_mut().pending.take());
+
+    if let Some((menu, pos)) = pending {
+        handle.show_context_menu(menu, pos);
+    }
+}
+
+/// Notifies the owner of the open menu that its item with `id` was selected,
+/// returning whether there was an open menu.
+pub(crate) fn dispatch(id: u32) -> bool {
+    // Menu is closed before calling the owner, which may open another one.
+    let open = CONTEXT_MENUS.with(|menus| menus.borrow_mut().open.take());
+
+    match open {
+        // Safety: owner is valid as ensured by openers of `ContextMenus`.
+        Some(open) => {
+            unsafe { &*open.owner }.item_selected(id);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Owner {
+        selected: Cell<Option<u32>>,
+    }
+
+    impl ContextMenuOwner for Owner {
+        fn item_selected(&self, id: u32) {
+            self.selected.set(Some(id));
+        }
+    }
+
+    fn open(owner: &Owner) -> ContextMenuKey {
+        CONTEXT_MENUS.with(|menus| unsafe {
+            let mut menus = menus.borrow_mut();
+            let key = menus.open(owner, Menu::new_for_popup(), Point::ZERO);
+
+            // Menu isn't shown outside of a window.
+            menus.pending = None;
+            key
+        })
+    }
+
+    #[test]
+    fn only_owner_of_the_open_menu_is_notified() {
+        let (first, second) = (Owner::default(), Owner::default());
+
+        let first_key = open(&first);
+        let second_key = open(&second);
+
+        // Opening the second menu closed the first one.
+        CONTEXT_MENUS.with(|menus| {
+            let menus = menus.borrow();
+            assert!(!menus.is_open(&first_key));
+            assert!(menus.is_open(&second_key));
+        });
+
+        assert!(dispatch(3));
+        assert_eq!(
+            (first.selected.get(), second.selected.get()),
+            (None, Some(3))
+        );
+
+        // Selection closes the menu.
+        assert!(!dispatch(4));
+
+        // Closed menu doesn't notify its owner.
+        let key = open(&first);
+        CONTEXT_MENUS.with(|menus| menus.borrow_mut().close(&key));
+        assert!(!dispatch(5));
+        assert_eq!(first.selected.get(), None);
+    }
+}
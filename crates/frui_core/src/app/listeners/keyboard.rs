@@ -1,17 +1,30 @@
 //! This implementation of KeyboardEventListeners
 
-use std::cell::RefCell;
+use std::{cell::RefCell, collections::HashSet};
 
-use druid_shell::KeyEvent;
+use druid_shell::{Code, KeyEvent, KeyState};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CallbackKey(usize);
 
 pub struct KeyboardEventListeners {
-    callbacks: Vec<*const dyn Fn(KeyEvent)>,
+    /// Registered callbacks. Unregistered ones are left as `None`, so that keys
+    /// of the remaining callbacks stay valid.
+    callbacks: Vec<Option<*const dyn Fn(KeyEvent)>>,
+    /// Widget which registered each of `callbacks`, see [`registered`].
+    ///
+    /// [`registered`]: Self::registered
+    registrars: Vec<&'static str>,
 }
 
 impl KeyboardEventListeners {
+    pub(crate) const fn new() -> Self {
+        KeyboardEventListeners {
+            callbacks: Vec::new(),
+            registrars: Vec::new(),
+        }
+    }
+
     /// Registers a callback which will be called when a keyboard event is received.
     ///
     /// ## Safety:
@@ -22,19 +35,57 @@ impl KeyboardEventListeners {
     /// with [`CallbackKey`] returned from this function, before that `callback` is
     /// going to be dropped.
     pub unsafe fn register<'a>(&mut self, callback: *const (dyn Fn(KeyEvent) + 'a)) -> CallbackKey {
-        self.callbacks.push(std::mem::transmute(callback));
-        // Todo: Fix this. After unregister-ing one callback, calling unregister again will
-        // cause a panic. Use SlotMap as a fix.
+        self.callbacks.push(Some(std::mem::transmute(callback)));
+        self.registrars.push(super::registrar());
         CallbackKey(self.callbacks.len() - 1)
     }
 
+    /// Replaces the callback registered with `key`, keeping its key and its
+    /// position among other callbacks.
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`register`](Self::register), value `callback` points to
+    /// must live until [`unregister`](Self::unregister) or another `update` is
+    /// called.
+    pub unsafe fn update<'a>(
+        &mut self,
+        key: &CallbackKey,
+        callback: *const (dyn Fn(KeyEvent) + 'a),
+    ) {
+        let slot = &mut self.callbacks[key.0];
+
+        assert!(slot.is_some(), "callback of {key:?} was unregistered");
+
+        *slot = Some(std::mem::transmute::<
+            *const (dyn Fn(KeyEvent) + 'a),
+            *const dyn Fn(KeyEvent),
+        >(callback));
+    }
+
     pub fn unregister(&mut self, key: &CallbackKey) {
-        // Todo: Optimize this.
-        self.callbacks.remove(key.0);
+        self.callbacks[key.0] = None;
+
+        // Shrink, so that repeatedly registered callbacks don't leave holes.
+        while let Some(None) = self.callbacks.last() {
+            self.callbacks.pop();
+            self.registrars.pop();
+        }
     }
 
     pub fn len(&self) -> usize {
-        self.callbacks.len()
+        self.callbacks.iter().filter(|c| c.is_some()).count()
+    }
+
+    /// Keys of registered callbacks, along with the type names of widgets
+    /// which registered them (known only in debug builds).
+    pub fn registered(&self) -> impl Iterator<Item = (CallbackKey, &'static str)> + '_ {
+        self.callbacks
+            .iter()
+            .zip(&self.registrars)
+            .enumerate()
+            .filter(|(_, (callback, _))| callback.is_some())
+            .map(|(n, (_, registrar))| (CallbackKey(n), *registrar))
     }
 
     pub(crate) fn iter<'a>(&'a self) -> CallbackIter<'a> {
@@ -47,28 +98,151 @@ impl KeyboardEventListeners {
 
 pub struct CallbackIter<'a> {
     idx: usize,
-    callbacks: &'a Vec<*const dyn Fn(KeyEvent)>,
+    callbacks: &'a Vec<Option<*const dyn Fn(KeyEvent)>>,
 }
 
 impl<'a> Iterator for CallbackIter<'a> {
     type Item = &'a dyn Fn(KeyEvent);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.idx == self.callbacks.len() {
-            return None;
+        while self.idx < self.callbacks.len() {
+            self.idx += 1;
+
+            if let Some(callback) = self.callbacks[self.idx - 1] {
+                // Safety: `callback` is valid as ensured by registrars to `KeyboardEventListeners`.
+                return unsafe { callback.as_ref() };
+            }
         }
 
-        self.idx += 1;
+        None
+    }
+}
+
+/// Physical keys which are currently held down.
+///
+/// It is maintained by the framework from key down and key up events of the
+/// window and can be read at any time (e.g. during build or from an event
+/// handler) through [`KeyboardState::with`].
+///
+/// When the window loses focus, it stops receiving key events, so all keys are
+/// treated as released at that point.
+#[derive(Debug, Default)]
+pub struct KeyboardState {
+    pressed: HashSet<Code>,
+}
+
+impl KeyboardState {
+    /// Calls `f` with the current keyboard state.
+    pub fn with<R>(f: impl FnOnce(&KeyboardState) -> R) -> R {
+        KEYBOARD_STATE.with(|state| f(&state.borrow()))
+    }
+
+    /// Whether the physical key `code` is currently held down.
+    pub fn is_pressed(&self, code: Code) -> bool {
+        self.pressed.contains(&code)
+    }
+
+    /// Whether either of the Alt keys is currently held down, e.g. to display
+    /// mnemonics.
+    pub fn is_alt_held(&self) -> bool {
+        self.is_pressed(Code::AltLeft) || self.is_pressed(Code::AltRight)
+    }
+
+    /// Physical keys which are currently held down, in no particular order.
+    pub fn pressed(&self) -> impl Iterator<Item = Code> + '_ {
+        self.pressed.iter().copied()
+    }
 
-        // Safety: `callback` is valid as ensured by registrars to `KeyboardEventListeners`.
-        unsafe { self.callbacks[self.idx - 1].as_ref() }
+    pub(crate) fn handle_event(&mut self, event: &KeyEvent) {
+        match event.state {
+            KeyState::Down => self.pressed.insert(event.code),
+            KeyState::Up => self.pressed.remove(&event.code),
+        };
+    }
+
+    /// Releases all keys, returning key up events for keys that were held.
+    pub(crate) fn release_all(&mut self) -> Vec<KeyEvent> {
+        self.pressed
+            .drain()
+            .map(|code| {
+                let mut event = KeyEvent::default();
+                event.state = KeyState::Up;
+                event.code = code;
+                event
+            })
+            .collect()
     }
 }
 
+thread_local! {
+    pub(crate) static KEYBOARD_STATE: RefCell<KeyboardState> = RefCell::new(KeyboardState::default());
+}
+
 thread_local! {
     /// Todo: Optimize this with something like SlotMap<(&f, PreviousNode, NextNode)> ?
     /// Basically linked tree but with advantages of slot map...?
-    pub static KEYBOARD_EVENT_LISTENERS: RefCell<KeyboardEventListeners>  = RefCell::new(KeyboardEventListeners {
-        callbacks: Vec::with_capacity(100),
-    });
+    pub static KEYBOARD_EVENT_LISTENERS: RefCell<KeyboardEventListeners>  = const { RefCell::new(KeyboardEventListeners::new()) };
+}
+
+thread_local! {
+    /// Listeners of both key down and key up events, used by raw keyboard
+    /// listeners.
+    pub static RAW_KEYBOARD_EVENT_LISTENERS: RefCell<KeyboardEventListeners>  = const { RefCell::new(KeyboardEventListeners::new()) };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn event(state: KeyState, code: Code) -> KeyEvent {
+        let mut event = KeyEvent::default();
+        event.state = state;
+        event.code = code;
+        event
+    }
+
+    #[test]
+    fn keys_stay_valid_after_other_callbacks_are_unregistered() {
+        let received = std::rc::Rc::new(RefCell::new(Vec::new()));
+        let callback = |n: usize| {
+            let received = received.clone();
+            move |_: KeyEvent| received.borrow_mut().push(n)
+        };
+        let (first, second, updated) = (callback(1), callback(2), callback(3));
+
+        let mut listeners = KeyboardEventListeners::new();
+
+        unsafe {
+            let first = listeners.register(&first);
+            let second = listeners.register(&second);
+
+            listeners.unregister(&first);
+            listeners.update(&second, &updated);
+        }
+
+        for listener in listeners.iter() {
+            listener(KeyEvent::default());
+        }
+
+        assert_eq!(listeners.len(), 1);
+        assert_eq!(*received.borrow(), vec![3]);
+    }
+
+    #[test]
+    fn released_keys_are_not_held() {
+        let mut state = KeyboardState::default();
+
+        state.handle_event(&event(KeyState::Down, Code::KeyW));
+        state.handle_event(&event(KeyState::Down, Code::KeyA));
+        state.handle_event(&event(KeyState::Up, Code::KeyA));
+
+        assert!(state.is_pressed(Code::KeyW));
+        assert!(!state.is_pressed(Code::KeyA));
+
+        // Losing focus releases remaining keys.
+        let released = state.release_all();
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].code, Code::KeyW);
+        assert_eq!(state.pressed().count(), 0);
+    }
 }
@@ -0,0 +1,140 @@
+//! Detection of listeners which outlived the widgets that registered them.
+//!
+//! In debug builds, every registry remembers which widget registered each of
+//! its entries, i.e. the widget whose lifecycle method (like `mount`) was
+//! running at that time.
+
+use std::{cell::Cell, fmt::Write};
+
+use super::{
+    clipboard::CLIPBOARD_TARGETS,
+    keyboard::{KEYBOARD_EVENT_LISTENERS, RAW_KEYBOARD_EVENT_LISTENERS},
+    shortcuts::SHORTCUT_SCOPES,
+};
+
+/// Registrar of entries registered outside of widget lifecycle methods.
+const UNKNOWN_REGISTRAR: &str = "<unknown>";
+
+thread_local! {
+    static REGISTRAR: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+/// Runs `f`, attributing entries registered during it to the widget `name`.
+pub(crate) fn with_registrar<R>(name: &'static str, f: impl FnOnce() -> R) -> R {
+    if !cfg!(debug_assertions) {
+        return f();
+    }
+
+    let previous = REGISTRAR.with(|registrar| registrar.replace(Some(name)));
+    let r = f();
+    REGISTRAR.with(|registrar| registrar.set(previous));
+    r
+}
+
+/// Name of the widget currently registering entries.
+pub(crate) fn registrar() -> &'static str {
+    REGISTRAR
+        .with(|registrar| registrar.get())
+        .unwrap_or(UNKNOWN_REGISTRAR)
+}
+
+/// Panics if any listener registry still has entries, listing each of them
+/// along with the widget type which registered it.
+///
+/// Call this after every widget tree was dropped, e.g. at the end of a test,
+/// to turn listeners which weren't unregistered into test failures. Does
+/// nothing in release builds.
+#[track_caller]
+pub fn debug_assert_no_leaks() {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    if let Some(report) = leak_report() {
+        panic!("{report}");
+    }
+}
+
+/// Logs entries of listener registries, if there are any. Called once the last
+/// widget tree was dropped.
+pub(crate) fn warn_about_leaks() {
+    if cfg!(debug_assertions) {
+        if let Some(report) = leak_report() {
+            diagnostic!(warn, "leaks", "{report}");
+        }
+    }
+}
+
+fn leak_report() -> Option<String> {
+    let mut leaks = Vec::new();
+
+    KEYBOARD_EVENT_LISTENERS.with(|listeners| {
+        for (key, registrar) in listeners.borrow().registered() {
+            leaks.push(format!(
+                "`KEYBOARD_EVENT_LISTENERS`: {key:?} registered by `{registrar}`"
+            ));
+        }
+    });
+
+    RAW_KEYBOARD_EVENT_LISTENERS.with(|listeners| {
+        for (key, registrar) in listeners.borrow().registered() {
+            leaks.push(format!(
+                "`RAW_KEYBOARD_EVENT_LISTENERS`: {key:?} registered by `{registrar}`"
+            ));
+        }
+    });
+
+    CLIPBOARD_TARGETS.with(|targets| {
+        for (key, registrar) in targets.borrow().registered() {
+            leaks.push(format!(
+                "`CLIPBOARD_TARGETS`: {key:?} registered by `{registrar}`"
+            ));
+        }
+    });
+
+    SHORTCUT_SCOPES.with(|scopes| {
+        for (key, registrar) in scopes.borrow().registered() {
+            leaks.push(format!(
+                "`SHORTCUT_SCOPES`: {key:?} registered by `{registrar}`"
+            ));
+        }
+    });
+
+    if leaks.is_empty() {
+        return None;
+    }
+
+    let mut report = format!("{} listener(s) were never unregistered:", leaks.len());
+
+    for leak in leaks {
+        let _ = write!(report, "\n  {leak}");
+    }
+
+    Some(report)
+}
+
+#[cfg(test)]
+mod test {
+    use druid_shell::KeyEvent;
+
+    use super::*;
+
+    #[test]
+    fn leaked_listeners_are_reported_with_their_registrar() {
+        let callback = |_: KeyEvent| {};
+
+        let key = with_registrar("Leaky", || {
+            KEYBOARD_EVENT_LISTENERS
+                .with(|listeners| unsafe { listeners.borrow_mut().register(&callback) })
+        });
+
+        let report = leak_report().unwrap();
+        assert!(report.contains("CallbackKey(0) registered by `Leaky`"));
+
+        // Registrar is forgotten outside of `with_registrar`.
+        assert_eq!(registrar(), UNKNOWN_REGISTRAR);
+
+        KEYBOARD_EVENT_LISTENERS.with(|listeners| listeners.borrow_mut().unregister(&key));
+        debug_assert_no_leaks();
+    }
+}
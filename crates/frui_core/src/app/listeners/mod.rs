@@ -1 +1,10 @@
+pub mod clipboard;
+pub mod context_menu;
 pub mod keyboard;
+pub(crate) mod pointer;
+pub mod shortcuts;
+
+mod leaks;
+
+pub use leaks::debug_assert_no_leaks;
+pub(crate) use leaks::{registrar, warn_about_leaks, with_registrar};
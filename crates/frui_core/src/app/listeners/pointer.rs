@@ -0,0 +1,532 @@
+//! Dispatch of pointer events, honoring the pointer capture (which routes
+//! pointer events to the widget which captured the pointer, regardless of
+//! where the pointer is) and the [`PointerBehavior`] of widgets.
+
+use std::cell::RefCell;
+
+use druid_shell::{
+    kurbo::{Point, Rect},
+    MouseButton, MouseButtons, MouseEvent,
+};
+
+use crate::{
+    api::{
+        contexts::render_ctx::AnyRenderContext,
+        events::{Event, PointerBehavior},
+    },
+    app::tree::{WidgetNode, WidgetNodeRef},
+};
+
+thread_local! {
+    static POINTER_CAPTURE: RefCell<Option<PointerCapture>> = const { RefCell::new(None) };
+    /// Last dispatched pointer event.
+    static LAST_EVENT: RefCell<Option<MouseEvent>> = const { RefCell::new(None) };
+    static ABSORPTION: RefCell<Option<Absorption>> = const { RefCell::new(None) };
+}
+
+struct PointerCapture {
+    node: WidgetNodeRef,
+    /// Button pressed when the pointer was captured.
+    button: MouseButton,
+    /// Whether the captured node received the currently dispatched event.
+    received: bool,
+}
+
+/// Captures the pointer for `node`, replacing the previous capture.
+pub(crate) fn capture(node: &WidgetNodeRef) {
+    let button = LAST_EVENT.with(|e| e.borrow().as_ref().map(|e| e.button));
+
+    POINTER_CAPTURE.with(|capture| {
+        *capture.borrow_mut() = Some(PointerCapture {
+            node: node.clone(),
+            button: button.unwrap_or(MouseButton::Left),
+            received: true,
+        })
+    });
+}
+
+/// Releases the pointer if it is captured by `node`.
+pub(crate) fn release(node: &WidgetNodeRef) {
+    POINTER_CAPTURE.with(|capture| {
+        let mut capture = capture.borrow_mut();
+
+        if capture.as_ref().is_some_and(|c| &c.node == node) {
+            *capture = None;
+        }
+    });
+}
+
+pub(crate) fn is_captured_by(node: &WidgetNodeRef) -> bool {
+    POINTER_CAPTURE.with(|capture| capture.borrow().as_ref().is_some_and(|c| &c.node == node))
+}
+
+/// Records that `node` received the currently dispatched event.
+pub(crate) fn mark_received(node: &WidgetNodeRef) {
+    POINTER_CAPTURE.with(|capture| {
+        if let Some(capture) = capture.borrow_mut().as_mut() {
+            if &capture.node == node {
+                capture.received = true;
+            }
+        }
+    });
+}
+
+/// Dispatches `event` to the tree of `root`, then delivers it to the widget
+/// which captured the pointer if that widget didn't receive it (e.g. because
+/// the pointer left its bounds). Capture is released on pointer up and when
+/// the capturing widget was unmounted.
+pub(crate) fn dispatch(root: &WidgetNodeRef, event: Event) {
+    let is_captured_event = match &event {
+        Event::MouseDown(e) | Event::MouseUp(e) | Event::MouseMove(e) | Event::MouseWheel(e) => {
+            LAST_EVENT.with(|last| *last.borrow_mut() = Some(e.clone()));
+            matches!(event, Event::MouseMove(_) | Event::MouseUp(_))
+        }
+    };
+
+    let captured = POINTER_CAPTURE.with(|capture| {
+        let mut capture = capture.borrow_mut();
+
+        match capture.as_mut() {
+            Some(c) if c.node.is_alive() => {
+                c.received = false;
+                is_captured_event
+            }
+            _ => {
+                *capture = None;
+                false
+            }
+        }
+    });
+
+    let absorption = event.pointer_pos().and_then(|pos| {
+        let mut absorber = None;
+        find_absorber(root, pos, &mut absorber);
+        absorber.map(Absorption::new)
+    });
+
+    ABSORPTION.with(|a| *a.borrow_mut() = absorption);
+    root.handle_event(event.clone());
+    ABSORPTION.with(|a| *a.borrow_mut() = None);
+
+    if !captured {
+        return;
+    }
+
+    let pending = POINTER_CAPTURE.with(|capture| {
+        let mut capture = capture.borrow_mut();
+
+        let pending = match capture.as_ref() {
+            Some(c) if !c.received && c.node.is_alive() => Some(c.node.clone()),
+            _ => None,
+        };
+
+        if let Event::MouseUp(_) = event {
+            *capture = None;
+        }
+
+        pending
+    });
+
+    if let Some(node) = pending {
+        AnyRenderContext::new(node).handle_event(&event);
+    }
+}
+
+/// Widget absorbing the currently dispatched pointer event, see
+/// [`PointerBehavior::Absorb`].
+struct Absorption {
+    absorber: WidgetNodeRef,
+    ancestors: Vec<WidgetNodeRef>,
+    /// Whether the absorber was already visited during the dispatch.
+    reached: bool,
+}
+
+impl Absorption {
+    fn new(absorber: WidgetNodeRef) -> Self {
+        let mut ancestors = Vec::new();
+        let mut node = absorber.parent();
+
+        while let Some(n) = node {
+            node = n.parent();
+            ancestors.push(n);
+        }
+
+        Absorption {
+            absorber,
+            ancestors,
+            reached: false,
+        }
+    }
+}
+
+/// Finds the last visited (painted on top) absorbing widget whose bounds
+/// contain `pos`.
+///
+/// Bounds of widgets are the ones they were laid out with, at their own
+/// origin, so `pos` (in the coordinates of the window) is mapped through the
+/// inverse of the transform every widget was painted with.
+fn find_absorber(node: &WidgetNodeRef, pos: Point, absorber: &mut Option<WidgetNodeRef>) {
+    let (behavior, bounds, transform) = {
+        let render_data = &node.borrow().render_data;
+        let bounds = Rect::from_origin_size(Point::ORIGIN, render_data.size);
+        (
+            render_data.pointer_behavior,
+            bounds,
+            render_data.paint_transform,
+        )
+    };
+
+    // Widgets collapsed by a transform (and their descendants) can't be hit.
+    if transform.determinant() == 0. {
+        return;
+    }
+
+    match behavior {
+        // Only children laid out during last layout of their parent were placed
+        // (e.g. the visible ones of a long list), the others can't be hit.
+        PointerBehavior::Pass => {
+            let laid_out = node.borrow().render_data.laid_out_children.clone();
+
+            for child in laid_out.into_iter().filter_map(|n| node.children().get(n)) {
+                find_absorber(&WidgetNode::node_ref(child), pos, absorber);
+            }
+        }
+        PointerBehavior::Absorb if bounds.contains(transform.inverse() * pos) => {
+            *absorber = Some(node.clone())
+        }
+        // Descendants of an absorbing widget don't receive pointer events.
+        PointerBehavior::Absorb | PointerBehavior::Ignore => {}
+    }
+}
+
+/// Whether the currently dispatched pointer event should be delivered to
+/// `node`, i.e. `node` isn't behind a widget absorbing it.
+pub(crate) fn is_delivered_to(node: &WidgetNodeRef) -> bool {
+    ABSORPTION.with(|a| match a.borrow_mut().as_mut() {
+        Some(a) if !a.reached => {
+            if &a.absorber == node {
+                a.reached = true;
+                true
+            } else {
+                a.ancestors.contains(node)
+            }
+        }
+        _ => true,
+    })
+}
+
+/// Whether `node` absorbs the currently dispatched pointer event, i.e. its
+/// descendants shouldn't receive it.
+pub(crate) fn is_absorbed_by(node: &WidgetNodeRef) -> bool {
+    ABSORPTION.with(|a| a.borrow().as_ref().is_some_and(|a| &a.absorber == node))
+}
+
+/// Releases the pointer capture after the window lost focus, delivering
+/// pointer up event to the capturing widget, since the real one will not be
+/// received.
+pub(crate) fn release_on_focus_lost() {
+    let capture = POINTER_CAPTURE.with(|capture| capture.borrow_mut().take());
+    let last_event = LAST_EVENT.with(|last| last.borrow().clone());
+
+    if let (Some(capture), Some(mut event)) = (capture, last_event) {
+        if capture.node.is_alive() {
+            event.button = capture.button;
+            event.buttons = MouseButtons::new();
+
+            AnyRenderContext::new(capture.node).handle_event(&Event::MouseUp(event));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{any::TypeId, cell::Cell, rc::Rc};
+
+    use druid_shell::{
+        kurbo::{Affine, Point, Vec2},
+        Modifiers,
+    };
+
+    use super::*;
+    use crate::{
+        api::{
+            events::WidgetEvent,
+            implementors::{leaf::LeafWidget, multi::MultiChildWidget, WidgetDerive},
+            Widget, WidgetKind, WidgetPtr,
+        },
+        app::tree::WidgetTree,
+        prelude::{BuildContext, Constraints, PaintContext, RenderContext, Size},
+    };
+
+    /// Counts received events, capturing the pointer on pointer down.
+    struct Recorder(Rc<Cell<usize>>);
+
+    impl Widget for Recorder {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Recorder>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::Leaf(self)
+        }
+    }
+
+    impl WidgetDerive for Recorder {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = Recorder;
+    }
+
+    impl LeafWidget for Recorder {
+        fn layout(&self, _: RenderContext<Self>, _: Constraints) -> Size {
+            Size::default()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    impl WidgetEvent for Recorder {
+        fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+            self.0.set(self.0.get() + 1);
+
+            if let Event::MouseDown(_) = event {
+                ctx.capture_pointer();
+            }
+
+            false
+        }
+    }
+
+    /// Passes events to its child unless `blocking` is set.
+    struct Blocker {
+        child: Recorder,
+        blocking: Rc<Cell<bool>>,
+    }
+
+    impl Widget for Blocker {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Blocker>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::MultiChild(self)
+        }
+    }
+
+    impl WidgetDerive for Blocker {
+        type Widget<'a> = &'a dyn Widget;
+
+        type UniqueTypeId = Blocker;
+    }
+
+    impl MultiChildWidget for Blocker {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+            vec![&self.child]
+        }
+
+        fn layout(&self, _: RenderContext<Self>, _: Constraints) -> Size {
+            Size::default()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    impl WidgetEvent for Blocker {
+        fn handle_event(&self, _: RenderContext<Self>, _: &Event) -> bool {
+            self.blocking.get()
+        }
+    }
+
+    /// Sets `behavior` of its recording child, covering the area of the
+    /// widgets laid out before it.
+    struct Behaving {
+        behavior: PointerBehavior,
+        child: Recorder,
+    }
+
+    impl Widget for Behaving {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Behaving>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::MultiChild(self)
+        }
+    }
+
+    impl WidgetDerive for Behaving {
+        type Widget<'a> = &'a dyn Widget;
+
+        type UniqueTypeId = Behaving;
+    }
+
+    impl MultiChildWidget for Behaving {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+            vec![&self.child]
+        }
+
+        fn layout(&self, ctx: RenderContext<Self>, _: Constraints) -> Size {
+            ctx.set_pointer_behavior(self.behavior);
+            Size::new(100., 100.)
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    /// Lays out the first `laid_out` of its layers on top of each other.
+    struct Layers {
+        layers: Vec<Behaving>,
+        laid_out: Rc<Cell<usize>>,
+    }
+
+    impl Widget for Layers {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Layers>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::MultiChild(self)
+        }
+    }
+
+    impl WidgetDerive for Layers {
+        type Widget<'a> = &'a dyn Widget;
+
+        type UniqueTypeId = Layers;
+    }
+
+    impl MultiChildWidget for Layers {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+            self.layers.iter().map(|w| w as &dyn Widget).collect()
+        }
+
+        fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+            for mut child in ctx.children().take(self.laid_out.get()) {
+                child.layout(constraints);
+            }
+
+            Size::new(100., 100.)
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    /// Whether a pointer at `pos` hits an absorbing widget of size 100 by 100
+    /// painted with `transform`.
+    fn hits_transformed(transform: Affine, pos: Point) -> bool {
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(Behaving {
+            behavior: PointerBehavior::Absorb,
+            child: Recorder(Rc::new(Cell::new(0))),
+        })));
+
+        tree.layout(Constraints::loose(Size::new(100., 100.)));
+
+        // As recorded during paint.
+        let root = tree.get_root();
+        root.borrow_mut().render_data.paint_transform = transform;
+
+        let mut absorber = None;
+        find_absorber(&root, pos, &mut absorber);
+        absorber.is_some()
+    }
+
+    #[test]
+    fn hit_test_follows_transformed_children() {
+        let center = Vec2::new(50., 50.);
+        let rotation = Affine::translate(center)
+            * Affine::rotate(std::f64::consts::FRAC_PI_4)
+            * Affine::translate(-center);
+
+        // Rotated square covers the area above its untransformed bounds, but
+        // not their corners.
+        assert!(hits_transformed(rotation, Point::new(50., -15.)));
+        assert!(!hits_transformed(rotation, Point::new(5., 5.)));
+        assert!(hits_transformed(Affine::default(), Point::new(5., 5.)));
+
+        // Widgets collapsed to a point can't be hit.
+        assert!(!hits_transformed(Affine::scale(0.), Point::ZERO));
+    }
+
+    /// Dispatches a pointer move to a recorder below another one with the
+    /// given `behavior`, returning the number of events both of them received.
+    ///
+    /// Both layers are laid out first, then only the `laid_out` first ones.
+    fn dispatch_to_layers(behavior: PointerBehavior, laid_out: usize) -> (usize, usize) {
+        let below = Rc::new(Cell::new(0));
+        let above = Rc::new(Cell::new(0));
+        let layers = Rc::new(Cell::new(2));
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(Layers {
+            layers: vec![
+                Behaving {
+                    behavior: PointerBehavior::Pass,
+                    child: Recorder(below.clone()),
+                },
+                Behaving {
+                    behavior,
+                    child: Recorder(above.clone()),
+                },
+            ],
+            laid_out: layers.clone(),
+        })));
+
+        tree.layout(Constraints::loose(Size::new(100., 100.)));
+
+        layers.set(laid_out);
+        tree.get_root().mark_needs_layout();
+        tree.layout(Constraints::loose(Size::new(100., 100.)));
+
+        tree.handle_event(Event::MouseMove(mouse_event()));
+
+        (below.get(), above.get())
+    }
+
+    #[test]
+    fn pointer_behavior_controls_which_widgets_receive_events() {
+        assert_eq!(dispatch_to_layers(PointerBehavior::Pass, 2), (1, 1));
+        assert_eq!(dispatch_to_layers(PointerBehavior::Ignore, 2), (1, 0));
+        assert_eq!(dispatch_to_layers(PointerBehavior::Absorb, 2), (0, 0));
+    }
+
+    #[test]
+    fn children_not_laid_out_again_are_not_hit() {
+        // Absorbing layer is no longer placed, so it doesn't cover the other.
+        assert_eq!(dispatch_to_layers(PointerBehavior::Absorb, 1), (1, 1));
+    }
+
+    fn mouse_event() -> MouseEvent {
+        MouseEvent {
+            pos: Point::ZERO,
+            buttons: MouseButtons::new(),
+            mods: Modifiers::empty(),
+            count: 1,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        }
+    }
+
+    #[test]
+    fn captured_widget_receives_events_until_pointer_up() {
+        let received = Rc::new(Cell::new(0));
+        let blocking = Rc::new(Cell::new(false));
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(Blocker {
+            child: Recorder(received.clone()),
+            blocking: blocking.clone(),
+        })));
+
+        tree.handle_event(Event::MouseDown(mouse_event()));
+        assert_eq!(received.get(), 1);
+
+        // Delivered even though the parent stops them.
+        blocking.set(true);
+        tree.handle_event(Event::MouseMove(mouse_event()));
+        tree.handle_event(Event::MouseUp(mouse_event()));
+        assert_eq!(received.get(), 3);
+
+        // Capture was released on pointer up.
+        tree.handle_event(Event::MouseMove(mouse_event()));
+        assert_eq!(received.get(), 3);
+    }
+}
@@ -0,0 +1,457 @@
+//! Dispatch of key combinations to intents and of intents to actions.
+//!
+//! Shortcut scopes (usually `Shortcuts` and `Actions` widgets) are focused by
+//! pressing the pointer within their bounds. Every scope containing the pressed
+//! point is focused, forming a chain from the outermost to the innermost one.
+//! A key down event is first looked up in the scopes of that chain, starting
+//! from the innermost one. The found intent is then invoked by the innermost
+//! scope handling it. If no scope handles it, the lookup continues with the
+//! scopes further out, so that the nearest ancestor always wins.
+//!
+//! Key combinations of Alt and a character which no focused scope handles
+//! activate the scope with that character as its mnemonic (see `MnemonicText`
+//! of `frui_widgets`), whether it's focused or not.
+
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use druid_shell::{KbKey, KeyEvent, KeyState, Modifiers};
+
+/// Key along with the modifiers which need to be held for a shortcut to apply.
+///
+/// Character keys are matched regardless of their case, and modifiers other
+/// than Shift, Control, Alt and Meta (like Caps Lock) are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyCombination {
+    pub key: KbKey,
+    pub modifiers: Modifiers,
+}
+
+impl KeyCombination {
+    pub fn new(key: KbKey, modifiers: Modifiers) -> Self {
+        let key = match key {
+            KbKey::Character(c) => KbKey::Character(c.to_lowercase()),
+            key => key,
+        };
+
+        Self {
+            key,
+            modifiers: modifiers
+                & (Modifiers::SHIFT | Modifiers::CONTROL | Modifiers::ALT | Modifiers::META),
+        }
+    }
+
+    /// Key without any modifiers.
+    pub fn key(key: KbKey) -> Self {
+        Self::new(key, Modifiers::empty())
+    }
+
+    pub fn from_key_event(event: &KeyEvent) -> Self {
+        Self::new(event.key.clone(), event.mods)
+    }
+}
+
+/// Meaning of a shortcut, e.g. "save" or "select all", which the closest
+/// action that handles its type carries out.
+pub trait Intent: Any {}
+
+/// Receiver of key combinations and intents.
+pub trait ShortcutScope {
+    /// Intent bound to `keys` in this scope.
+    fn intent(&self, keys: &KeyCombination) -> Option<Rc<dyn Intent>> {
+        let _ = keys;
+        None
+    }
+
+    /// Carries out `intent`, returning whether this scope handles it.
+    fn invoke(&self, intent: &dyn Intent) -> bool {
+        let _ = intent;
+        false
+    }
+
+    /// Character which activates this scope when pressed along with Alt.
+    fn mnemonic(&self) -> Option<char> {
+        None
+    }
+
+    /// Called once the [`mnemonic`](Self::mnemonic) of this scope is pressed.
+    fn activate_mnemonic(&self) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortcutScopeKey(usize);
+
+/// Registered shortcut scopes, some of which are focused.
+pub struct ShortcutScopes {
+    scopes: Vec<Option<*const dyn ShortcutScope>>,
+    /// Widget which registered each of `scopes`.
+    registrars: Vec<&'static str>,
+    /// Focused scopes, from the outermost to the innermost one.
+    focused: Vec<usize>,
+}
+
+impl ShortcutScopes {
+    /// Registers a shortcut scope. It receives key events only once focused.
+    ///
+    /// # Safety
+    ///
+    /// Value `scope` points to must live until [`unregister`](Self::unregister)
+    /// or [`update`](Self::update) is called with the returned key.
+    pub unsafe fn register<'a>(
+        &mut self,
+        scope: *const (dyn ShortcutScope + 'a),
+    ) -> ShortcutScopeKey {
+        self.scopes.push(Some(std::mem::transmute::<
+            *const (dyn ShortcutScope + 'a),
+            *const dyn ShortcutScope,
+        >(scope)));
+        self.registrars.push(super::registrar());
+
+        ShortcutScopeKey(self.scopes.len() - 1)
+    }
+
+    /// Replaces the scope registered with `key`, keeping its focus.
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`register`](Self::register).
+    pub unsafe fn update<'a>(
+        &mut self,
+        key: &ShortcutScopeKey,
+        scope: *const (dyn ShortcutScope + 'a),
+    ) {
+        let slot = &mut self.scopes[key.0];
+
+        assert!(slot.is_some(), "scope of {key:?} was unregistered");
+
+        *slot = Some(std::mem::transmute::<
+            *const (dyn ShortcutScope + 'a),
+            *const dyn ShortcutScope,
+        >(scope));
+    }
+
+    pub fn unregister(&mut self, key: &ShortcutScopeKey) {
+        self.focused.retain(|idx| *idx != key.0);
+        self.scopes[key.0] = None;
+
+        while let Some(None) = self.scopes.last() {
+            self.scopes.pop();
+            self.registrars.pop();
+        }
+    }
+
+    /// Focuses the scope registered with `key`, inside of the scopes focused
+    /// since the pointer was last pressed.
+    ///
+    /// Scopes should call this when the pointer is pressed within their bounds.
+    /// Since parents receive events before their children, the chain of focused
+    /// scopes ends up ordered from the outermost to the innermost one.
+    pub fn press(&mut self, key: &ShortcutScopeKey) {
+        if !self.is_focused(key) {
+            self.focused.push(key.0);
+        }
+    }
+
+    /// Unfocuses the scope registered with `key`, keeping other scopes of the
+    /// chain focused.
+    pub fn unfocus(&mut self, key: &ShortcutScopeKey) {
+        self.focused.retain(|idx| *idx != key.0);
+    }
+
+    pub fn is_focused(&self, key: &ShortcutScopeKey) -> bool {
+        self.focused.contains(&key.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.scopes.iter().filter(|s| s.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // Trailing unregistered scopes are popped.
+        self.scopes.is_empty()
+    }
+
+    /// Keys of registered scopes, along with the type names of widgets which
+    /// registered them (known only in debug builds).
+    pub fn registered(&self) -> impl Iterator<Item = (ShortcutScopeKey, &'static str)> + '_ {
+        self.scopes
+            .iter()
+            .zip(&self.registrars)
+            .enumerate()
+            .filter(|(_, (scope, _))| scope.is_some())
+            .map(|(n, (_, registrar))| (ShortcutScopeKey(n), *registrar))
+    }
+
+    /// All registered scopes, from the most recently registered one.
+    fn registered_scopes(&self) -> Vec<*const dyn ShortcutScope> {
+        self.scopes.iter().rev().flatten().copied().collect()
+    }
+
+    /// Focused scopes, from the innermost to the outermost one.
+    fn focused(&self) -> Vec<*const dyn ShortcutScope> {
+        self.focused
+            .iter()
+            .rev()
+            .filter_map(|idx| self.scopes[*idx])
+            .collect()
+    }
+}
+
+thread_local! {
+    pub static SHORTCUT_SCOPES: RefCell<ShortcutScopes> = const {
+        RefCell::new(ShortcutScopes {
+            scopes: Vec::new(),
+            registrars: Vec::new(),
+            focused: Vec::new(),
+        })
+    };
+}
+
+/// Unfocuses all scopes before a pointer press is dispatched, so that only
+/// scopes containing the new point get focused.
+pub(crate) fn before_pointer_down() {
+    SHORTCUT_SCOPES.with(|scopes| scopes.borrow_mut().focused.clear());
+}
+
+/// Dispatches the key combination of a key down `event` to the focused
+/// scopes, returning whether an action handled it.
+pub(crate) fn dispatch(event: &KeyEvent) -> bool {
+    if event.state != KeyState::Down {
+        return false;
+    }
+
+    let keys = KeyCombination::from_key_event(event);
+
+    // Borrow is released before calling the scopes, which may (un)register
+    // other scopes.
+    let chain = SHORTCUT_SCOPES.with(|scopes| scopes.borrow().focused());
+
+    // Safety: scopes are valid as ensured by registrars to `ShortcutScopes`.
+    let chain = chain
+        .iter()
+        .map(|scope| unsafe { &**scope })
+        .collect::<Vec<_>>();
+
+    for scope in chain.iter() {
+        if let Some(intent) = scope.intent(&keys) {
+            if chain.iter().any(|scope| scope.invoke(&*intent)) {
+                return true;
+            }
+        }
+    }
+
+    dispatch_mnemonic(&keys)
+}
+
+/// Activates the scope with the mnemonic pressed in `keys`, if there is one.
+///
+/// Of scopes with the same mnemonic, the most recently registered one wins, so
+/// that mnemonics of a newly opened menu take precedence over those behind it.
+fn dispatch_mnemonic(keys: &KeyCombination) -> bool {
+    if keys.modifiers != Modifiers::ALT {
+        return false;
+    }
+
+    let mnemonic = match &keys.key {
+        KbKey::Character(c) if c.chars().count() == 1 => c.chars().next().unwrap(),
+        _ => return false,
+    };
+
+    let scopes = SHORTCUT_SCOPES.with(|scopes| scopes.borrow().registered_scopes());
+
+    // Safety: same as in `dispatch`.
+    let scope = scopes
+        .iter()
+        .map(|scope| unsafe { &**scope })
+        .find(|scope| {
+            scope
+                .mnemonic()
+                .is_some_and(|c| c.to_lowercase().eq(mnemonic.to_lowercase()))
+        });
+
+    match scope {
+        Some(scope) => {
+            scope.activate_mnemonic();
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+
+    struct Save;
+
+    impl Intent for Save {}
+
+    /// Binds Ctrl+S to `Save` and handles it if `handles` is set.
+    struct Scope {
+        binds: bool,
+        handles: bool,
+        saved: Cell<usize>,
+    }
+
+    impl Scope {
+        fn new(binds: bool, handles: bool) -> Self {
+            Self {
+                binds,
+                handles,
+                saved: Cell::new(0),
+            }
+        }
+    }
+
+    impl ShortcutScope for Scope {
+        fn intent(&self, keys: &KeyCombination) -> Option<Rc<dyn Intent>> {
+            let save = KeyCombination::new(KbKey::Character("s".into()), Modifiers::CONTROL);
+
+            (self.binds && *keys == save).then(|| Rc::new(Save) as Rc<dyn Intent>)
+        }
+
+        fn invoke(&self, intent: &dyn Intent) -> bool {
+            let handles = self.handles && (intent as &dyn Any).is::<Save>();
+
+            if handles {
+                self.saved.set(self.saved.get() + 1);
+            }
+
+            handles
+        }
+    }
+
+    fn key_down(key: &str, mods: Modifiers) -> KeyEvent {
+        let mut event = KeyEvent::default();
+        event.key = KbKey::Character(key.into());
+        event.mods = mods;
+        event
+    }
+
+    #[test]
+    fn combinations_ignore_case_and_lock_modifiers() {
+        assert_eq!(
+            KeyCombination::from_key_event(&key_down(
+                "S",
+                Modifiers::CONTROL | Modifiers::CAPS_LOCK
+            )),
+            KeyCombination::new(KbKey::Character("s".into()), Modifiers::CONTROL)
+        );
+        assert_ne!(
+            KeyCombination::from_key_event(&key_down("s", Modifiers::CONTROL | Modifiers::SHIFT)),
+            KeyCombination::new(KbKey::Character("s".into()), Modifiers::CONTROL)
+        );
+    }
+
+    #[test]
+    fn nearest_focused_scope_handles_intent() {
+        let outer = Scope::new(true, true);
+        let middle = Scope::new(false, true);
+        let inner = Scope::new(true, false);
+        let save = key_down("s", Modifiers::CONTROL);
+
+        let keys = SHORTCUT_SCOPES.with(|scopes| unsafe {
+            let mut scopes = scopes.borrow_mut();
+            [
+                scopes.register(&outer),
+                scopes.register(&middle),
+                scopes.register(&inner),
+            ]
+        });
+
+        // Nothing is focused yet.
+        assert!(!dispatch(&save));
+
+        let press = |pressed: &[ShortcutScopeKey]| {
+            before_pointer_down();
+            SHORTCUT_SCOPES.with(|scopes| {
+                for key in pressed {
+                    scopes.borrow_mut().press(key);
+                }
+            });
+        };
+
+        // Intent of the inner scope is handled by the nearest scope handling it.
+        press(&keys);
+        assert!(dispatch(&save));
+        assert_eq!((outer.saved.get(), middle.saved.get()), (0, 1));
+
+        // Only focused scopes receive key events.
+        press(&keys[..1]);
+        assert!(dispatch(&save));
+        assert!(!dispatch(&key_down("s", Modifiers::empty())));
+        assert_eq!((outer.saved.get(), middle.saved.get()), (1, 1));
+
+        // Unregistered scope loses focus.
+        SHORTCUT_SCOPES.with(|scopes| {
+            let mut scopes = scopes.borrow_mut();
+            for key in keys.iter().rev() {
+                scopes.unregister(key);
+            }
+            assert!(scopes.is_empty());
+        });
+
+        assert!(!dispatch(&save));
+    }
+
+    /// Scope activated by its mnemonic.
+    struct Labeled {
+        mnemonic: char,
+        activated: Cell<usize>,
+    }
+
+    impl ShortcutScope for Labeled {
+        fn mnemonic(&self) -> Option<char> {
+            Some(self.mnemonic)
+        }
+
+        fn activate_mnemonic(&self) {
+            self.activated.set(self.activated.get() + 1);
+        }
+    }
+
+    #[test]
+    fn alt_and_mnemonic_activate_latest_scope_without_focus() {
+        let labeled = |mnemonic| Labeled {
+            mnemonic,
+            activated: Cell::new(0),
+        };
+        let (file, other_file, exit) = (labeled('F'), labeled('f'), labeled('x'));
+
+        let keys = SHORTCUT_SCOPES.with(|scopes| unsafe {
+            let mut scopes = scopes.borrow_mut();
+            [
+                scopes.register(&file),
+                scopes.register(&other_file),
+                scopes.register(&exit),
+            ]
+        });
+
+        assert!(dispatch(&key_down("f", Modifiers::ALT)));
+        assert!(dispatch(&key_down("X", Modifiers::ALT)));
+        assert_eq!(
+            [&file, &other_file, &exit].map(|s| s.activated.get()),
+            [0, 1, 1]
+        );
+
+        // Alt needs to be the only modifier.
+        assert!(!dispatch(&key_down("f", Modifiers::empty())));
+        assert!(!dispatch(&key_down(
+            "f",
+            Modifiers::ALT | Modifiers::CONTROL
+        )));
+        assert!(!dispatch(&key_down("e", Modifiers::ALT)));
+
+        // Unregistered scope isn't activated anymore.
+        SHORTCUT_SCOPES.with(|scopes| scopes.borrow_mut().unregister(&keys[1]));
+        assert!(dispatch(&key_down("f", Modifiers::ALT)));
+        assert_eq!((file.activated.get(), other_file.activated.get()), (1, 1));
+
+        SHORTCUT_SCOPES.with(|scopes| {
+            for key in [keys[0], keys[2]] {
+                scopes.borrow_mut().unregister(&key);
+            }
+        });
+    }
+}
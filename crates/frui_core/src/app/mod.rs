@@ -2,8 +2,11 @@ use std::cell::{RefCell, RefMut};
 
 use druid_shell::piet::PietText;
 
+pub mod executor;
 pub mod listeners;
 pub mod runner;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod tree;
 
 pub struct TextFactory(RefCell<Option<PietText>>);
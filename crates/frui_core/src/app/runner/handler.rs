@@ -1,16 +1,31 @@
-use std::{cell::RefCell, sync::Mutex};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
 use druid_shell::{
     kurbo::Rect,
     piet::{Color, RenderContext},
-    Cursor, IdleToken, KeyEvent, MouseEvent,
+    Cursor, IdleToken, KbKey, KeyEvent, MouseEvent, Scale,
 };
 
 use crate::{
-    api::{events::Event, WidgetPtr},
+    api::{
+        events::Event,
+        lifecycle::{self, AppLifecycleState},
+        media_query::{MediaQuery, MediaQueryData},
+        WidgetPtr,
+    },
     app::{
-        listeners::keyboard::KEYBOARD_EVENT_LISTENERS,
-        tree::{WidgetNodeRef, WidgetTree},
+        executor,
+        listeners::{
+            clipboard, context_menu,
+            keyboard::{KEYBOARD_EVENT_LISTENERS, KEYBOARD_STATE, RAW_KEYBOARD_EVENT_LISTENERS},
+            pointer, shortcuts,
+        },
+        tree::{self, WidgetNodeRef, WidgetTree},
         TEXT_FACTORY,
     },
     prelude::{Constraints, Size, Widget},
@@ -19,38 +34,322 @@ use crate::{
 use super::{Application, FruiWindowHandler, IdleHandle, PaintContext, WindowHandle};
 
 thread_local! {
-    pub(crate) static APP_HANDLE: std::cell::RefCell<Option<IdleHandle>> = RefCell::new(None);
+    /// Windows which are currently open.
+    static WINDOWS: RefCell<Vec<OpenWindow>> = const { RefCell::new(Vec::new()) };
+    /// Whether the application quits once its last window is closed.
+    pub(crate) static QUIT_ON_LAST_WINDOW_CLOSED: Cell<bool> = const { Cell::new(true) };
+    static NEXT_WINDOW_ID: Cell<u64> = const { Cell::new(0) };
+    /// Whether a frame was scheduled since [`take_scheduled_frame`] was called.
+    static FRAME_SCHEDULED: Cell<bool> = const { Cell::new(false) };
 }
 
+thread_local! {
+    /// Callbacks run once after the next frame is painted.
+    static POST_FRAME_CALLBACKS: RefCell<Vec<Box<dyn FnOnce()>>> = const { RefCell::new(Vec::new()) };
+    /// Callbacks run at the start of every frame, see [`add_frame_callback`].
+    static FRAME_CALLBACKS: RefCell<Vec<(FrameCallbackId, FrameCallback)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_FRAME_CALLBACK_ID: Cell<u64> = const { Cell::new(0) };
+    /// Windows which painted since the current frame started, see
+    /// [`starts_frame`].
+    static PAINTED_WINDOWS: RefCell<Vec<WindowId>> = const { RefCell::new(Vec::new()) };
+}
+
+type FrameCallback = Rc<RefCell<dyn FnMut(Instant)>>;
+
 thread_local! {
     pub(crate) static NEED_REBUILD: Mutex<Vec<WidgetNodeRef>>  = Mutex::new(Vec::with_capacity(100));
+    /// Time a frame can spend rebuilding dirty widgets, see [`set_build_budget`].
+    static BUILD_BUDGET: Cell<Option<Duration>> = const { Cell::new(None) };
+}
+
+/// Identifier of a window opened by the application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WindowId(u64);
+
+impl WindowId {
+    fn next() -> Self {
+        NEXT_WINDOW_ID.with(|id| {
+            let next = id.get();
+            id.set(next + 1);
+            WindowId(next)
+        })
+    }
+}
+
+struct OpenWindow {
+    id: WindowId,
+    handle: WindowHandle,
+    idle_handle: IdleHandle,
+}
+
+/// Wakes up every open window, so that it rebuilds dirty widgets, lays out
+/// and paints its widget tree during the next frame.
+///
+/// Dirty widgets are shared by all windows, since the state one window depends
+/// on can be changed from within the other one.
+pub(crate) fn schedule_frame() {
+    FRAME_SCHEDULED.with(|s| s.set(true));
+
+    WINDOWS.with(|windows| {
+        for window in windows.borrow_mut().iter_mut() {
+            window.idle_handle.schedule_idle(IdleToken::new(0));
+        }
+    });
+}
+
+/// Whether a frame was scheduled since the last call, also when no window is
+/// open.
+pub(crate) fn take_scheduled_frame() -> bool {
+    FRAME_SCHEDULED.with(|s| s.replace(false))
+}
+
+/// Schedules `callback` to run once, right after the next frame is laid out
+/// and painted.
+///
+/// Unlike layout and paint, post-frame callbacks are allowed to change the
+/// state of widgets, which makes them suitable for reacting to the results of
+/// layout (e.g. the size of a widget). Such change is then applied during the
+/// frame which follows.
+pub fn add_post_frame_callback(callback: impl FnOnce() + 'static) {
+    POST_FRAME_CALLBACKS.with(|callbacks| callbacks.borrow_mut().push(Box::new(callback)));
+    schedule_frame();
+}
+
+/// Runs callbacks scheduled with [`add_post_frame_callback`]. Callbacks
+/// scheduled by those callbacks run after the next frame.
+pub(crate) fn run_post_frame_callbacks() {
+    let callbacks =
+        POST_FRAME_CALLBACKS.with(|callbacks| std::mem::take(&mut *callbacks.borrow_mut()));
+
+    for callback in callbacks {
+        callback();
+    }
+}
+
+/// Identifier of a callback added with [`add_frame_callback`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameCallbackId(u64);
+
+/// Adds `callback` which is called at the start of every frame, before dirty
+/// widgets are rebuilt, with the time that frame is produced for. It keeps
+/// being called until it's removed with [`remove_frame_callback`].
+///
+/// Unlike animations of widgets, frame callbacks are global and are called
+/// once per frame, including frames requested by the platform (e.g. after a
+/// window was uncovered) which rebuild nothing. With several windows open, a
+/// frame is painted by every window which needs it, and callbacks are called
+/// before the first of those windows paints. They
+/// don't request frames on their own, which makes them suitable for keeping
+/// external systems (like audio or video playback) in sync with what is
+/// being painted.
+///
+/// Callbacks can add and remove frame callbacks, including themselves.
+/// Callbacks added that way are first called during the next frame, and
+/// callbacks removed that way aren't called anymore, even during the current
+/// frame.
+pub fn add_frame_callback(callback: impl FnMut(Instant) + 'static) -> FrameCallbackId {
+    let id = NEXT_FRAME_CALLBACK_ID.with(|id| {
+        let next = id.get();
+        id.set(next + 1);
+        FrameCallbackId(next)
+    });
+
+    let callback: FrameCallback = Rc::new(RefCell::new(callback));
+    FRAME_CALLBACKS.with(|callbacks| callbacks.borrow_mut().push((id, callback)));
+    schedule_frame();
+
+    id
+}
+
+/// Removes the callback added with [`add_frame_callback`]. Does nothing if
+/// it was already removed.
+pub fn remove_frame_callback(id: FrameCallbackId) {
+    FRAME_CALLBACKS.with(|callbacks| callbacks.borrow_mut().retain(|(i, _)| *i != id));
+}
+
+/// Whether paint of `window` starts a new frame, which is when it already
+/// painted during the current one (or no window did). Windows are painted at
+/// different times, so the current frame is taken to last until some window
+/// paints again.
+fn starts_frame(window: WindowId) -> bool {
+    PAINTED_WINDOWS.with(|painted| {
+        let mut painted = painted.borrow_mut();
+        let starts = painted.is_empty() || painted.contains(&window);
+
+        if starts {
+            painted.clear();
+        }
+
+        painted.push(window);
+        starts
+    })
+}
+
+/// Runs callbacks added with [`add_frame_callback`] for a frame produced for
+/// `frame_time`.
+pub(crate) fn run_frame_callbacks(frame_time: Instant) {
+    // Callbacks run outside of the borrow, since they may add or remove other
+    // callbacks.
+    let callbacks = FRAME_CALLBACKS.with(|callbacks| callbacks.borrow().clone());
+
+    for (id, callback) in callbacks {
+        let is_registered =
+            FRAME_CALLBACKS.with(|callbacks| callbacks.borrow().iter().any(|(i, _)| *i == id));
+
+        if is_registered {
+            (callback.borrow_mut())(frame_time);
+        }
+    }
+}
+
+/// Limits the time a frame spends rebuilding dirty widgets to `budget`, or
+/// removes that limit with `None` (which is the default).
+///
+/// Once a frame runs out of its budget, the remaining dirty widgets are
+/// rebuilt during the following frames, which keeps a large number of widgets
+/// changing at once (e.g. a long list receiving new data) from dropping frames.
+/// Widgets painted during the last frame are rebuilt first and are never
+/// deferred, and a deferred widget which comes into view is rebuilt right
+/// before it is painted.
+///
+/// The tradeoff is that for a few frames, offscreen widgets show their
+/// previous state and their layout may be based on it. A deferred widget
+/// whose size changes once it's rebuilt leaves the layout of its parent
+/// stale until the next frame. Applications relying on every widget being
+/// up to date right after `setState` (e.g. measuring offscreen widgets in a
+/// post-frame callback) should keep the budget unset.
+pub fn set_build_budget(budget: Option<Duration>) {
+    BUILD_BUDGET.with(|b| b.set(budget));
+}
+
+/// Rebuilds dirty widgets of every window, within the build budget.
+pub(crate) fn rebuild_dirty() {
+    let budget = BUILD_BUDGET.with(|b| b.get());
+    let start = Instant::now();
+
+    // Widgets painted during the last frame of any window.
+    let windows = WINDOWS.with(|windows| windows.borrow().len() as u64);
+    let recently_painted = tree::paint_pass().saturating_sub(windows.max(1));
+    let is_visible =
+        |node: &WidgetNodeRef| node.is_alive() && node.painted_pass() > recently_painted;
+
+    let mut deferred = Vec::new();
+
+    NEED_REBUILD.with(|need_rebuild| {
+        if budget.is_some() {
+            // Visible widgets are rebuilt first, before the budget runs out.
+            need_rebuild
+                .lock()
+                .unwrap()
+                .sort_by_key(|node| !is_visible(node));
+        }
+
+        let mut idx = 0;
+
+        loop {
+            let need_rebuild = need_rebuild.lock().unwrap();
+
+            // We use idx instead of iterator, since every call to `update_subtree` may add
+            // new widgets that need to be rebuilt.
+            if idx == need_rebuild.len() {
+                break;
+            }
+
+            // Todo: Sort widgets according to their depth, which will allow us to avoid
+            // rebuilding some widgets multiple times. Remember to sort those widgets every
+            // time you update a widget (don't sort if length of vec didn't change).
+
+            // Acquire node reference.
+            let node = need_rebuild[idx].clone();
+
+            // Drop the lock.
+            drop(need_rebuild);
+
+            if node.is_alive() {
+                if node.borrow().dirty {
+                    let out_of_budget = matches!(budget, Some(b) if start.elapsed() >= b);
+
+                    // Deferred widget stays dirty until it's rebuilt.
+                    if out_of_budget && !is_visible(&node) {
+                        deferred.push(node);
+                    } else {
+                        node.update_subtree();
+                    }
+                }
+            }
+
+            idx += 1;
+        }
+
+        let mut need_rebuild = need_rebuild.lock().unwrap();
+        need_rebuild.clear();
+        need_rebuild.extend(deferred.iter().cloned());
+    });
+
+    if !deferred.is_empty() {
+        schedule_frame();
+    }
+}
+
+/// Requests the window identified by `id` to close. Does nothing if that
+/// window was already closed.
+pub(crate) fn close_window(id: WindowId) {
+    let handle = WINDOWS.with(|windows| {
+        let windows = windows.borrow();
+        windows
+            .iter()
+            .find(|w| w.id == id)
+            .map(|w| w.handle.clone())
+    });
+
+    // Window is destroyed outside of the borrow of `WINDOWS`.
+    if let Some(handle) = handle {
+        handle.close();
+    }
 }
 
 pub struct WindowHandler {
+    id: WindowId,
     /// Current size of main window.
     window_size: Size,
     /// Clone of window handle received from `connect`.
     window_handle: WindowHandle,
+    /// Color the window is cleared with before painting widgets.
+    pub(crate) background: Color,
 
     pending_update: bool,
     widget_tree: WidgetTree,
 
+    /// Media query data provided to the widget tree of this window.
+    media_query: MediaQueryData,
+    /// Whether this window is focused. Assumed for a newly opened window,
+    /// since not every platform reports the focus it opens with.
+    focused: bool,
+
     /// Temporary field to store root widget before constructing the widget tree
     /// (which requires WindowHandle which can be obtained only after `connect`).
-    root_temp: Option<WidgetPtr<'static>>,
+    root_temp: Option<Box<dyn Widget>>,
 }
 
 impl WindowHandler {
     pub fn new<W: Widget + 'static>(widget: W) -> Self {
         Self {
+            id: WindowId::next(),
             window_size: Size::default(),
             window_handle: WindowHandle::default(),
+            background: Color::from_hex_str("#202324").unwrap(),
             pending_update: true,
             widget_tree: WidgetTree::default(),
-            root_temp: Some(WidgetPtr::from_owned(Box::new(widget))),
+            media_query: MediaQueryData::default(),
+            focused: true,
+            root_temp: Some(Box::new(widget)),
         }
     }
 
+    pub fn id(&self) -> WindowId {
+        self.id
+    }
+
     /// Will schedule an update for the next frame.
     pub fn schedule_update(&mut self) {
         if !self.pending_update {
@@ -60,51 +359,40 @@ impl WindowHandler {
         }
     }
 
-    fn rebuild_dirty(&mut self) {
-        NEED_REBUILD.with(|need_rebuild| {
-            let mut idx = 0;
-
-            loop {
-                let need_rebuild = need_rebuild.lock().unwrap();
-
-                // We use idx instead of iterator, since every call to `update_subtree` may add
-                // new widgets that need to be rebuilt.
-                if idx == need_rebuild.len() {
-                    break;
-                }
-
-                // Todo: Sort widgets according to their depth, which will allow us to avoid
-                // rebuilding some widgets multiple times. Remember to sort those widgets every
-                // time you update a widget (don't sort if length of vec didn't change).
-
-                // Acquire node reference.
-                let node = need_rebuild[idx].clone();
-
-                // Drop the lock.
-                drop(need_rebuild);
-
-                if node.is_alive() {
-                    if node.borrow().dirty {
-                        node.update_subtree();
-                    }
-                }
-
-                idx += 1;
-            }
-
-            need_rebuild.lock().unwrap().clear();
-        });
+    /// Updates the lifecycle state of this window after its focus or size
+    /// changed. Window minimized by the platform has zero size.
+    fn update_lifecycle(&self) {
+        let state = if self.window_size.width == 0. || self.window_size.height == 0. {
+            AppLifecycleState::Paused
+        } else if self.focused {
+            AppLifecycleState::Resumed
+        } else {
+            AppLifecycleState::Inactive
+        };
+
+        lifecycle::set_window_state(self.id, Some(state));
     }
 }
 
 impl FruiWindowHandler for WindowHandler {
     fn connect(&mut self, handle: &WindowHandle) {
-        APP_HANDLE.with(|r| *r.borrow_mut() = Some(handle.get_idle_handle().unwrap()));
+        WINDOWS.with(|windows| {
+            windows.borrow_mut().push(OpenWindow {
+                id: self.id,
+                handle: handle.clone(),
+                idle_handle: handle.get_idle_handle().unwrap(),
+            })
+        });
+        executor::add_idle_handle(self.id, handle.get_idle_handle().unwrap());
         #[cfg(not(feature = "miri"))]
         TEXT_FACTORY.with(|f| f.set(self.window_handle.text()));
 
-        let root_widget = std::mem::take(&mut self.root_temp);
-        self.widget_tree = WidgetTree::new(root_widget.unwrap());
+        if let Ok(scale) = handle.get_scale() {
+            self.media_query.device_pixel_ratio = scale.x();
+        }
+
+        let root_widget = MediaQuery::new(self.media_query, self.root_temp.take().unwrap());
+        self.widget_tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(root_widget)));
         self.window_handle = handle.clone();
 
         self.window_handle.set_cursor(&Cursor::Arrow);
@@ -114,24 +402,30 @@ impl FruiWindowHandler for WindowHandler {
 
     fn paint(&mut self, piet: &mut PaintContext, _invalid: &druid_shell::Region) {
         //
-        // Fill screen with one color (temp).
+        // Fill screen with the background color.
 
         let size = self.window_size;
         let rect = Rect::new(0., 0., size.width, size.height);
-        let brush = &piet.solid_brush(Color::from_hex_str("#202324").unwrap());
+        let brush = &piet.solid_brush(self.background.clone());
 
         druid_shell::piet::RenderContext::fill(piet, rect, brush);
 
+        if starts_frame(self.id) {
+            run_frame_callbacks(Instant::now());
+        }
+
         //
         // Rebuild widget tree.
 
         self.pending_update = false;
-        self.rebuild_dirty();
+        rebuild_dirty();
 
         //
         // Layout & Paint
 
-        // Todo: Optimize layout.
+        // Layout of widgets which weren't rebuilt nor marked with
+        // `mark_needs_layout` is cached, so frames scheduled only to paint (with
+        // `mark_needs_paint`) don't lay out anything.
         self.widget_tree.layout(Constraints {
             min_width: 0.,
             max_width: self.window_size.width,
@@ -141,18 +435,50 @@ impl FruiWindowHandler for WindowHandler {
 
         // Todo: Optimize paint.
         self.widget_tree.paint(piet);
+
+        run_post_frame_callbacks();
     }
 
     fn size(&mut self, size: druid_shell::kurbo::Size) {
         self.window_size = size.into();
+
+        self.media_query.size = self.window_size;
+        self.widget_tree.set_media_query(self.media_query);
+
+        self.update_lifecycle();
     }
 
-    fn idle(&mut self, _token: IdleToken) {
-        self.schedule_update();
+    fn scale(&mut self, scale: Scale) {
+        // Piet context is already scaled by druid_shell, so widgets are laid
+        // out and painted in logical pixels.
+        self.media_query.device_pixel_ratio = scale.x();
+        self.widget_tree.set_media_query(self.media_query);
+    }
+
+    fn idle(&mut self, token: IdleToken) {
+        if token == executor::POLL_TOKEN {
+            // Completed tasks rebuild their widgets, scheduling an update.
+            executor::poll_woken();
+        } else {
+            self.schedule_update();
+        }
     }
 
     fn destroy(&mut self) {
-        Application::global().quit()
+        let is_last = WINDOWS.with(|windows| {
+            let mut windows = windows.borrow_mut();
+            windows.retain(|w| w.id != self.id);
+            windows.is_empty()
+        });
+        executor::remove_idle_handle(self.id);
+        lifecycle::set_window_state(self.id, None);
+
+        // Unmount widgets of this window, releasing their listeners.
+        drop(std::mem::take(&mut self.widget_tree));
+
+        if is_last && QUIT_ON_LAST_WINDOW_CLOSED.with(|quit| quit.get()) {
+            Application::global().quit()
+        }
     }
 
     fn as_any(&mut self) -> &mut dyn std::any::Any {
@@ -164,6 +490,8 @@ impl FruiWindowHandler for WindowHandler {
     fn mouse_down(&mut self, event: &MouseEvent) {
         self.widget_tree
             .handle_event(Event::MouseDown(event.clone()));
+
+        context_menu::show_pending(&self.window_handle);
     }
 
     fn mouse_move(&mut self, event: &MouseEvent) {
@@ -183,16 +511,136 @@ impl FruiWindowHandler for WindowHandler {
     }
 
     fn key_down(&mut self, event: KeyEvent) -> bool {
-        KEYBOARD_EVENT_LISTENERS.with(|listeners| {
-            for listener in listeners.borrow_mut().iter() {
-                listener(event.clone());
-            }
-        });
-
+        dispatch_key_down(event);
         true
     }
 
+    fn key_up(&mut self, event: KeyEvent) {
+        KEYBOARD_STATE.with(|state| state.borrow_mut().handle_event(&event));
+        dispatch_raw_key_event(&event);
+        repaint_mnemonics(&event);
+    }
+
+    fn got_focus(&mut self) {
+        self.focused = true;
+        self.update_lifecycle();
+    }
+
+    fn lost_focus(&mut self) {
+        self.focused = false;
+        self.update_lifecycle();
+
+        // Key up events of keys held while the window loses focus are never
+        // received, so we release those keys here to not leave them held.
+        let released = KEYBOARD_STATE.with(|state| state.borrow_mut().release_all());
+
+        for event in released.iter() {
+            dispatch_raw_key_event(event);
+        }
+
+        // Mnemonics displayed while Alt was held are hidden.
+        if !released.is_empty() {
+            schedule_frame();
+        }
+
+        // Pointer up events are not received either.
+        pointer::release_on_focus_lost();
+    }
+
     fn request_close(&mut self) {
         self.window_handle.close();
     }
+
+    fn command(&mut self, id: u32) {
+        if !context_menu::dispatch(id) {
+            diagnostic!(debug, "context_menu", "item {id} selected in a closed menu");
+        }
+    }
+}
+
+/// Dispatches a key down event to clipboard handlers, shortcuts and then
+/// keyboard listeners.
+pub(crate) fn dispatch_key_down(event: KeyEvent) {
+    KEYBOARD_STATE.with(|state| state.borrow_mut().handle_event(&event));
+    dispatch_raw_key_event(&event);
+    repaint_mnemonics(&event);
+
+    if clipboard::dispatch(&event) || shortcuts::dispatch(&event) {
+        return;
+    }
+
+    KEYBOARD_EVENT_LISTENERS.with(|listeners| {
+        for listener in listeners.borrow_mut().iter() {
+            listener(event.clone());
+        }
+    });
+}
+
+/// Schedules a frame once Alt is pressed or released, since mnemonics are
+/// underlined only while it's held.
+fn repaint_mnemonics(event: &KeyEvent) {
+    if event.key == KbKey::Alt {
+        schedule_frame();
+    }
+}
+
+fn dispatch_raw_key_event(event: &KeyEvent) {
+    RAW_KEYBOARD_EVENT_LISTENERS.with(|listeners| {
+        for listener in listeners.borrow_mut().iter() {
+            listener(event.clone());
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn frame_callbacks_can_be_removed_and_added_while_running() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+
+        let c = calls.clone();
+        let second = Rc::new(Cell::new(None));
+        let s = second.clone();
+        let first = add_frame_callback(move |_| {
+            c.borrow_mut().push("first");
+
+            // Removes the second callback before it's called during this frame.
+            if let Some(second) = s.take() {
+                remove_frame_callback(second);
+
+                let c = c.clone();
+                add_frame_callback(move |_| c.borrow_mut().push("third"));
+            }
+        });
+
+        let c = calls.clone();
+        second.set(Some(add_frame_callback(move |_| {
+            c.borrow_mut().push("second")
+        })));
+
+        run_frame_callbacks(Instant::now());
+        assert_eq!(*calls.borrow(), ["first"]);
+
+        run_frame_callbacks(Instant::now());
+        assert_eq!(*calls.borrow(), ["first", "first", "third"]);
+
+        remove_frame_callback(first);
+        run_frame_callbacks(Instant::now());
+        assert_eq!(*calls.borrow(), ["first", "first", "third", "third"]);
+    }
+
+    #[test]
+    fn frame_is_started_once_for_all_windows() {
+        let (a, b) = (WindowId(100), WindowId(101));
+
+        // Both windows paint during the first two frames, `a` starting the
+        // first one and `b` the second one.
+        assert!(starts_frame(a));
+        assert!(!starts_frame(b));
+        assert!(starts_frame(b));
+        assert!(!starts_frame(a));
+        assert!(starts_frame(a));
+    }
 }
@@ -2,10 +2,7 @@ use druid_shell::{
     kurbo::{Rect, Size},
     KeyEvent, Region,
 };
-use log::LevelFilter;
-use simplelog::{ColorChoice, Config, TermLogger, TerminalMode};
-
-use super::{handler::WindowHandler, FruiWindowHandler};
+use super::{handler::WindowHandler, init_logger, FruiWindowHandler};
 use crate::{app::listeners::keyboard::KEYBOARD_EVENT_LISTENERS, prelude::Widget};
 
 mod substitutes;
@@ -17,14 +14,7 @@ pub struct MiriAppRunner {
 
 impl MiriAppRunner {
     pub fn new<W: Widget + 'static>(widget: W) -> Self {
-        // Enable debug logging:
-        TermLogger::init(
-            LevelFilter::Info,
-            Config::default(),
-            TerminalMode::Mixed,
-            ColorChoice::AlwaysAnsi,
-        )
-        .unwrap();
+        init_logger();
 
         let mut window_handler = WindowHandler::new(widget);
 
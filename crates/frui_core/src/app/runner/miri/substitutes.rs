@@ -4,7 +4,7 @@ use druid_shell::{
     piet::{
         Color, CoreGraphicsImage, CoreGraphicsText, CoreGraphicsTextLayout, IntoBrush, PietText,
     },
-    Cursor, IdleToken,
+    Cursor, IdleToken, Scale,
 };
 
 /// Placeholder for [`IdleHandle`](druid_shell::IdleHandle) that allows us to test Frui in Miri.
@@ -40,6 +40,14 @@ impl WindowHandle {
 
     pub fn invalidate(&self) {}
 
+    pub fn close(&self) {}
+
+    pub fn show_context_menu(&self, _: druid_shell::Menu, _: druid_shell::kurbo::Point) {}
+
+    pub fn get_scale(&self) -> Result<Scale, druid_shell::Error> {
+        Ok(Scale::default())
+    }
+
     #[track_caller]
     pub fn text(&self) -> PietText {
         todo!()
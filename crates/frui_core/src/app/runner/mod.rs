@@ -1,4 +1,4 @@
-use druid_shell::{IdleToken, KeyEvent, MouseEvent};
+use druid_shell::{IdleToken, KeyEvent, MouseEvent, Scale};
 
 pub mod handler;
 
@@ -6,6 +6,18 @@ pub mod handler;
 pub mod miri;
 pub mod native;
 
+/// Installs a terminal logger, unless the application installed its own logger
+/// before starting.
+fn init_logger() {
+    #[cfg(feature = "logging")]
+    let _ = simplelog::TermLogger::init(
+        log::LevelFilter::Info,
+        simplelog::Config::default(),
+        simplelog::TerminalMode::Mixed,
+        simplelog::ColorChoice::AlwaysAnsi,
+    );
+}
+
 #[cfg(feature = "miri")]
 pub type IdleHandle = miri::IdleHandle;
 #[cfg(not(feature = "miri"))]
@@ -37,6 +49,8 @@ pub trait FruiWindowHandler {
 
     fn size(&mut self, size: druid_shell::kurbo::Size);
 
+    fn scale(&mut self, scale: Scale);
+
     fn idle(&mut self, token: IdleToken);
 
     fn destroy(&mut self);
@@ -55,5 +69,14 @@ pub trait FruiWindowHandler {
 
     fn key_down(&mut self, event: KeyEvent) -> bool;
 
+    fn key_up(&mut self, event: KeyEvent);
+
+    fn got_focus(&mut self);
+
+    fn lost_focus(&mut self);
+
     fn request_close(&mut self);
+
+    /// Called once an item of a context menu was selected.
+    fn command(&mut self, id: u32);
 }
@@ -0,0 +1,190 @@
+use std::{fmt, time::Duration};
+
+use druid_shell::{kurbo::Point, piet::Color, Application};
+
+use crate::{
+    api::contexts::render_ctx::layout_guard,
+    app::runner::{
+        handler::{set_build_budget, WindowHandler, QUIT_ON_LAST_WINDOW_CLOSED},
+        init_logger,
+    },
+    prelude::{Size, Widget},
+};
+
+use super::WindowDescriptor;
+
+/// Configuration of the application window, started with [`AppBuilder::run`].
+///
+/// ```no_run
+/// # #![feature(type_alias_impl_trait)]
+/// # extern crate frui_core as frui;
+/// # use frui::prelude::*;
+/// # #[derive(ViewWidget)]
+/// # struct App;
+/// # impl ViewWidget for App {
+/// #     fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+/// #         ()
+/// #     }
+/// # }
+/// # fn main() {
+/// AppBuilder::new(App)
+///     .with_title("Counter")
+///     .with_window_size(Size::new(400., 300.))
+///     .with_min_size(Size::new(200., 150.))
+///     .run()
+///     .unwrap();
+/// # }
+/// ```
+pub struct AppBuilder<W: Widget + 'static> {
+    widget: W,
+    window: WindowDescriptor,
+    windows: Vec<(WindowHandler, WindowDescriptor)>,
+    quit_on_last_window_closed: bool,
+    max_layouts_per_frame: usize,
+    build_budget: Option<Duration>,
+}
+
+impl<W: Widget + 'static> AppBuilder<W> {
+    // Currently there is `'static` lifetime requirement for the root widget
+    // because of the requirements of `WinHandle` from the druid_shell.
+    //
+    // In the future this requirement may be lifted.
+    pub fn new(widget: W) -> Self {
+        Self {
+            widget,
+            window: WindowDescriptor::default(),
+            windows: Vec::new(),
+            quit_on_last_window_closed: true,
+            max_layouts_per_frame: 10,
+            build_budget: None,
+        }
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.window = self.window.with_title(title);
+        self
+    }
+
+    /// See [`WindowDescriptor::with_window_size`].
+    pub fn with_window_size(mut self, size: Size) -> Self {
+        self.window = self.window.with_window_size(size);
+        self
+    }
+
+    /// See [`WindowDescriptor::with_min_size`].
+    pub fn with_min_size(mut self, size: Size) -> Self {
+        self.window = self.window.with_min_size(size);
+        self
+    }
+
+    /// See [`WindowDescriptor::with_position`].
+    pub fn with_position(mut self, position: Point) -> Self {
+        self.window = self.window.with_position(position);
+        self
+    }
+
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.window = self.window.with_resizable(resizable);
+        self
+    }
+
+    /// See [`WindowDescriptor::with_background`].
+    pub fn with_background(mut self, color: Color) -> Self {
+        self.window = self.window.with_background(color);
+        self
+    }
+
+    /// Opens another window next to the main one when the application starts.
+    /// See [`open_window`](super::open_window).
+    pub fn with_window(
+        mut self,
+        widget: impl Widget + 'static,
+        descriptor: WindowDescriptor,
+    ) -> Self {
+        self.windows.push((WindowHandler::new(widget), descriptor));
+        self
+    }
+
+    /// Whether the application quits once its last window closes. Defaults to
+    /// `true`.
+    ///
+    /// Otherwise the application keeps running without windows, until it
+    /// opens another one or quits on its own.
+    pub fn with_quit_on_last_window_closed(mut self, quit: bool) -> Self {
+        self.quit_on_last_window_closed = quit;
+        self
+    }
+
+    /// How many times a widget can be laid out during a single frame. Defaults
+    /// to 10.
+    ///
+    /// Layout which doesn't settle (e.g. because the size of a parent depends
+    /// on a child whose size depends on that parent) is stopped after that
+    /// many layouts with a warning, and the widget keeps its last size.
+    pub fn with_max_layouts_per_frame(mut self, max: usize) -> Self {
+        self.max_layouts_per_frame = max;
+        self
+    }
+
+    /// Time a frame can spend rebuilding dirty widgets, after which the rest
+    /// of them is rebuilt during the following frames. Unlimited by default.
+    ///
+    /// See [`set_build_budget`] for the tradeoffs.
+    pub fn with_build_budget(mut self, budget: Duration) -> Self {
+        self.build_budget = Some(budget);
+        self
+    }
+
+    /// Opens the windows and runs the application until it quits.
+    pub fn run(self) -> Result<(), AppError> {
+        if cfg!(feature = "miri") {
+            panic!(concat!(
+                "feature `miri` is enabled which is not supported for `run_app`. ",
+                "Disable feature `miri` to use `run_app`. To test application using ",
+                "Miri use `MiriRunner` instead."
+            ));
+        }
+
+        init_logger();
+
+        QUIT_ON_LAST_WINDOW_CLOSED.with(|quit| quit.set(self.quit_on_last_window_closed));
+        layout_guard::set_max_layouts_per_frame(self.max_layouts_per_frame);
+        set_build_budget(self.build_budget);
+
+        let app = Application::new().map_err(AppError::Application)?;
+
+        self.window.build(&app, WindowHandler::new(self.widget))?;
+
+        for (handler, descriptor) in self.windows {
+            descriptor.build(&app, handler)?;
+        }
+
+        app.run(None);
+
+        Ok(())
+    }
+}
+
+/// Error returned when the platform backend fails to start the application.
+#[derive(Debug, Clone)]
+pub enum AppError {
+    /// Platform application couldn't be created, e.g. because it already
+    /// exists.
+    Application(druid_shell::Error),
+    /// Platform failed to create the window.
+    Window(druid_shell::Error),
+    /// Window was opened before the application started.
+    NotRunning,
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Application(e) => write!(f, "failed to create the application: {e}"),
+            AppError::Window(e) => write!(f, "failed to create the window: {e}"),
+            AppError::NotRunning => write!(f, "the application isn't running"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
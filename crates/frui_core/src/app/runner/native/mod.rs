@@ -1,51 +1,28 @@
 use druid_shell::{
-    kurbo::Size, piet::Piet, Application, IdleToken, KeyEvent, MouseEvent, Region, WinHandler,
-    WindowBuilder, WindowHandle,
+    kurbo::Size, piet::Piet, IdleToken, KeyEvent, MouseEvent, Region, Scale, WinHandler,
+    WindowHandle,
 };
-use log::LevelFilter;
-use simplelog::{ColorChoice, Config, TermLogger, TerminalMode};
 
 use crate::prelude::Widget;
 
 use super::{handler::WindowHandler, FruiWindowHandler};
 
-// Currently there is `'static` lifetime requirement for the root widget
-// because of the requirements of `WinHandle` from the druid_shell.
-//
-// In the future this requirement may be lifted.
-pub fn run_app<'a>(widget: impl Widget + 'static) {
-    if cfg!(feature = "miri") {
-        panic!(concat!(
-            "feature `miri` is enabled which is not supported for `run_app`. ",
-            "Disable feature `miri` to use `run_app`. To test application using ",
-            "Miri use `MiriRunner` instead."
-        ));
-    }
-
-    // Enable debug logging:
-    TermLogger::init(
-        LevelFilter::Info,
-        Config::default(),
-        TerminalMode::Mixed,
-        ColorChoice::AlwaysAnsi,
-    )
-    .unwrap();
-
-    //
-    // Run app:
-
-    let app = Application::new().unwrap();
-
-    let mut window = WindowBuilder::new(app.clone());
-    window.set_handler(Box::new(WindowHandler::new(widget)));
-    window.set_title("Frui App");
-
-    let window = window.build().unwrap();
+pub use self::{
+    builder::{AppBuilder, AppError},
+    window::{close_window, open_window, WindowDescriptor, WindowId},
+};
 
-    window.show();
-    app.run(None);
+mod builder;
+mod window;
 
-    drop(window);
+/// Runs the application with the default window configuration.
+///
+/// Panics if the window couldn't be created. Use [`AppBuilder`] to configure
+/// the window and handle that error.
+pub fn run_app(widget: impl Widget + 'static) {
+    if let Err(e) = AppBuilder::new(widget).run() {
+        panic!("{e}");
+    }
 }
 
 impl druid_shell::AppHandler for WindowHandler {
@@ -83,6 +60,10 @@ impl WinHandler for WindowHandler {
         FruiWindowHandler::size(self, size)
     }
 
+    fn scale(&mut self, scale: Scale) {
+        FruiWindowHandler::scale(self, scale)
+    }
+
     fn idle(&mut self, token: IdleToken) {
         FruiWindowHandler::idle(self, token)
     }
@@ -111,7 +92,23 @@ impl WinHandler for WindowHandler {
         FruiWindowHandler::key_down(self, event)
     }
 
+    fn key_up(&mut self, event: KeyEvent) {
+        FruiWindowHandler::key_up(self, event)
+    }
+
+    fn got_focus(&mut self) {
+        FruiWindowHandler::got_focus(self)
+    }
+
+    fn lost_focus(&mut self) {
+        FruiWindowHandler::lost_focus(self)
+    }
+
     fn request_close(&mut self) {
         FruiWindowHandler::request_close(self)
     }
+
+    fn command(&mut self, id: u32) {
+        FruiWindowHandler::command(self, id)
+    }
 }
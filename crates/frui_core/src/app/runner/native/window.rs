@@ -0,0 +1,143 @@
+use druid_shell::{kurbo::Point, piet::Color, Application, WindowBuilder};
+
+use crate::{
+    app::runner::handler::WindowHandler,
+    prelude::{Size, Widget},
+};
+
+use super::AppError;
+
+pub use crate::app::runner::handler::WindowId;
+
+/// Configuration of a window, used by [`AppBuilder`](super::AppBuilder) and
+/// [`open_window`].
+#[derive(Debug, Clone)]
+pub struct WindowDescriptor {
+    title: String,
+    size: Option<Size>,
+    min_size: Option<Size>,
+    position: Option<Point>,
+    resizable: bool,
+    background: Option<Color>,
+}
+
+impl Default for WindowDescriptor {
+    fn default() -> Self {
+        Self {
+            title: String::from("Frui App"),
+            size: None,
+            min_size: None,
+            position: None,
+            resizable: true,
+            background: None,
+        }
+    }
+}
+
+impl WindowDescriptor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Initial size of the window's drawing area, in display points. Defaults
+    /// to the size chosen by the platform.
+    pub fn with_window_size(mut self, size: Size) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Minimum size of the window's drawing area, in display points.
+    pub fn with_min_size(mut self, size: Size) -> Self {
+        self.min_size = Some(size);
+        self
+    }
+
+    /// Initial position of the window, in pixels, relative to the origin of
+    /// the virtual screen. Defaults to the position chosen by the platform.
+    pub fn with_position(mut self, position: Point) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Color the window is cleared with before painting widgets.
+    pub fn with_background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Creates and shows the window hosting the widget tree of `handler`.
+    pub(super) fn build(
+        self,
+        app: &Application,
+        mut handler: WindowHandler,
+    ) -> Result<(), AppError> {
+        if let Some(background) = self.background {
+            handler.background = background;
+        }
+
+        let mut window = WindowBuilder::new(app.clone());
+        window.set_handler(Box::new(handler));
+        window.set_title(self.title);
+        window.resizable(self.resizable);
+
+        if let Some(size) = self.size {
+            window.set_size(size.into());
+        }
+
+        if let Some(size) = self.min_size {
+            window.set_min_size(size.into());
+        }
+
+        if let Some(position) = self.position {
+            window.set_position(position);
+        }
+
+        window.build().map_err(AppError::Window)?.show();
+
+        Ok(())
+    }
+}
+
+/// Opens another window of the running application, hosting its own widget
+/// tree.
+///
+/// Every window is laid out and painted separately, but all of them are driven
+/// by the same run loop. Widget trees of different windows don't share
+/// inherited widgets, so state shared by windows has to be passed to each of
+/// their root widgets (e.g. as an `Rc`). Rebuild scheduled in any window wakes
+/// up all of them.
+///
+/// Closing a window unmounts its widget tree. Whether the application quits
+/// after its last window closes is configured by
+/// [`AppBuilder::with_quit_on_last_window_closed`](super::AppBuilder::with_quit_on_last_window_closed).
+///
+/// Returns [`AppError::NotRunning`] if called before the application started.
+pub fn open_window(
+    widget: impl Widget + 'static,
+    descriptor: WindowDescriptor,
+) -> Result<WindowId, AppError> {
+    let app = Application::try_global().ok_or(AppError::NotRunning)?;
+
+    let handler = WindowHandler::new(widget);
+    let id = handler.id();
+
+    descriptor.build(&app, handler)?;
+
+    Ok(id)
+}
+
+/// Closes the window identified by `id`, unmounting its widget tree. Does
+/// nothing if that window is already closed.
+pub fn close_window(id: WindowId) {
+    crate::app::runner::handler::close_window(id);
+}
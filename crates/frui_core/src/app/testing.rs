@@ -0,0 +1,95 @@
+//! Widget tree driven without a window, for tests of widgets and benchmarks
+//! of their layout.
+//!
+//! Frames are rebuilt and laid out the same way the run loop does it. They are
+//! painted only with [`WidgetTester::paint`], into an offscreen bitmap.
+
+use std::time::Instant;
+
+#[cfg(not(feature = "miri"))]
+use druid_shell::piet::Device;
+use druid_shell::KeyEvent;
+
+use crate::{
+    api::{
+        events::Event,
+        media_query::{MediaQuery, MediaQueryData},
+        WidgetPtr,
+    },
+    app::{runner::handler, tree::WidgetTree},
+    prelude::{Constraints, Size, Widget},
+};
+
+pub struct WidgetTester {
+    tree: WidgetTree,
+    window_size: Size,
+}
+
+impl WidgetTester {
+    /// Mounts `widget` in a window of `window_size` and lays out the first
+    /// frame.
+    pub fn new(widget: impl Widget + 'static, window_size: Size) -> Self {
+        let data = MediaQueryData {
+            size: window_size,
+            ..MediaQueryData::default()
+        };
+        let root = MediaQuery::new(data, Box::new(widget));
+
+        let mut this = Self {
+            tree: WidgetTree::new(WidgetPtr::from_owned(Box::new(root))),
+            window_size,
+        };
+
+        this.pump();
+        this
+    }
+
+    /// Runs a frame: calls frame callbacks, rebuilds dirty widgets, lays out
+    /// the tree within the window and calls post-frame callbacks.
+    pub fn pump(&mut self) {
+        handler::run_frame_callbacks(Instant::now());
+        handler::rebuild_dirty();
+        self.layout(Constraints::loose(self.window_size));
+        handler::run_post_frame_callbacks();
+    }
+
+    /// Lays out the tree within `constraints`, without rebuilding anything.
+    pub fn layout(&mut self, constraints: Constraints) {
+        self.tree.layout(constraints);
+    }
+
+    /// Paints the last layout into an offscreen bitmap of the window size,
+    /// like the window does after layout.
+    #[cfg(not(feature = "miri"))]
+    pub fn paint(&mut self) {
+        let mut device = Device::new().unwrap();
+        let (width, height) = (self.window_size.width, self.window_size.height);
+        let mut target = device
+            .bitmap_target(width.ceil() as usize, height.ceil() as usize, 1.)
+            .unwrap();
+
+        self.tree.paint(&mut target.render_context());
+    }
+
+    /// Size of the root widget from the last layout.
+    pub fn size(&mut self) -> Size {
+        self.tree.get_root().borrow().render_data.size
+    }
+
+    /// Whether a frame was scheduled since the last call of this method, e.g.
+    /// by a running animation or a widget which needs to be laid out again.
+    pub fn take_scheduled_frame(&mut self) -> bool {
+        handler::take_scheduled_frame()
+    }
+
+    /// Dispatches pointer `event` to widgets, like the window does.
+    pub fn handle_event(&mut self, event: Event) {
+        self.tree.handle_event(event);
+    }
+
+    /// Dispatches key down `event` to shortcuts and keyboard listeners (e.g.
+    /// of `KeyboardEventDetector`), like the window does.
+    pub fn send_keyboard_event(&mut self, event: KeyEvent) {
+        handler::dispatch_key_down(event);
+    }
+}
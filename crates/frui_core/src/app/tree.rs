@@ -2,24 +2,99 @@ use std::{
     any::{Any, TypeId},
     cell::{Cell, Ref, RefCell, RefMut, UnsafeCell},
     collections::{HashMap, HashSet},
+    fmt::Write,
     hash::Hash,
     rc::Rc,
     sync::Arc,
 };
 
-use druid_shell::IdleToken;
+use druid_shell::kurbo::Affine;
 
 use crate::{
     api::{
-        contexts::{render_ctx::AnyRenderContext, Context},
-        events::Event,
-        local_key::LocalKeyAny,
+        contexts::{
+            render_ctx::{layout_guard, AnyRenderContext, RenderPhase},
+            Context,
+        },
+        events::{Event, PointerBehavior},
+        gestures, layers, lifecycle,
+        local_key::Key,
+        media_query::MediaQueryData,
+        paint,
+        prewarm::PrewarmedSubtree,
         IntoWidgetPtr, WidgetPtr,
     },
-    app::runner::handler::{APP_HANDLE, NEED_REBUILD},
+    app::{
+        listeners::{self, pointer, shortcuts},
+        runner::handler::{add_post_frame_callback, schedule_frame, NEED_REBUILD},
+    },
     prelude::{Constraints, Offset, PaintContext, Size, WidgetKind},
 };
 
+thread_local! {
+    /// Relayout boundaries which need layout, see
+    /// [`WidgetNodeRef::mark_needs_layout_up_to_boundary`].
+    static RELAYOUT_BOUNDARIES: RefCell<Vec<WidgetNodeRef>> = const { RefCell::new(Vec::new()) };
+
+    /// Number of widget trees which weren't dropped yet.
+    static LIVE_TREES: Cell<usize> = const { Cell::new(0) };
+
+    /// Debug id of the last created node, see [`WidgetNodeRef::debug_id`].
+    static LAST_DEBUG_ID: Cell<u64> = const { Cell::new(0) };
+
+    /// Number of the last paint pass, counted across all widget trees.
+    static PAINT_PASS: Cell<u64> = const { Cell::new(0) };
+
+    /// Nodes built since the last frame, see [`schedule_after_first_build`].
+    static FIRST_BUILT: RefCell<Vec<WidgetNodeRef>> = const { RefCell::new(Vec::new()) };
+
+    /// Node whose `build` is currently running.
+    static BUILDING_NODE: RefCell<Option<WidgetNodeRef>> = const { RefCell::new(None) };
+}
+
+/// Node whose `build` is currently running, if any.
+pub(crate) fn building_node() -> Option<WidgetNodeRef> {
+    BUILDING_NODE.with(|node| node.borrow().clone())
+}
+
+/// Runs `build` of `node`, marking it as being built.
+fn with_building<R>(node: &WidgetNodeRef, build: impl FnOnce() -> R) -> R {
+    let previous = BUILDING_NODE.with(|n| n.replace(Some(node.clone())));
+    let r = build();
+    BUILDING_NODE.with(|n| *n.borrow_mut() = previous);
+    r
+}
+
+/// Calls `after_first_build` of `node` after the next frame.
+fn schedule_after_first_build(node: WidgetNodeRef) {
+    let first = FIRST_BUILT.with(|nodes| {
+        let mut nodes = nodes.borrow_mut();
+        nodes.push(node);
+        nodes.len() == 1
+    });
+
+    // One callback handles every node built during the frame.
+    if first {
+        add_post_frame_callback(run_after_first_build);
+    }
+}
+
+/// Calls `after_first_build` of nodes built since the last frame, in the order
+/// in which they were mounted (children before their parents).
+fn run_after_first_build() {
+    let nodes = FIRST_BUILT.with(|nodes| std::mem::take(&mut *nodes.borrow_mut()));
+
+    for node in nodes.into_iter().filter(WidgetNodeRef::is_alive) {
+        let context = unsafe { &*node.ptr.context_ptr() };
+        node.widget().after_first_build(context);
+    }
+}
+
+/// Number of the last paint pass, see [`WidgetNodeRef::painted_pass`].
+pub(crate) fn paint_pass() -> u64 {
+    PAINT_PASS.with(|pass| pass.get())
+}
+
 pub struct WidgetTree {
     /// Root widget contains necessary configuration to support `InheritedWidget`s.
     /// Child of that dummy node is the actual root node.
@@ -37,30 +112,104 @@ impl WidgetTree {
         // Make that root widget child of dummy node.
         unsafe { (&mut *dummy_root.children_ptr_mut()).push(root) };
 
+        LIVE_TREES.with(|trees| trees.set(trees.get() + 1));
+
         Self { dummy_root }
     }
 
     pub(crate) fn layout(&mut self, constraints: Constraints) {
-        AnyRenderContext::new(self.get_root()).layout(constraints);
+        let root = self.get_root();
+
+        RenderPhase::Layout.run(|| {
+            layout_guard::begin_pass();
+
+            AnyRenderContext::new(root).layout(constraints);
+
+            // Relayout boundaries which weren't reached from the root, since
+            // their ancestors kept cached layout.
+            let boundaries = RELAYOUT_BOUNDARIES.with(|b| std::mem::take(&mut *b.borrow_mut()));
+
+            for node in boundaries {
+                if node.is_alive() && node.borrow().render_data.needs_layout {
+                    let constraints = node.borrow().render_data.constraints;
+                    AnyRenderContext::new(node).layout(constraints);
+                }
+            }
+        });
     }
 
     pub(crate) fn paint(&mut self, piet: &mut PaintContext) {
-        AnyRenderContext::new(self.get_root()).paint(piet, &Offset::default());
+        let root = self.get_root();
+
+        PAINT_PASS.with(|pass| pass.set(pass.get() + 1));
+
+        RenderPhase::Paint.run(|| {
+            layers::record_frame(|| AnyRenderContext::new(root).paint(piet, &Offset::default()))
+        });
+
+        if cfg!(debug_assertions) {
+            let unbalanced = paint::restore_unbalanced(piet);
+
+            assert!(
+                unbalanced == 0,
+                "canvas was saved {unbalanced} more time(s) than restored during paint, which \
+                leaks transforms and clips into the paint of following widgets (use \
+                `PaintContextExt::save_guard` to restore it automatically)"
+            );
+        }
     }
 
     pub(crate) fn handle_event(&mut self, event: Event) {
+        let root = self.get_root();
+
         // Handle the event.
-        self.get_root().handle_event(event);
+        gestures::before_dispatch(&event);
+        if let Event::MouseDown(_) = event {
+            shortcuts::before_pointer_down();
+        }
+        pointer::dispatch(&root, event.clone());
+        gestures::after_dispatch(&event);
+    }
+
+    /// Updates media query data of the root
+    /// [`MediaQuery`](crate::api::media_query::MediaQuery), rebuilding widgets
+    /// which depend on it if it changed.
+    pub(crate) fn set_media_query(&mut self, data: MediaQueryData) {
+        // Tree wasn't constructed yet.
+        if unsafe { (&*self.dummy_root.children_ptr_mut()).is_empty() } {
+            return;
+        }
+
+        let root = self.get_root();
+
+        let changed = match root.borrow_mut().state.downcast_mut::<MediaQueryData>() {
+            Some(state) if *state != data => {
+                *state = data;
+                true
+            }
+            _ => false,
+        };
+
+        if changed {
+            root.mark_dependent_widgets_as_dirty();
+        }
+    }
+
+    /// Removes the root widget node from this tree, leaving it empty.
+    pub(crate) fn take_root(&mut self) -> UnsafeCell<Box<WidgetNode>> {
+        unsafe { (&mut *self.dummy_root.children_ptr_mut()).remove(0) }
     }
 
     /// Returns the root widget node, extracting it from the dummy node.
-    fn get_root(&mut self) -> WidgetNodeRef {
+    pub(crate) fn get_root(&mut self) -> WidgetNodeRef {
         unsafe { WidgetNode::node_ref(&(&*self.dummy_root.children_ptr_mut())[0]) }
     }
 }
 
 impl Default for WidgetTree {
     fn default() -> Self {
+        LIVE_TREES.with(|trees| trees.set(trees.get() + 1));
+
         Self {
             dummy_root: WidgetNode::default(),
         }
@@ -71,6 +220,11 @@ impl Drop for WidgetTree {
     fn drop(&mut self) {
         // Safety: `drop_mut` is called only once here.
         unsafe { WidgetNode::drop_mut(&mut self.dummy_root) };
+
+        // Every widget was disposed, so nothing should be registered anymore.
+        if LIVE_TREES.with(|trees| trees.replace(trees.get() - 1)) == 1 {
+            listeners::warn_about_leaks();
+        }
     }
 }
 
@@ -102,9 +256,20 @@ pub(crate) enum Inheritance {
 
 pub(crate) struct WidgetInner {
     pub dirty: bool,
+    /// Whether `build` of this widget is currently running.
+    pub building: bool,
+    /// Whether an inherited widget this widget depends on changed since the
+    /// last build.
+    pub dependencies_changed: bool,
+    /// Whether this widget looked up an inherited widget which wasn't found.
+    /// Such lookup may succeed once the subtree is attached elsewhere, see
+    /// [`WidgetNode::attach`].
+    pub missed_dependencies: bool,
     pub state: Box<dyn Any>,
     pub render_data: RenderData,
     pub inheritance: Inheritance,
+    /// Id assigned when this node was created, see [`WidgetNodeRef::debug_id`].
+    pub debug_id: u64,
 }
 
 pub(crate) struct WidgetNode {
@@ -115,6 +280,9 @@ pub(crate) struct WidgetNode {
 
     parent: Option<WidgetNodeRef>,
     children: Vec<UnsafeCell<Box<WidgetNode>>>,
+
+    /// Number of ancestors of this node. Root widget has depth `0`.
+    depth: usize,
 }
 
 impl WidgetNode {
@@ -124,10 +292,22 @@ impl WidgetNode {
         parent: Option<WidgetNodeRef>,
         mut inherited_ancestor: WidgetNodeRef,
     ) -> UnsafeCell<Box<Self>> {
+        // Subtree built ahead of time is attached here instead of being built.
+        if let Some(mut node) = PrewarmedSubtree::take_detached(&widget) {
+            WidgetNode::attach(&mut node, parent, &inherited_ancestor);
+
+            // Safety: Attached node keeps its own configuration.
+            unsafe { WidgetPtr::drop(widget) };
+
+            return node;
+        }
+
         // Safety: We enforce correct lifetime of each pointer manually.
         let widget_ptr =
             unsafe { std::mem::transmute::<WidgetPtr, WidgetPtr<'static>>(widget.clone()) };
 
+        let depth = parent.as_ref().map_or(0, |parent| parent.depth() + 1);
+
         let mut this = UnsafeCell::new(Box::new(WidgetNode {
             widget_ptr,
             context: Context {
@@ -138,12 +318,20 @@ impl WidgetNode {
             },
             inner: RefCell::new(WidgetInner {
                 dirty: false,
+                building: false,
+                dependencies_changed: false,
+                missed_dependencies: false,
                 state: widget.create_state(),
                 render_data: RenderData::new(&widget),
                 inheritance: Inheritance::new(&widget, &inherited_ancestor),
+                debug_id: LAST_DEBUG_ID.with(|id| {
+                    id.set(id.get() + 1);
+                    id.get()
+                }),
             }),
             parent,
             children: Vec::new(),
+            depth,
         }));
 
         // We access fields of `WidgetNode` in this way to not pop tag for `context.node_ref.ptr`
@@ -180,27 +368,97 @@ impl WidgetNode {
         // From this point on, `WidgetNode` cannot be accessed mutably or otherwise its
         // pointer tag will be popped and `context` invalidated.
 
-        let children = unsafe {
-            widget
-                .build(&*this.context_ptr())
-                .into_iter()
-                .map(|child_widget_ptr| {
-                    WidgetNode::new(
-                        child_widget_ptr,
-                        Some(node_ref.clone()),
-                        inherited_ancestor.clone(),
-                    )
-                })
-                .collect::<Vec<_>>()
-        };
+        inner_ref.borrow_mut().building = true;
+        let children_build =
+            with_building(&node_ref, || unsafe { widget.build(&*this.context_ptr()) });
+        inner_ref.borrow_mut().building = false;
+
+        let children = children_build
+            .into_iter()
+            .map(|child_widget_ptr| {
+                WidgetNode::new(
+                    child_widget_ptr,
+                    Some(node_ref.clone()),
+                    inherited_ancestor.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
 
         *children_ref_mut = children;
 
         WidgetNode::mount(&this);
 
+        // Dependencies were registered during the first build.
+        WidgetNode::did_change_dependencies(&this);
+
+        schedule_after_first_build(node_ref);
+
         this
     }
 
+    /// Moves subtree starting at this detached node under `parent`, without
+    /// rebuilding or mounting it again.
+    ///
+    /// Widgets of the subtree which didn't find an inherited widget while it
+    /// was detached are rebuilt, since they may find it now.
+    fn attach(
+        s: &mut UnsafeCell<Box<Self>>,
+        parent: Option<WidgetNodeRef>,
+        inherited_ancestor: &WidgetNodeRef,
+    ) {
+        let depth = parent.as_ref().map_or(0, |parent| parent.depth() + 1);
+
+        unsafe {
+            *s.parent_ptr_mut() = parent;
+            *s.depth_ptr_mut() = depth;
+        }
+
+        let node_ref = WidgetNode::node_ref(s);
+        let mut inner = node_ref.borrow_mut();
+
+        // Inherited widgets of the subtree now also inherit from its new
+        // ancestors. Other widgets depend on the closest inherited ancestor.
+        let children_ancestor = match &mut inner.inheritance {
+            Inheritance::Inheritor {
+                active_inheritors, ..
+            } => {
+                let mut inheritors = inherited_ancestor
+                    .borrow()
+                    .inheritance
+                    .active_inheritors()
+                    .clone();
+
+                for (key, inheritor) in active_inheritors.iter() {
+                    if *inheritor == node_ref {
+                        inheritors.insert(*key, inheritor.clone());
+                    }
+                }
+
+                *active_inheritors = inheritors;
+                node_ref.clone()
+            }
+            Inheritance::Inheritee {
+                inherited_ancestor: ancestor,
+                ..
+            } => {
+                *ancestor = inherited_ancestor.clone();
+                inherited_ancestor.clone()
+            }
+        };
+
+        let missed_dependencies = std::mem::take(&mut inner.missed_dependencies);
+        drop(inner);
+
+        if missed_dependencies {
+            node_ref.borrow_mut().dependencies_changed = true;
+            node_ref.mark_dirty();
+        }
+
+        for child in unsafe { (&mut *s.children_ptr_mut()).iter_mut() } {
+            WidgetNode::attach(child, Some(node_ref.clone()), &children_ancestor);
+        }
+    }
+
     /// Update subtree starting at this node.
     pub fn update_subtree(s: &UnsafeCell<Box<Self>>) {
         unsafe { Self::update_subtree_ptr(std::ptr::addr_of_mut!(*(*s.get()))) }
@@ -233,46 +491,46 @@ impl WidgetNode {
             .into_iter()
             .map(|c| Some(c))
             .collect::<Vec<_>>();
-        let new_children_build = widget_ref.build(context_ref);
+
+        let dependencies_changed = std::mem::take(&mut inner_ref.borrow_mut().dependencies_changed);
+
+        if dependencies_changed {
+            widget_ref.did_change_dependencies(context_ref);
+        }
+
+        inner_ref.borrow_mut().building = true;
+        let new_children_build = with_building(&context_ref.node, || widget_ref.build(context_ref));
+        inner_ref.borrow_mut().building = false;
+
         let mut new_children = Vec::with_capacity(new_children_build.len());
 
-        for (n, new_child) in new_children_build.into_iter().enumerate() {
-            if new_child.has_key() {
-                if let Some(n) = old_children.find_key(&new_child) {
+        // Old children are matched with new ones first by key, wherever they
+        // are, and then by position among the children without a key. Matched
+        // old child keeps its state if its type is the same as of the new one.
+        let mut unkeyed_old_children = (0..old_children.len())
+            .filter(|&n| match &old_children[n] {
+                Some(old_child) => !(&*old_child.widget_ptr()).has_key(),
+                None => false,
+            })
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        for new_child in new_children_build {
+            let old_child = if new_child.has_key() {
+                old_children.find_key(&new_child)
+            } else {
+                unkeyed_old_children.next()
+            };
+
+            match old_child {
+                Some(n) => {
                     // Remove old_child from old_children.
-                    let old_child = std::mem::take(old_children.get_mut(n).unwrap()).unwrap();
+                    let old_child = std::mem::take(&mut old_children[n]).unwrap();
 
                     // Try to update old_child with new_child.
                     new_children.push(WidgetNode::update(old_child, new_child));
-                } else {
-                    // Build new_child.
-                    let child = WidgetNode::new(
-                        new_child,
-                        Some(context_ref.node.clone()),
-                        inherited_ancestor.clone(),
-                    );
-
-                    new_children.push(child);
                 }
-            } else {
-                if let Some(Some(old_child)) = old_children.get(n) {
-                    if (&*old_child.widget_ptr()).has_key() {
-                        // Build new_child.
-                        let child = WidgetNode::new(
-                            new_child,
-                            Some(context_ref.node.clone()),
-                            inherited_ancestor.clone(),
-                        );
-
-                        new_children.push(child);
-                    } else {
-                        // Remove old_child from old_children.
-                        let old_child = std::mem::take(old_children.get_mut(n).unwrap()).unwrap();
-
-                        // Try to update old_child with new_child.
-                        new_children.push(WidgetNode::update(old_child, new_child));
-                    }
-                } else {
+                None => {
                     // Build new_child.
                     let child = WidgetNode::new(
                         new_child,
@@ -335,6 +593,13 @@ impl WidgetNode {
             // if they are ZST, however it is currently treated as UB to dereference ZST to
             // deallocated memory, so we don't do it.
             if widget_ref.eq(&new_widget) {
+                diagnostic!(
+                    trace,
+                    "rebuild",
+                    "reusing configuration of {}, skipping its rebuild",
+                    widget_ref.debug_name_short()
+                );
+
                 // Safety: Since we reuse old widget ptr, we can drop the newly created one.
                 unsafe { WidgetPtr::drop(new_widget) };
 
@@ -354,9 +619,14 @@ impl WidgetNode {
                 let old_widget_ptr =
                     unsafe { std::mem::replace(&mut *s.widget_ptr_mut(), new_widget) };
 
+                WidgetNode::did_update_widget(&s);
+
                 // Update descendants of this node, stopping at equal widgets or a leaf node.
                 WidgetNode::update_subtree(&s);
 
+                // New configuration may lay out differently.
+                WidgetNode::node_ref(&s).mark_needs_layout();
+
                 // Safety:
                 //
                 // There are no children widgets referencing the old pointer (because we
@@ -402,7 +672,23 @@ impl WidgetNode {
 
         let mut render_ctx = AnyRenderContext::new(s.clone());
 
-        if !widget_ref.handle_event(&mut render_ctx, event) {
+        let behavior = match event.pointer_pos() {
+            Some(_) => s.borrow().render_data.pointer_behavior,
+            None => PointerBehavior::Pass,
+        };
+
+        if behavior == PointerBehavior::Ignore {
+            return;
+        }
+
+        let handled = pointer::is_delivered_to(s) && {
+            pointer::mark_received(s);
+            widget_ref.handle_event(&mut render_ctx, event)
+        };
+
+        let absorbed = behavior == PointerBehavior::Absorb && pointer::is_absorbed_by(s);
+
+        if !handled && !absorbed {
             for child in children_ref.iter() {
                 let child = WidgetNode::node_ref(child);
                 WidgetNode::handle_event(&child, event);
@@ -433,6 +719,7 @@ impl WidgetNode {
         }
 
         WidgetNode::unmount(&s);
+        WidgetNode::dispose(s);
 
         // Remove this widget from its parent's list of inheriting widgets.
         match &(&*inner_ref).borrow().inheritance {
@@ -486,6 +773,27 @@ impl WidgetNode {
         widget.unmount(context)
     }
 
+    pub fn did_update_widget(s: &UnsafeCell<Box<Self>>) {
+        let widget = unsafe { &*s.widget_ptr() };
+        let context = unsafe { &*s.context_ptr() };
+
+        widget.did_update_widget(context)
+    }
+
+    pub fn did_change_dependencies(s: &UnsafeCell<Box<Self>>) {
+        let widget = unsafe { &*s.widget_ptr() };
+        let context = unsafe { &*s.context_ptr() };
+
+        widget.did_change_dependencies(context)
+    }
+
+    pub fn dispose(s: &UnsafeCell<Box<Self>>) {
+        let widget = unsafe { &*s.widget_ptr() };
+        let context = unsafe { &*s.context_ptr() };
+
+        widget.dispose(context)
+    }
+
     //
     //
 
@@ -493,8 +801,8 @@ impl WidgetNode {
         unsafe { (&*s.context_ptr()).node.clone() }
     }
 
-    pub fn local_key<'a>(s: &UnsafeCell<Box<WidgetNode>>) -> Option<LocalKeyAny<'a>> {
-        unsafe { (&*s.widget_ptr()).local_key() }
+    pub fn key<'a>(s: &UnsafeCell<Box<WidgetNode>>) -> Option<Key<'a>> {
+        unsafe { (&*s.widget_ptr()).key() }
     }
 }
 
@@ -532,13 +840,9 @@ impl WidgetNodeRef {
     pub fn mark_dirty(&self) {
         assert_eq!(self.is_alive.get(), true);
 
-        APP_HANDLE.with(|handle| {
-            handle
-                .borrow_mut()
-                .as_mut()
-                .expect("APP_HANDLE wasn't set")
-                .schedule_idle(IdleToken::new(0));
-        });
+        self.mark_needs_layout();
+
+        schedule_frame();
 
         if !self.borrow_mut().dirty {
             self.borrow_mut().dirty = true;
@@ -549,18 +853,81 @@ impl WidgetNodeRef {
         }
     }
 
+    /// Schedules paint of the next frame, without rebuilding this node or
+    /// invalidating its layout.
+    ///
+    /// Paint requested during paint (i.e. the next frame of an animation) is
+    /// deferred while the application isn't resumed, see
+    /// [`lifecycle`](crate::api::lifecycle).
+    pub fn mark_needs_paint(&self) {
+        assert!(self.is_alive.get());
+
+        if RenderPhase::current() == RenderPhase::Paint {
+            lifecycle::schedule_animation_frame();
+        } else {
+            schedule_frame();
+        }
+    }
+
+    /// Invalidates cached layout of this node and all of its ancestors, so that
+    /// they are laid out again during the next layout.
+    pub fn mark_needs_layout(&self) {
+        assert!(self.is_alive.get());
+
+        // We don't stop at ancestors which already need layout, since a parent
+        // could have been laid out without laying out this node.
+        let mut node = Some(self.clone());
+
+        while let Some(n) = node {
+            n.borrow_mut().render_data.needs_layout = true;
+            node = unsafe { (&*n.ptr.parent_ptr()).clone() };
+        }
+    }
+
+    /// Invalidates cached layout of this node and of its ancestors up to the
+    /// closest relayout boundary, which is then laid out again on its own
+    /// during the next layout.
+    ///
+    /// Relayout boundary is a node laid out with tight constraints, whose size
+    /// can't change, so its ancestors don't have to be laid out again. This is
+    /// only correct if configurations of those nodes didn't change, which is
+    /// why rebuilt nodes use [`mark_needs_layout`](Self::mark_needs_layout).
+    pub fn mark_needs_layout_up_to_boundary(&self) {
+        assert!(self.is_alive.get());
+
+        let mut node = Some(self.clone());
+
+        while let Some(n) = node {
+            let is_boundary = {
+                let render_data = &mut n.borrow_mut().render_data;
+                render_data.needs_layout = true;
+                render_data.laid_out && render_data.constraints.is_tight()
+            };
+
+            if is_boundary {
+                RELAYOUT_BOUNDARIES.with(|b| b.borrow_mut().push(n));
+                return;
+            }
+
+            node = unsafe { (&*n.ptr.parent_ptr()).clone() };
+        }
+    }
+
     pub fn mark_dependent_widgets_as_dirty(&self) {
         assert_eq!(self.is_alive.get(), true);
 
-        match &self.borrow().inheritance {
+        // Dependents are collected first, since marking them dirty invalidates
+        // layout of their ancestors, including this node.
+        let inheriting_widgets = match &self.borrow().inheritance {
             Inheritance::Inheritor {
                 inheriting_widgets, ..
-            } => {
-                for widget in inheriting_widgets.iter() {
-                    widget.mark_dirty()
-                }
-            }
+            } => inheriting_widgets.iter().cloned().collect::<Vec<_>>(),
             _ => unreachable!(),
+        };
+
+        for widget in inheriting_widgets {
+            widget.borrow_mut().dependencies_changed = true;
+            widget.mark_dirty()
         }
     }
 
@@ -592,7 +959,14 @@ impl WidgetNodeRef {
         };
 
         // Target InheritedWidget (matching K).
-        let inherited_widget = active_inheritors.get(&key)?.clone();
+        let inherited_widget = match active_inheritors.get(&key) {
+            Some(inherited_widget) => inherited_widget.clone(),
+            None => {
+                drop(inherited_ref);
+                node_ref.missed_dependencies = true;
+                return None;
+            }
+        };
         drop(inherited_ref);
         let mut inherited_widget_ref = inherited_widget.borrow_mut();
 
@@ -612,6 +986,117 @@ impl WidgetNodeRef {
         Some(inherited_widget.clone())
     }
 
+    /// Laid out size of the closest ancestor whose widget has `unique_type`,
+    /// or `None` if there is no such ancestor or it wasn't laid out yet.
+    pub fn find_ancestor_size(&self, unique_type: TypeId) -> Option<Size> {
+        assert!(self.is_alive.get());
+
+        let mut node = unsafe { (&*self.ptr.parent_ptr()).clone() };
+
+        while let Some(n) = node {
+            if n.widget().unique_type_id() == unique_type {
+                let render_data = &n.borrow().render_data;
+                return render_data.laid_out.then_some(render_data.size);
+            }
+
+            node = unsafe { (&*n.ptr.parent_ptr()).clone() };
+        }
+
+        None
+    }
+
+    /// Bubbles `notification` up the tree, starting at the parent of this node,
+    /// until an ancestor stops it.
+    pub fn dispatch_notification(&self, notification: &dyn Any) {
+        assert!(self.is_alive.get());
+
+        // Ancestors outlive their descendants, so they can be safely accessed
+        // even if this node is being built right now.
+        let mut node = unsafe { (&*self.ptr.parent_ptr()).clone() };
+
+        while let Some(n) = node {
+            if n.widget().handle_notification(notification) {
+                break;
+            }
+
+            node = unsafe { (&*n.ptr.parent_ptr()).clone() };
+        }
+    }
+
+    /// Number of ancestors of this node.
+    pub fn depth(&self) -> usize {
+        assert!(self.is_alive.get());
+        unsafe { *self.ptr.depth_ptr() }
+    }
+
+    /// Short type names of this node and its ancestors, joined with `/` and
+    /// starting at the root.
+    pub fn debug_path(&self) -> String {
+        assert!(self.is_alive.get());
+
+        let mut names = Vec::with_capacity(self.depth() + 1);
+        let mut node = Some(self.clone());
+
+        while let Some(n) = node {
+            names.push(n.widget().debug_name_short());
+            node = unsafe { (&*n.ptr.parent_ptr()).clone() };
+        }
+
+        names.reverse();
+        names.join("/")
+    }
+
+    /// Id of this node, unique among nodes created on this thread.
+    ///
+    /// It is assigned once the node is created, so it stays the same while the
+    /// widget is rebuilt or updated with a new configuration, and changes only
+    /// if the widget is built from scratch (e.g. after its type changed).
+    pub fn debug_id(&self) -> u64 {
+        self.borrow().debug_id
+    }
+
+    /// Paint pass during which this node was last painted, or 0 if it wasn't
+    /// painted yet. See [`paint_pass`].
+    pub(crate) fn painted_pass(&self) -> u64 {
+        self.borrow().render_data.painted_pass
+    }
+
+    /// Describes the subtree starting at this node, one node per line with
+    /// children indented under their parent.
+    pub fn dump_tree(&self) -> String {
+        assert!(self.is_alive.get());
+
+        let mut dump = String::new();
+        self.describe_subtree(0, &mut dump);
+        dump
+    }
+
+    fn describe_subtree(&self, depth: usize, dump: &mut String) {
+        let (debug_id, size) = {
+            let inner = self.borrow();
+            (inner.debug_id, inner.render_data.size)
+        };
+
+        let _ = writeln!(
+            dump,
+            "{:indent$}{} #{debug_id} size ({:?}, {:?})",
+            "",
+            self.widget().debug_name_short(),
+            size.width,
+            size.height,
+            indent = depth * 2,
+        );
+
+        for child in self.children() {
+            WidgetNode::node_ref(child).describe_subtree(depth + 1, dump);
+        }
+    }
+
+    pub fn parent(&self) -> Option<WidgetNodeRef> {
+        assert!(self.is_alive.get());
+        unsafe { (&*self.ptr.parent_ptr()).clone() }
+    }
+
     pub fn is_alive(&self) -> bool {
         self.is_alive.get()
     }
@@ -648,13 +1133,33 @@ pub(crate) struct RenderData {
     pub state: Box<dyn Any>,
     /// Size computed during last layout.
     pub size: Size,
-    /// Position computed during last paint.
+    /// Origin of this widget during last paint, as the sum of its offset and
+    /// of the offsets of its ancestors (not including transforms of the canvas
+    /// they painted it with, see `paint_transform`).
     pub offset: Offset,
+    /// Offset from the origin of the parent, set by the parent during layout.
+    pub parent_offset: Option<Offset>,
     /// Incoming constraints received during last layout.
     pub constraints: Constraints,
     /// Whether child was laid out. Used to display an error message when
     /// painting children without laying them out beforehand.
     pub laid_out: bool,
+    /// Whether layout of this widget or any of its descendants was invalidated
+    /// since the last layout.
+    pub needs_layout: bool,
+    /// Indices of children laid out during last layout of this widget, in
+    /// ascending order. Other children weren't placed, so they can't be hit.
+    pub laid_out_children: Vec<usize>,
+    pub pointer_behavior: PointerBehavior,
+    /// Transform of the canvas during last paint, from the coordinates of
+    /// this widget to those of the window. Used to map pointer positions when
+    /// hit testing.
+    pub paint_transform: Affine,
+    /// Layout pass during which this widget was last laid out, and how many
+    /// times it was laid out during that pass.
+    pub layout_pass: (u64, usize),
+    /// Paint pass during which this widget was last painted.
+    pub painted_pass: u64,
 }
 
 impl RenderData {
@@ -663,8 +1168,24 @@ impl RenderData {
             state: widget.create_render_state(),
             size: Size::default(),
             offset: Offset::default(),
+            parent_offset: None,
             constraints: Constraints::default(),
             laid_out: false,
+            needs_layout: true,
+            laid_out_children: Vec::new(),
+            pointer_behavior: PointerBehavior::Pass,
+            paint_transform: Affine::default(),
+            layout_pass: (0, 0),
+            painted_pass: 0,
+        }
+    }
+
+    /// Size computed during last layout, if it can be reused for `constraints`.
+    pub fn cached_size(&self, constraints: Constraints) -> Option<Size> {
+        if self.laid_out && !self.needs_layout && self.constraints == constraints {
+            Some(self.size)
+        } else {
+            None
         }
     }
 }
@@ -732,17 +1253,12 @@ trait FindKey {
 
 impl FindKey for Vec<Option<UnsafeCell<Box<WidgetNode>>>> {
     fn find_key(&mut self, key: &WidgetPtr) -> Option<usize> {
-        if let None = key.local_key() {
-            return None;
-        }
+        let key = key.key()?;
 
         self.iter_mut()
             .enumerate()
             .find(|(_, w)| match w {
-                Some(w) => match WidgetNode::local_key(w) {
-                    Some(k) => k == key.local_key().unwrap(),
-                    None => false,
-                },
+                Some(w) => WidgetNode::key(w) == Some(key),
                 None => false,
             })
             .map(|(n, _)| n)
@@ -765,15 +1281,21 @@ impl WidgetNode {
             },
             inner: RefCell::new(WidgetInner {
                 dirty: false,
+                building: false,
+                dependencies_changed: false,
+                missed_dependencies: false,
                 state: widget_ptr.create_state(),
                 render_data: RenderData::new(&widget_ptr),
                 inheritance: Inheritance::Inheritor {
                     active_inheritors: HashMap::new(),
                     inheriting_widgets: HashSet::new(),
                 },
+                // Dummy node isn't a part of the tree visible to widgets.
+                debug_id: 0,
             }),
             parent: None,
             children: Vec::new(),
+            depth: 0,
         }));
 
         // Safety:
@@ -797,6 +1319,7 @@ trait WidgetNodePtrExt {
     fn parent_ptr(&self) -> *const Option<WidgetNodeRef>;
     fn context_ptr(&self) -> *const Context;
     fn children_ptr(&self) -> *const Vec<UnsafeCell<Box<WidgetNode>>>;
+    fn depth_ptr(&self) -> *const usize;
 
     fn inner_ptr_mut(&self) -> *mut RefCell<WidgetInner>;
     fn widget_ptr_mut(&self) -> *mut WidgetPtr<'static>;
@@ -826,6 +1349,10 @@ impl WidgetNodePtrExt for *mut WidgetNode {
         unsafe { std::ptr::addr_of!((**self).children) }
     }
 
+    fn depth_ptr(&self) -> *const usize {
+        unsafe { std::ptr::addr_of!((**self).depth) }
+    }
+
     fn context_ptr_mut(&self) -> *mut Context {
         unsafe { std::ptr::addr_of_mut!((**self).context) }
     }
@@ -863,6 +1390,7 @@ trait UnsafeCellWidgetNodePtrExt {
     fn parent_ptr_mut(&mut self) -> *mut Option<WidgetNodeRef>;
     fn context_ptr_mut(&mut self) -> *mut Context;
     fn children_ptr_mut(&mut self) -> *mut Vec<UnsafeCell<Box<WidgetNode>>>;
+    fn depth_ptr_mut(&mut self) -> *mut usize;
 }
 
 impl UnsafeCellWidgetNodePtrExt for UnsafeCell<Box<WidgetNode>> {
@@ -913,4 +1441,1015 @@ impl UnsafeCellWidgetNodePtrExt for UnsafeCell<Box<WidgetNode>> {
     fn children_ptr_mut(&mut self) -> *mut Vec<UnsafeCell<Box<WidgetNode>>> {
         unsafe { std::ptr::addr_of_mut!((*self.get()).children) }
     }
+
+    fn depth_ptr_mut(&mut self) -> *mut usize {
+        unsafe { std::ptr::addr_of_mut!((*self.get()).depth) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use druid_shell::kurbo::{Point, Vec2};
+
+    use crate::{
+        api::{
+            contexts::build_ctx::{BuildContext, WidgetState},
+            implementors::{leaf::LeafWidget, multi::MultiChildWidget, WidgetDerive},
+            Widget,
+        },
+        app::runner::handler::run_post_frame_callbacks,
+        prelude::RenderContext,
+    };
+
+    fn root_size(tree: &mut WidgetTree) -> Size {
+        tree.get_root().borrow().render_data.size
+    }
+
+    /// Overrides size of the root, so that it is possible to tell whether the
+    /// next layout reused the cached size.
+    fn poison_root_size(tree: &mut WidgetTree) {
+        tree.get_root().borrow_mut().render_data.size = Size::new(1., 1.);
+    }
+
+    #[test]
+    fn layout_is_cached_for_same_constraints() {
+        let mut tree = WidgetTree::new(().into_widget_ptr());
+        let constraints = Constraints::loose(Size::new(100., 100.));
+
+        tree.layout(constraints);
+        poison_root_size(&mut tree);
+
+        tree.layout(constraints);
+        assert_eq!(root_size(&mut tree), Size::new(1., 1.));
+
+        // Different constraints miss the cache.
+        tree.layout(Constraints::loose(Size::new(50., 50.)));
+        assert_eq!(root_size(&mut tree), Size::default());
+    }
+
+    #[test]
+    fn invalidated_layout_is_not_cached() {
+        let mut tree = WidgetTree::new(().into_widget_ptr());
+        let constraints = Constraints::loose(Size::new(100., 100.));
+
+        tree.layout(constraints);
+        poison_root_size(&mut tree);
+
+        // This is what changing state of a widget does (through `mark_dirty`).
+        tree.get_root().mark_needs_layout();
+
+        tree.layout(constraints);
+        assert_eq!(root_size(&mut tree), Size::default());
+        assert!(!tree.get_root().borrow().render_data.needs_layout);
+    }
+
+    #[test]
+    fn paint_reuses_cached_layout() {
+        let mut tree = WidgetTree::new(().into_widget_ptr());
+        let constraints = Constraints::loose(Size::new(100., 100.));
+
+        tree.layout(constraints);
+        poison_root_size(&mut tree);
+
+        tree.get_root().mark_needs_paint();
+        assert!(!tree.get_root().borrow().dirty);
+
+        tree.layout(constraints);
+        assert_eq!(root_size(&mut tree), Size::new(1., 1.));
+    }
+
+    #[test]
+    #[should_panic(expected = "returned infinite height from layout")]
+    fn infinite_size_under_unbounded_constraints_is_explained() {
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(Expanding)));
+
+        // Bounded constraints are fine.
+        tree.layout(Constraints::loose(Size::new(100., 100.)));
+
+        tree.layout(Constraints::loose(Size::new(100., f64::INFINITY)));
+    }
+
+    #[test]
+    fn layout_is_invalidated_up_to_relayout_boundary() {
+        let parent = Parent {
+            children: [
+                Disposable(Rc::new(Cell::new(0))),
+                Disposable(Rc::new(Cell::new(0))),
+            ],
+            drop_first: Rc::new(Cell::new(false)),
+        };
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(parent)));
+        let constraints = Constraints::loose(Size::new(100., 100.));
+        tree.layout(constraints);
+
+        // `Parent` doesn't lay out its children, so we pretend it did.
+        let child = WidgetNode::node_ref(&tree.get_root().children()[1]);
+        child.borrow_mut().render_data.laid_out = true;
+        child.borrow_mut().render_data.constraints = Constraints::tight(Size::default());
+
+        child.mark_needs_layout_up_to_boundary();
+        assert!(!tree.get_root().borrow().render_data.needs_layout);
+
+        // Boundary is laid out even though its parent is not.
+        tree.layout(constraints);
+        assert!(!child.borrow().render_data.needs_layout);
+    }
+
+    /// Counts how many times it was laid out.
+    struct LayoutCounter(Rc<Cell<usize>>);
+
+    impl Widget for LayoutCounter {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<LayoutCounter>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::Leaf(self)
+        }
+    }
+
+    impl WidgetDerive for LayoutCounter {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = LayoutCounter;
+    }
+
+    impl LeafWidget for LayoutCounter {
+        fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+            self.0.set(self.0.get() + 1);
+            constraints.biggest()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    /// Lays out its child with different constraints until it runs out of
+    /// attempts, like a layout which never settles.
+    struct Oscillating(LayoutCounter);
+
+    impl Widget for Oscillating {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Oscillating>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::MultiChild(self)
+        }
+    }
+
+    impl WidgetDerive for Oscillating {
+        type Widget<'a> = &'a dyn Widget;
+
+        type UniqueTypeId = Oscillating;
+    }
+
+    impl MultiChildWidget for Oscillating {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+            vec![&self.0]
+        }
+
+        fn layout(&self, ctx: RenderContext<Self>, _: Constraints) -> Size {
+            for n in 0..100 {
+                let mut child = ctx.children().next().unwrap();
+                child.layout(Constraints::tight(Size::new(n as f64, 0.)));
+            }
+
+            Size::default()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    #[test]
+    fn oscillating_layout_is_stopped() {
+        let layouts = Rc::new(Cell::new(0));
+        let widget = Oscillating(LayoutCounter(layouts.clone()));
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(widget)));
+
+        tree.layout(Constraints::loose(Size::new(100., 100.)));
+        assert_eq!(layouts.get(), 10);
+
+        // Limit is per frame.
+        tree.layout(Constraints::loose(Size::new(50., 50.)));
+        assert_eq!(layouts.get(), 20);
+    }
+
+    /// Lays out its child many times with the same constraints, invalidating
+    /// its layout in between (like a descendant changing during layout).
+    struct Repeating {
+        child: LayoutCounter,
+        child_node: Rc<RefCell<Option<WidgetNodeRef>>>,
+    }
+
+    impl Widget for Repeating {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Repeating>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::MultiChild(self)
+        }
+    }
+
+    impl WidgetDerive for Repeating {
+        type Widget<'a> = &'a dyn Widget;
+
+        type UniqueTypeId = Repeating;
+    }
+
+    impl MultiChildWidget for Repeating {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+            vec![&self.child]
+        }
+
+        fn layout(&self, ctx: RenderContext<Self>, _: Constraints) -> Size {
+            for _ in 0..100 {
+                if let Some(node) = &*self.child_node.borrow() {
+                    node.mark_needs_layout();
+                }
+
+                let mut child = ctx.children().next().unwrap();
+                child.layout(Constraints::tight(Size::new(10., 10.)));
+            }
+
+            Size::default()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    #[test]
+    fn layouts_with_same_constraints_are_not_stopped() {
+        let layouts = Rc::new(Cell::new(0));
+        let child_node = Rc::new(RefCell::new(None));
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(Repeating {
+            child: LayoutCounter(layouts.clone()),
+            child_node: child_node.clone(),
+        })));
+
+        *child_node.borrow_mut() = Some(WidgetNode::node_ref(&tree.get_root().children()[0]));
+
+        tree.layout(Constraints::loose(Size::new(100., 100.)));
+        assert_eq!(layouts.get(), 100);
+    }
+
+    /// Takes all the available space.
+    struct Expanding;
+
+    impl Widget for Expanding {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Expanding>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::Leaf(self)
+        }
+    }
+
+    impl WidgetDerive for Expanding {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = Expanding;
+    }
+
+    impl LeafWidget for Expanding {
+        fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+            constraints.max()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    /// Counts how many times it was disposed.
+    struct Disposable(Rc<Cell<usize>>);
+
+    impl Widget for Disposable {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Disposable>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::Leaf(self)
+        }
+    }
+
+    impl WidgetDerive for Disposable {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = Disposable;
+    }
+
+    impl WidgetState for Disposable {
+        type State = ();
+
+        fn create_state(&self) -> Self::State {}
+
+        fn dispose<'a>(&'a self, _: BuildContext<'a, Self>) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    impl LeafWidget for Disposable {
+        fn layout(&self, _: RenderContext<Self>, _: Constraints) -> Size {
+            Size::default()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    /// Builds both of its children, or only the second one if `drop_first` is set.
+    struct Parent {
+        children: [Disposable; 2],
+        drop_first: Rc<Cell<bool>>,
+    }
+
+    impl Widget for Parent {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Parent>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::MultiChild(self)
+        }
+    }
+
+    impl WidgetDerive for Parent {
+        type Widget<'a> = &'a dyn Widget;
+
+        type UniqueTypeId = Parent;
+    }
+
+    impl MultiChildWidget for Parent {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+            if self.drop_first.get() {
+                // Different type than `Disposable`, so the first node is dropped.
+                vec![&(), &self.children[1]]
+            } else {
+                vec![&self.children[0], &self.children[1]]
+            }
+        }
+
+        fn layout(&self, _: RenderContext<Self>, _: Constraints) -> Size {
+            Size::default()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    #[test]
+    fn widgets_are_disposed_exactly_once() {
+        let counters = [Rc::new(Cell::new(0)), Rc::new(Cell::new(0))];
+        let drop_first = Rc::new(Cell::new(false));
+
+        let parent = Parent {
+            children: [
+                Disposable(counters[0].clone()),
+                Disposable(counters[1].clone()),
+            ],
+            drop_first: drop_first.clone(),
+        };
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(parent)));
+
+        // Rebuilding with the same children doesn't dispose anything.
+        tree.get_root().update_subtree();
+        assert_eq!((counters[0].get(), counters[1].get()), (0, 0));
+
+        drop_first.set(true);
+        tree.get_root().update_subtree();
+        assert_eq!((counters[0].get(), counters[1].get()), (1, 0));
+
+        // Remaining widgets are disposed when the whole tree is torn down.
+        drop(tree);
+        assert_eq!((counters[0].get(), counters[1].get()), (1, 1));
+    }
+
+    /// Paints its child, built from the current `generation`.
+    struct Stamp {
+        generation: Rc<Cell<usize>>,
+        painted: Rc<RefCell<Vec<usize>>>,
+    }
+
+    impl Widget for Stamp {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Stamp>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::MultiChild(self)
+        }
+    }
+
+    impl WidgetDerive for Stamp {
+        type Widget<'a> = Painted;
+
+        type UniqueTypeId = Stamp;
+    }
+
+    impl MultiChildWidget for Stamp {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+            vec![Painted {
+                generation: self.generation.get(),
+                painted: self.painted.clone(),
+            }]
+        }
+
+        fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+            ctx.children().next().unwrap().layout(constraints)
+        }
+
+        fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+            ctx.children().next().unwrap().paint(canvas)
+        }
+    }
+
+    /// Records the generation it was built with whenever it's painted.
+    struct Painted {
+        generation: usize,
+        painted: Rc<RefCell<Vec<usize>>>,
+    }
+
+    impl Widget for Painted {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Painted>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::Leaf(self)
+        }
+    }
+
+    impl WidgetDerive for Painted {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = Painted;
+    }
+
+    impl LeafWidget for Painted {
+        fn layout(&self, _: RenderContext<Self>, _: Constraints) -> Size {
+            Size::new(10., 10.)
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {
+            self.painted.borrow_mut().push(self.generation);
+        }
+    }
+
+    /// Lays out and paints its stamps next to each other.
+    struct Stamps(Vec<Stamp>);
+
+    impl Widget for Stamps {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Stamps>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::MultiChild(self)
+        }
+    }
+
+    impl WidgetDerive for Stamps {
+        type Widget<'a> = &'a dyn Widget;
+
+        type UniqueTypeId = Stamps;
+    }
+
+    impl MultiChildWidget for Stamps {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+            self.0.iter().map(|w| w as &dyn Widget).collect()
+        }
+
+        fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+            for (n, mut child) in ctx.children().enumerate() {
+                child.layout(constraints.loosen());
+                child.set_offset(Offset {
+                    x: 10. * n as f64,
+                    y: 0.,
+                });
+            }
+
+            Size::new(10. * self.0.len() as f64, 10.)
+        }
+
+        fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+            for mut child in ctx.children() {
+                child.paint(canvas);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "miri"))]
+    fn widgets_deferred_by_build_budget_are_rebuilt_before_paint() {
+        use std::time::Duration;
+
+        use druid_shell::piet::Device;
+
+        use crate::app::runner::handler::{rebuild_dirty, set_build_budget};
+
+        let generation = Rc::new(Cell::new(0));
+        let painted = Rc::new(RefCell::new(Vec::new()));
+
+        let stamps = (0..4).map(|_| Stamp {
+            generation: generation.clone(),
+            painted: painted.clone(),
+        });
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(Stamps(stamps.collect()))));
+        let constraints = Constraints::loose(Size::new(100., 100.));
+        tree.layout(constraints);
+
+        // More widgets are dirty than can be rebuilt within the budget, and
+        // none of them was painted yet, so all of them are deferred.
+        generation.set(1);
+        let root = tree.get_root();
+        let stamps = root.children().iter().map(WidgetNode::node_ref);
+        let stamps = stamps.collect::<Vec<_>>();
+
+        for stamp in &stamps {
+            stamp.mark_dirty();
+        }
+
+        set_build_budget(Some(Duration::ZERO));
+        rebuild_dirty();
+        set_build_budget(None);
+
+        assert!(stamps.iter().all(|stamp| stamp.borrow().dirty));
+
+        tree.layout(constraints);
+
+        let mut device = Device::new().unwrap();
+        let mut target = device.bitmap_target(40, 10, 1.).unwrap();
+        tree.paint(&mut target.render_context());
+
+        assert_eq!(*painted.borrow(), [1, 1, 1, 1]);
+        assert!(stamps.iter().all(|stamp| !stamp.borrow().dirty));
+    }
+
+    /// Leaf which records its offset and the translation of the canvas it was
+    /// painted with.
+    struct Probe(Rc<RefCell<Vec<(Vec2, Vec2)>>>);
+
+    impl Widget for Probe {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Probe>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::Leaf(self)
+        }
+    }
+
+    impl WidgetDerive for Probe {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = Probe;
+    }
+
+    impl LeafWidget for Probe {
+        fn layout(&self, _: RenderContext<Self>, _: Constraints) -> Size {
+            Size::new(10., 10.)
+        }
+
+        fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+            use druid_shell::piet::RenderContext as _;
+
+            let Offset { x, y } = ctx.offset();
+            let translation = (canvas.current_transform() * Point::ORIGIN).to_vec2();
+            self.0.borrow_mut().push((Vec2::new(x, y), translation));
+        }
+    }
+
+    /// Positions its probes diagonally during layout.
+    struct Probes(Vec<Probe>);
+
+    impl Widget for Probes {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Probes>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::MultiChild(self)
+        }
+    }
+
+    impl WidgetDerive for Probes {
+        type Widget<'a> = &'a dyn Widget;
+
+        type UniqueTypeId = Probes;
+    }
+
+    impl MultiChildWidget for Probes {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+            self.0.iter().map(|w| w as &dyn Widget).collect()
+        }
+
+        fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+            for (n, mut child) in ctx.children().enumerate() {
+                child.layout(constraints.loosen());
+                child.set_offset(Offset {
+                    x: 10. * n as f64,
+                    y: 5. * n as f64,
+                });
+            }
+
+            constraints.biggest()
+        }
+
+        fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+            for mut child in ctx.children() {
+                child.paint(canvas);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "miri"))]
+    fn children_are_painted_at_offsets_stored_during_layout() {
+        use druid_shell::piet::Device;
+
+        let painted = Rc::new(RefCell::new(Vec::new()));
+        let probes = (0..3).map(|_| Probe(painted.clone())).collect();
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(Probes(probes))));
+        tree.layout(Constraints::tight(Size::new(40., 20.)));
+
+        let mut device = Device::new().unwrap();
+        let mut target = device.bitmap_target(40, 20, 1.).unwrap();
+        tree.paint(&mut target.render_context());
+
+        // Canvas of every child is translated to its origin.
+        let expected = (0..3).map(|n| {
+            let origin = Vec2::new(10. * n as f64, 5. * n as f64);
+            (origin, origin)
+        });
+
+        assert_eq!(*painted.borrow(), expected.collect::<Vec<_>>());
+    }
+
+    /// Has a fixed size, regardless of its child.
+    struct Frame(Reader);
+
+    impl Widget for Frame {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Frame>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::MultiChild(self)
+        }
+    }
+
+    impl WidgetDerive for Frame {
+        type Widget<'a> = &'a Reader;
+
+        type UniqueTypeId = Frame;
+    }
+
+    impl MultiChildWidget for Frame {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+            vec![&self.0]
+        }
+
+        fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+            for mut child in ctx.children() {
+                child.layout(constraints.loosen());
+            }
+
+            Size::new(40., 20.)
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    /// Sizes of the [`Frame`] ancestor, with the phase they were read in.
+    type SeenSizes = Rc<RefCell<Vec<(&'static str, Option<Size>)>>>;
+
+    /// Records the size of its [`Frame`] ancestor during build and layout.
+    struct Reader(SeenSizes);
+
+    impl Widget for Reader {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Reader>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::MultiChild(self)
+        }
+    }
+
+    impl WidgetDerive for Reader {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = Reader;
+    }
+
+    impl MultiChildWidget for Reader {
+        fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+            let size = ctx.find_ancestor_size::<Frame>();
+            self.0.borrow_mut().push(("build", size));
+
+            Vec::new()
+        }
+
+        fn layout(&self, ctx: RenderContext<Self>, _: Constraints) -> Size {
+            let size = ctx.find_ancestor_size::<Frame>();
+            self.0.borrow_mut().push(("layout", size));
+
+            Size::default()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    #[test]
+    fn ancestor_size_is_known_once_it_was_laid_out() {
+        use crate::app::runner::handler::rebuild_dirty;
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let frame = Frame(Reader(seen.clone()));
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(frame)));
+        let constraints = Constraints::loose(Size::new(100., 100.));
+
+        // Ancestor isn't laid out during the first frame until its descendants
+        // are, so its size is unknown to them.
+        tree.layout(constraints);
+        assert_eq!(*seen.borrow(), [("build", None), ("layout", None)]);
+
+        seen.borrow_mut().clear();
+        let reader = WidgetNode::node_ref(&tree.get_root().children()[0]);
+        reader.mark_dirty();
+        reader.mark_needs_layout();
+
+        rebuild_dirty();
+        tree.layout(constraints);
+
+        let size = Some(Size::new(40., 20.));
+        assert_eq!(*seen.borrow(), [("build", size), ("layout", size)]);
+    }
+
+    /// Leaf with an optional key, whose state is a unique id assigned when it
+    /// is created.
+    struct Stateful {
+        key: Option<usize>,
+        next_id: Rc<Cell<usize>>,
+    }
+
+    impl Widget for Stateful {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Stateful>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::Leaf(self)
+        }
+
+        fn key(&self) -> Option<Key<'_>> {
+            self.key.as_ref().map(Key::new)
+        }
+    }
+
+    impl WidgetDerive for Stateful {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = Stateful;
+    }
+
+    impl WidgetState for Stateful {
+        type State = usize;
+
+        fn create_state(&self) -> Self::State {
+            let id = self.next_id.get();
+            self.next_id.set(id + 1);
+            id
+        }
+    }
+
+    impl LeafWidget for Stateful {
+        fn layout(&self, _: RenderContext<Self>, _: Constraints) -> Size {
+            Size::default()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    /// Builds a `Stateful` child for each of `keys`.
+    struct Siblings {
+        keys: Rc<RefCell<Vec<Option<usize>>>>,
+        next_id: Rc<Cell<usize>>,
+    }
+
+    impl Widget for Siblings {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<Siblings>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::MultiChild(self)
+        }
+    }
+
+    impl WidgetDerive for Siblings {
+        type Widget<'a> = Box<dyn Widget + 'a>;
+
+        type UniqueTypeId = Siblings;
+    }
+
+    impl MultiChildWidget for Siblings {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+            self.keys
+                .borrow()
+                .iter()
+                .map(|&key| {
+                    Box::new(Stateful {
+                        key,
+                        next_id: self.next_id.clone(),
+                    }) as Box<dyn Widget>
+                })
+                .collect()
+        }
+
+        fn layout(&self, _: RenderContext<Self>, _: Constraints) -> Size {
+            Size::default()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    #[test]
+    fn children_are_matched_by_key_then_by_position() {
+        let keys = Rc::new(RefCell::new(vec![Some(1), None, Some(2), None]));
+
+        let widget = Siblings {
+            keys: keys.clone(),
+            next_id: Rc::new(Cell::new(0)),
+        };
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(widget)));
+
+        let ids = |tree: &mut WidgetTree| {
+            let root = tree.get_root();
+            let ids = root
+                .children()
+                .iter()
+                .map(|c| {
+                    *WidgetNode::node_ref(c)
+                        .borrow()
+                        .state
+                        .downcast_ref::<usize>()
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            ids
+        };
+
+        assert_eq!(ids(&mut tree), [0, 1, 2, 3]);
+
+        // Keyed children move with their keys, unkeyed ones stay in order.
+        *keys.borrow_mut() = vec![Some(2), None, Some(1), None];
+        tree.get_root().update_subtree();
+        assert_eq!(ids(&mut tree), [2, 1, 0, 3]);
+
+        // Unkeyed children are matched among themselves, regardless of where
+        // keyed ones are, and new ones are created for the rest.
+        *keys.borrow_mut() = vec![None, Some(1), None, None, Some(3)];
+        tree.get_root().update_subtree();
+        assert_eq!(ids(&mut tree), [1, 0, 3, 4, 5]);
+    }
+
+    #[test]
+    fn nodes_know_their_depth_and_path() {
+        let parent = Parent {
+            children: [
+                Disposable(Rc::new(Cell::new(0))),
+                Disposable(Rc::new(Cell::new(0))),
+            ],
+            drop_first: Rc::new(Cell::new(false)),
+        };
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(parent)));
+
+        let root = tree.get_root();
+        assert_eq!(root.depth(), 0);
+        assert_eq!(root.debug_path(), "Parent");
+
+        let child = WidgetNode::node_ref(&root.children()[1]);
+        assert_eq!(child.depth(), 1);
+        assert_eq!(child.debug_path(), "Parent/Disposable");
+    }
+
+    #[test]
+    fn debug_id_survives_updates_but_not_remounts() {
+        let keys = Rc::new(RefCell::new(vec![Some(1), None]));
+
+        let widget = Siblings {
+            keys: keys.clone(),
+            next_id: Rc::new(Cell::new(0)),
+        };
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(widget)));
+
+        let ids = |tree: &mut WidgetTree| {
+            let root = tree.get_root();
+            let ids = root
+                .children()
+                .iter()
+                .map(|c| WidgetNode::node_ref(c).debug_id())
+                .collect::<Vec<_>>();
+            ids
+        };
+
+        let root_id = tree.get_root().debug_id();
+        let [first, second] = ids(&mut tree)[..] else {
+            unreachable!()
+        };
+        assert!(root_id < first && first < second);
+
+        tree.get_root().update_subtree();
+        assert_eq!(ids(&mut tree), [first, second]);
+        assert_eq!(tree.get_root().debug_id(), root_id);
+
+        // Child whose key changed is built again in the same position.
+        *keys.borrow_mut() = vec![Some(2), None];
+        tree.get_root().update_subtree();
+
+        let new_ids = ids(&mut tree);
+        assert!(new_ids[0] > second);
+        assert_eq!(new_ids[1], second);
+
+        assert_eq!(
+            tree.get_root().dump_tree(),
+            format!(
+                "Siblings #{root_id} size (0.0, 0.0)\n  \
+                Stateful #{} size (0.0, 0.0)\n  \
+                Stateful #{second} size (0.0, 0.0)\n",
+                new_ids[0]
+            )
+        );
+    }
+
+    /// Leaf counting calls of `after_first_build` in its state.
+    struct FirstBuild;
+
+    impl Widget for FirstBuild {
+        fn unique_type(&self) -> TypeId {
+            TypeId::of::<FirstBuild>()
+        }
+
+        fn kind(&self) -> WidgetKind<'_> {
+            WidgetKind::Leaf(self)
+        }
+    }
+
+    impl WidgetDerive for FirstBuild {
+        type Widget<'a> = ();
+
+        type UniqueTypeId = FirstBuild;
+    }
+
+    impl WidgetState for FirstBuild {
+        type State = usize;
+
+        fn create_state(&self) -> Self::State {
+            0
+        }
+
+        fn after_first_build<'a>(&'a self, ctx: BuildContext<'a, Self>) {
+            *ctx.state_mut() += 1;
+        }
+    }
+
+    impl LeafWidget for FirstBuild {
+        fn layout(&self, _: RenderContext<Self>, _: Constraints) -> Size {
+            Size::default()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    #[test]
+    fn after_first_build_is_called_once_after_the_first_frame() {
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(FirstBuild)));
+        let root = tree.get_root();
+        root.borrow_mut().dirty = false;
+
+        let calls = |root: &WidgetNodeRef| *root.borrow().state.downcast_ref::<usize>().unwrap();
+        assert_eq!(calls(&root), 0);
+
+        run_post_frame_callbacks();
+        assert_eq!(calls(&root), 1);
+
+        // Changing state in it schedules a rebuild.
+        assert!(root.borrow().dirty);
+
+        root.update_subtree();
+        run_post_frame_callbacks();
+        assert_eq!(calls(&root), 1);
+    }
 }
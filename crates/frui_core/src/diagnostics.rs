@@ -0,0 +1,26 @@
+//! Diagnostics of the framework itself, like comparisons of widgets of different
+//! types or reused widget configurations.
+//!
+//! With feature `logging` enabled, they are reported through the `log` crate,
+//! with targets starting with `frui::`, so they can be filtered or redirected
+//! by the logger of the application. Otherwise they are compiled out.
+//!
+//! Widgets of other crates of the framework report them the same way, with
+//! `frui::diagnostic!`.
+
+/// Whether diagnostics are reported, read by [`diagnostic!`] wherever it is
+/// expanded.
+pub const ENABLED: bool = cfg!(feature = "logging");
+
+/// Reports a diagnostic at given `log` level, e.g.
+/// `diagnostic!(warn, "structural_eq", "..")` is logged with target
+/// `frui::structural_eq`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! diagnostic {
+    ($level:ident, $target:literal, $($arg:tt)+) => {{
+        if $crate::macro_exports::DIAGNOSTICS_ENABLED {
+            $crate::macro_exports::log::$level!(target: concat!("frui::", $target), $($arg)+);
+        }
+    }};
+}
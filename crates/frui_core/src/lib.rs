@@ -5,6 +5,9 @@
 #![allow(incomplete_features)]
 #![feature(specialization)]
 
+#[macro_use]
+mod diagnostics;
+
 pub mod api;
 pub mod app;
 
@@ -15,21 +18,47 @@ pub mod prelude {
 
     pub use super::{
         api::{
+            capture::CaptureKey,
             contexts::{
                 build_ctx::{
                     BuildContext, InheritedState, InheritedStateRef, InheritedStateRefMut,
                     WidgetState,
                 },
-                render_ctx::{ChildContext, Constraints, Offset, RenderContext, RenderState, Size},
+                render_ctx::{
+                    ChildContext, Constraints, Offset, RenderContext, RenderState, Size,
+                    SkipConstraintsCheck,
+                },
             },
+            events::PointerBehavior,
+            gestures::{DragEvent, DragGestureRecognizer, TapGestureRecognizer, VelocityTracker},
             implementors::{
                 inherited::InheritedWidget, leaf::LeafWidget, multi::MultiChildWidget,
                 single::SingleChildWidget, view::ViewWidget,
             },
             impls::BoxedWidget,
+            layers::dump_layers,
+            lazy_state::LazyState,
+            lifecycle::{animation_now, app_lifecycle, AppLifecycleState},
+            listenable::{Listenable, Listener, Listeners, ValueNotifier},
+            media_query::{MediaQuery, MediaQueryData},
+            paint::{BlendMode, CanvasGuard, PaintContextExt},
+            prewarm::PrewarmedSubtree,
+            signal::{create_memo, create_signal, Memo, ReadSignal, WriteSignal},
+            state_handle::StateHandle,
             Widget, WidgetKind,
         },
-        app::runner::{native::run_app, PaintContext},
+        app::executor::{spawn_local, Task},
+        app::runner::{
+            handler::{
+                add_frame_callback, add_post_frame_callback, remove_frame_callback,
+                set_build_budget, FrameCallbackId,
+            },
+            native::{
+                close_window, open_window, run_app, AppBuilder, AppError, WindowDescriptor,
+                WindowId,
+            },
+            PaintContext,
+        },
     };
 
     pub use druid_shell::{
@@ -47,7 +76,9 @@ pub mod prelude {
 #[doc(hidden)]
 pub mod macro_exports {
     pub use crate::api::implementors::WidgetDerive;
-    pub use crate::api::structural_eq::{StructuralEq, StructuralEqImpl};
+    pub use crate::api::structural_eq::{assert_static, PtrEq, StructuralEq, StructuralEqImpl};
+    pub use crate::diagnostics::ENABLED as DIAGNOSTICS_ENABLED;
+    pub use log;
 }
 
 #[doc(hidden)]
@@ -11,27 +11,118 @@ pub fn impl_widget_list(tokens: TokenStream) -> TokenStream {
 // Widget Implementations
 //
 
-#[proc_macro_derive(LeafWidget)]
+/// See [`ViewWidget`](derive@ViewWidget) for supported field attributes.
+#[proc_macro_derive(LeafWidget, attributes(structural_eq))]
 pub fn leaf_widget(tokens: TokenStream) -> TokenStream {
     macros::widget::Leaf(&syn::parse_macro_input!(tokens as syn::ItemStruct)).into()
 }
 
-#[proc_macro_derive(SingleChildWidget)]
+/// See [`ViewWidget`](derive@ViewWidget) for supported field attributes.
+#[proc_macro_derive(SingleChildWidget, attributes(structural_eq))]
 pub fn single_child_widget(tokens: TokenStream) -> TokenStream {
     macros::widget::SingleChild(&syn::parse_macro_input!(tokens as syn::ItemStruct)).into()
 }
 
-#[proc_macro_derive(MultiChildWidget)]
+/// See [`ViewWidget`](derive@ViewWidget) for supported field attributes.
+#[proc_macro_derive(MultiChildWidget, attributes(structural_eq))]
 pub fn multi_child_widget(tokens: TokenStream) -> TokenStream {
     macros::widget::MultiChild(&syn::parse_macro_input!(tokens as syn::ItemStruct)).into()
 }
 
-#[proc_macro_derive(ViewWidget)]
+/// ## Field attributes
+///
+/// Widget configurations are compared field by field to decide if a subtree
+/// can skip the rebuild. Comparing of a field can be customized with:
+///
+/// - `#[structural_eq(skip)]` – field is never compared, as if it was always
+///   equal. Use it only for fields which don't affect the built subtree (or
+///   which are known to never change). Otherwise an outdated configuration
+///   may be kept and changes of that field will be silently ignored.
+///
+/// - `#[structural_eq(ptr)]` – field is compared by its pointer, using
+///   `Rc::ptr_eq` or `Arc::ptr_eq`. It's allowed only for `Rc` and `Arc`.
+///
+/// - `#[structural_eq(deps(a, b))]` – field is not compared, instead it is
+///   considered changed only if any of the listed fields changed. Those fields
+///   must be compared themselves. It's meant for callbacks, which can't be
+///   compared, but are fully described by the values they capture.
+///
+/// These attributes require the field to be `'static`, since a reused
+/// configuration must not borrow anything.
+///
+/// ```
+/// # #![feature(type_alias_impl_trait)]
+/// # use std::sync::Arc;
+/// # use frui::prelude::*;
+/// #[derive(ViewWidget)]
+/// struct Image {
+///     #[structural_eq(ptr)]
+///     bytes: Arc<Vec<u8>>,
+///     #[structural_eq(skip)]
+///     cache: Vec<u8>,
+/// }
+/// # impl ViewWidget for Image {
+/// #     fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {}
+/// # }
+/// ```
+///
+/// Without `deps(..)` a widget with a callback would never be equal to its
+/// previous configuration, so it would be updated (unmounted and mounted
+/// again) on every rebuild of its parent. With it, the previous configuration
+/// is reused as long as `count` stays the same, together with its callback,
+/// and the widget isn't unmounted nor mounted. Once `count` changes, the
+/// widget is unmounted, updated with the new callback and mounted again:
+///
+/// ```
+/// # #![feature(type_alias_impl_trait)]
+/// # use frui::prelude::*;
+/// #[derive(ViewWidget)]
+/// struct Counter<F: Fn() + 'static> {
+///     #[structural_eq(deps(count))]
+///     on_click: F,
+///     count: usize,
+/// }
+/// # impl<F: Fn() + 'static> ViewWidget for Counter<F> {
+/// #     fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {}
+/// # }
+/// ```
+///
+/// Pointer comparison of a field which is not `Rc` or `Arc` fails to compile:
+///
+/// ```compile_fail,E0277
+/// # #![feature(type_alias_impl_trait)]
+/// # use frui::prelude::*;
+/// #[derive(ViewWidget)]
+/// struct Image {
+///     #[structural_eq(ptr)]
+///     bytes: Vec<u8>,
+/// }
+/// # impl ViewWidget for Image {
+/// #     fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {}
+/// # }
+/// ```
+///
+/// As does skipping a borrowed field:
+///
+/// ```compile_fail,E0521
+/// # #![feature(type_alias_impl_trait)]
+/// # use frui::prelude::*;
+/// #[derive(ViewWidget)]
+/// struct Label<'a> {
+///     #[structural_eq(skip)]
+///     text: &'a str,
+/// }
+/// # impl ViewWidget for Label<'_> {
+/// #     fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {}
+/// # }
+/// ```
+#[proc_macro_derive(ViewWidget, attributes(structural_eq))]
 pub fn view_widget(tokens: TokenStream) -> TokenStream {
     macros::widget::View(&syn::parse_macro_input!(tokens as syn::ItemStruct)).into()
 }
 
-#[proc_macro_derive(InheritedWidget)]
+/// See [`ViewWidget`](derive@ViewWidget) for supported field attributes.
+#[proc_macro_derive(InheritedWidget, attributes(structural_eq))]
 pub fn inherited_widget(tokens: TokenStream) -> TokenStream {
     macros::widget::Inherited(&syn::parse_macro_input!(tokens as syn::ItemStruct)).into()
 }
@@ -30,7 +30,10 @@ fn impl_widget(input: &ItemStruct, kind: WidgetKind) -> TokenStream {
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     let WidgetKindVariant = kind.into_token();
-    let (eq_enabled, eq_impl) = eq_impl(input.clone());
+    let (eq_enabled, eq_impl) = match eq_impl(input.clone()) {
+        Ok(eq) => eq,
+        Err(e) => return e.to_compile_error(),
+    };
     let WidgetDeriveImpl = widget_derive_impl(kind, input, name);
     let (UniqueTypeId, _, _, _) = widget_derive_helpers(name, &input.generics);
 
@@ -67,6 +70,7 @@ fn widget_derive_impl(kind: WidgetKind, input: &ItemStruct, name: &Ident) -> Tok
 
     let (UniqueTypeId, WidgetAlias, AliasImplBounds, AliasTypeBounds) =
         widget_derive_helpers(name, &input.generics);
+    let AliasWhereClause = &AliasImplBounds.where_clause;
     let AliasTypeBounds = AliasTypeBounds.split_for_impl().1;
 
     match kind {
@@ -88,9 +92,11 @@ fn widget_derive_impl(kind: WidgetKind, input: &ItemStruct, name: &Ident) -> Tok
             //
             // This however requires that used type parameters must be explicitly defined
             // in the type alias. Those are `AliasImplBounds` and `AliasTypeBounds` here.
+            // Bounds from the where clause are repeated as well, since the inferred type
+            // may depend on them.
             //
             #[doc(hidden)]
-            type #WidgetAlias #AliasImplBounds = impl #Widget + #LT;
+            type #WidgetAlias #AliasImplBounds #AliasWhereClause = impl #Widget + #LT;
 
             impl #impl_generics #WidgetDerive for #name #ty_generics #where_clause {
                 type Widget<#LT> = #WidgetAlias #AliasTypeBounds where Self: #LT;
@@ -138,32 +144,128 @@ fn widget_derive_helpers(name: &Ident, gparam: &Generics) -> (Ident, Ident, Gene
     (UniqueTypeId, alias_ident, impl_param, type_param)
 }
 
-fn eq_impl(input: ItemStruct) -> (bool, TokenStream) {
+fn eq_impl(input: ItemStruct) -> syn::Result<(bool, TokenStream)> {
     let (_, _, _, StructuralEq, _, _) = imports();
+    let macro_path = quote! { ::frui::macro_exports };
+
+    let fields = input
+        .fields
+        .iter()
+        .map(|field| Ok((field, FieldEq::of(field)?)))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    // Fields listed in `deps(..)` must be compared, since they decide whether
+    // the field depending on them changed.
+    for (_, eq) in fields.iter() {
+        if let FieldEq::Deps(deps) = eq {
+            for dep in deps {
+                let compared = fields.iter().any(|(field, eq)| {
+                    field.ident.as_ref() == Some(dep) && matches!(eq, FieldEq::Deep | FieldEq::Ptr)
+                });
+
+                if !compared {
+                    return Err(syn::Error::new_spanned(
+                        dep,
+                        "`structural_eq(deps(..))` must list compared fields of this struct",
+                    ));
+                }
+            }
+        }
+    }
 
     // Constant-evaluated (optimized) expression indicating if all fields are cheap to compare.
     // That means that e.g. no field contains another widget, which would cause recursive
     // equality tests of the widget subtree to be performed.
-    let fields_cheap_to_eq = input.fields.iter().map(|t| {
-        let ty = &t.ty;
-        quote!(<#ty as #StructuralEq>::EQ_ENABLED &&)
+    let fields_cheap_to_eq = fields.iter().filter_map(|(t, eq)| match eq {
+        FieldEq::Deep => {
+            let ty = &t.ty;
+            Some(quote!(<#ty as #StructuralEq>::EQ_ENABLED &&))
+        }
+        // Skipped fields are always equal and pointers are cheap to compare.
+        // Fields with dependencies are equal if those dependencies are.
+        FieldEq::Skip | FieldEq::Ptr | FieldEq::Deps(_) => None,
     });
 
-    let fields_eq = input.fields.iter().enumerate().map(|(n, t)| {
+    let fields_eq = fields.iter().enumerate().map(|(n, (t, eq))| {
         let field_ident = t.ident.clone().map_or(
             // Unnamed struct field.
             Literal::usize_unsuffixed(n).to_token_stream(),
             // Named struct field.
             |v| v.to_token_stream(),
         );
-        quote!(#StructuralEq::eq(&self.#field_ident, &other.#field_ident) &&)
+
+        match eq {
+            FieldEq::Deep => quote!(#StructuralEq::eq(&self.#field_ident, &other.#field_ident) &&),
+            FieldEq::Skip | FieldEq::Deps(_) => quote!(({
+                #macro_path::assert_static(&self.#field_ident);
+                true
+            }) &&),
+            FieldEq::Ptr => quote!(({
+                #macro_path::assert_static(&self.#field_ident);
+                #macro_path::PtrEq::ptr_eq(&self.#field_ident, &other.#field_ident)
+            }) &&),
+        }
     });
 
     let eq_impl = quote! (#(#fields_cheap_to_eq)*  #(#fields_eq)* true);
 
     let cheap_to_cmp = input.fields.len() == 0;
 
-    (cheap_to_cmp, eq_impl)
+    Ok((cheap_to_cmp, eq_impl))
+}
+
+/// How a field is compared, specified by `#[structural_eq(..)]` attribute.
+enum FieldEq {
+    /// Compare using `StructuralEq`.
+    Deep,
+    /// Don't compare, `#[structural_eq(skip)]`.
+    Skip,
+    /// Compare `Rc`/`Arc` pointers, `#[structural_eq(ptr)]`.
+    Ptr,
+    /// Don't compare, since the listed fields are compared instead,
+    /// `#[structural_eq(deps(a, b))]`.
+    Deps(Vec<Ident>),
+}
+
+impl FieldEq {
+    fn of(field: &syn::Field) -> syn::Result<Self> {
+        let mut eq = FieldEq::Deep;
+
+        for attr in field.attrs.iter() {
+            if !attr.path.is_ident("structural_eq") {
+                continue;
+            }
+
+            let expected = "expected `structural_eq(skip)`, `structural_eq(ptr)` or \
+                `structural_eq(deps(..))`";
+
+            eq = match attr.parse_args::<syn::Meta>()? {
+                syn::Meta::Path(path) if path.is_ident("skip") => FieldEq::Skip,
+                syn::Meta::Path(path) if path.is_ident("ptr") => FieldEq::Ptr,
+                syn::Meta::List(list) if list.path.is_ident("deps") => {
+                    let deps = list
+                        .nested
+                        .iter()
+                        .map(|dep| {
+                            let ident = match dep {
+                                syn::NestedMeta::Meta(syn::Meta::Path(path)) => path.get_ident(),
+                                _ => None,
+                            };
+
+                            ident.cloned().ok_or_else(|| {
+                                syn::Error::new_spanned(dep, "expected a field name")
+                            })
+                        })
+                        .collect::<syn::Result<Vec<_>>>()?;
+
+                    FieldEq::Deps(deps)
+                }
+                meta => return Err(syn::Error::new_spanned(meta, expected)),
+            };
+        }
+
+        Ok(eq)
+    }
 }
 
 fn imports() -> (
@@ -217,3 +319,68 @@ impl WidgetKind {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn eq_error(input: ItemStruct) -> String {
+        match eq_impl(input) {
+            Ok(_) => panic!("expected an error"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn deps_must_be_compared_fields() {
+        let expected = "`structural_eq(deps(..))` must list compared fields of this struct";
+
+        // Not a field.
+        assert_eq!(
+            eq_error(parse_quote! {
+                struct Counter<F> {
+                    #[structural_eq(deps(value))]
+                    on_click: F,
+                    count: usize,
+                }
+            }),
+            expected
+        );
+
+        // Field which isn't compared itself.
+        assert_eq!(
+            eq_error(parse_quote! {
+                struct Counter<F> {
+                    #[structural_eq(deps(count))]
+                    on_click: F,
+                    #[structural_eq(skip)]
+                    count: usize,
+                }
+            }),
+            expected
+        );
+
+        assert_eq!(
+            eq_error(parse_quote! {
+                struct Counter<F> {
+                    #[structural_eq(deps("count"))]
+                    on_click: F,
+                    count: usize,
+                }
+            }),
+            "expected a field name"
+        );
+
+        assert!(eq_impl(parse_quote! {
+            struct Counter<F> {
+                #[structural_eq(deps(count))]
+                on_click: F,
+                #[structural_eq(ptr)]
+                count: std::rc::Rc<usize>,
+            }
+        })
+        .is_ok());
+    }
+}
@@ -0,0 +1,96 @@
+#![feature(type_alias_impl_trait)]
+
+//! Widgets with generic parameters of every kind should be derivable. These
+//! tests pass as long as they compile, except for the field attribute ones.
+
+use frui::prelude::*;
+
+#[derive(ViewWidget)]
+struct Grid<const N: usize, W: Widget> {
+    children: [W; N],
+}
+
+impl<const N: usize, W: Widget> ViewWidget for Grid<N, W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.children[0]
+    }
+}
+
+#[derive(SingleChildWidget)]
+struct Bounded<W>
+where
+    W: Widget,
+{
+    child: W,
+}
+
+impl<W> SingleChildWidget for Bounded<W>
+where
+    W: Widget,
+{
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        Center::child(&self.child)
+    }
+}
+
+#[derive(ViewWidget)]
+struct Labels<'a, 'b, W: Widget> {
+    first: &'a str,
+    second: &'b str,
+    child: W,
+}
+
+impl<'a, 'b, W: Widget> ViewWidget for Labels<'a, 'b, W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        Text::new(format!("{} {}", self.first, self.second))
+    }
+}
+
+#[derive(LeafWidget)]
+struct Dims<const W: usize, const H: usize>;
+
+impl<const W: usize, const H: usize> LeafWidget for Dims<W, H> {
+    fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+        constraints.constrain(Size::new(W as f64, H as f64))
+    }
+
+    fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+}
+
+#[derive(ViewWidget)]
+struct Counter<F: Fn() -> usize + 'static> {
+    #[structural_eq(deps(count))]
+    on_click: F,
+    count: usize,
+}
+
+impl<F: Fn() -> usize + 'static> ViewWidget for Counter<F> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {}
+}
+
+fn counter(count: usize) -> Counter<impl Fn() -> usize> {
+    Counter {
+        on_click: move || count + 1,
+        count,
+    }
+}
+
+#[test]
+fn generic_widgets_are_derived() {
+    let _ = Grid { children: [(), ()] };
+    let _ = Bounded { child: () };
+    let _ = Labels {
+        first: "a",
+        second: "b",
+        child: (),
+    };
+    let _ = Dims::<1, 2>;
+}
+
+#[test]
+fn callbacks_are_compared_by_their_deps() {
+    use frui::macro_exports::StructuralEq;
+
+    assert!(StructuralEq::eq(&counter(1), &counter(1)));
+    assert!(!StructuralEq::eq(&counter(1), &counter(2)));
+}
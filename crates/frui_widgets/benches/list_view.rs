@@ -0,0 +1,71 @@
+//! Cost of a scroll frame of a `ListView`, which should be about the same
+//! regardless of the number of its children.
+//!
+//! Run with `cargo bench -p frui_widgets --bench list_view`.
+
+#![feature(test)]
+
+extern crate test;
+
+use frui::{
+    api::events::Event,
+    app::testing::WidgetTester,
+    druid_shell::{Modifiers, MouseButton, MouseButtons, MouseEvent},
+    prelude::*,
+};
+use frui_widgets::{Container, ListView};
+use test::Bencher;
+
+const WINDOW: Size = Size {
+    width: 400.,
+    height: 600.,
+};
+/// Frames scrolled in one direction before scrolling back, which stays within
+/// the content of the shortest list.
+const FRAMES_PER_DIRECTION: usize = 100;
+
+fn wheel(delta: f64) -> Event {
+    Event::MouseWheel(MouseEvent {
+        pos: Point::new(WINDOW.width / 2., WINDOW.height / 2.),
+        buttons: MouseButtons::new(),
+        mods: Modifiers::default(),
+        count: 0,
+        focus: false,
+        button: MouseButton::None,
+        wheel_delta: Vec2::new(0., delta),
+    })
+}
+
+/// Scrolls through a list of `len` children of different heights, running a
+/// frame (rebuild and layout) after every scroll.
+fn scroll_frames(b: &mut Bencher, len: usize) {
+    let children = (0..len).map(|n| {
+        Container::builder()
+            .height(20. + (n % 7) as f64 * 10.)
+            .child(())
+    });
+
+    let mut tester = WidgetTester::new(ListView::builder().children_iter(children), WINDOW);
+    let mut frame = 0;
+
+    b.iter(|| {
+        let delta = match (frame / FRAMES_PER_DIRECTION) % 2 {
+            0 => 37.,
+            _ => -37.,
+        };
+        frame += 1;
+
+        tester.handle_event(wheel(delta));
+        tester.pump();
+    });
+}
+
+#[bench]
+fn scroll_frame_of_1k_children(b: &mut Bencher) {
+    scroll_frames(b, 1_000);
+}
+
+#[bench]
+fn scroll_frame_of_1m_children(b: &mut Bencher) {
+    scroll_frames(b, 1_000_000);
+}
@@ -0,0 +1,334 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use frui::prelude::*;
+
+use crate::{AnimationController, Curve, SizeTransition};
+
+const DEFAULT_DURATION: Duration = Duration::from_millis(300);
+
+type RemovedBuilder = Rc<dyn Fn(f64) -> Box<dyn Widget>>;
+
+struct ListEntry {
+    /// Unique key of this entry used to preserve state of its widget while
+    /// items around it are inserted and removed.
+    key: usize,
+    animation: AnimationController,
+    /// Set once the item was removed. Removed entries are displayed using this
+    /// builder until their animation is dismissed.
+    removed: Option<RemovedBuilder>,
+}
+
+impl ListEntry {
+    fn is_removed(&self) -> bool {
+        self.removed.is_some()
+    }
+
+    /// Animate from the current value to `target`, using latest `duration`.
+    fn animate_to(&mut self, target: f64, duration: Duration) {
+        let value = self.animation.value();
+
+        self.animation = AnimationController::new(duration);
+        self.animation.set_value(value);
+        self.animation.animate_to(target);
+    }
+}
+
+struct ListEntries {
+    entries: Vec<ListEntry>,
+    next_key: usize,
+    duration: Duration,
+    /// Whether the last build of the list used final values of all animations.
+    settled: bool,
+}
+
+impl ListEntries {
+    fn new_entry(&mut self) -> ListEntry {
+        let key = self.next_key;
+        self.next_key += 1;
+
+        ListEntry {
+            key,
+            animation: AnimationController::new(self.duration),
+            removed: None,
+        }
+    }
+
+    /// Position in `entries` of the item at `index`, skipping removed entries.
+    fn position(&self, index: usize) -> Option<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| !e.is_removed())
+            .nth(index)
+            .map(|(n, _)| n)
+    }
+
+    fn len(&self) -> usize {
+        self.entries.iter().filter(|e| !e.is_removed()).count()
+    }
+
+    fn is_animating(&self) -> bool {
+        self.entries.iter().any(|e| e.animation.is_animating())
+    }
+
+    /// Drop entries which finished animating out.
+    fn prune(&mut self) {
+        self.entries
+            .retain(|e| !e.is_removed() || e.animation.is_animating());
+    }
+}
+
+/// Controls items displayed by an [`AnimatedList`].
+///
+/// Controller doesn't store the items themselves, only their count. Every call
+/// to [`insert_item`] and [`remove_item`] should be accompanied by the same
+/// change to the data the `item_builder` of [`AnimatedList`] reads from, done
+/// through `state_mut` so that the list gets rebuilt.
+///
+/// Indices passed to the controller refer to items which are still in the list.
+/// Items that are animating out are not counted, which makes it possible to
+/// insert and remove items at the same index in a rapid succession.
+///
+/// [`insert_item`]: AnimatedListController::insert_item
+/// [`remove_item`]: AnimatedListController::remove_item
+#[derive(Clone)]
+pub struct AnimatedListController {
+    inner: Rc<RefCell<ListEntries>>,
+}
+
+impl AnimatedListController {
+    /// Creates a controller for a list of `initial_count` items, which are not
+    /// animated in.
+    pub fn new(initial_count: usize) -> Self {
+        let mut entries = ListEntries {
+            entries: Vec::with_capacity(initial_count),
+            next_key: 0,
+            duration: DEFAULT_DURATION,
+            settled: false,
+        };
+
+        for _ in 0..initial_count {
+            let mut entry = entries.new_entry();
+            entry.animation.set_value(1.0);
+            entries.entries.push(entry);
+        }
+
+        Self {
+            inner: Rc::new(RefCell::new(entries)),
+        }
+    }
+
+    /// Duration of insert and remove animations started after this call.
+    pub fn duration(self, duration: Duration) -> Self {
+        self.inner.borrow_mut().duration = duration;
+        self
+    }
+
+    /// Number of items in the list, not counting items animating out.
+    pub fn len(&self) -> usize {
+        self.inner.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Inserts an item at `index`, animating it in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    #[track_caller]
+    pub fn insert_item(&self, index: usize) {
+        let mut inner = self.inner.borrow_mut();
+
+        let len = inner.len();
+        assert!(
+            index <= len,
+            "insertion index (is {index}) should be <= len (is {len})"
+        );
+
+        let position = inner.position(index).unwrap_or(inner.entries.len());
+
+        let mut entry = inner.new_entry();
+        entry.animation.forward();
+        inner.entries.insert(position, entry);
+    }
+
+    /// Removes the item at `index`, animating it out.
+    ///
+    /// Since the data of the removed item is usually gone by the time it is
+    /// animated out, `removed_builder` is used to build it instead of the
+    /// `item_builder` of the list. It receives the same animation value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    #[track_caller]
+    pub fn remove_item<W, F>(&self, index: usize, removed_builder: F)
+    where
+        W: Widget + 'static,
+        F: Fn(f64) -> W + 'static,
+    {
+        let mut inner = self.inner.borrow_mut();
+
+        let len = inner.len();
+        let position = match inner.position(index) {
+            Some(position) => position,
+            None => panic!("removal index (is {index}) should be < len (is {len})"),
+        };
+
+        let duration = inner.duration;
+
+        let entry = &mut inner.entries[position];
+        entry.removed = Some(Rc::new(move |t| removed_builder(t).boxed()));
+        entry.animate_to(0.0, duration);
+    }
+}
+
+/// A vertical list which animates its items when they are inserted or removed.
+///
+/// `item_builder` is called with the index of the item and the current value of
+/// its animation in range `0.0..=1.0`. The list itself animates the height of
+/// every item, so that surrounding items shift smoothly. Other transitions (like
+/// fading colors in) can be implemented by the item using the provided value.
+///
+/// See [`AnimatedListController`] for how to insert and remove items.
+#[derive(MultiChildWidget)]
+pub struct AnimatedList<W: Widget, F: Fn(usize, f64) -> W> {
+    pub controller: AnimatedListController,
+    pub item_builder: F,
+    pub curve: Curve,
+}
+
+impl<W: Widget, F: Fn(usize, f64) -> W> AnimatedList<W, F> {
+    pub fn new(controller: AnimatedListController, item_builder: F) -> Self {
+        Self {
+            controller,
+            item_builder,
+            curve: Curve::EaseInOut,
+        }
+    }
+
+    pub fn curve(mut self, curve: Curve) -> Self {
+        self.curve = curve;
+        self
+    }
+}
+
+impl<W: Widget, F: Fn(usize, f64) -> W> MultiChildWidget for AnimatedList<W, F> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        let mut inner = self.controller.inner.borrow_mut();
+
+        inner.prune();
+        inner.settled = !inner.is_animating();
+
+        let mut index = 0;
+
+        inner
+            .entries
+            .iter()
+            .map(|entry| {
+                let t = self.curve.transform(entry.animation.value());
+
+                let child: Box<dyn Widget + 'w> = match &entry.removed {
+                    Some(removed_builder) => removed_builder(t),
+                    None => {
+                        index += 1;
+                        (self.item_builder)(index - 1, t).boxed()
+                    }
+                };
+
+                LocalKey::new(entry.key, SizeTransition::new(t, child))
+            })
+            .collect()
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let child_constraints = Constraints {
+            min_width: 0.,
+            max_width: constraints.max_width,
+            min_height: 0.,
+            max_height: f64::INFINITY,
+        };
+
+        let mut size = Size::default();
+
+        for mut child in ctx.children() {
+            let child_size = child.layout(child_constraints);
+            child.set_offset(Offset {
+                x: 0.,
+                y: size.height,
+            });
+
+            size.width = size.width.max(child_size.width);
+            size.height += child_size.height;
+        }
+
+        constraints.constrain(size)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        for mut child in ctx.children() {
+            child.paint(canvas);
+        }
+
+        let inner = self.controller.inner.borrow();
+
+        if !inner.settled || inner.is_animating() {
+            drop(inner);
+            ctx.schedule_rebuild();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn keys(controller: &AnimatedListController) -> Vec<(usize, bool)> {
+        let inner = controller.inner.borrow();
+        inner
+            .entries
+            .iter()
+            .map(|e| (e.key, e.is_removed()))
+            .collect()
+    }
+
+    #[test]
+    fn indices_skip_removed_items() {
+        let controller = AnimatedListController::new(2);
+
+        controller.remove_item(0, |_| ());
+        assert_eq!(controller.len(), 1);
+
+        // Inserting at the same index doesn't land before the removed item.
+        controller.insert_item(0);
+        controller.remove_item(0, |_| ());
+        controller.insert_item(0);
+
+        assert_eq!(controller.len(), 2);
+        assert_eq!(
+            keys(&controller),
+            vec![(0, true), (2, true), (3, false), (1, false)]
+        );
+    }
+
+    #[test]
+    fn removed_items_are_pruned_after_animation() {
+        let controller = AnimatedListController::new(3).duration(Duration::ZERO);
+
+        controller.remove_item(1, |_| ());
+        controller.inner.borrow_mut().prune();
+
+        assert_eq!(keys(&controller), vec![(0, false), (2, false)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn remove_out_of_bounds() {
+        let controller = AnimatedListController::new(1);
+        controller.remove_item(0, |_| ());
+        controller.remove_item(0, |_| ());
+    }
+}
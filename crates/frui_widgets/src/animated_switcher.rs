@@ -0,0 +1,297 @@
+use std::{cell::RefCell, time::Duration};
+
+use frui::prelude::*;
+
+use crate::{AnimationController, Curve, ScaleTransition};
+
+const DEFAULT_DURATION: Duration = Duration::from_millis(300);
+
+/// Transitions between a new and an old child whenever the child changes.
+///
+/// Child is considered changed if its type or its [`LocalKey`] is different
+/// from the previous one. Otherwise the current child is just updated, without
+/// any transition.
+///
+/// While transitioning, the outgoing child is animated out and the incoming
+/// child is animated in, using `transition_builder` which receives a child
+/// and the current value of its animation. Both children are centered in the
+/// size of the larger one. The default transition scales children.
+///
+/// If the child changes again during a transition, only the latest child is
+/// animated in. The child that was shown before it is animated out and any
+/// older children are unmounted immediately.
+///
+/// Since the outgoing child must outlive the configuration it came from, the
+/// child has to be `Clone + 'static`. To switch between children of different
+/// types, type erase them with `Rc<dyn Widget>`.
+#[derive(MultiChildWidget)]
+pub struct AnimatedSwitcher<W: Widget + Clone + 'static, F: Fn(W, f64) -> T, T: Widget> {
+    pub child: W,
+    pub duration: Duration,
+    pub curve: Curve,
+    pub transition_builder: F,
+}
+
+impl<W: Widget + Clone + 'static>
+    AnimatedSwitcher<W, fn(W, f64) -> ScaleTransition<W>, ScaleTransition<W>>
+{
+    pub fn new(child: W) -> Self {
+        Self {
+            child,
+            duration: DEFAULT_DURATION,
+            curve: Curve::EaseInOut,
+            transition_builder: |child, t| ScaleTransition::new(t, child),
+        }
+    }
+}
+
+impl<W, F, T> AnimatedSwitcher<W, F, T>
+where
+    W: Widget + Clone + 'static,
+    F: Fn(W, f64) -> T,
+    T: Widget,
+{
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn curve(mut self, curve: Curve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    pub fn transition_builder<F2, T2>(self, transition_builder: F2) -> AnimatedSwitcher<W, F2, T2>
+    where
+        F2: Fn(W, f64) -> T2,
+        T2: Widget,
+    {
+        AnimatedSwitcher {
+            child: self.child,
+            duration: self.duration,
+            curve: self.curve,
+            transition_builder,
+        }
+    }
+}
+
+struct SwitcherEntry<W> {
+    key: usize,
+    child: W,
+    animation: AnimationController,
+}
+
+#[doc(hidden)]
+pub struct SwitcherEntries<W> {
+    current: SwitcherEntry<W>,
+    outgoing: Option<SwitcherEntry<W>>,
+    next_key: usize,
+    /// Whether the last build used final values of all animations.
+    settled: bool,
+}
+
+impl<W: Widget + Clone + 'static> SwitcherEntries<W> {
+    /// Updates current child, starting a transition if `child` is different.
+    fn update(&mut self, child: &W, duration: Duration) {
+        if is_same_widget(&self.current.child, child) {
+            self.current.child = child.clone();
+            return;
+        }
+
+        // Switching back to the outgoing child reverses the transition.
+        if let Some(outgoing) = &mut self.outgoing {
+            if is_same_widget(&outgoing.child, child) {
+                std::mem::swap(&mut self.current, outgoing);
+
+                self.current.child = child.clone();
+                animate_to(&mut self.current.animation, 1.0, duration);
+                animate_to(&mut outgoing.animation, 0.0, duration);
+                return;
+            }
+        }
+
+        let mut animation = AnimationController::new(duration);
+        animation.forward();
+
+        let incoming = SwitcherEntry {
+            key: self.next_key,
+            child: child.clone(),
+            animation,
+        };
+
+        self.next_key += 1;
+
+        // Superseded outgoing child (if any) is dropped here.
+        let mut outgoing = std::mem::replace(&mut self.current, incoming);
+        animate_to(&mut outgoing.animation, 0.0, duration);
+        self.outgoing = Some(outgoing);
+    }
+
+    fn is_animating(&self) -> bool {
+        self.current.animation.is_animating()
+            || matches!(&self.outgoing, Some(e) if e.animation.is_animating())
+    }
+}
+
+/// Animate from the current value to `target`, using given `duration`.
+fn animate_to(animation: &mut AnimationController, target: f64, duration: Duration) {
+    let value = animation.value();
+
+    *animation = AnimationController::new(duration);
+    animation.set_value(value);
+    animation.animate_to(target);
+}
+
+/// Whether `a` and `b` are of the same type and have the same key.
+fn is_same_widget(a: &dyn Widget, b: &dyn Widget) -> bool {
+    a.unique_type() == b.unique_type() && a.key() == b.key()
+}
+
+impl<W, F, T> WidgetState for AnimatedSwitcher<W, F, T>
+where
+    W: Widget + Clone + 'static,
+    F: Fn(W, f64) -> T,
+    T: Widget,
+{
+    type State = RefCell<SwitcherEntries<W>>;
+
+    fn create_state(&self) -> Self::State {
+        let mut animation = AnimationController::new(self.duration);
+        animation.set_value(1.0);
+
+        RefCell::new(SwitcherEntries {
+            current: SwitcherEntry {
+                key: 0,
+                child: self.child.clone(),
+                animation,
+            },
+            outgoing: None,
+            next_key: 1,
+            settled: true,
+        })
+    }
+}
+
+impl<W, F, T> MultiChildWidget for AnimatedSwitcher<W, F, T>
+where
+    W: Widget + Clone + 'static,
+    F: Fn(W, f64) -> T,
+    T: Widget,
+{
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        // State is mutated through `RefCell`, since the build itself shouldn't
+        // schedule another rebuild.
+        let state = ctx.state();
+        let mut entries = state.borrow_mut();
+
+        entries.update(&self.child, self.duration);
+
+        if let Some(outgoing) = &entries.outgoing {
+            if !outgoing.animation.is_animating() {
+                entries.outgoing = None;
+            }
+        }
+
+        entries.settled = !entries.is_animating();
+
+        let entries = &*entries;
+
+        entries
+            .outgoing
+            .iter()
+            .chain(std::iter::once(&entries.current))
+            .map(|entry| {
+                let t = self.curve.transform(entry.animation.value());
+                let child = (self.transition_builder)(entry.child.clone(), t);
+
+                LocalKey::new(entry.key, child)
+            })
+            .collect()
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let mut size = Size::default();
+
+        for mut child in ctx.children() {
+            let child_size = child.layout(constraints.loosen());
+
+            size.width = size.width.max(child_size.width);
+            size.height = size.height.max(child_size.height);
+        }
+
+        let size = constraints.constrain(size);
+
+        for mut child in ctx.children() {
+            let child_size = child.size();
+
+            child.set_offset(Offset {
+                x: (size.width - child_size.width) / 2.,
+                y: (size.height - child_size.height) / 2.,
+            });
+        }
+
+        size
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        for mut child in ctx.children() {
+            child.paint(canvas);
+        }
+
+        let needs_rebuild = {
+            let state = ctx.wstate();
+            let entries = state.borrow();
+            !entries.settled || entries.is_animating()
+        };
+
+        if needs_rebuild {
+            ctx.schedule_rebuild();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entries(child: LocalKey<usize, ()>) -> SwitcherEntries<LocalKey<usize, ()>> {
+        let mut animation = AnimationController::new(DEFAULT_DURATION);
+        animation.set_value(1.0);
+
+        SwitcherEntries {
+            current: SwitcherEntry {
+                key: 0,
+                child,
+                animation,
+            },
+            outgoing: None,
+            next_key: 1,
+            settled: true,
+        }
+    }
+
+    #[test]
+    fn rapid_swaps_keep_only_latest_transition() {
+        let mut entries = entries(LocalKey::new(0, ()));
+
+        entries.update(&LocalKey::new(0, ()), DEFAULT_DURATION);
+        assert!(entries.outgoing.is_none());
+
+        entries.update(&LocalKey::new(1, ()), DEFAULT_DURATION);
+        entries.update(&LocalKey::new(2, ()), DEFAULT_DURATION);
+
+        assert_eq!(entries.current.child.key, 2);
+        assert_eq!(entries.outgoing.as_ref().unwrap().child.key, 1);
+    }
+
+    #[test]
+    fn swapping_back_reverses_transition() {
+        let mut entries = entries(LocalKey::new(0, ()));
+
+        entries.update(&LocalKey::new(1, ()), DEFAULT_DURATION);
+        entries.update(&LocalKey::new(0, ()), DEFAULT_DURATION);
+
+        assert_eq!(entries.current.key, 0);
+        assert_eq!(entries.outgoing.as_ref().unwrap().key, 1);
+    }
+}
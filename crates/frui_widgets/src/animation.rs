@@ -0,0 +1,176 @@
+use std::time::{Duration, Instant};
+
+use frui::prelude::{animation_now, Listenable, Listener, Listeners};
+
+/// Drives a value from `0.0` to `1.0` (or back) over a given duration.
+///
+/// Controller doesn't schedule frames by itself. A widget using it should store
+/// the controller in its state and call `ctx.mark_needs_paint()` from `paint`
+/// as long as [`AnimationController::is_animating`] returns `true`, which
+/// paints the next frame without laying out anything again. Only a widget
+/// whose layout depends on the animated value (e.g. its size) should call
+/// `ctx.mark_needs_layout()` instead. Since paint is never called for an
+/// unmounted widget, the animation stops requesting frames as soon as its
+/// widget leaves the tree.
+///
+/// Time is measured with [`animation_now`], so animations pause while the
+/// application isn't resumed (e.g. its window is unfocused or minimized) and
+/// continue from the same value once it is.
+///
+/// Controller is [`Listenable`], notifying its listeners whenever an animation
+/// is started or stopped, or its value is set. Listeners are not notified on
+/// every frame of an animation. Clones of a controller share its listeners.
+#[derive(Debug, Clone)]
+pub struct AnimationController {
+    duration: Duration,
+    start: Option<Instant>,
+    from: f64,
+    to: f64,
+    repeat: bool,
+    listeners: Listeners,
+}
+
+impl AnimationController {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            start: None,
+            from: 0.0,
+            to: 0.0,
+            repeat: false,
+            listeners: Listeners::new(),
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Animate towards `1.0` starting from the current value.
+    pub fn forward(&mut self) {
+        self.animate_to(1.0);
+    }
+
+    /// Animate towards `0.0` starting from the current value.
+    pub fn reverse(&mut self) {
+        self.animate_to(0.0);
+    }
+
+    /// Animate from the current value to `target`.
+    pub fn animate_to(&mut self, target: f64) {
+        self.from = self.value();
+        self.to = target.clamp(0.0, 1.0);
+        self.start = Some(animation_now());
+        self.repeat = false;
+        self.listeners.notify();
+    }
+
+    /// Repeatedly animate from `0.0` to `1.0` until [`stop`] is called.
+    ///
+    /// [`stop`]: AnimationController::stop
+    pub fn repeat(&mut self) {
+        if !self.repeat {
+            self.from = 0.0;
+            self.to = 1.0;
+            self.start = Some(animation_now());
+            self.repeat = true;
+            self.listeners.notify();
+        }
+    }
+
+    /// Stop the animation at its current value.
+    pub fn stop(&mut self) {
+        self.from = self.value();
+        self.to = self.from;
+        self.start = None;
+        self.repeat = false;
+        self.listeners.notify();
+    }
+
+    /// Jump to `value` without animating.
+    pub fn set_value(&mut self, value: f64) {
+        self.stop();
+        self.from = value.clamp(0.0, 1.0);
+        self.to = self.from;
+    }
+
+    pub fn value(&self) -> f64 {
+        let t = self.progress();
+        self.from + (self.to - self.from) * t
+    }
+
+    pub fn is_animating(&self) -> bool {
+        match self.start {
+            Some(start) => self.repeat || animation_now() - start < self.duration,
+            None => false,
+        }
+    }
+
+    pub fn is_completed(&self) -> bool {
+        !self.is_animating() && self.to == 1.0
+    }
+
+    pub fn is_dismissed(&self) -> bool {
+        !self.is_animating() && self.to == 0.0
+    }
+
+    /// Linear progress of the current run in `0.0..=1.0`.
+    fn progress(&self) -> f64 {
+        let start = match self.start {
+            Some(start) => start,
+            None => return 1.0,
+        };
+
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+
+        let t = (animation_now() - start).as_secs_f64() / self.duration.as_secs_f64();
+
+        if self.repeat {
+            t.fract()
+        } else {
+            t.min(1.0)
+        }
+    }
+}
+
+impl Listenable for AnimationController {
+    fn add_listener(&self, listener: Listener) {
+        self.listeners.add(listener)
+    }
+
+    fn remove_listener(&self, listener: &Listener) {
+        self.listeners.remove(listener)
+    }
+}
+
+/// Maps linear progress of an animation to the displayed value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Curve {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Curve {
+    /// Transforms `t` in range `0.0..=1.0`. The result is also in that range.
+    pub fn transform(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Curve::Linear => t,
+            Curve::EaseIn => t * t * t,
+            Curve::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Curve::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
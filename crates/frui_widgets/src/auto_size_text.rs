@@ -0,0 +1,269 @@
+use frui::{api::fonts, prelude::*};
+
+use crate::{Text, TextOverflow, TextRenderState};
+
+/// Maximal number of trial layouts done to find the font size of an
+/// [`AutoSizeText`], not counting the final layout.
+const MAX_TRIAL_LAYOUTS: usize = 8;
+
+/// Text which picks the largest font size (between `min_font_size` and
+/// `max_font_size`, in multiples of `step` above the minimum) at which the
+/// whole text fits in its constraints, e.g. in the space a [`Flexible`] child
+/// of a [`Row`] gets.
+///
+/// Set [`max_lines`](Self::max_lines) to `1` to shrink the text instead of
+/// wrapping it. If the text doesn't fit even at `min_font_size`, it is laid out
+/// at that size and its overflow is handled like in [`Text`].
+///
+/// Font size is found by a binary search over trial layouts of the text, of
+/// which there are at most a few per layout. Found font size is kept until the
+/// constraints, the text or the range of font sizes change, so laying out the
+/// same text again in the same constraints lays it out only once.
+///
+/// [`Flexible`]: crate::Flexible
+/// [`Row`]: crate::Row
+#[derive(LeafWidget)]
+pub struct AutoSizeText<S: AsRef<str>> {
+    text: Text<S>,
+    min_font_size: f64,
+    max_font_size: f64,
+    step: f64,
+}
+
+impl<S: AsRef<str>> AutoSizeText<S> {
+    /// Text fitted at font size of at most `16` and at least `8`.
+    pub fn new(string: S) -> Self {
+        Self {
+            text: Text::new(string),
+            min_font_size: 8.,
+            max_font_size: 16.,
+            step: 1.,
+        }
+    }
+
+    #[track_caller]
+    pub fn min_font_size(mut self, size: f64) -> Self {
+        assert!(size > 0., "min_font_size must be > 0");
+        self.min_font_size = size;
+        self.max_font_size = self.max_font_size.max(size);
+        self
+    }
+
+    #[track_caller]
+    pub fn max_font_size(mut self, size: f64) -> Self {
+        assert!(size > 0., "max_font_size must be > 0");
+        self.max_font_size = size;
+        self.min_font_size = self.min_font_size.min(size);
+        self
+    }
+
+    /// Granularity of tried font sizes. The default is `1`.
+    #[track_caller]
+    pub fn step(mut self, step: f64) -> Self {
+        assert!(step > 0., "step must be > 0");
+        self.step = step;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.text = self.text.color(color);
+        self
+    }
+
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.text = self.text.weight(weight);
+        self
+    }
+
+    pub fn font(mut self, font: FontFamily) -> Self {
+        self.text = self.text.font(font);
+        self
+    }
+
+    #[track_caller]
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.text = self.text.max_lines(max_lines);
+        self
+    }
+
+    pub fn overflow(mut self, overflow: TextOverflow) -> Self {
+        self.text = self.text.overflow(overflow);
+        self
+    }
+}
+
+/// Largest font size from `min` to `max` (in multiples of `step` above `min`,
+/// and `max` itself) for which `fits` returns `true`, assuming that it returns
+/// `true` for all sizes smaller than that. Calls `fits` at most `max_trials`
+/// times, returning the largest size known to fit once they run out.
+///
+/// Returns `None` if no tried size fits.
+fn largest_fitting_size(
+    min: f64,
+    max: f64,
+    step: f64,
+    max_trials: usize,
+    mut fits: impl FnMut(f64) -> bool,
+) -> Option<f64> {
+    let steps = ((max - min) / step).ceil().max(0.) as usize;
+    let size = |n: usize| (min + n as f64 * step).min(max);
+
+    // Sizes up to `lo` (exclusive) fit and sizes from `hi` don't.
+    let (mut lo, mut hi) = (0, steps + 1);
+    let mut trials = 0;
+
+    // Fast path for text which fits without shrinking.
+    if max_trials > 0 {
+        trials += 1;
+
+        if fits(max) {
+            return Some(max);
+        }
+
+        hi = steps;
+    }
+
+    while lo < hi && trials < max_trials {
+        let mid = (lo + hi) / 2;
+        trials += 1;
+
+        if fits(size(mid)) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    lo.checked_sub(1).map(size)
+}
+
+/// Font size found during the last layout, along with what it was found for.
+struct FontSizeCache {
+    constraints: Constraints,
+    text: String,
+    /// Minimum, maximum and step of the searched font sizes.
+    range: (f64, f64, f64),
+    /// Whether the font was loaded, since text is measured with a fallback
+    /// font before that.
+    font_loaded: bool,
+    font_size: f64,
+}
+
+#[doc(hidden)]
+pub struct AutoSizeTextRenderState {
+    text: TextRenderState,
+    cache: Option<FontSizeCache>,
+}
+
+#[cfg(not(feature = "miri"))]
+impl<S: AsRef<str>> RenderState for AutoSizeText<S> {
+    type State = AutoSizeTextRenderState;
+
+    fn create_state(&self) -> Self::State {
+        AutoSizeTextRenderState {
+            text: TextRenderState::empty(),
+            cache: None,
+        }
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl<S: AsRef<str>> LeafWidget for AutoSizeText<S> {
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let text = self.text.as_str();
+        let range = (self.min_font_size, self.max_font_size, self.step);
+
+        ctx.depend_on_font(&self.text.font_family);
+        let font_loaded = fonts::is_font_loaded(&self.text.font_family);
+
+        let cached = match &ctx.rstate().cache {
+            Some(cache)
+                if cache.constraints == constraints
+                    && cache.text == text
+                    && cache.range == range
+                    && cache.font_loaded == font_loaded =>
+            {
+                Some(cache.font_size)
+            }
+            _ => None,
+        };
+
+        let font_size = cached.unwrap_or_else(|| {
+            largest_fitting_size(
+                self.min_font_size,
+                self.max_font_size,
+                self.step,
+                MAX_TRIAL_LAYOUTS,
+                |font_size| self.text.fits(font_size, constraints),
+            )
+            .unwrap_or(self.min_font_size)
+        });
+
+        let state = self.text.layout_lines(font_size, constraints.max().width);
+        let size = state.size(constraints);
+
+        *ctx.rstate_mut() = AutoSizeTextRenderState {
+            text: state,
+            cache: Some(FontSizeCache {
+                constraints,
+                text: text.to_owned(),
+                range,
+                font_loaded,
+                font_size,
+            }),
+        };
+
+        size
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.rstate().text.paint(ctx.size(), canvas);
+    }
+}
+
+#[cfg(feature = "miri")]
+impl<S: AsRef<str>> LeafWidget for AutoSizeText<S> {
+    fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+        constraints.max()
+    }
+
+    fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn largest_fitting_size_is_found_within_trials() {
+        let trials = Cell::new(0);
+        let fits = |limit: f64| {
+            let trials = &trials;
+            move |size: f64| {
+                trials.set(trials.get() + 1);
+                size <= limit
+            }
+        };
+
+        assert_eq!(largest_fitting_size(8., 20., 1., 8, fits(13.5)), Some(13.));
+        assert!(trials.get() <= 8);
+
+        // Maximum which isn't a multiple of the step is tried too.
+        assert_eq!(
+            largest_fitting_size(8., 20.5, 1., 8, fits(100.)),
+            Some(20.5)
+        );
+        assert_eq!(largest_fitting_size(8., 20.5, 1., 8, fits(20.)), Some(20.));
+
+        // Not even the minimum fits.
+        assert_eq!(largest_fitting_size(8., 20., 1., 8, fits(7.)), None);
+
+        // Out of trials, the largest size known to fit is used.
+        trials.set(0);
+        let size = largest_fitting_size(8., 100., 1., 3, fits(90.)).unwrap();
+        assert!((8. ..=90.).contains(&size));
+        assert_eq!(trials.get(), 3);
+    }
+}
@@ -0,0 +1,86 @@
+use std::cell::Cell;
+
+use frui::prelude::*;
+
+/// Blurs whatever was painted behind it within its bounds and then paints its
+/// child on top.
+///
+/// # Note
+///
+/// Piet can't read back what was already painted, so the backdrop can't be
+/// blurred yet. Instead, the bounds of this widget are filled with
+/// `fallback_color` (a semi-transparent white by default) and a message is
+/// logged once.
+#[derive(SingleChildWidget)]
+pub struct BackdropFilter<W: Widget> {
+    /// Standard deviation of the gaussian blur.
+    pub sigma: f64,
+    /// Painted behind the child when the backdrop can't be blurred.
+    pub fallback_color: Color,
+    pub child: W,
+}
+
+impl<W: Widget> BackdropFilter<W> {
+    #[track_caller]
+    pub fn blur(sigma: f64, child: W) -> Self {
+        assert!(sigma >= 0., "blur sigma (is {sigma}) should be >= 0");
+
+        Self {
+            sigma,
+            fallback_color: Color::rgba8(255, 255, 255, 160),
+            child,
+        }
+    }
+
+    pub fn fallback_color(mut self, color: Color) -> Self {
+        self.fallback_color = color;
+        self
+    }
+}
+
+#[doc(hidden)]
+pub struct BackdropFilterRenderState {
+    /// Whether a message about the missing backdrop support was logged.
+    logged: Cell<bool>,
+}
+
+impl<W: Widget> RenderState for BackdropFilter<W> {
+    type State = BackdropFilterRenderState;
+
+    fn create_state(&self) -> Self::State {
+        BackdropFilterRenderState {
+            logged: Cell::new(false),
+        }
+    }
+}
+
+impl<W: Widget> SingleChildWidget for BackdropFilter<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        if self.sigma > 0. {
+            if !ctx.rstate().logged.replace(true) {
+                frui::diagnostic!(
+                    warn,
+                    "paint",
+                    "render backend can't blur the backdrop, \
+                    filling it with a solid color instead"
+                );
+            }
+
+            // Solid fill is already limited to the bounds of this widget.
+            let rect = Rect::from_origin_size(Point::ORIGIN, ctx.size());
+            let brush = &canvas.solid_brush(self.fallback_color.clone());
+
+            PietRenderContext::fill(canvas, rect, brush);
+        }
+
+        ctx.child().paint(canvas);
+    }
+}
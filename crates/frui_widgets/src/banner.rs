@@ -0,0 +1,185 @@
+use std::f64::consts::FRAC_PI_4;
+
+use druid_shell::piet::{PietTextLayout, TextLayout};
+use frui::prelude::*;
+
+use crate::Text;
+
+/// Distance from the origin of the ribbon to its lower edge, and half of its
+/// length.
+const OFFSET: f64 = 40.;
+const HEIGHT: f64 = 12.;
+/// Distance from the edges of the bottom corners to their ribbon origin.
+const BOTTOM_OFFSET: f64 = OFFSET + std::f64::consts::FRAC_1_SQRT_2 * HEIGHT;
+const FONT_SIZE: f64 = 10.2;
+
+/// Corner of a [`Banner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BannerLocation {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Paints a diagonal ribbon with `message` over a corner of its child.
+///
+/// Ribbon is painted after the child, so to have it above everything in the
+/// window, wrap the root widget of the application in it. It doesn't receive
+/// pointer events, which are passed to the child as usual. Disabled banner
+/// paints just the child.
+///
+/// ```no_run
+/// # #![feature(type_alias_impl_trait)]
+/// # use frui::prelude::*;
+/// # use frui_widgets::*;
+/// # #[derive(ViewWidget)]
+/// # struct App;
+/// # impl ViewWidget for App {
+/// #     fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {}
+/// # }
+/// # fn main() {
+/// # let is_beta = cfg!(debug_assertions);
+/// run_app(Banner::new("BETA", BannerLocation::TopRight, App).enabled(is_beta));
+/// # }
+/// ```
+#[derive(SingleChildWidget)]
+pub struct Banner<S: AsRef<str>, W: Widget> {
+    pub message: S,
+    pub location: BannerLocation,
+    pub color: Color,
+    pub enabled: bool,
+    pub child: W,
+}
+
+impl<S: AsRef<str>, W: Widget> Banner<S, W> {
+    pub fn new(message: S, location: BannerLocation, child: W) -> Self {
+        Self {
+            message,
+            location,
+            color: Color::rgba8(0xB7, 0x1C, 0x1C, 0xA0),
+            enabled: true,
+            child,
+        }
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+impl<W: Widget> Banner<&'static str, W> {
+    /// "DEBUG" banner in the top right corner, displayed only in debug builds.
+    pub fn debug(child: W) -> Self {
+        Banner::new("DEBUG", BannerLocation::TopRight, child).enabled(cfg!(debug_assertions))
+    }
+}
+
+/// Transform of the ribbon of a banner at `location` over a widget of `size`.
+///
+/// Untransformed ribbon is horizontally centered at the origin, with its lower
+/// edge at [`OFFSET`] below it.
+fn ribbon_transform(location: BannerLocation, size: Size) -> Affine {
+    let (x, y, rotation) = match location {
+        BannerLocation::TopLeft => (0., 0., -FRAC_PI_4),
+        BannerLocation::TopRight => (size.width, 0., FRAC_PI_4),
+        BannerLocation::BottomLeft => (BOTTOM_OFFSET, size.height - BOTTOM_OFFSET, FRAC_PI_4),
+        BannerLocation::BottomRight => (
+            size.width - BOTTOM_OFFSET,
+            size.height - BOTTOM_OFFSET,
+            -FRAC_PI_4,
+        ),
+    };
+
+    Affine::translate((x, y)) * Affine::rotate(rotation)
+}
+
+#[cfg(not(feature = "miri"))]
+impl<S: AsRef<str>, W: Widget> RenderState for Banner<S, W> {
+    type State = Option<PietTextLayout>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+}
+
+impl<S: AsRef<str>, W: Widget> SingleChildWidget for Banner<S, W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        #[cfg(not(feature = "miri"))]
+        {
+            *ctx.rstate_mut() = self.enabled.then(|| {
+                Text::new(self.message.as_ref())
+                    .size(FONT_SIZE)
+                    .weight(FontWeight::BOLD)
+                    .build_layout(f64::INFINITY)
+            });
+        }
+
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas);
+
+        #[cfg(not(feature = "miri"))]
+        if let Some(label) = &*ctx.rstate() {
+            let transform = ribbon_transform(self.location, ctx.size());
+
+            canvas.with_transform(transform, |canvas| {
+                let ribbon = Rect::new(-OFFSET, OFFSET - HEIGHT, OFFSET, OFFSET);
+                let brush = &canvas.solid_brush(self.color.clone());
+                PietRenderContext::fill(canvas, ribbon, brush);
+
+                let size = label.size();
+                let origin = Point::new(-size.width / 2., ribbon.y0 + (HEIGHT - size.height) / 2.);
+
+                PietRenderContext::draw_text(canvas, label, origin);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ribbon_crosses_its_corner_diagonally() {
+        let size = Size::new(400., 300.);
+        // Middle of the lower edge of the ribbon.
+        let edge = Point::new(0., OFFSET);
+
+        let corners = [
+            (BannerLocation::TopLeft, Point::new(0., 0.)),
+            (BannerLocation::TopRight, Point::new(400., 0.)),
+            (BannerLocation::BottomLeft, Point::new(0., 300.)),
+            (BannerLocation::BottomRight, Point::new(400., 300.)),
+        ];
+
+        for (location, corner) in corners {
+            let edge = ribbon_transform(location, size) * edge;
+            let (dx, dy) = ((edge.x - corner.x).abs(), (edge.y - corner.y).abs());
+
+            // Edge is crossed by the diagonal through the corner.
+            let expected = if corner.y == 0. {
+                OFFSET * FRAC_PI_4.sin()
+            } else {
+                BOTTOM_OFFSET - OFFSET * FRAC_PI_4.sin()
+            };
+
+            assert!((dx - expected).abs() < 1e-9, "{location:?}: {edge:?}");
+            assert!((dx - dy).abs() < 1e-9, "{location:?}: {edge:?}");
+        }
+    }
+}
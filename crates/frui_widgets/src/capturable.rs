@@ -0,0 +1,36 @@
+use frui::{api::capture::CaptureKey, prelude::*};
+
+/// Attaches `capture_key` to its child, so that images of the child can be
+/// captured with [`CaptureKey::capture_image`], e.g. for thumbnails.
+#[derive(ViewWidget)]
+pub struct Capturable<W: Widget> {
+    pub capture_key: CaptureKey,
+    pub child: W,
+}
+
+impl<W: Widget> Capturable<W> {
+    pub fn new(capture_key: CaptureKey, child: W) -> Self {
+        Self { capture_key, child }
+    }
+}
+
+impl<W: Widget> WidgetState for Capturable<W> {
+    type State = ();
+
+    fn create_state(&self) -> Self::State {}
+
+    fn mount(&self, ctx: BuildContext<Self>) {
+        self.capture_key.attach(ctx);
+    }
+
+    fn did_update_widget(&self, ctx: BuildContext<Self>) {
+        // Key could have been replaced.
+        self.capture_key.attach(ctx);
+    }
+}
+
+impl<W: Widget> ViewWidget for Capturable<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+}
@@ -0,0 +1,257 @@
+use std::cell::Cell;
+
+use frui::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorFilter {
+    /// Blends the given color (the source) with the child (the destination).
+    Mode(Color, BlendMode),
+    /// Transforms colors of the child by a 4x5 row-major matrix, where each row
+    /// computes one of the `r`, `g`, `b` and `a` components from the original
+    /// `r`, `g`, `b`, `a` and a constant offset. Components (and offsets) are
+    /// in range `0.0..=255.0` and not premultiplied.
+    Matrix([f32; 20]),
+}
+
+impl ColorFilter {
+    /// Matrix which removes the saturation of colors.
+    pub fn greyscale() -> Self {
+        // Luminance coefficients from ITU-R BT.709.
+        const R: f32 = 0.2126;
+        const G: f32 = 0.7152;
+        const B: f32 = 0.0722;
+
+        #[rustfmt::skip]
+        let matrix = [
+            R, G, B, 0., 0.,
+            R, G, B, 0., 0.,
+            R, G, B, 0., 0.,
+            0., 0., 0., 1., 0.,
+        ];
+
+        ColorFilter::Matrix(matrix)
+    }
+
+    /// Filters premultiplied RGBA `pixels`.
+    fn apply(&self, pixels: &mut [u8]) {
+        match self {
+            ColorFilter::Mode(color, mode) => blend(pixels, color, *mode),
+            ColorFilter::Matrix(matrix) => transform(pixels, matrix),
+        }
+    }
+}
+
+/// Blends `color` (the source) with premultiplied RGBA `pixels` (the
+/// destination) using `mode`.
+fn blend(pixels: &mut [u8], color: &Color, mode: BlendMode) {
+    let (r, g, b, a) = color.as_rgba();
+    let src = [r * a, g * a, b * a, a];
+
+    for pixel in pixels.chunks_exact_mut(4) {
+        let dst: [f64; 4] = std::array::from_fn(|n| pixel[n] as f64 / 255.);
+        let (sa, da) = (src[3], dst[3]);
+
+        for n in 0..4 {
+            let (s, d) = (src[n], dst[n]);
+
+            let blended = match mode {
+                BlendMode::Clear => 0.,
+                BlendMode::Src => s,
+                BlendMode::Dst => d,
+                BlendMode::SrcOver => s + d * (1. - sa),
+                BlendMode::DstOver => d + s * (1. - da),
+                BlendMode::SrcIn => s * da,
+                BlendMode::DstIn => d * sa,
+                BlendMode::SrcOut => s * (1. - da),
+                BlendMode::DstOut => d * (1. - sa),
+                BlendMode::SrcATop => s * da + d * (1. - sa),
+                BlendMode::DstATop => d * sa + s * (1. - da),
+                BlendMode::Xor => s * (1. - da) + d * (1. - sa),
+                BlendMode::Multiply => s * d + s * (1. - da) + d * (1. - sa),
+                BlendMode::Screen => s + d - s * d,
+            };
+
+            pixel[n] = (blended.clamp(0., 1.) * 255.).round() as u8;
+        }
+    }
+}
+
+/// Transforms premultiplied RGBA `pixels` by a matrix of [`ColorFilter::Matrix`].
+fn transform(pixels: &mut [u8], matrix: &[f32; 20]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        let alpha = pixel[3] as f32;
+
+        let rgba: [f32; 4] = std::array::from_fn(|n| match n {
+            3 => alpha,
+            _ if alpha == 0. => 0.,
+            _ => pixel[n] as f32 * 255. / alpha,
+        });
+
+        let transformed: [f32; 4] = std::array::from_fn(|n| {
+            let row = &matrix[n * 5..n * 5 + 5];
+            let value = (0..4).map(|m| row[m] * rgba[m]).sum::<f32>() + row[4];
+            value.clamp(0., 255.)
+        });
+
+        let alpha = transformed[3] / 255.;
+
+        for n in 0..3 {
+            pixel[n] = (transformed[n] * alpha).round() as u8;
+        }
+
+        pixel[3] = transformed[3].round() as u8;
+    }
+}
+
+/// Paints its child through a [`ColorFilter`].
+///
+/// [`ColorFilter::Mode`] with [`BlendMode::Clear`], [`BlendMode::Src`],
+/// [`BlendMode::Dst`], [`BlendMode::SrcOver`] or [`BlendMode::DstOver`] is
+/// painted directly, as the color over or under the child. Any other filter
+/// paints the child offscreen at the resolution of the canvas (see
+/// [`PaintContextExt::with_filter`]) and clips it to the bounds of this
+/// widget. If painting offscreen fails, the child is painted unfiltered and a
+/// warning is logged.
+#[derive(SingleChildWidget)]
+pub struct ColorFiltered<W: Widget> {
+    pub filter: ColorFilter,
+    pub child: W,
+}
+
+impl<W: Widget> ColorFiltered<W> {
+    pub fn new(filter: ColorFilter, child: W) -> Self {
+        Self { filter, child }
+    }
+
+    /// Removes the saturation of its child.
+    pub fn greyscale(child: W) -> Self {
+        Self::new(ColorFilter::greyscale(), child)
+    }
+}
+
+#[doc(hidden)]
+pub struct ColorFilteredRenderState {
+    /// Whether a warning about a child painted unfiltered was logged.
+    warned: Cell<bool>,
+}
+
+impl<W: Widget> RenderState for ColorFiltered<W> {
+    type State = ColorFilteredRenderState;
+
+    fn create_state(&self) -> Self::State {
+        ColorFilteredRenderState {
+            warned: Cell::new(false),
+        }
+    }
+}
+
+impl<W: Widget> SingleChildWidget for ColorFiltered<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let rect = Rect::from_origin_size(Point::ORIGIN, ctx.size());
+
+        match &self.filter {
+            ColorFilter::Mode(_, BlendMode::Clear) => {}
+            ColorFilter::Mode(color, BlendMode::Src) => {
+                let brush = &canvas.solid_brush(color.clone());
+                PietRenderContext::fill(canvas, rect, brush);
+            }
+            ColorFilter::Mode(_, BlendMode::Dst) => ctx.child().paint(canvas),
+            ColorFilter::Mode(color, BlendMode::SrcOver) => {
+                ctx.child().paint(canvas);
+
+                let brush = &canvas.solid_brush(color.clone());
+                PietRenderContext::fill(canvas, rect, brush);
+            }
+            ColorFilter::Mode(color, BlendMode::DstOver) => {
+                let brush = &canvas.solid_brush(color.clone());
+                PietRenderContext::fill(canvas, rect, brush);
+
+                ctx.child().paint(canvas);
+            }
+            filter => {
+                let filtered = canvas.with_filter(
+                    rect,
+                    |pixels| filter.apply(pixels),
+                    |canvas| ctx.child().paint(canvas),
+                );
+
+                if !filtered && !ctx.rstate().warned.replace(true) {
+                    frui::diagnostic!(
+                        warn,
+                        "paint",
+                        "painting {filter:?} offscreen failed, painting child unfiltered"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use frui::app::testing::WidgetTester;
+
+    use super::*;
+
+    /// Counts how many times it was painted.
+    #[derive(LeafWidget)]
+    struct Probe {
+        painted: Rc<Cell<usize>>,
+    }
+
+    impl LeafWidget for Probe {
+        fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+            constraints.constrain(Size::new(10., 10.))
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {
+            self.painted.set(self.painted.get() + 1);
+        }
+    }
+
+    #[test]
+    fn filtered_child_is_painted_once() {
+        let painted = Rc::new(Cell::new(0));
+
+        let probe = Probe {
+            painted: painted.clone(),
+        };
+        let mut tester = WidgetTester::new(ColorFiltered::greyscale(probe), Size::new(100., 100.));
+
+        tester.paint();
+        assert_eq!(painted.get(), 1);
+    }
+
+    #[test]
+    fn greyscale_keeps_luminance_and_alpha() {
+        // Opaque red and half-transparent white, premultiplied.
+        let mut pixels = [255, 0, 0, 255, 128, 128, 128, 128];
+        ColorFilter::greyscale().apply(&mut pixels);
+
+        assert_eq!(pixels, [54, 54, 54, 255, 128, 128, 128, 128]);
+
+        let mut transparent = [0; 4];
+        ColorFilter::greyscale().apply(&mut transparent);
+        assert_eq!(transparent, [0; 4]);
+    }
+
+    #[test]
+    fn src_in_tints_opaque_pixels_only() {
+        let tint = ColorFilter::Mode(Color::rgb8(0, 0, 255), BlendMode::SrcIn);
+
+        let mut pixels = [255, 0, 0, 255, 10, 20, 30, 128, 0, 0, 0, 0];
+        tint.apply(&mut pixels);
+
+        assert_eq!(pixels, [0, 0, 255, 255, 0, 0, 128, 128, 0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,188 @@
+use frui::prelude::*;
+
+/// Builds one of two children depending on `condition`, so that `build` can
+/// branch between widgets of different types without boxing them by hand.
+///
+/// ```
+/// # #![feature(type_alias_impl_trait)]
+/// # use frui::prelude::*;
+/// # use frui_widgets::*;
+/// # #[derive(ViewWidget)]
+/// # struct LoginForm;
+/// # impl ViewWidget for LoginForm {
+/// #     fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {}
+/// # }
+/// # struct Session { logged_in: bool }
+/// # fn greeting<T: WidgetState<State = Session>>(ctx: BuildContext<T>) {
+/// If::new(ctx.state().logged_in, || Text::new("Welcome back"))
+///     .else_(|| Center::child(LoginForm));
+/// # }
+/// ```
+///
+/// Only the closure of the active branch is called. While `condition` stays
+/// the same between rebuilds, the child of the active branch is updated and
+/// keeps its state. Once it changes, the child of the previous branch is
+/// unmounted and the child of the other branch is built from scratch, even
+/// if both are of the same type. Without `else_`, nothing is built while
+/// `condition` is `false`.
+#[derive(ViewWidget)]
+pub struct If<'a> {
+    condition: bool,
+    child: Box<dyn Widget + 'a>,
+}
+
+impl<'a> If<'a> {
+    pub fn new<W: Widget + 'a>(condition: bool, then: impl FnOnce() -> W) -> Self {
+        Self {
+            condition,
+            child: match condition {
+                true => then().boxed(),
+                false => ().boxed(),
+            },
+        }
+    }
+
+    /// Builds the child with `otherwise` while the condition is `false`.
+    pub fn else_<W: Widget + 'a>(mut self, otherwise: impl FnOnce() -> W) -> Self {
+        if !self.condition {
+            self.child = otherwise().boxed();
+        }
+
+        self
+    }
+}
+
+impl<'a> ViewWidget for If<'a> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        // Children of different branches have different keys, so they are
+        // never updated with one another.
+        LocalKey::new(self.condition, &self.child)
+    }
+}
+
+/// Builds `child` if there is one, and nothing otherwise.
+///
+/// Changing between some child and none unmounts the previous child, while a
+/// child present during consecutive rebuilds keeps its state.
+#[derive(ViewWidget)]
+pub struct Maybe<W: Widget> {
+    child: Option<W>,
+}
+
+impl<W: Widget> Maybe<W> {
+    pub fn some_or_empty(child: Option<W>) -> Self {
+        Self { child }
+    }
+}
+
+impl<W: Widget> ViewWidget for Maybe<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        match &self.child {
+            Some(child) => child.boxed(),
+            None => ().boxed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc};
+
+    use frui::app::testing::WidgetTester;
+
+    use super::*;
+    use crate::ListenableBuilder;
+
+    /// Counts how many times its state was created.
+    #[derive(LeafWidget)]
+    struct Probe {
+        created: Rc<Cell<usize>>,
+    }
+
+    impl WidgetState for Probe {
+        type State = ();
+
+        fn create_state(&self) -> Self::State {
+            self.created.set(self.created.get() + 1);
+        }
+    }
+
+    impl LeafWidget for Probe {
+        fn layout(&self, _: RenderContext<Self>, _: Constraints) -> Size {
+            Size::default()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    /// Rebuilds the widget built by `build` whenever `condition` notifies.
+    fn tester<W: Widget + 'static>(
+        condition: &ValueNotifier<bool>,
+        build: impl Fn(bool) -> W + 'static,
+    ) -> WidgetTester {
+        let listenable = condition.clone();
+        let condition = condition.clone();
+
+        WidgetTester::new(
+            ListenableBuilder::new(listenable, move || build(condition.get())),
+            Size::new(100., 100.),
+        )
+    }
+
+    #[test]
+    fn if_keeps_child_state_until_condition_flips() {
+        let condition = ValueNotifier::new(true);
+        let created = Rc::new(Cell::new(0));
+
+        let probe = created.clone();
+        let mut tester = tester(&condition, move |condition| {
+            let (then, otherwise) = (probe.clone(), probe.clone());
+
+            If::new(condition, move || Probe { created: then })
+                .else_(move || Probe { created: otherwise })
+        });
+        assert_eq!(created.get(), 1);
+
+        // Rebuilt with the same condition.
+        condition.update(|_| {});
+        tester.pump();
+        assert_eq!(created.get(), 1);
+
+        // Child of the other branch is built from scratch, even though it's of
+        // the same type.
+        condition.set(false);
+        tester.pump();
+        assert_eq!(created.get(), 2);
+
+        condition.update(|_| {});
+        tester.pump();
+        assert_eq!(created.get(), 2);
+    }
+
+    #[test]
+    fn maybe_keeps_child_state_while_there_is_one() {
+        let condition = ValueNotifier::new(true);
+        let created = Rc::new(Cell::new(0));
+
+        let probe = created.clone();
+        let mut tester = tester(&condition, move |condition| {
+            Maybe::some_or_empty(condition.then_some(Probe {
+                created: probe.clone(),
+            }))
+        });
+        assert_eq!(created.get(), 1);
+
+        condition.update(|_| {});
+        tester.pump();
+        assert_eq!(created.get(), 1);
+
+        condition.set(false);
+        tester.pump();
+        assert_eq!(created.get(), 1);
+
+        // Previous child was unmounted, so its state is created again.
+        condition.set(true);
+        tester.pump();
+        assert_eq!(created.get(), 2);
+    }
+}
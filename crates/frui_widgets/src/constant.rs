@@ -0,0 +1,79 @@
+use frui::{macro_exports::StructuralEqImpl, prelude::*};
+
+/// Marks a subtree as immutable, skipping its rebuilds once it's built.
+///
+/// `Const` is given a `'static` reference to its child, e.g. a reference to
+/// a `static` or to a child leaked once. Configurations of two `Const` widgets
+/// are equal if they point to the same instance, so when the parent rebuilds
+/// with the same instance, neither this widget nor its descendants rebuild and
+/// their configurations aren't compared. Pointing to a different instance
+/// rebuilds the subtree once, as with any other changed configuration.
+///
+/// Descendants can still rebuild on their own, e.g. after their state changes
+/// or when an inherited widget they depend on is updated.
+///
+/// ## Interior mutability
+///
+/// Changes made to the child through interior mutability (e.g. through `Cell`
+/// or `RefCell`) are never picked up, since the instance stays the same. Such
+/// child under `Const` is a bug.
+///
+/// ```
+/// # use frui::prelude::*;
+/// # use frui_widgets::*;
+/// fn title() -> &'static Text<&'static str> {
+///     thread_local! {
+///         static TITLE: &'static Text<&'static str> = Box::leak(Box::new(Text::new("Title")));
+///     }
+///
+///     TITLE.with(|title| *title)
+/// }
+///
+/// let title = Const::new(title());
+/// ```
+#[derive(ViewWidget)]
+pub struct Const<W: Widget + 'static> {
+    child: Identity<W>,
+}
+
+impl<W: Widget + 'static> Const<W> {
+    pub const fn new(child: &'static W) -> Self {
+        Self {
+            child: Identity(child),
+        }
+    }
+}
+
+impl<W: Widget + 'static> ViewWidget for Const<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        self.child.0
+    }
+}
+
+/// Reference compared by its address.
+#[doc(hidden)]
+pub struct Identity<W: 'static>(&'static W);
+
+unsafe impl<W: 'static> StructuralEqImpl for Identity<W> {
+    const EQ_ENABLED: bool = true;
+
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.0, other.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use frui::macro_exports::StructuralEq;
+
+    use super::*;
+
+    static A: u8 = 0;
+    static B: u8 = 0;
+
+    #[test]
+    fn references_are_compared_by_identity() {
+        assert!(StructuralEq::eq(&Identity(&A), &Identity(&A)));
+        assert!(!StructuralEq::eq(&Identity(&A), &Identity(&B)));
+    }
+}
@@ -0,0 +1,51 @@
+use frui::prelude::*;
+
+/// Lays out its child with constraints adjusted by `transform`, e.g. to debug
+/// how a subtree behaves under different constraints, or to build widgets
+/// like [`MinTouchTarget`](crate::MinTouchTarget) on top of it.
+///
+/// Size of the child is constrained back into the incoming constraints, so
+/// that this widget honors them even if `transform` loosens them.
+///
+/// ```
+/// # use frui::prelude::*;
+/// # use frui_widgets::*;
+/// # let child = Text::new("Child");
+/// // Lays out the child as if the window was at most 320 wide.
+/// ConstraintsTransform::new(
+///     |c| Constraints {
+///         max_width: c.max_width.min(320.),
+///         ..c
+///     },
+///     child,
+/// );
+/// ```
+#[derive(SingleChildWidget)]
+pub struct ConstraintsTransform<W: Widget, F: Fn(Constraints) -> Constraints> {
+    pub child: W,
+    pub transform: F,
+}
+
+impl<W: Widget, F: Fn(Constraints) -> Constraints> ConstraintsTransform<W, F> {
+    pub fn new(transform: F, child: W) -> Self {
+        Self { child, transform }
+    }
+}
+
+impl<W: Widget, F: Fn(Constraints) -> Constraints> SingleChildWidget
+    for ConstraintsTransform<W, F>
+{
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let size = ctx.child().layout((self.transform)(constraints));
+
+        constraints.constrain(size)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
+    }
+}
@@ -2,12 +2,55 @@ use druid_shell::kurbo::Rect;
 
 use frui::prelude::*;
 
+use crate::{
+    alignment::{Alignment, AlignmentGeometry},
+    EdgeInsets, ShapeBorder,
+};
+
+/// Box painted with `color`, sized by its `width`, `height` and other
+/// constraints, with `padding` around its child and `margin` around itself.
+///
+/// Constraints of a container are intersected with the ones from its parent
+/// (see [`Constraints::enforce`]), so a container can't make its child larger
+/// or smaller than its parent allows. Fixed `width` or `height` take
+/// precedence over the minimal and maximal ones on the same axis.
+///
+/// Without `alignment`, the child is painted at the origin of the container,
+/// which takes the size of its child. With `alignment`, the child is laid out
+/// loosely and aligned within the container, which then takes as much space
+/// as it can (falling back to the size of its child along unbounded axes).
+///
+/// Layout of a container goes from the outside in:
+///
+/// 1. Constraints of the parent are deflated by `margin`.
+/// 2. Constraints of the container (`width`, `min_width` etc.) are enforced,
+///    so they size the painted box, which includes `padding` but not `margin`.
+/// 3. Those are deflated by `padding` and the child is laid out within them.
+///
+/// So the size of a container is `margin` + the painted box, where the painted
+/// box is `padding` + the child (or the space it's aligned within), constrained
+/// by the constraints of the container. Only the painted box is painted with
+/// `color`, so `margin` leaves transparent space around it. With `shape`, the
+/// painted box is filled in that shape, and its outline is painted along it.
+///
+/// ```
+/// # use frui::prelude::*;
+/// # use frui_widgets::*;
+/// # let child = Text::new("Child");
+/// // At least 100 wide, at most 300.
+/// Container::builder().min_width(100.).max_width(300.).child(child);
+/// ```
 #[derive(SingleChildWidget)]
 pub struct Container<W: Widget> {
     child: W,
     width: Option<f64>,
     height: Option<f64>,
+    constraints: Constraints,
+    alignment: Option<Alignment>,
+    padding: EdgeInsets,
+    margin: EdgeInsets,
     color: Option<Color>,
+    shape: Option<Box<dyn ShapeBorder>>,
 }
 
 impl Container<()> {
@@ -16,7 +59,12 @@ impl Container<()> {
             child: (),
             width: None,
             height: None,
+            constraints: Constraints::default(),
+            alignment: None,
+            padding: EdgeInsets::ZERO,
+            margin: EdgeInsets::ZERO,
             color: None,
+            shape: None,
         }
     }
 }
@@ -27,10 +75,16 @@ impl<W: Widget> Container<W> {
             child,
             width: self.width,
             height: self.height,
+            constraints: self.constraints,
+            alignment: self.alignment,
+            padding: self.padding,
+            margin: self.margin,
             color: self.color,
+            shape: self.shape,
         }
     }
 
+    /// Sets both minimal and maximal width to `width`.
     #[track_caller]
     pub fn width(mut self, width: f64) -> Self {
         assert!(width >= 0.0, "width must be >= 0.0");
@@ -38,6 +92,7 @@ impl<W: Widget> Container<W> {
         self
     }
 
+    /// Sets both minimal and maximal height to `height`.
     #[track_caller]
     pub fn height(mut self, height: f64) -> Self {
         assert!(height >= 0.0, "height must be >= 0.0");
@@ -45,10 +100,146 @@ impl<W: Widget> Container<W> {
         self
     }
 
+    #[track_caller]
+    pub fn min_width(self, min_width: f64) -> Self {
+        let constraints = Constraints {
+            min_width,
+            ..self.constraints
+        };
+
+        self.constraints(constraints)
+    }
+
+    #[track_caller]
+    pub fn max_width(self, max_width: f64) -> Self {
+        let constraints = Constraints {
+            max_width,
+            ..self.constraints
+        };
+
+        self.constraints(constraints)
+    }
+
+    #[track_caller]
+    pub fn min_height(self, min_height: f64) -> Self {
+        let constraints = Constraints {
+            min_height,
+            ..self.constraints
+        };
+
+        self.constraints(constraints)
+    }
+
+    #[track_caller]
+    pub fn max_height(self, max_height: f64) -> Self {
+        let constraints = Constraints {
+            max_height,
+            ..self.constraints
+        };
+
+        self.constraints(constraints)
+    }
+
+    /// Replaces minimal and maximal width and height set before.
+    #[track_caller]
+    pub fn constraints(mut self, constraints: Constraints) -> Self {
+        let Constraints {
+            min_width,
+            max_width,
+            min_height,
+            max_height,
+        } = constraints;
+
+        assert!(
+            (0.0..=max_width).contains(&min_width) && min_width.is_finite(),
+            "min_width must be finite and within 0.0..=max_width, got {min_width}..={max_width}"
+        );
+        assert!(
+            (0.0..=max_height).contains(&min_height) && min_height.is_finite(),
+            "min_height must be finite and within 0.0..=max_height, got {min_height}..={max_height}"
+        );
+
+        self.constraints = constraints;
+        self
+    }
+
+    /// Aligns the child within the container.
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = Some(alignment);
+        self
+    }
+
+    /// Space between the child and the edges of the painted box.
+    pub fn padding(mut self, padding: EdgeInsets) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Transparent space around the painted box.
+    pub fn margin(mut self, margin: EdgeInsets) -> Self {
+        self.margin = margin;
+        self
+    }
+
     pub fn color(mut self, color: Color) -> Self {
         self.color = Some(color);
         self
     }
+
+    pub fn shape(mut self, shape: impl ShapeBorder + 'static) -> Self {
+        self.shape = Some(Box::new(shape));
+        self
+    }
+}
+
+impl<W: Widget> Container<W> {
+    /// Constraints of this container intersected with `constraints` of its
+    /// parent, which it lays out its child with.
+    fn effective_constraints(&self, constraints: Constraints) -> Constraints {
+        let width = finite_extent(self.width, constraints.max_width);
+        let height = finite_extent(self.height, constraints.max_height);
+
+        // Fixed extents take precedence over minimal and maximal ones.
+        let own = Constraints {
+            min_width: width.unwrap_or(self.constraints.min_width),
+            max_width: width.unwrap_or(self.constraints.max_width),
+            min_height: height.unwrap_or(self.constraints.min_height),
+            max_height: height.unwrap_or(self.constraints.max_height),
+        };
+
+        own.enforce(constraints)
+    }
+
+    /// Size of this container laid out with `constraints`, along with the
+    /// offset of its child laid out with `layout_child`.
+    fn layout_with(
+        &self,
+        constraints: Constraints,
+        layout_child: impl FnOnce(Constraints) -> Size,
+    ) -> (Size, Offset) {
+        let outer = self.margin.deflate_constraints(constraints);
+        let decoration = self.effective_constraints(outer);
+        let inner = self.padding.deflate_constraints(decoration);
+
+        let (content, content_offset) = match self.alignment {
+            Some(alignment) => align(alignment, inner, layout_child(inner.loosen())),
+            None => (inner.constrain(layout_child(inner)), Offset::default()),
+        };
+
+        let decoration = decoration.constrain(Size::new(
+            content.width + self.padding.horizontal(),
+            content.height + self.padding.vertical(),
+        ));
+
+        let size = constraints.constrain(Size::new(
+            decoration.width + self.margin.horizontal(),
+            decoration.height + self.margin.vertical(),
+        ));
+
+        let child_offset = self.margin.top_left() + self.padding.top_left() + content_offset;
+
+        (size, child_offset)
+    }
 }
 
 impl<W: Widget> SingleChildWidget for Container<W> {
@@ -57,25 +248,222 @@ impl<W: Widget> SingleChildWidget for Container<W> {
     }
 
     fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
-        let size = ctx.child().layout(Constraints {
-            max_width: self.width.unwrap_or(constraints.max_width),
-            max_height: self.height.unwrap_or(constraints.max_height),
-            ..constraints
-        });
+        let (size, child_offset) =
+            self.layout_with(constraints, |constraints| ctx.child().layout(constraints));
 
-        Size {
-            width: self.width.unwrap_or(size.width),
-            height: self.height.unwrap_or(size.height),
-        }
+        ctx.child().set_offset(child_offset);
+
+        size
     }
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, offset: &Offset) {
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let origin = self.margin.top_left();
+        let decoration = Rect::from_origin_size(origin, self.margin.deflate_size(ctx.size()));
+
         if let Some(color) = &self.color {
             let brush = &canvas.solid_brush(color.clone());
 
-            PietRenderContext::fill(canvas, Rect::from_origin_size(offset, ctx.size()), brush);
+            match &self.shape {
+                Some(shape) => PietRenderContext::fill(canvas, shape.path(decoration), brush),
+                None => PietRenderContext::fill(canvas, decoration, brush),
+            }
+        }
+
+        if let Some(shape) = &self.shape {
+            shape.paint(canvas, decoration);
         }
 
-        ctx.child().paint(canvas, offset)
+        ctx.child().paint(canvas)
+    }
+}
+
+/// Size of a container with `alignment` and the offset of its child of
+/// `child_size` within it.
+fn align(alignment: Alignment, constraints: Constraints, child_size: Size) -> (Size, Offset) {
+    let size = constraints.constrain(Size {
+        width: match constraints.has_bounded_width() {
+            true => constraints.max_width,
+            false => child_size.width,
+        },
+        height: match constraints.has_bounded_height() {
+            true => constraints.max_height,
+            false => child_size.height,
+        },
+    });
+
+    (size, alignment.along(size - child_size))
+}
+
+/// Width or height of a container, given the maximal one of its constraints.
+///
+/// Infinite `extent` (i.e. as large as possible) is ignored when `max` is
+/// unbounded, so that the container falls back to the size of its child rather
+/// than sizing itself to infinity.
+fn finite_extent(extent: Option<f64>, max: f64) -> Option<f64> {
+    extent.filter(|extent| extent.is_finite() || max.is_finite())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn infinite_extent_falls_back_to_child_when_unbounded() {
+        assert_eq!(
+            finite_extent(Some(f64::INFINITY), 100.),
+            Some(f64::INFINITY)
+        );
+        assert_eq!(finite_extent(Some(f64::INFINITY), f64::INFINITY), None);
+        assert_eq!(finite_extent(Some(50.), f64::INFINITY), Some(50.));
+
+        // Container without a size takes the size of its child, which is
+        // finite even under unbounded constraints.
+        let unbounded = Constraints::loose(Size::new(f64::INFINITY, f64::INFINITY));
+        let child = Size::new(20., 30.);
+
+        let container = Container::builder().height(f64::INFINITY);
+        let constraints = container.effective_constraints(unbounded);
+
+        assert_eq!(constraints, unbounded);
+        assert_eq!(constraints.constrain(child), child);
+    }
+
+    #[test]
+    fn constraints_are_intersected_with_incoming_ones() {
+        let container = Container::builder().min_width(100.).max_width(300.);
+        let incoming = |min_width, max_width| Constraints {
+            min_width,
+            max_width,
+            min_height: 0.,
+            max_height: 50.,
+        };
+
+        assert_eq!(
+            container.effective_constraints(incoming(0., 500.)),
+            incoming(100., 300.)
+        );
+        assert_eq!(
+            container.effective_constraints(incoming(150., 200.)),
+            incoming(150., 200.)
+        );
+
+        // Conflicting incoming constraints win, tightening to their bound
+        // closest to the ones of the container.
+        assert_eq!(
+            container.effective_constraints(incoming(0., 50.)),
+            incoming(50., 50.)
+        );
+        assert_eq!(
+            container.effective_constraints(incoming(400., 500.)),
+            incoming(400., 400.)
+        );
+
+        // Fixed width wins over the minimal and maximal one.
+        let container = container.width(500.);
+        assert_eq!(
+            container.effective_constraints(incoming(0., 1000.)),
+            incoming(500., 500.)
+        );
+        assert_eq!(
+            container.effective_constraints(incoming(0., 200.)),
+            incoming(200., 200.)
+        );
+    }
+
+    #[test]
+    fn aligned_child_falls_back_to_its_size_along_unbounded_axis() {
+        let child = Size::new(20., 10.);
+        let constraints = Constraints {
+            min_width: 0.,
+            max_width: 100.,
+            min_height: 0.,
+            max_height: f64::INFINITY,
+        };
+
+        let (size, Offset { x, y }) = align(Alignment::CENTER, constraints, child);
+        assert_eq!(size, Size::new(100., 10.));
+        assert_eq!((x, y), (40., 0.));
+
+        let (size, Offset { x, y }) = align(
+            Alignment::BOTTOM_RIGHT,
+            Constraints::tight(Size::new(50., 50.)),
+            child,
+        );
+        assert_eq!(size, Size::new(50., 50.));
+        assert_eq!((x, y), (30., 40.));
+    }
+
+    #[test]
+    fn padding_larger_than_parent_leaves_child_zero_size() {
+        let container = Container::builder().padding(EdgeInsets::all(20.));
+
+        for parent in [
+            Constraints::tight(Size::new(30., 30.)),
+            Constraints::loose(Size::new(30., 10.)),
+        ] {
+            let (size, _) = container.layout_with(parent, |constraints| {
+                assert!(constraints.is_normalized());
+                assert_eq!(constraints.max(), Size::new(0., 0.));
+                constraints.smallest()
+            });
+
+            // Container itself still satisfies its parent.
+            assert!(parent.is_satisfied_by(size));
+        }
+
+        // Inverted constraints are normalized before layout.
+        let inverted = Constraints {
+            min_width: 50.,
+            max_width: 20.,
+            min_height: 0.,
+            max_height: -5.,
+        };
+        assert!(!inverted.is_normalized());
+        assert_eq!(
+            inverted.normalize(),
+            Constraints {
+                min_width: 20.,
+                max_width: 20.,
+                min_height: 0.,
+                max_height: 0.,
+            }
+        );
+    }
+
+    #[test]
+    fn margin_surrounds_painted_box_which_includes_padding() {
+        let child = Size::new(20., 10.);
+        let container = Container::builder()
+            .padding(EdgeInsets::all(5.))
+            .margin(EdgeInsets::symmetric(10., 1.));
+
+        let loose = Constraints::loose(Size::new(200., 200.));
+        let (size, Offset { x, y }) = container.layout_with(loose, |_| child);
+
+        // Child within padding (colored), within margin (transparent).
+        assert_eq!(size, Size::new(20. + 10. + 20., 10. + 10. + 2.));
+        assert_eq!((x, y), (15., 6.));
+        assert_eq!(
+            container.margin.deflate_size(size),
+            Size::new(20. + 10., 10. + 10.)
+        );
+
+        // Fixed width sizes the painted box, so the margin comes on top of it
+        // while the child is left with what remains within the padding.
+        let container = container.width(100.);
+        let (size, _) = container.layout_with(loose, |constraints| {
+            assert_eq!(constraints.min_width, 90.);
+            assert_eq!(constraints.max_width, 90.);
+            child
+        });
+        assert_eq!(size.width, 100. + 20.);
+
+        // Margin shrinks the space given to the container first.
+        let tight = Constraints::tight(Size::new(60., 40.));
+        let (size, _) = container.layout_with(tight, |constraints| {
+            assert_eq!((constraints.max_width, constraints.max_height), (30., 28.));
+            constraints.smallest()
+        });
+        assert_eq!(size, Size::new(60., 40.));
     }
 }
@@ -0,0 +1,268 @@
+use std::cell::Cell;
+
+use druid_shell::MouseButton;
+use frui::{
+    api::events::{Event, WidgetEvent},
+    app::listeners::context_menu::{ContextMenuKey, ContextMenuOwner, Menu, CONTEXT_MENUS},
+    prelude::*,
+};
+
+use crate::Enabled;
+
+/// Item of a context menu opened by [`ContextMenuRegion`].
+///
+/// Labels can mark a mnemonic with `&`, like [`MnemonicText`](crate::MnemonicText).
+pub struct MenuItem<'a> {
+    pub label: String,
+    pub on_selected: Box<dyn Fn() + 'a>,
+    pub enabled: bool,
+    /// Items of the submenu opened by this item, if it isn't empty. Item with
+    /// a submenu is never selected itself.
+    pub submenu: Vec<MenuItem<'a>>,
+}
+
+impl<'a> MenuItem<'a> {
+    /// Item calling `on_selected` once selected. Like other callbacks, it may
+    /// capture the `BuildContext` of the widget building this.
+    pub fn new(label: impl Into<String>, on_selected: impl Fn() + 'a) -> Self {
+        Self {
+            label: label.into(),
+            on_selected: Box::new(on_selected),
+            enabled: true,
+            submenu: Vec::new(),
+        }
+    }
+
+    /// Item opening a submenu of `items`.
+    pub fn submenu(label: impl Into<String>, items: Vec<MenuItem<'a>>) -> Self {
+        Self {
+            submenu: items,
+            ..Self::new(label, || {})
+        }
+    }
+
+    /// Disabled item is displayed, but can't be selected.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+/// Adds `items` to `menu`, numbering items which can be selected depth-first
+/// starting from `next_id`.
+fn add_items(menu: &mut Menu, items: &[MenuItem], next_id: &mut u32) {
+    for item in items {
+        if item.submenu.is_empty() {
+            menu.add_item(*next_id, &item.label, None, item.enabled, false);
+            *next_id += 1;
+        } else {
+            let mut submenu = Menu::new_for_popup();
+            add_items(&mut submenu, &item.submenu, next_id);
+            menu.add_dropdown(submenu, &item.label, item.enabled);
+        }
+    }
+}
+
+/// Item numbered `id` by [`add_items`], and whether it and its parents are
+/// enabled.
+fn find_item<'m, 'a>(items: &'m [MenuItem<'a>], id: u32) -> Option<(&'m MenuItem<'a>, bool)> {
+    fn find<'m, 'a>(
+        items: &'m [MenuItem<'a>],
+        id: u32,
+        next_id: &mut u32,
+    ) -> Option<(&'m MenuItem<'a>, bool)> {
+        for item in items {
+            if item.submenu.is_empty() {
+                if *next_id == id {
+                    return Some((item, item.enabled));
+                }
+                *next_id += 1;
+            } else if let Some((found, enabled)) = find(&item.submenu, id, next_id) {
+                return Some((found, enabled && item.enabled));
+            }
+        }
+
+        None
+    }
+
+    find(items, id, &mut 0)
+}
+
+/// Opens a context menu of `items` once the secondary (right) pointer button
+/// is pressed within its child.
+///
+/// ```
+/// # use frui::prelude::*;
+/// # use frui_widgets::*;
+/// # fn copy() {}
+/// # fn paste() {}
+/// # fn email() {}
+/// # let (can_paste, document) = (true, Text::new("Document"));
+/// ContextMenuRegion::new(
+///     vec![
+///         MenuItem::new("&Copy", || copy()),
+///         MenuItem::new("&Paste", || paste()).enabled(can_paste),
+///         MenuItem::submenu("&Share", vec![MenuItem::new("&Email", || email())]),
+///     ],
+///     document,
+/// )
+/// # ;
+/// ```
+///
+/// Menu is a native pop-up menu opened at the pointer, which the platform
+/// keeps on screen and navigates with the keyboard (arrows, Enter, Escape).
+/// It is dismissed once an item is selected or the pointer is pressed outside
+/// of it. Only one menu is open at a time, and opening another one forgets the
+/// previous one. If the region is removed (or disabled by [`Enabled`]) while
+/// its menu is open, selecting an item of it does nothing.
+#[derive(SingleChildWidget)]
+pub struct ContextMenuRegion<'a, W: Widget> {
+    pub items: Vec<MenuItem<'a>>,
+    pub child: W,
+}
+
+impl<'a, W: Widget> ContextMenuRegion<'a, W> {
+    pub fn new(items: Vec<MenuItem<'a>>, child: W) -> Self {
+        Self { items, child }
+    }
+}
+
+impl<'a, W: Widget> ContextMenuOwner for ContextMenuRegion<'a, W> {
+    fn item_selected(&self, id: u32) {
+        if let Some((item, true)) = find_item(&self.items, id) {
+            (item.on_selected)();
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ContextMenuRegionState {
+    /// Key of the menu opened by this region.
+    menu: Cell<Option<ContextMenuKey>>,
+    enabled: Cell<bool>,
+}
+
+impl ContextMenuRegionState {
+    fn close(&self) {
+        if let Some(key) = self.menu.take() {
+            CONTEXT_MENUS.with(|menus| menus.borrow_mut().close(&key));
+        }
+    }
+}
+
+impl<'a, W: Widget> WidgetState for ContextMenuRegion<'a, W> {
+    type State = ContextMenuRegionState;
+
+    fn create_state(&self) -> Self::State {
+        ContextMenuRegionState::default()
+    }
+
+    fn did_update_widget(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = ctx.state().menu.get() {
+            CONTEXT_MENUS.with(|menus| unsafe {
+                menus
+                    .borrow_mut()
+                    .update(&key, self as &dyn ContextMenuOwner)
+            });
+        }
+    }
+
+    fn did_change_dependencies(&self, ctx: BuildContext<Self>) {
+        let enabled = Enabled::of(ctx);
+        let state = ctx.state();
+
+        state.enabled.set(enabled);
+
+        if !enabled {
+            state.close();
+        }
+    }
+
+    fn dispose(&self, ctx: BuildContext<Self>) {
+        ctx.state().close();
+    }
+}
+
+impl<'a, W: Widget> SingleChildWidget for ContextMenuRegion<'a, W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
+    }
+}
+
+impl<'a, W: Widget> WidgetEvent for ContextMenuRegion<'a, W> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        // Child gets the event first, so that the innermost region opens its
+        // menu instead of the outer ones.
+        ctx.child().handle_event(event);
+
+        if let Event::MouseDown(e) = event {
+            let state = ctx.wstate();
+            let opened_by_child = CONTEXT_MENUS.with(|menus| menus.borrow().is_pending());
+
+            if e.button == MouseButton::Right
+                && state.enabled.get()
+                && !opened_by_child
+                && ctx.point_in_layout_bounds(e.pos)
+            {
+                let mut menu = Menu::new_for_popup();
+                add_items(&mut menu, &self.items, &mut 0);
+
+                let key = CONTEXT_MENUS.with(|menus| unsafe {
+                    menus
+                        .borrow_mut()
+                        .open(self as &dyn ContextMenuOwner, menu, e.pos)
+                });
+
+                state.menu.set(Some(key));
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn items_are_numbered_depth_first_skipping_submenus() {
+        let items = vec![
+            MenuItem::new("Copy", || {}),
+            MenuItem::submenu(
+                "Share",
+                vec![
+                    MenuItem::new("Email", || {}),
+                    MenuItem::new("Print", || {}).enabled(false),
+                ],
+            ),
+            MenuItem::submenu("Export", vec![MenuItem::new("PDF", || {})]).enabled(false),
+            MenuItem::new("Delete", || {}),
+        ];
+
+        let found = (0..5)
+            .map(|id| find_item(&items, id).map(|(item, enabled)| (item.label.as_str(), enabled)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            found,
+            [
+                Some(("Copy", true)),
+                Some(("Email", true)),
+                Some(("Print", false)),
+                // Item of a disabled submenu can't be selected either.
+                Some(("PDF", false)),
+                Some(("Delete", true)),
+            ]
+        );
+        assert!(find_item(&items, 5).is_none());
+    }
+}
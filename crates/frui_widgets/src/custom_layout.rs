@@ -0,0 +1,238 @@
+use std::fmt::Debug;
+
+use frui::prelude::*;
+
+use crate::WidgetList;
+
+/// Lays out the children of [`CustomMultiChildLayout`].
+pub trait MultiChildLayoutDelegate {
+    /// Type of ids children are tagged with using [`LayoutId`].
+    type Id: Clone + PartialEq + Debug + 'static;
+
+    /// Lays out and positions every child, returning the size of the whole
+    /// layout which must satisfy `constraints`.
+    ///
+    /// Children are given in the order they were passed to
+    /// [`CustomMultiChildLayout`]. Every one of them should be positioned with
+    /// [`ChildHandle::set_offset`], which can be done only after it was laid
+    /// out. Child which wasn't positioned is placed at the origin of the
+    /// layout (and laid out within loosened `constraints` if it wasn't laid
+    /// out either).
+    fn layout(&self, children: &mut [ChildHandle<Self::Id>], constraints: Constraints) -> Size;
+}
+
+/// Child of [`CustomMultiChildLayout`] given to its delegate.
+pub struct ChildHandle<'a, I> {
+    id: I,
+    child: ChildContext<'a>,
+    offset: Option<Offset>,
+    laid_out: bool,
+}
+
+impl<'a, I> ChildHandle<'a, I> {
+    /// Id of this child given by its [`LayoutId`].
+    pub fn id(&self) -> &I {
+        &self.id
+    }
+
+    pub fn layout(&mut self, constraints: Constraints) -> Size {
+        self.laid_out = true;
+        self.child.layout(constraints)
+    }
+
+    /// Size of this child from its last layout.
+    pub fn size(&self) -> Size {
+        self.child.size()
+    }
+
+    /// Positions this child relative to the top-left corner of the layout.
+    pub fn set_offset(&mut self, offset: Offset) {
+        self.offset = Some(offset);
+    }
+}
+
+/// Tags `child` of [`CustomMultiChildLayout`] with an `id` its delegate
+/// recognizes it by.
+#[derive(SingleChildWidget)]
+pub struct LayoutId<I: Clone + 'static, W: Widget> {
+    pub id: I,
+    pub child: W,
+}
+
+impl<I: Clone + 'static, W: Widget> LayoutId<I, W> {
+    pub fn new(id: I, child: W) -> Self {
+        Self { id, child }
+    }
+}
+
+/// RenderData of [`LayoutId`], read by its [`CustomMultiChildLayout`] parent.
+#[doc(hidden)]
+pub struct LayoutIdData<I> {
+    id: I,
+}
+
+impl<I: Clone + 'static, W: Widget> RenderState for LayoutId<I, W> {
+    type State = LayoutIdData<I>;
+
+    fn create_state(&self) -> Self::State {
+        LayoutIdData {
+            id: self.id.clone(),
+        }
+    }
+}
+
+impl<I: Clone + 'static, W: Widget> SingleChildWidget for LayoutId<I, W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
+    }
+}
+
+/// Lays out its children with a [`MultiChildLayoutDelegate`], which measures
+/// and positions each of them by the id given with [`LayoutId`].
+///
+/// Every child must be wrapped in a [`LayoutId`], otherwise layout panics.
+/// Children the delegate didn't position are placed at the origin of the
+/// layout.
+#[derive(MultiChildWidget)]
+pub struct CustomMultiChildLayout<D: MultiChildLayoutDelegate, WL: WidgetList> {
+    pub delegate: D,
+    pub children: WL,
+}
+
+impl<D: MultiChildLayoutDelegate, WL: WidgetList> CustomMultiChildLayout<D, WL> {
+    pub fn new(delegate: D, children: WL) -> Self {
+        Self { delegate, children }
+    }
+}
+
+impl<D: MultiChildLayoutDelegate, WL: WidgetList> MultiChildWidget
+    for CustomMultiChildLayout<D, WL>
+{
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        self.children.get()
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let mut children = ctx
+            .children()
+            .enumerate()
+            .map(|(n, child)| {
+                let id = match child.try_data::<LayoutIdData<D::Id>>() {
+                    Some(data) => data.id.clone(),
+                    None => panic!(
+                        "child {n} of `CustomMultiChildLayout` must be wrapped in `LayoutId<{}, _>`",
+                        std::any::type_name::<D::Id>(),
+                    ),
+                };
+
+                ChildHandle {
+                    id,
+                    child,
+                    offset: None,
+                    laid_out: false,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let size = self.delegate.layout(&mut children, constraints);
+
+        for child in &mut children {
+            let offset = match child.offset {
+                Some(offset) => offset,
+                None => {
+                    frui::diagnostic!(
+                        warn,
+                        "layout",
+                        "`{}` didn't position child with id {:?}, it's placed at the origin \
+                        of the layout",
+                        std::any::type_name::<D>(),
+                        child.id,
+                    );
+
+                    if !child.laid_out {
+                        child.child.layout(constraints.loosen());
+                    }
+
+                    Offset::default()
+                }
+            };
+
+            child.child.set_offset(offset);
+        }
+
+        size
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        for mut child in ctx.children() {
+            child.paint(canvas);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use frui::app::testing::WidgetTester;
+
+    use super::*;
+
+    /// Positions only the child with id 0, below the origin.
+    struct PositionFirst;
+
+    impl MultiChildLayoutDelegate for PositionFirst {
+        type Id = usize;
+
+        fn layout(&self, children: &mut [ChildHandle<usize>], constraints: Constraints) -> Size {
+            for child in children.iter_mut().filter(|c| *c.id() == 0) {
+                child.layout(constraints.loosen());
+                child.set_offset(Offset { x: 0., y: 20. });
+            }
+
+            constraints.biggest()
+        }
+    }
+
+    /// Leaf which records its offset whenever it's painted.
+    #[derive(LeafWidget)]
+    struct Probe {
+        id: usize,
+        painted: Rc<RefCell<Vec<(usize, f64)>>>,
+    }
+
+    impl LeafWidget for Probe {
+        fn layout(&self, _: RenderContext<Self>, _: Constraints) -> Size {
+            Size::new(10., 10.)
+        }
+
+        fn paint(&self, ctx: RenderContext<Self>, _: &mut PaintContext) {
+            self.painted.borrow_mut().push((self.id, ctx.offset().y));
+        }
+    }
+
+    #[test]
+    fn unpositioned_child_is_laid_out_at_origin() {
+        let painted = Rc::new(RefCell::new(Vec::new()));
+
+        let children = (0..2).map(|id| {
+            let painted = painted.clone();
+            LayoutId::new(id, Probe { id, painted })
+        });
+
+        let layout = CustomMultiChildLayout::new(PositionFirst, children.collect::<Vec<_>>());
+
+        let mut tester = WidgetTester::new(layout, Size::new(100., 100.));
+        tester.paint();
+
+        assert_eq!(*painted.borrow(), [(0, 20.), (1, 0.)]);
+    }
+}
@@ -0,0 +1,57 @@
+use frui::prelude::*;
+
+/// Direction in which text (and layouts following it) flows horizontally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextDirection {
+    /// Left to right, e.g. English.
+    #[default]
+    Ltr,
+    /// Right to left, e.g. Arabic or Hebrew.
+    Rtl,
+}
+
+/// Provides the [`TextDirection`] of its subtree, which resolves the `start`
+/// and `end` of directional values (like [`EdgeInsetsDirectional`]) to left
+/// and right, and in which [`Row`] places its children.
+///
+/// Widgets outside of any `Directionality` use [`TextDirection::Ltr`].
+///
+/// [`EdgeInsetsDirectional`]: crate::EdgeInsetsDirectional
+/// [`Row`]: crate::Row
+#[derive(InheritedWidget)]
+pub struct Directionality<W: Widget> {
+    pub direction: TextDirection,
+    pub child: W,
+}
+
+impl Directionality<()> {
+    /// Returns text direction of the closest `Directionality` ancestor,
+    /// rebuilding the widget of `ctx` whenever it changes.
+    pub fn of<'a, T>(ctx: BuildContext<'a, T>) -> TextDirection {
+        match ctx.depend_on_inherited_widget::<Self>() {
+            Some(state) => *state.as_ref(),
+            None => TextDirection::default(),
+        }
+    }
+}
+
+impl<W: Widget> WidgetState for Directionality<W> {
+    type State = TextDirection;
+
+    fn create_state(&self) -> Self::State {
+        self.direction
+    }
+
+    fn did_update_widget<'a>(&'a self, ctx: BuildContext<'a, Self>) {
+        if *ctx.state() != self.direction {
+            *ctx.state_mut() = self.direction;
+            ctx.notify_dependents();
+        }
+    }
+}
+
+impl<W: Widget> InheritedWidget for Directionality<W> {
+    fn child<'w>(&'w self) -> &'w Self::Widget<'w> {
+        &self.child
+    }
+}
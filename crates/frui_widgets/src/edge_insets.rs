@@ -0,0 +1,161 @@
+use frui::prelude::*;
+
+use crate::TextDirection;
+
+/// Insets along the edges of a box, e.g. a padding, which are either absolute
+/// ([`EdgeInsets`]) or relative to the text direction
+/// ([`EdgeInsetsDirectional`]).
+pub trait EdgeInsetsGeometry {
+    /// Insets with their `start` and `end` resolved to left and right in
+    /// `direction`.
+    fn resolve(&self, direction: TextDirection) -> EdgeInsets;
+}
+
+/// Insets along the left, top, right and bottom edges of a box.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EdgeInsets {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl EdgeInsets {
+    pub const ZERO: EdgeInsets = EdgeInsets::all(0.);
+
+    /// Insets of `value` along every edge.
+    pub const fn all(value: f64) -> Self {
+        EdgeInsets {
+            left: value,
+            top: value,
+            right: value,
+            bottom: value,
+        }
+    }
+
+    /// Insets of `horizontal` along the left and right edge, and of `vertical`
+    /// along the top and bottom edge.
+    pub const fn symmetric(horizontal: f64, vertical: f64) -> Self {
+        EdgeInsets {
+            left: horizontal,
+            top: vertical,
+            right: horizontal,
+            bottom: vertical,
+        }
+    }
+
+    /// Sum of the left and right insets.
+    pub fn horizontal(&self) -> f64 {
+        self.left + self.right
+    }
+
+    /// Sum of the top and bottom insets.
+    pub fn vertical(&self) -> f64 {
+        self.top + self.bottom
+    }
+
+    /// Shrinks `size` by these insets, never below zero.
+    pub fn deflate_size(&self, size: Size) -> Size {
+        Size::new(
+            (size.width - self.horizontal()).max(0.),
+            (size.height - self.vertical()).max(0.),
+        )
+    }
+
+    /// Shrinks `constraints` by these insets, never below zero.
+    pub fn deflate_constraints(&self, constraints: Constraints) -> Constraints {
+        let min_width = (constraints.min_width - self.horizontal()).max(0.);
+        let min_height = (constraints.min_height - self.vertical()).max(0.);
+
+        Constraints {
+            min_width,
+            max_width: (constraints.max_width - self.horizontal()).max(min_width),
+            min_height,
+            max_height: (constraints.max_height - self.vertical()).max(min_height),
+        }
+    }
+
+    /// Offset of the top left corner of a box inset by these insets.
+    pub fn top_left(&self) -> Offset {
+        Offset {
+            x: self.left,
+            y: self.top,
+        }
+    }
+}
+
+impl EdgeInsetsGeometry for EdgeInsets {
+    fn resolve(&self, _: TextDirection) -> EdgeInsets {
+        *self
+    }
+}
+
+/// Insets along the start, top, end and bottom edges of a box, where start is
+/// the left edge in [`TextDirection::Ltr`] and the right edge in
+/// [`TextDirection::Rtl`].
+///
+/// Read the direction with [`Directionality::of`](crate::Directionality::of)
+/// and [`resolve`](EdgeInsetsGeometry::resolve) them to [`EdgeInsets`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EdgeInsetsDirectional {
+    pub start: f64,
+    pub top: f64,
+    pub end: f64,
+    pub bottom: f64,
+}
+
+impl EdgeInsetsDirectional {
+    pub const ZERO: EdgeInsetsDirectional = EdgeInsetsDirectional::all(0.);
+
+    /// Insets of `value` along every edge.
+    pub const fn all(value: f64) -> Self {
+        EdgeInsetsDirectional {
+            start: value,
+            top: value,
+            end: value,
+            bottom: value,
+        }
+    }
+}
+
+impl EdgeInsetsGeometry for EdgeInsetsDirectional {
+    fn resolve(&self, direction: TextDirection) -> EdgeInsets {
+        let (left, right) = match direction {
+            TextDirection::Ltr => (self.start, self.end),
+            TextDirection::Rtl => (self.end, self.start),
+        };
+
+        EdgeInsets {
+            left,
+            top: self.top,
+            right,
+            bottom: self.bottom,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn directional_insets_flip_under_rtl() {
+        let insets = EdgeInsetsDirectional {
+            start: 1.,
+            top: 2.,
+            end: 3.,
+            bottom: 4.,
+        };
+
+        let ltr = insets.resolve(TextDirection::Ltr);
+        assert_eq!((ltr.left, ltr.top, ltr.right, ltr.bottom), (1., 2., 3., 4.));
+
+        let rtl = insets.resolve(TextDirection::Rtl);
+        assert_eq!((rtl.left, rtl.top, rtl.right, rtl.bottom), (3., 2., 1., 4.));
+
+        // Absolute insets don't depend on the direction.
+        let absolute = EdgeInsets::symmetric(5., 6.);
+        assert_eq!(absolute.resolve(TextDirection::Rtl), absolute);
+        assert_eq!(absolute.deflate_size(Size::new(8., 20.)), Size::new(0., 8.));
+    }
+}
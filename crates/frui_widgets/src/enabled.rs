@@ -0,0 +1,157 @@
+use frui::prelude::*;
+
+use crate::AbsorbPointer;
+
+/// Enables or disables interactive widgets of its subtree, e.g. to disable a
+/// whole form while it's being submitted.
+///
+/// Disabled subtree doesn't receive pointer events and interactive widgets in
+/// it (like [`Radio`](crate::Radio) or [`InkWell`](crate::InkWell)) render a
+/// disabled appearance and ignore input. Focused widgets of the subtree (like
+/// [`ClipboardListener`](crate::ClipboardListener)) lose focus once it's
+/// disabled.
+///
+/// `Enabled` can't enable a subtree of a disabled ancestor, so an
+/// `Enabled(false)` keeps its subtree disabled regardless of the `Enabled`
+/// widgets around or inside of it.
+#[derive(ViewWidget)]
+pub struct Enabled<W: Widget> {
+    pub enabled: bool,
+    pub child: W,
+}
+
+impl<W: Widget> Enabled<W> {
+    pub fn new(enabled: bool, child: W) -> Self {
+        Self { enabled, child }
+    }
+}
+
+impl Enabled<()> {
+    /// Returns whether the widget of `ctx` is enabled, i.e. none of its
+    /// `Enabled` ancestors is disabled, rebuilding it whenever that changes.
+    ///
+    /// Widgets outside of any `Enabled` are enabled.
+    pub fn of<'a, T>(ctx: BuildContext<'a, T>) -> bool {
+        match ctx.depend_on_inherited_widget::<EnabledScope<()>>() {
+            Some(state) => *state.as_ref(),
+            None => true,
+        }
+    }
+}
+
+impl<W: Widget> ViewWidget for Enabled<W> {
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        let enabled = self.enabled && Enabled::of(ctx);
+
+        AbsorbPointer {
+            absorbing: !enabled,
+            child: EnabledScope {
+                enabled,
+                child: &self.child,
+            },
+        }
+    }
+}
+
+/// Provides whether the subtree of an [`Enabled`] is enabled, taking its
+/// ancestors into account.
+#[doc(hidden)]
+#[derive(InheritedWidget)]
+pub struct EnabledScope<W: Widget> {
+    enabled: bool,
+    child: W,
+}
+
+impl<W: Widget> WidgetState for EnabledScope<W> {
+    type State = bool;
+
+    fn create_state(&self) -> Self::State {
+        self.enabled
+    }
+
+    fn did_update_widget<'a>(&'a self, ctx: BuildContext<'a, Self>) {
+        if *ctx.state() != self.enabled {
+            *ctx.state_mut() = self.enabled;
+            ctx.notify_dependents();
+        }
+    }
+}
+
+impl<W: Widget> InheritedWidget for EnabledScope<W> {
+    fn child<'w>(&'w self) -> &'w Self::Widget<'w> {
+        &self.child
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc};
+
+    use frui::app::testing::WidgetTester;
+
+    use super::*;
+    use crate::ListenableBuilder;
+
+    /// Depends on [`Enabled`], counting its builds and changes of its
+    /// dependencies.
+    #[derive(ViewWidget)]
+    struct Dependent {
+        generation: usize,
+        changes: Rc<Cell<usize>>,
+        builds: Rc<Cell<usize>>,
+    }
+
+    impl WidgetState for Dependent {
+        type State = ();
+
+        fn create_state(&self) -> Self::State {}
+
+        fn did_change_dependencies<'a>(&'a self, _: BuildContext<'a, Self>) {
+            self.changes.set(self.changes.get() + 1);
+        }
+    }
+
+    impl ViewWidget for Dependent {
+        fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Self::Widget<'w> {
+            self.builds.set(self.builds.get() + 1);
+            Enabled::of(ctx);
+        }
+    }
+
+    #[test]
+    fn dependencies_change_only_with_inherited_state() {
+        let config = ValueNotifier::new((true, 0));
+        let (changes, builds) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+
+        let tree = {
+            let (listenable, config) = (config.clone(), config.clone());
+            let (changes, builds) = (changes.clone(), builds.clone());
+
+            ListenableBuilder::new(listenable, move || {
+                let (enabled, generation) = config.get();
+
+                Enabled::new(
+                    enabled,
+                    Dependent {
+                        generation,
+                        changes: changes.clone(),
+                        builds: builds.clone(),
+                    },
+                )
+            })
+        };
+
+        // Dependencies are first registered during the first build.
+        let mut tester = WidgetTester::new(tree, Size::new(100., 100.));
+        assert_eq!((changes.get(), builds.get()), (1, 1));
+
+        // Rebuilt with a new configuration, under the same `Enabled`.
+        config.set((true, 1));
+        tester.pump();
+        assert_eq!((changes.get(), builds.get()), (1, 2));
+
+        config.set((false, 1));
+        tester.pump();
+        assert_eq!((changes.get(), builds.get()), (2, 3));
+    }
+}
@@ -0,0 +1,116 @@
+use frui::{
+    api::events::{Event, WidgetEvent},
+    app::listeners::clipboard::{ClipboardTarget, ClipboardTargetKey, CLIPBOARD_TARGETS},
+    prelude::*,
+};
+
+use crate::Enabled;
+
+pub use frui::app::listeners::clipboard::ClipboardShortcut;
+
+/// Calls `on_copy`, `on_cut` and `on_paste` when the platform copy, cut and
+/// paste shortcuts are pressed while this widget is focused (see
+/// [`ClipboardShortcut`]). Pasted text is read from the clipboard beforehand.
+///
+/// Widget is focused by pressing the pointer within its bounds and loses focus
+/// when the pointer is pressed anywhere else. Only one widget is focused at a
+/// time; of nested ones, it is the innermost one that was pressed.
+#[derive(SingleChildWidget)]
+pub struct ClipboardListener<W: Widget, C: Fn(), X: Fn(), P: Fn(&str)> {
+    pub on_copy: C,
+    pub on_cut: X,
+    pub on_paste: P,
+    pub child: W,
+}
+
+impl<W: Widget, C: Fn(), X: Fn(), P: Fn(&str)> ClipboardTarget for ClipboardListener<W, C, X, P> {
+    fn copy(&self) {
+        (self.on_copy)()
+    }
+
+    fn cut(&self) {
+        (self.on_cut)()
+    }
+
+    fn paste(&self, text: &str) {
+        (self.on_paste)(text)
+    }
+}
+
+impl<W: Widget, C: Fn(), X: Fn(), P: Fn(&str)> WidgetState for ClipboardListener<W, C, X, P> {
+    type State = Option<ClipboardTargetKey>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+
+    fn mount(&self, ctx: BuildContext<Self>) {
+        let mut key = ctx.state_mut();
+
+        // Updated widget is mounted again, but it is still registered.
+        if key.is_none() {
+            *key = Some(CLIPBOARD_TARGETS.with(|targets| unsafe {
+                targets.borrow_mut().register(self as &dyn ClipboardTarget)
+            }));
+        }
+    }
+
+    fn did_update_widget(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = *ctx.state() {
+            CLIPBOARD_TARGETS.with(|targets| unsafe {
+                targets
+                    .borrow_mut()
+                    .update(&key, self as &dyn ClipboardTarget)
+            });
+        }
+    }
+
+    /// Focus is dropped once an [`Enabled`] ancestor disables this widget.
+    fn did_change_dependencies(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = *ctx.state() {
+            if !Enabled::of(ctx) {
+                CLIPBOARD_TARGETS.with(|targets| targets.borrow_mut().unfocus(&key));
+            }
+        }
+    }
+
+    fn dispose(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = ctx.state_mut().take() {
+            CLIPBOARD_TARGETS.with(|targets| targets.borrow_mut().unregister(&key));
+        }
+    }
+}
+
+impl<W: Widget, C: Fn(), X: Fn(), P: Fn(&str)> SingleChildWidget for ClipboardListener<W, C, X, P> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
+    }
+}
+
+impl<W: Widget, C: Fn(), X: Fn(), P: Fn(&str)> WidgetEvent for ClipboardListener<W, C, X, P> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        if let Event::MouseDown(e) = event {
+            if let Some(key) = *ctx.wstate() {
+                CLIPBOARD_TARGETS.with(|targets| {
+                    let mut targets = targets.borrow_mut();
+
+                    if ctx.point_in_layout_bounds(e.pos) {
+                        targets.focus(&key);
+                    } else {
+                        targets.unfocus(&key);
+                    }
+                });
+            }
+        }
+
+        false
+    }
+}
@@ -0,0 +1,70 @@
+use std::cell::RefCell;
+
+use frui::{
+    api::events::{Event, WidgetEvent},
+    prelude::*,
+};
+
+/// Calls `on_tap` when its child is tapped.
+///
+/// Taps compete with other gestures in the gesture arena (see
+/// [`frui::api::gestures`]), so e.g. a tap inside of a [`Scroll`] which moves
+/// the pointer far enough to scroll it doesn't call `on_tap`.
+///
+/// [`Scroll`]: crate::Scroll
+#[derive(SingleChildWidget)]
+pub struct GestureDetector<W: Widget, F: Fn()> {
+    pub on_tap: F,
+    pub child: W,
+}
+
+impl<W: Widget, F: Fn()> GestureDetector<W, F> {
+    pub fn new(on_tap: F, child: W) -> Self {
+        Self { on_tap, child }
+    }
+}
+
+impl<W: Widget, F: Fn()> WidgetState for GestureDetector<W, F> {
+    type State = RefCell<TapGestureRecognizer>;
+
+    fn create_state(&self) -> Self::State {
+        RefCell::default()
+    }
+}
+
+impl<W: Widget, F: Fn()> SingleChildWidget for GestureDetector<W, F> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
+    }
+}
+
+impl<W: Widget, F: Fn()> WidgetEvent for GestureDetector<W, F> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        let is_tap = {
+            let state = ctx.wstate();
+            let mut tap = state.borrow_mut();
+
+            if let Event::MouseDown(e) = event {
+                if e.button == MouseButton::Left && ctx.point_in_layout_bounds(e.pos) {
+                    tap.add_pointer(e.pos);
+                }
+            }
+
+            tap.handle_event(event)
+        };
+
+        if is_tap {
+            (self.on_tap)();
+        }
+
+        false
+    }
+}
@@ -1,9 +1,18 @@
 use druid_shell::KeyEvent;
 use frui::{
-    app::listeners::keyboard::{CallbackKey, KEYBOARD_EVENT_LISTENERS},
+    app::listeners::keyboard::{
+        CallbackKey, KEYBOARD_EVENT_LISTENERS, RAW_KEYBOARD_EVENT_LISTENERS,
+    },
     prelude::*,
 };
 
+pub use frui::app::listeners::keyboard::KeyboardState;
+
+/// Calls `on_event` with every key down event received by the window.
+///
+/// Callback is registered once, when this widget is first mounted. When the
+/// widget is rebuilt with a new `on_event`, the registered callback is
+/// replaced with it, so the latest one is always called.
 #[derive(ViewWidget)]
 pub struct KeyboardEventDetector<W: Widget, F: Fn(KeyEvent)> {
     pub on_event: F,
@@ -18,16 +27,28 @@ impl<W: Widget, F: Fn(KeyEvent)> WidgetState for KeyboardEventDetector<W, F> {
     }
 
     fn mount(&self, ctx: BuildContext<Self>) {
-        *ctx.state_mut() = Some(
+        let mut key = ctx.state_mut();
+
+        // Updated widget is mounted again, but its callback is still registered.
+        if key.is_none() {
+            *key = Some(
+                KEYBOARD_EVENT_LISTENERS
+                    .with(|listeners| unsafe { listeners.borrow_mut().register(&self.on_event) }),
+            );
+        }
+    }
+
+    fn did_update_widget(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = *ctx.state() {
             KEYBOARD_EVENT_LISTENERS
-                .with(|listeners| unsafe { listeners.borrow_mut().register(&self.on_event) }),
-        );
+                .with(|listeners| unsafe { listeners.borrow_mut().update(&key, &self.on_event) });
+        }
     }
 
-    fn unmount(&self, ctx: BuildContext<Self>) {
-        let mut key = ctx.state_mut();
-        KEYBOARD_EVENT_LISTENERS.with(|listeners| listeners.borrow_mut().unregister(&key.unwrap()));
-        *key = None;
+    fn dispose(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = ctx.state_mut().take() {
+            KEYBOARD_EVENT_LISTENERS.with(|listeners| listeners.borrow_mut().unregister(&key));
+        }
     }
 }
 
@@ -36,3 +57,113 @@ impl<W: Widget, F: Fn(KeyEvent)> ViewWidget for KeyboardEventDetector<W, F> {
         &self.child
     }
 }
+
+/// Calls `on_event` with every key down and key up event received by the window.
+///
+/// Unlike [`KeyboardEventDetector`] it also receives key up events, which
+/// makes it suitable for game-like input, where it matters for how long a key
+/// is held. Physical position of the key is the `code` of the event, which
+/// doesn't depend on the keyboard layout. Keys currently held down can be also
+/// queried at any time through [`KeyboardState`].
+///
+/// If the window loses focus while keys are held, `on_event` receives a key up
+/// event for each of them, since their actual release won't be delivered.
+#[derive(ViewWidget)]
+pub struct RawKeyboardListener<W: Widget, F: Fn(KeyEvent)> {
+    pub on_event: F,
+    pub child: W,
+}
+
+impl<W: Widget, F: Fn(KeyEvent)> RawKeyboardListener<W, F> {
+    pub fn new(on_event: F, child: W) -> Self {
+        Self { on_event, child }
+    }
+}
+
+impl<W: Widget, F: Fn(KeyEvent)> WidgetState for RawKeyboardListener<W, F> {
+    type State = Option<CallbackKey>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+
+    fn mount(&self, ctx: BuildContext<Self>) {
+        let mut key = ctx.state_mut();
+
+        // Updated widget is mounted again, but its callback is still registered.
+        if key.is_none() {
+            *key = Some(
+                RAW_KEYBOARD_EVENT_LISTENERS
+                    .with(|listeners| unsafe { listeners.borrow_mut().register(&self.on_event) }),
+            );
+        }
+    }
+
+    fn did_update_widget(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = *ctx.state() {
+            RAW_KEYBOARD_EVENT_LISTENERS
+                .with(|listeners| unsafe { listeners.borrow_mut().update(&key, &self.on_event) });
+        }
+    }
+
+    fn dispose(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = ctx.state_mut().take() {
+            RAW_KEYBOARD_EVENT_LISTENERS.with(|listeners| listeners.borrow_mut().unregister(&key));
+        }
+    }
+}
+
+impl<W: Widget, F: Fn(KeyEvent)> ViewWidget for RawKeyboardListener<W, F> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc};
+
+    use frui::app::{listeners::debug_assert_no_leaks, testing::WidgetTester};
+
+    use super::*;
+    use crate::ListenableBuilder;
+
+    #[test]
+    fn updated_detector_keeps_its_callback_registered() {
+        let generation = ValueNotifier::new(1);
+        let received = Rc::new(Cell::new(0));
+
+        let (listenable, current) = (generation.clone(), generation.clone());
+        let on_event = received.clone();
+
+        let mut tester = WidgetTester::new(
+            ListenableBuilder::new(listenable, move || {
+                let (generation, received) = (current.get(), on_event.clone());
+
+                KeyboardEventDetector {
+                    on_event: move |_| received.set(generation),
+                    child: (),
+                }
+            }),
+            Size::new(100., 100.),
+        );
+
+        let registered = || KEYBOARD_EVENT_LISTENERS.with(|l| l.borrow().len());
+        assert_eq!(registered(), 1);
+
+        tester.send_keyboard_event(KeyEvent::default());
+        assert_eq!(received.get(), 1);
+
+        // Callback of the rebuilt detector replaced the old one.
+        generation.set(2);
+        tester.pump();
+        assert_eq!(registered(), 1);
+
+        tester.send_keyboard_event(KeyEvent::default());
+        assert_eq!(received.get(), 2);
+
+        drop(tester);
+        assert_eq!(registered(), 0);
+        debug_assert_no_leaks();
+    }
+}
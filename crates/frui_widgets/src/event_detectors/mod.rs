@@ -1 +1,5 @@
+pub mod clipboard;
+pub mod gesture;
 pub mod keyboard;
+pub mod shortcuts;
+pub mod size;
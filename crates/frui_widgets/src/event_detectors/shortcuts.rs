@@ -0,0 +1,252 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use frui::{
+    api::events::{Event, WidgetEvent},
+    app::listeners::shortcuts::{ShortcutScope, ShortcutScopeKey, SHORTCUT_SCOPES},
+    prelude::*,
+};
+
+use crate::Enabled;
+
+pub use frui::app::listeners::shortcuts::{Intent, KeyCombination};
+
+type Action<'a> = Box<dyn Fn(&dyn Intent) + 'a>;
+
+/// Maps key combinations pressed while this widget is focused to intents,
+/// which are then carried out by the nearest [`Actions`] handling them.
+///
+/// Widget is focused by pressing the pointer within its bounds and loses focus
+/// when the pointer is pressed anywhere else. All nested `Shortcuts` and
+/// `Actions` containing the pressed point are focused, and the innermost
+/// `Shortcuts` binding a combination wins over the outer ones, unless no
+/// focused `Actions` handles its intent.
+///
+/// ```ignore
+/// struct Save;
+///
+/// impl Intent for Save {}
+///
+/// Shortcuts::new(
+///     Actions::new(editor).action(|_: &Save| save()),
+/// )
+/// .shortcut(KeyCombination::new(KbKey::Character("s".into()), Modifiers::CONTROL), Save)
+/// ```
+#[derive(SingleChildWidget)]
+pub struct Shortcuts<W: Widget> {
+    shortcuts: HashMap<KeyCombination, Rc<dyn Intent>>,
+    child: W,
+}
+
+impl<W: Widget> Shortcuts<W> {
+    pub fn new(child: W) -> Self {
+        Self {
+            shortcuts: HashMap::new(),
+            child,
+        }
+    }
+
+    /// Binds `keys` to `intent`, replacing the intent previously bound to them.
+    pub fn shortcut(mut self, keys: KeyCombination, intent: impl Intent) -> Self {
+        // Combinations constructed directly are normalized here.
+        let keys = KeyCombination::new(keys.key, keys.modifiers);
+        self.shortcuts.insert(keys, Rc::new(intent));
+        self
+    }
+}
+
+impl<W: Widget> ShortcutScope for Shortcuts<W> {
+    fn intent(&self, keys: &KeyCombination) -> Option<Rc<dyn Intent>> {
+        self.shortcuts.get(keys).cloned()
+    }
+}
+
+/// Carries out intents produced by focused [`Shortcuts`] with the handlers
+/// registered for their types.
+///
+/// Focus of `Actions` works the same way as for [`Shortcuts`]. Of focused
+/// `Actions` handling the same intent type, the innermost one wins.
+#[derive(SingleChildWidget)]
+pub struct Actions<'a, W: Widget> {
+    actions: HashMap<TypeId, Action<'a>>,
+    child: W,
+}
+
+impl<'a, W: Widget> Actions<'a, W> {
+    pub fn new(child: W) -> Self {
+        Self {
+            actions: HashMap::new(),
+            child,
+        }
+    }
+
+    /// Handles intents of type `I` with `action`, replacing the previous
+    /// handler of that type. Like other callbacks, `action` may capture the
+    /// `BuildContext` of the widget building this.
+    pub fn action<I: Intent>(mut self, action: impl Fn(&I) + 'a) -> Self {
+        self.actions.insert(
+            TypeId::of::<I>(),
+            Box::new(move |intent: &dyn Intent| {
+                if let Some(intent) = (intent as &dyn Any).downcast_ref::<I>() {
+                    action(intent);
+                }
+            }),
+        );
+        self
+    }
+}
+
+impl<'a, W: Widget> ShortcutScope for Actions<'a, W> {
+    fn invoke(&self, intent: &dyn Intent) -> bool {
+        match self.actions.get(&(intent as &dyn Any).type_id()) {
+            Some(action) => {
+                action(intent);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<W: Widget> WidgetState for Shortcuts<W> {
+    type State = Option<ShortcutScopeKey>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+
+    fn mount(&self, ctx: BuildContext<Self>) {
+        let mut key = ctx.state_mut();
+
+        // Updated widget is mounted again, but it is still registered.
+        if key.is_none() {
+            *key = Some(SHORTCUT_SCOPES.with(|scopes| unsafe {
+                scopes.borrow_mut().register(self as &dyn ShortcutScope)
+            }));
+        }
+    }
+
+    fn did_update_widget(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = *ctx.state() {
+            SHORTCUT_SCOPES.with(|scopes| unsafe {
+                scopes.borrow_mut().update(&key, self as &dyn ShortcutScope)
+            });
+        }
+    }
+
+    fn did_change_dependencies(&self, ctx: BuildContext<Self>) {
+        unfocus_if_disabled(ctx, *ctx.state());
+    }
+
+    fn dispose(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = ctx.state_mut().take() {
+            SHORTCUT_SCOPES.with(|scopes| scopes.borrow_mut().unregister(&key));
+        }
+    }
+}
+
+impl<W: Widget> SingleChildWidget for Shortcuts<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
+    }
+}
+
+impl<W: Widget> WidgetEvent for Shortcuts<W> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        if let Event::MouseDown(e) = event {
+            if let Some(key) = *ctx.wstate() {
+                if ctx.point_in_layout_bounds(e.pos) {
+                    SHORTCUT_SCOPES.with(|scopes| scopes.borrow_mut().press(&key));
+                }
+            }
+        }
+
+        false
+    }
+}
+
+impl<'a, W: Widget> WidgetState for Actions<'a, W> {
+    type State = Option<ShortcutScopeKey>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+
+    fn mount(&self, ctx: BuildContext<Self>) {
+        let mut key = ctx.state_mut();
+
+        // Updated widget is mounted again, but it is still registered.
+        if key.is_none() {
+            *key = Some(SHORTCUT_SCOPES.with(|scopes| unsafe {
+                scopes.borrow_mut().register(self as &dyn ShortcutScope)
+            }));
+        }
+    }
+
+    fn did_update_widget(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = *ctx.state() {
+            SHORTCUT_SCOPES.with(|scopes| unsafe {
+                scopes.borrow_mut().update(&key, self as &dyn ShortcutScope)
+            });
+        }
+    }
+
+    fn did_change_dependencies(&self, ctx: BuildContext<Self>) {
+        unfocus_if_disabled(ctx, *ctx.state());
+    }
+
+    fn dispose(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = ctx.state_mut().take() {
+            SHORTCUT_SCOPES.with(|scopes| scopes.borrow_mut().unregister(&key));
+        }
+    }
+}
+
+impl<'a, W: Widget> SingleChildWidget for Actions<'a, W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
+    }
+}
+
+impl<'a, W: Widget> WidgetEvent for Actions<'a, W> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        if let Event::MouseDown(e) = event {
+            if let Some(key) = *ctx.wstate() {
+                if ctx.point_in_layout_bounds(e.pos) {
+                    SHORTCUT_SCOPES.with(|scopes| scopes.borrow_mut().press(&key));
+                }
+            }
+        }
+
+        false
+    }
+}
+
+/// Unfocuses the scope registered with `key` once the widget of `ctx` is
+/// disabled by an [`Enabled`] ancestor.
+fn unfocus_if_disabled<T>(ctx: BuildContext<T>, key: Option<ShortcutScopeKey>) {
+    if let Some(key) = key {
+        if !Enabled::of(ctx) {
+            SHORTCUT_SCOPES.with(|scopes| scopes.borrow_mut().unfocus(&key));
+        }
+    }
+}
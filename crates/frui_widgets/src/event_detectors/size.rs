@@ -0,0 +1,88 @@
+use std::cell::Cell;
+
+use frui::prelude::*;
+
+/// Differences of sizes smaller than this are considered layout jitter, not a
+/// change of size.
+const EPSILON: f64 = 1e-3;
+
+/// Calls `on_size_changed` with the size of `child` after the first layout and
+/// every time that size changes.
+///
+/// The callback runs after the frame in which the size changed was painted
+/// (see [`add_post_frame_callback`]), so it can safely change the state of
+/// widgets. Such change is applied in the following frame.
+///
+/// Since `on_size_changed` outlives the frame it was scheduled in, it has to
+/// be `Clone + 'static`, e.g. a closure capturing an `Rc`.
+#[derive(SingleChildWidget)]
+pub struct SizeChangedDetector<W: Widget, F: Fn(Size) + Clone + 'static> {
+    pub on_size_changed: F,
+    pub child: W,
+}
+
+impl<W: Widget, F: Fn(Size) + Clone + 'static> SizeChangedDetector<W, F> {
+    pub fn new(on_size_changed: F, child: W) -> Self {
+        Self {
+            on_size_changed,
+            child,
+        }
+    }
+}
+
+impl<W: Widget, F: Fn(Size) + Clone + 'static> RenderState for SizeChangedDetector<W, F> {
+    /// Size last reported to `on_size_changed`.
+    type State = Cell<Option<Size>>;
+
+    fn create_state(&self) -> Self::State {
+        Cell::new(None)
+    }
+}
+
+impl<W: Widget, F: Fn(Size) + Clone + 'static> SingleChildWidget for SizeChangedDetector<W, F> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let size = ctx.child().layout(constraints);
+
+        if size_changed(ctx.rstate().get(), size) {
+            ctx.rstate().set(Some(size));
+
+            let on_size_changed = self.on_size_changed.clone();
+            add_post_frame_callback(move || on_size_changed(size));
+        }
+
+        size
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
+    }
+}
+
+/// Whether `size` differs from the `previous` one by more than [`EPSILON`].
+fn size_changed(previous: Option<Size>, size: Size) -> bool {
+    match previous {
+        Some(previous) => {
+            (previous.width - size.width).abs() > EPSILON
+                || (previous.height - size.height).abs() > EPSILON
+        }
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn jitter_is_not_a_change_of_size() {
+        let size = Size::new(100., 50.);
+
+        assert!(size_changed(None, size));
+        assert!(!size_changed(Some(size), Size::new(100.0001, 49.9999)));
+        assert!(size_changed(Some(size), Size::new(100., 51.)));
+    }
+}
@@ -2,6 +2,8 @@ use frui::prelude::{Offset, Size};
 use std::fmt::{Display, Formatter};
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
+use crate::TextDirection;
+
 pub trait AlignmentGeometry {
     fn x(&self) -> f64;
 
@@ -10,6 +12,9 @@ pub trait AlignmentGeometry {
     fn y(&self) -> f64;
 
     fn along(&self, other: Size) -> Offset;
+
+    /// Alignment with its `start` resolved to left or right in `direction`.
+    fn resolve(&self, direction: TextDirection) -> Alignment;
 }
 
 #[derive(PartialEq, Copy, Clone, Debug, Default)]
@@ -39,6 +44,10 @@ impl AlignmentGeometry for Alignment {
             y: center_y + self.y * center_y,
         }
     }
+
+    fn resolve(&self, _: TextDirection) -> Alignment {
+        *self
+    }
 }
 
 impl Alignment {
@@ -128,13 +137,19 @@ impl AlignmentGeometry for AlignmentDirectional {
         self.y
     }
 
+    /// Offset of this alignment within `other`, as if it was laid out in
+    /// [`TextDirection::Ltr`].
     fn along(&self, other: Size) -> Offset {
-        let center_x = other.width / 2.0;
-        let center_y = other.height / 2.0;
-        Offset {
-            x: center_x + self.x() * center_x,
-            y: center_y + self.y * center_y,
-        }
+        self.resolve(TextDirection::Ltr).along(other)
+    }
+
+    fn resolve(&self, direction: TextDirection) -> Alignment {
+        let x = match direction {
+            TextDirection::Ltr => self.start,
+            TextDirection::Rtl => -self.start,
+        };
+
+        Alignment { x, y: self.y }
     }
 }
 
@@ -276,3 +291,23 @@ impl Display for AlignmentDirectional {
         write!(f, "AlignmentDirectional({}, {})", &self.start, &self.y)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn directional_alignment_flips_under_rtl() {
+        let space = Size::new(100., 50.);
+
+        let ltr = AlignmentDirectional::TOP_START.resolve(TextDirection::Ltr);
+        let rtl = AlignmentDirectional::TOP_START.resolve(TextDirection::Rtl);
+        assert_eq!((ltr, rtl), (Alignment::TOP_LEFT, Alignment::TOP_RIGHT));
+
+        let Offset { x, y } = AlignmentDirectional::CENTER_END.along(space);
+        assert_eq!((x, y), (100., 25.));
+
+        let center = Alignment::CENTER.resolve(TextDirection::Rtl);
+        assert_eq!(center, Alignment::CENTER);
+    }
+}
@@ -0,0 +1,175 @@
+use frui::prelude::*;
+
+/// Direction along which linear layouts (like [`Row`] and [`Column`]) place
+/// their children, i.e. their main axis.
+///
+/// It converts sizes, offsets and constraints between their width and height
+/// and their extents along the main and cross axis, so that a layout can be
+/// written once for both directions.
+///
+/// [`Row`]: crate::Row
+/// [`Column`]: crate::Column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+impl Axis {
+    /// The other axis, i.e. the cross axis of this one.
+    pub fn flip(self) -> Self {
+        match self {
+            Axis::Horizontal => Axis::Vertical,
+            Axis::Vertical => Axis::Horizontal,
+        }
+    }
+
+    /// Extent of `size` along this axis.
+    pub fn main_extent(self, size: Size) -> f64 {
+        match self {
+            Axis::Horizontal => size.width,
+            Axis::Vertical => size.height,
+        }
+    }
+
+    /// Extent of `size` along the cross axis.
+    pub fn cross_extent(self, size: Size) -> f64 {
+        self.flip().main_extent(size)
+    }
+
+    /// Size with the given extents along this and the cross axis.
+    pub fn size_from(self, main: f64, cross: f64) -> Size {
+        match self {
+            Axis::Horizontal => Size::new(main, cross),
+            Axis::Vertical => Size::new(cross, main),
+        }
+    }
+
+    /// Component of `offset` along this axis.
+    pub fn main_offset(self, offset: Offset) -> f64 {
+        match self {
+            Axis::Horizontal => offset.x,
+            Axis::Vertical => offset.y,
+        }
+    }
+
+    /// Component of `offset` along the cross axis.
+    pub fn cross_offset(self, offset: Offset) -> f64 {
+        self.flip().main_offset(offset)
+    }
+
+    /// Offset with the given components along this and the cross axis.
+    pub fn offset_from(self, main: f64, cross: f64) -> Offset {
+        match self {
+            Axis::Horizontal => Offset { x: main, y: cross },
+            Axis::Vertical => Offset { x: cross, y: main },
+        }
+    }
+
+    /// Projects `constraints` onto this axis.
+    pub fn project(self, constraints: Constraints) -> AxisConstraints {
+        match self {
+            Axis::Horizontal => AxisConstraints {
+                min_main: constraints.min_width,
+                max_main: constraints.max_width,
+                min_cross: constraints.min_height,
+                max_cross: constraints.max_height,
+            },
+            Axis::Vertical => AxisConstraints {
+                min_main: constraints.min_height,
+                max_main: constraints.max_height,
+                min_cross: constraints.min_width,
+                max_cross: constraints.max_width,
+            },
+        }
+    }
+
+    /// Constraints of the given projection onto this axis, the inverse of
+    /// [`project`](Axis::project).
+    pub fn constraints_from(self, constraints: AxisConstraints) -> Constraints {
+        let AxisConstraints {
+            min_main,
+            max_main,
+            min_cross,
+            max_cross,
+        } = constraints;
+
+        match self {
+            Axis::Horizontal => Constraints {
+                min_width: min_main,
+                max_width: max_main,
+                min_height: min_cross,
+                max_height: max_cross,
+            },
+            Axis::Vertical => Constraints {
+                min_width: min_cross,
+                max_width: max_cross,
+                min_height: min_main,
+                max_height: max_main,
+            },
+        }
+    }
+}
+
+/// [`Constraints`] projected onto an [`Axis`], see [`Axis::project`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisConstraints {
+    pub min_main: f64,
+    pub max_main: f64,
+    pub min_cross: f64,
+    pub max_cross: f64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const AXES: [Axis; 2] = [Axis::Horizontal, Axis::Vertical];
+
+    #[test]
+    fn extents_round_trip_for_both_axes() {
+        let size = Size::new(30., 20.);
+        let offset = Offset { x: 3., y: 2. };
+
+        for axis in AXES {
+            assert_eq!(axis.flip().flip(), axis);
+
+            let (main, cross) = (axis.main_extent(size), axis.cross_extent(size));
+            assert_eq!(axis.size_from(main, cross), size);
+
+            // Flipped axis swaps extents.
+            assert_eq!(axis.flip().size_from(cross, main), size);
+
+            let (main, cross) = (axis.main_offset(offset), axis.cross_offset(offset));
+            let Offset { x, y } = axis.offset_from(main, cross);
+            assert_eq!((x, y), (offset.x, offset.y));
+        }
+
+        assert_eq!(Axis::Horizontal.main_extent(size), 30.);
+        assert_eq!(Axis::Vertical.main_extent(size), 20.);
+    }
+
+    #[test]
+    fn constraints_round_trip_for_both_axes() {
+        let constraints = Constraints {
+            min_width: 1.,
+            max_width: 2.,
+            min_height: 3.,
+            max_height: f64::INFINITY,
+        };
+
+        for axis in AXES {
+            assert_eq!(
+                axis.constraints_from(axis.project(constraints)),
+                constraints
+            );
+        }
+
+        let projected = Axis::Vertical.project(constraints);
+        assert_eq!(
+            (projected.min_main, projected.max_main),
+            (3., f64::INFINITY)
+        );
+        assert_eq!((projected.min_cross, projected.max_cross), (1., 2.));
+    }
+}
@@ -27,18 +27,13 @@ impl<W: Widget> SingleChildWidget for Center<W> {
             size.width = child_size.width;
         }
 
-        size
-    }
+        let size = constraints.constrain(size);
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, offset: &Offset) {
-        let self_size = ctx.size();
-        let child_size = ctx.child().size();
+        ctx.child().set_offset(Offset {
+            x: (size.width - child_size.width) / 2.,
+            y: (size.height - child_size.height) / 2.,
+        });
 
-        let child_offset = Offset {
-            x: offset.x + (self_size.width - child_size.width) / 2.,
-            y: offset.y + (self_size.height - child_size.height) / 2.,
-        };
-
-        ctx.child().paint(canvas, &child_offset);
+        size
     }
 }
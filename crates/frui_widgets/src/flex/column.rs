@@ -4,7 +4,7 @@ use crate::{
     widget_list::WidgetList, CrossAxisAlignment, CrossAxisSize, MainAxisAlignment, MainAxisSize,
 };
 
-use super::{compute_cross_axis_offset, compute_main_axis_offset, get_flex};
+use super::{compute_cross_axis_offset, compute_main_axis_offset, get_fit, get_flex};
 
 #[derive(MultiChildWidget)]
 pub struct Column<T: WidgetList> {
@@ -27,6 +27,12 @@ impl Column<()> {
             cross_axis_alignment: Default::default(),
         }
     }
+
+    /// Shorthand for `Column::builder().children(children)`. See [`WidgetList`]
+    /// for all the types that you can use as `children`.
+    pub fn new(children: impl WidgetList) -> Column<impl WidgetList> {
+        Column::builder().children(children)
+    }
 }
 
 impl<WidgetList_: WidgetList> Column<WidgetList_> {
@@ -46,6 +52,15 @@ impl<WidgetList_: WidgetList> Column<WidgetList_> {
         }
     }
 
+    /// Children collected from `children`, e.g. an iterator mapping data to
+    /// widgets. Use [`BoxedWidget::boxed`] for children of different types.
+    pub fn children_iter<W: Widget>(
+        self,
+        children: impl IntoIterator<Item = W>,
+    ) -> Column<impl WidgetList> {
+        self.children(children.into_iter().collect::<Vec<_>>())
+    }
+
     /// Whether [`Column`] should take full available height or only the minimum to fit children.
     ///
     /// # Note
@@ -101,22 +116,6 @@ impl<WidgetList_: WidgetList> Column<WidgetList_> {
     }
 }
 
-pub struct ColumnRenderState {
-    initial_offset_y: f64,
-    space_between_y: f64,
-}
-
-impl<T: WidgetList> RenderState for Column<T> {
-    type State = ColumnRenderState;
-
-    fn create_state(&self) -> Self::State {
-        ColumnRenderState {
-            initial_offset_y: 0.,
-            space_between_y: 0.,
-        }
-    }
-}
-
 impl<T: WidgetList> MultiChildWidget for Column<T> {
     fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
         self.children.get()
@@ -167,9 +166,6 @@ impl<T: WidgetList> MultiChildWidget for Column<T> {
                 size.height,
             );
 
-        ctx.rstate_mut().space_between_y = space_between_y;
-        ctx.rstate_mut().initial_offset_y = initial_offset_y;
-
         //
         // Layout flexible items.
 
@@ -183,8 +179,12 @@ impl<T: WidgetList> MultiChildWidget for Column<T> {
                 let child_flex = get_flex(&child);
 
                 if child_flex > 0 {
+                    let allocated = space_per_flex * child_flex as f64;
+                    let (min_height, max_height) = get_fit(&child).main_axis_extent(allocated);
+
                     let constraints = Constraints {
-                        max_height: space_per_flex * child_flex as f64,
+                        min_height,
+                        max_height,
                         ..constraints
                     };
 
@@ -205,30 +205,34 @@ impl<T: WidgetList> MultiChildWidget for Column<T> {
             size.width = size.width.max(constraints.max_width)
         }
 
-        size
-    }
+        let size = constraints.constrain(size);
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, offset: &Offset) {
-        let self_width = ctx.size().width;
-        let space_between_y = ctx.rstate().space_between_y;
+        //
+        // Position children.
 
-        let mut offset_y = offset.y + ctx.rstate().initial_offset_y;
+        let mut offset_y = initial_offset_y;
 
         for mut child in ctx.children() {
             let offset_x = compute_cross_axis_offset(
                 self.cross_axis_alignment,
-                offset.x,
-                self_width,
+                0.,
+                size.width,
                 child.size().width,
             );
 
-            let offset = Offset {
+            child.set_offset(Offset {
                 x: offset_x,
                 y: offset_y,
-            };
-
-            child.paint(canvas, &offset);
+            });
             offset_y += child.size().height + space_between_y;
         }
+
+        size
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        for mut child in ctx.children() {
+            child.paint(canvas);
+        }
     }
 }
@@ -0,0 +1,291 @@
+use frui::prelude::*;
+
+use crate::{widget_list::WidgetList, Axis, AxisConstraints, CrossAxisAlignment};
+
+use super::compute_cross_axis_offset;
+
+/// Lays out its children one after another from left to right, with `spacing`
+/// between them.
+///
+/// Unlike [`Row`](crate::Row), it has no flexible children. It takes the sum
+/// of widths of its children (and the spacing) as its width, and the largest
+/// height of its children as its height, which makes it cheaper to lay out.
+#[derive(MultiChildWidget)]
+pub struct HStack<T: WidgetList> {
+    pub children: T,
+    pub spacing: f64,
+    pub alignment: CrossAxisAlignment,
+}
+
+impl HStack<()> {
+    pub fn builder() -> Self {
+        HStack {
+            children: (),
+            spacing: 0.,
+            alignment: CrossAxisAlignment::Start,
+        }
+    }
+
+    /// Shorthand for `HStack::builder().children(children)`. See [`WidgetList`]
+    /// for all the types that you can use as `children`.
+    pub fn new(children: impl WidgetList) -> HStack<impl WidgetList> {
+        HStack::builder().children(children)
+    }
+}
+
+impl<T: WidgetList> HStack<T> {
+    /// See [`WidgetList`] for all the types that you can use as `children`.
+    pub fn children(self, children: impl WidgetList) -> HStack<impl WidgetList> {
+        HStack {
+            children,
+            spacing: self.spacing,
+            alignment: self.alignment,
+        }
+    }
+
+    /// Children collected from `children`, e.g. an iterator mapping data to
+    /// widgets. Use [`BoxedWidget::boxed`] for children of different types.
+    pub fn children_iter<W: Widget>(
+        self,
+        children: impl IntoIterator<Item = W>,
+    ) -> HStack<impl WidgetList> {
+        self.children(children.into_iter().collect::<Vec<_>>())
+    }
+
+    #[track_caller]
+    pub fn spacing(mut self, spacing: f64) -> Self {
+        assert!(spacing >= 0., "spacing must be >= 0.0");
+        self.spacing = spacing;
+        self
+    }
+
+    /// How children are aligned vertically.
+    pub fn alignment(mut self, alignment: CrossAxisAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+}
+
+impl<T: WidgetList> MultiChildWidget for HStack<T> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        self.children.get()
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let mut children = ctx.children().collect::<Vec<_>>();
+
+        layout_linear(
+            Axis::Horizontal,
+            self.spacing,
+            self.alignment,
+            constraints,
+            &mut children,
+        )
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        for mut child in ctx.children() {
+            child.paint(canvas);
+        }
+    }
+}
+
+/// Lays out its children one after another from top to bottom, with `spacing`
+/// between them.
+///
+/// Unlike [`Column`](crate::Column), it has no flexible children. It takes the
+/// sum of heights of its children (and the spacing) as its height, and the
+/// largest width of its children as its width, which makes it cheaper to lay
+/// out.
+#[derive(MultiChildWidget)]
+pub struct VStack<T: WidgetList> {
+    pub children: T,
+    pub spacing: f64,
+    pub alignment: CrossAxisAlignment,
+}
+
+impl VStack<()> {
+    pub fn builder() -> Self {
+        VStack {
+            children: (),
+            spacing: 0.,
+            alignment: CrossAxisAlignment::Start,
+        }
+    }
+
+    /// Shorthand for `VStack::builder().children(children)`. See [`WidgetList`]
+    /// for all the types that you can use as `children`.
+    pub fn new(children: impl WidgetList) -> VStack<impl WidgetList> {
+        VStack::builder().children(children)
+    }
+}
+
+impl<T: WidgetList> VStack<T> {
+    /// See [`WidgetList`] for all the types that you can use as `children`.
+    pub fn children(self, children: impl WidgetList) -> VStack<impl WidgetList> {
+        VStack {
+            children,
+            spacing: self.spacing,
+            alignment: self.alignment,
+        }
+    }
+
+    /// Children collected from `children`, e.g. an iterator mapping data to
+    /// widgets. Use [`BoxedWidget::boxed`] for children of different types.
+    pub fn children_iter<W: Widget>(
+        self,
+        children: impl IntoIterator<Item = W>,
+    ) -> VStack<impl WidgetList> {
+        self.children(children.into_iter().collect::<Vec<_>>())
+    }
+
+    #[track_caller]
+    pub fn spacing(mut self, spacing: f64) -> Self {
+        assert!(spacing >= 0., "spacing must be >= 0.0");
+        self.spacing = spacing;
+        self
+    }
+
+    /// How children are aligned horizontally.
+    pub fn alignment(mut self, alignment: CrossAxisAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+}
+
+impl<T: WidgetList> MultiChildWidget for VStack<T> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        self.children.get()
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let mut children = ctx.children().collect::<Vec<_>>();
+
+        layout_linear(
+            Axis::Vertical,
+            self.spacing,
+            self.alignment,
+            constraints,
+            &mut children,
+        )
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        for mut child in ctx.children() {
+            child.paint(canvas);
+        }
+    }
+}
+
+/// Lays out `children` one after another along `axis`, returning the size of
+/// a widget containing them.
+fn layout_linear(
+    axis: Axis,
+    spacing: f64,
+    alignment: CrossAxisAlignment,
+    constraints: Constraints,
+    children: &mut [ChildContext],
+) -> Size {
+    let projected = axis.project(constraints);
+    let stretch =
+        matches!(alignment, CrossAxisAlignment::Stretch) && projected.max_cross.is_finite();
+
+    let child_constraints = axis.constraints_from(AxisConstraints {
+        min_main: 0.,
+        max_main: f64::INFINITY,
+        min_cross: if stretch { projected.max_cross } else { 0. },
+        max_cross: projected.max_cross,
+    });
+
+    let sizes = children
+        .iter_mut()
+        .map(|child| child.layout(child_constraints))
+        .collect::<Vec<_>>();
+
+    let (size, offsets) = place(axis, spacing, alignment, constraints, &sizes);
+
+    for (child, offset) in children.iter_mut().zip(offsets) {
+        child.set_offset(offset);
+    }
+
+    size
+}
+
+/// Size of a widget containing children of `sizes` placed one after another
+/// along `axis`, and offsets of those children.
+fn place(
+    axis: Axis,
+    spacing: f64,
+    alignment: CrossAxisAlignment,
+    constraints: Constraints,
+    sizes: &[Size],
+) -> (Size, Vec<Offset>) {
+    let gaps = sizes.len().saturating_sub(1) as f64;
+    let main = sizes.iter().map(|s| axis.main_extent(*s)).sum::<f64>() + spacing * gaps;
+
+    // Unbounded cross axis falls back to the largest child.
+    let cross = sizes
+        .iter()
+        .map(|s| axis.cross_extent(*s))
+        .fold(0., f64::max);
+
+    let size = constraints.constrain(axis.size_from(main, cross));
+    let cross = axis.cross_extent(size);
+
+    let mut main_offset = 0.;
+    let offsets = sizes
+        .iter()
+        .map(|child| {
+            let cross_offset =
+                compute_cross_axis_offset(alignment, 0., cross, axis.cross_extent(*child));
+            let offset = axis.offset_from(main_offset, cross_offset);
+
+            main_offset += axis.main_extent(*child) + spacing;
+            offset
+        })
+        .collect();
+
+    (size, offsets)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn spacing_adds_to_main_axis_between_children() {
+        let sizes = [Size::new(10., 5.), Size::new(20., 15.), Size::new(30., 10.)];
+        let unbounded = Constraints::default();
+
+        let (size, _) = place(
+            Axis::Horizontal,
+            0.,
+            CrossAxisAlignment::Start,
+            unbounded,
+            &sizes,
+        );
+        assert_eq!(size, Size::new(60., 15.));
+
+        // Two gaps between three children.
+        let (size, offsets) = place(
+            Axis::Horizontal,
+            4.,
+            CrossAxisAlignment::Center,
+            unbounded,
+            &sizes,
+        );
+        assert_eq!(size, Size::new(68., 15.));
+
+        let offsets = offsets.iter().map(|o| (o.x, o.y)).collect::<Vec<_>>();
+        assert_eq!(offsets, [(0., 5.), (14., 0.), (38., 2.5)]);
+
+        // Vertical stack sizes to the widest child across.
+        let loose = Constraints::loose(Size::new(100., 100.));
+        let (size, offsets) = place(Axis::Vertical, 4., CrossAxisAlignment::End, loose, &sizes);
+        assert_eq!(size, Size::new(30., 38.));
+        assert_eq!((offsets[1].x, offsets[1].y), (10., 9.));
+
+        let (size, _) = place(Axis::Vertical, 4., CrossAxisAlignment::Start, loose, &[]);
+        assert_eq!(size, Size::new(0., 0.));
+    }
+}
@@ -1,21 +1,27 @@
 use frui::prelude::*;
 
 pub use alignment::*;
+pub use axis::*;
 pub use center::*;
 pub use column::*;
+pub use linear_stack::*;
 pub use row::*;
 pub use stack::*;
 
 pub mod alignment;
+pub mod axis;
 pub mod center;
 pub mod column;
+pub mod linear_stack;
 pub mod row;
 pub mod stack;
 
+/// Layout data common to children of box layouts.
+///
+/// Offsets of children aren't part of it, they are stored with
+/// `ChildContext::set_offset` instead.
 #[derive(Debug, Clone, Copy, Default)]
-pub struct BoxLayoutData {
-    offset: Offset,
-}
+pub struct BoxLayoutData {}
 
 pub trait LayoutData<T = BoxLayoutData> {
     fn layout_data(&self) -> &T;
@@ -172,7 +178,9 @@ fn compute_main_axis_offset(
         };
     }
 
+    // Without children there is no space around them.
     let space_between_count = match main_axis_alignment {
+        _ if children_count == 0. => 0.,
         MainAxisAlignment::SpaceEvenly => children_count + 1.,
         _ => children_count - 1.,
     }
@@ -211,19 +219,46 @@ fn get_flex(child: &ChildContext) -> usize {
     }
 }
 
+fn get_fit(child: &ChildContext) -> FlexFit {
+    match child.try_data::<FlexData>() {
+        Some(data) => data.fit,
+        None => FlexFit::default(),
+    }
+}
+
 /// Used by flexible widgets to determine the flex factor of a child.
 pub struct FlexData {
     flex_factor: usize,
+    fit: FlexFit,
 }
 
-//
-// Todo:
-
+/// How a flexible child fills the space allocated to it on the main axis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum FlexFit {
+    /// Child can be at most as large as the allocated space, keeping its
+    /// natural size if it's smaller.
+    #[default]
     Loose,
+    /// Child is forced to fill the allocated space.
     Tight,
 }
 
+impl FlexFit {
+    /// Minimum and maximum extent on the main axis of a child given
+    /// `allocated` space.
+    fn main_axis_extent(&self, allocated: f64) -> (f64, f64) {
+        match self {
+            FlexFit::Loose => (0., allocated),
+            FlexFit::Tight => (allocated, allocated),
+        }
+    }
+}
+
+/// Makes its child flexible inside of a [`Row`] or a [`Column`].
+///
+/// Remaining space on the main axis (after laying out inflexible children) is
+/// divided between flexible children proportionally to their `flex` factor.
+/// How a child fills its share is specified by its [`FlexFit`].
 #[derive(SingleChildWidget)]
 pub struct Flexible<W: Widget> {
     pub fit: FlexFit,
@@ -231,12 +266,45 @@ pub struct Flexible<W: Widget> {
     pub child: W,
 }
 
+impl<W: Widget> Flexible<W> {
+    /// Flexible child with flex factor of 1 and [`FlexFit::Loose`] fit.
+    pub fn child(child: W) -> Self {
+        Self {
+            fit: FlexFit::Loose,
+            flex: 1,
+            child,
+        }
+    }
+
+    pub fn flex(mut self, flex: usize) -> Self {
+        self.flex = flex;
+        self
+    }
+
+    pub fn fit(mut self, fit: FlexFit) -> Self {
+        self.fit = fit;
+        self
+    }
+}
+
+/// Shorthand for a [`Flexible`] with [`FlexFit::Tight`] fit, which makes its
+/// child fill all of the space allocated to it.
+pub struct Expanded;
+
+impl Expanded {
+    /// Expanded child with flex factor of 1.
+    pub fn child<W: Widget>(child: W) -> Flexible<W> {
+        Flexible::child(child).fit(FlexFit::Tight)
+    }
+}
+
 impl<W: Widget> RenderState for Flexible<W> {
     type State = FlexData;
 
     fn create_state(&self) -> Self::State {
         FlexData {
             flex_factor: self.flex,
+            fit: self.fit,
         }
     }
 }
@@ -250,7 +318,56 @@ impl<W: Widget> SingleChildWidget for Flexible<W> {
         ctx.child().layout(constraints)
     }
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, offset: &Offset) {
-        ctx.child().paint(canvas, offset)
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn loose_child_keeps_natural_size_and_tight_child_fills() {
+        let natural = Size::new(20., 10.);
+
+        let child_size = |fit: FlexFit| {
+            let (min_height, max_height) = fit.main_axis_extent(50.);
+
+            Constraints {
+                min_height,
+                max_height,
+                ..Constraints::loose(Size::new(100., 100.))
+            }
+            .constrain(natural)
+        };
+
+        assert_eq!(child_size(FlexFit::Loose), natural);
+        assert_eq!(child_size(FlexFit::Tight), Size::new(20., 50.));
+    }
+
+    #[test]
+    fn no_children_take_no_space() {
+        for alignment in [
+            MainAxisAlignment::Start,
+            MainAxisAlignment::SpaceBetween,
+            MainAxisAlignment::SpaceEvenly,
+        ] {
+            let (_, _, _, total) =
+                compute_main_axis_offset(10., MainAxisSize::Min, alignment, 0., 0., 100., 0.);
+            assert_eq!(total, 0.);
+        }
+
+        // Spacing is still added between children.
+        let (_, _, _, total) = compute_main_axis_offset(
+            10.,
+            MainAxisSize::Min,
+            MainAxisAlignment::SpaceEvenly,
+            0.,
+            2.,
+            100.,
+            30.,
+        );
+        assert_eq!(total, 60.);
     }
 }
@@ -1,10 +1,13 @@
+use std::cell::Cell;
+
 use frui::prelude::*;
 
 use crate::{
-    widget_list::WidgetList, CrossAxisAlignment, CrossAxisSize, MainAxisAlignment, MainAxisSize,
+    widget_list::WidgetList, CrossAxisAlignment, CrossAxisSize, Directionality, MainAxisAlignment,
+    MainAxisSize, TextDirection,
 };
 
-use super::{compute_cross_axis_offset, compute_main_axis_offset, get_flex};
+use super::{compute_cross_axis_offset, compute_main_axis_offset, get_fit, get_flex};
 
 #[derive(MultiChildWidget)]
 pub struct Row<T: WidgetList> {
@@ -27,6 +30,12 @@ impl Row<()> {
             cross_axis_alignment: Default::default(),
         }
     }
+
+    /// Shorthand for `Row::builder().children(children)`. See [`WidgetList`]
+    /// for all the types that you can use as `children`.
+    pub fn new(children: impl WidgetList) -> Row<impl WidgetList> {
+        Row::builder().children(children)
+    }
 }
 
 impl<WidgetList_: WidgetList> Row<WidgetList_> {
@@ -46,6 +55,15 @@ impl<WidgetList_: WidgetList> Row<WidgetList_> {
         }
     }
 
+    /// Children collected from `children`, e.g. an iterator mapping data to
+    /// widgets. Use [`BoxedWidget::boxed`] for children of different types.
+    pub fn children_iter<W: Widget>(
+        self,
+        children: impl IntoIterator<Item = W>,
+    ) -> Row<impl WidgetList> {
+        self.children(children.into_iter().collect::<Vec<_>>())
+    }
+
     /// Whether [`Row`] should take full available width or only the minimum to fit children.
     ///
     /// # Note
@@ -61,6 +79,11 @@ impl<WidgetList_: WidgetList> Row<WidgetList_> {
 
     /// Specifies how [`Row`] should layout its children on the horizontal axis.
     ///
+    /// Start of the axis is on the left in [`TextDirection::Ltr`] and on the
+    /// right in [`TextDirection::Rtl`], as read from the closest
+    /// [`Directionality`]. Children are placed from the start, so in the
+    /// latter the first child is the rightmost one.
+    ///
     /// # Note
     ///
     /// The default is [`MainAxisAlignment::Start`].
@@ -101,24 +124,21 @@ impl<WidgetList_: WidgetList> Row<WidgetList_> {
     }
 }
 
-pub struct ColumnRenderState {
-    initial_offset_x: f64,
-    space_between_x: f64,
-}
-
-impl<T: WidgetList> RenderState for Row<T> {
-    type State = ColumnRenderState;
+impl<T: WidgetList> WidgetState for Row<T> {
+    /// Text direction as of the last build, in which children are painted.
+    type State = Cell<TextDirection>;
 
     fn create_state(&self) -> Self::State {
-        ColumnRenderState {
-            initial_offset_x: 0.,
-            space_between_x: 0.,
-        }
+        Cell::default()
     }
 }
 
 impl<T: WidgetList> MultiChildWidget for Row<T> {
-    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        // State can't stay borrowed while depending on `Directionality`.
+        let direction = Directionality::of(ctx);
+        ctx.state().set(direction);
+
         self.children.get()
     }
 
@@ -167,9 +187,6 @@ impl<T: WidgetList> MultiChildWidget for Row<T> {
                 size.width,
             );
 
-        ctx.rstate_mut().space_between_x = space_between_x;
-        ctx.rstate_mut().initial_offset_x = initial_offset_x;
-
         //
         // Layout flexible items.
 
@@ -183,8 +200,12 @@ impl<T: WidgetList> MultiChildWidget for Row<T> {
                 let child_flex = get_flex(&child);
 
                 if child_flex > 0 {
+                    let allocated = space_per_flex * child_flex as f64;
+                    let (min_width, max_width) = get_fit(&child).main_axis_extent(allocated);
+
                     let constraints = Constraints {
-                        max_width: space_per_flex * child_flex as f64,
+                        min_width,
+                        max_width,
                         ..constraints
                     };
 
@@ -205,30 +226,117 @@ impl<T: WidgetList> MultiChildWidget for Row<T> {
             size.height = size.height.max(constraints.max_height)
         }
 
+        let size = constraints.constrain(size);
+
+        //
+        // Position children.
+
+        let direction = ctx.wstate().get();
+
+        // Offset of the next child from the start of the row.
+        let mut main_offset = initial_offset_x;
+
+        for mut child in ctx.children() {
+            let Size { width, height } = child.size();
+
+            child.set_offset(Offset {
+                x: horizontal_offset(direction, main_offset, size.width, width),
+                y: compute_cross_axis_offset(self.cross_axis_alignment, 0., size.height, height),
+            });
+            main_offset += width + space_between_x;
+        }
+
         size
     }
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, offset: &Offset) {
-        let self_height = ctx.size().height;
-        let space_between_x = ctx.rstate().space_between_x;
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        for mut child in ctx.children() {
+            child.paint(canvas);
+        }
+    }
+}
 
-        let mut offset_x = offset.x + ctx.rstate().initial_offset_x;
+/// Horizontal offset of a child of `child_width` which is `main_offset` away
+/// from the start of a row of `row_width`.
+fn horizontal_offset(
+    direction: TextDirection,
+    main_offset: f64,
+    row_width: f64,
+    child_width: f64,
+) -> f64 {
+    match direction {
+        TextDirection::Ltr => main_offset,
+        TextDirection::Rtl => row_width - main_offset - child_width,
+    }
+}
 
-        for mut child in ctx.children() {
-            let offset_y = compute_cross_axis_offset(
-                self.cross_axis_alignment,
-                offset.y,
-                self_height,
-                child.size().height,
-            );
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use frui::app::testing::WidgetTester;
 
-            let offset = Offset {
-                x: offset_x,
-                y: offset_y,
-            };
+    use super::*;
 
-            child.paint(canvas, &offset);
-            offset_x += child.size().width + space_between_x;
+    /// Box of a fixed width, recording its offset whenever it's painted.
+    #[derive(LeafWidget)]
+    struct Item {
+        width: f64,
+        painted: Rc<RefCell<Vec<f64>>>,
+    }
+
+    impl LeafWidget for Item {
+        fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+            constraints.constrain(Size::new(self.width, 10.))
         }
+
+        fn paint(&self, ctx: RenderContext<Self>, _: &mut PaintContext) {
+            self.painted.borrow_mut().push(ctx.offset().x);
+        }
+    }
+
+    #[test]
+    fn children_are_mirrored_under_rtl() {
+        let widths = [10., 20., 30.];
+        let (row_width, space_between) = (100., 5.);
+
+        let offsets = |direction| {
+            let mut main_offset = 0.;
+
+            widths
+                .iter()
+                .map(|&width| {
+                    let x = horizontal_offset(direction, main_offset, row_width, width);
+                    main_offset += width + space_between;
+                    x
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(offsets(TextDirection::Ltr), [0., 15., 40.]);
+        // First child is the rightmost one.
+        assert_eq!(offsets(TextDirection::Rtl), [90., 65., 30.]);
+    }
+
+    #[test]
+    fn row_follows_ambient_direction() {
+        let painted = Rc::new(RefCell::new(Vec::new()));
+
+        let item = |width| Item {
+            width,
+            painted: painted.clone(),
+        };
+
+        let row = Row::new((item(10.), item(20.))).main_axis_size(MainAxisSize::Max);
+        let mut tester = WidgetTester::new(
+            Directionality {
+                direction: TextDirection::Rtl,
+                child: row,
+            },
+            Size::new(100., 100.),
+        );
+
+        tester.paint();
+        assert_eq!(*painted.borrow(), [90., 70.]);
     }
 }
@@ -1,5 +1,7 @@
-use crate::alignment::{AlignmentDirectional, AlignmentGeometry};
-use crate::{BoxLayoutData, LayoutData, WidgetList};
+use std::cell::Cell;
+
+use crate::alignment::{Alignment, AlignmentDirectional, AlignmentGeometry};
+use crate::{BoxLayoutData, Directionality, LayoutData, TextDirection, WidgetList};
 
 use frui::prelude::*;
 
@@ -67,10 +69,16 @@ impl Stack<(), AlignmentDirectional> {
         }
     }
 
+    /// Shorthand for `Stack::builder().children(children)`. See [`WidgetList`]
+    /// for all the types that you can use as `children`.
+    pub fn new(children: impl WidgetList) -> Stack<impl WidgetList, AlignmentDirectional> {
+        Stack::builder().children(children)
+    }
+
     fn layout_positioned_child(
         child: &mut ChildContext,
         size: Size,
-        alignment: &impl AlignmentGeometry,
+        alignment: &Alignment,
     ) -> bool {
         let mut has_visual_overflow = false;
         let mut child_constraints = Constraints::default();
@@ -104,8 +112,8 @@ impl Stack<(), AlignmentDirectional> {
         }
         child.layout(child_constraints);
         let child_size = child.size();
-        {
-            let mut child_layout_data = child.try_data_mut::<StackLayoutData>().unwrap();
+        let offset = {
+            let child_layout_data = child.try_data::<StackLayoutData>().unwrap();
             let x = child_layout_data.left.unwrap_or_else(|| {
                 child_layout_data.right.map_or_else(
                     || alignment.along(size - child_size).x,
@@ -125,8 +133,9 @@ impl Stack<(), AlignmentDirectional> {
                 || y < 0.0
                 || y + child_size.height > size.height;
 
-            child_layout_data.base.offset = Offset { x, y };
-        }
+            Offset { x, y }
+        };
+        child.set_offset(offset);
         has_visual_overflow
     }
 
@@ -146,6 +155,15 @@ impl<WL: WidgetList, A: AlignmentGeometry> Stack<WL, A> {
         }
     }
 
+    /// Children collected from `children`, e.g. an iterator mapping data to
+    /// widgets. Use [`BoxedWidget::boxed`] for children of different types.
+    pub fn children_iter<W: Widget>(
+        self,
+        children: impl IntoIterator<Item = W>,
+    ) -> Stack<impl WidgetList, A> {
+        self.children(children.into_iter().collect::<Vec<_>>())
+    }
+
     pub fn alignment(self, alignment: impl AlignmentGeometry) -> Stack<WL, impl AlignmentGeometry> {
         Stack {
             children: self.children,
@@ -158,22 +176,29 @@ impl<WL: WidgetList, A: AlignmentGeometry> Stack<WL, A> {
         self.fit = fit;
         self
     }
+}
 
-    fn get_layout_offset(&self, child: &ChildContext, size: Size) -> Offset {
-        let child_size = child.size();
-        child.try_data::<StackLayoutData>().map_or_else(
-            || self.alignment.along(size - child_size),
-            |data| data.base.offset,
-        )
+impl<WL: WidgetList, A: AlignmentGeometry> WidgetState for Stack<WL, A> {
+    /// Text direction as of the last build, which resolves `alignment`.
+    type State = Cell<TextDirection>;
+
+    fn create_state(&self) -> Self::State {
+        Cell::default()
     }
 }
 
 impl<WL: WidgetList, A: AlignmentGeometry> MultiChildWidget for Stack<WL, A> {
-    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        // State can't stay borrowed while depending on `Directionality`.
+        let direction = Directionality::of(ctx);
+        ctx.state().set(direction);
+
         self.children.get()
     }
 
     fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let alignment = self.alignment.resolve(ctx.wstate().get());
+
         let mut width = constraints.min_width;
         let mut height = constraints.min_height;
         let non_positioned_constraints = match self.fit {
@@ -192,7 +217,7 @@ impl<WL: WidgetList, A: AlignmentGeometry> MultiChildWidget for Stack<WL, A> {
         }
 
         let size = if has_non_positioned_child {
-            Size::new(width, height)
+            constraints.constrain(Size::new(width, height))
         } else {
             constraints.biggest()
         };
@@ -200,20 +225,17 @@ impl<WL: WidgetList, A: AlignmentGeometry> MultiChildWidget for Stack<WL, A> {
         for mut child in ctx.children() {
             let child_size = child.size();
             if !Stack::is_positioned(&child) {
-                if let Some(mut layout_data) = child.try_data_mut::<StackLayoutData>() {
-                    layout_data.base.offset = self.alignment.along(size - child_size);
-                }
+                child.set_offset(alignment.along(size - child_size));
             } else {
-                Stack::layout_positioned_child(&mut child, size, &self.alignment);
+                Stack::layout_positioned_child(&mut child, size, &alignment);
             }
         }
         size
     }
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, _: &Offset) {
-        let size = ctx.size();
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
         for mut child in ctx.children() {
-            child.paint(canvas, &self.get_layout_offset(&child, size));
+            child.paint(canvas);
         }
     }
 }
@@ -229,6 +251,52 @@ pub struct Positioned<T: Widget> {
     pub height: Option<f64>,
 }
 
+impl<T: Widget> Positioned<T> {
+    /// Positions `child` at every edge of the [`Stack`], stretching it to the
+    /// size of the stack.
+    pub fn fill(child: T) -> Self {
+        Positioned {
+            child,
+            left: Some(0.),
+            right: Some(0.),
+            top: Some(0.),
+            bottom: Some(0.),
+            width: None,
+            height: None,
+        }
+    }
+
+    /// Positions `child` relative to the `start` and `end` edges of the
+    /// [`Stack`], which are its left and right edges respectively in
+    /// [`TextDirection::Ltr`], and the other way around in
+    /// [`TextDirection::Rtl`].
+    ///
+    /// Text direction of the stack can be read with [`Directionality::of`].
+    pub fn directional(
+        direction: TextDirection,
+        child: T,
+        start: Option<f64>,
+        top: Option<f64>,
+        end: Option<f64>,
+        bottom: Option<f64>,
+    ) -> Self {
+        let (left, right) = match direction {
+            TextDirection::Ltr => (start, end),
+            TextDirection::Rtl => (end, start),
+        };
+
+        Positioned {
+            child,
+            left,
+            right,
+            top,
+            bottom,
+            width: None,
+            height: None,
+        }
+    }
+}
+
 impl<T: Widget> RenderState for Positioned<T> {
     type State = StackLayoutData;
 
@@ -257,7 +325,7 @@ where
         ctx.child().layout(constraints)
     }
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, offset: &Offset) {
-        ctx.child().paint(canvas, offset)
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
     }
 }
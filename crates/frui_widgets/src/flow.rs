@@ -0,0 +1,259 @@
+use std::cell::RefCell;
+
+use frui::{
+    api::events::{Event, WidgetEvent},
+    prelude::*,
+};
+
+use crate::WidgetList;
+
+/// Positions the children of [`Flow`] with transforms computed during paint.
+pub trait FlowDelegate: Clone + 'static {
+    /// Constraints the child at `index` is laid out with. Defaults to the
+    /// loosened constraints of the [`Flow`].
+    fn child_constraints(&self, index: usize, constraints: Constraints) -> Constraints {
+        let _ = index;
+        constraints.loosen()
+    }
+
+    /// Size of the [`Flow`], which must satisfy `constraints`. Defaults to the
+    /// biggest size allowed.
+    fn size(&self, constraints: Constraints) -> Size {
+        constraints.biggest()
+    }
+
+    /// Paints children with [`FlowPaintingContext::paint_child`]. Children
+    /// which aren't painted are hidden and don't receive pointer events.
+    fn paint_children(&self, ctx: &mut FlowPaintingContext);
+
+    /// Whether transforms computed by the `old` delegate are outdated. If not,
+    /// frames painted after the [`Flow`] was rebuilt with this delegate reuse
+    /// the transforms from the previous paint.
+    fn should_repaint(&self, old: &Self) -> bool;
+}
+
+/// Context given to [`FlowDelegate::paint_children`].
+pub struct FlowPaintingContext {
+    size: Size,
+    child_sizes: Vec<Size>,
+    /// Painted children in the order they were painted, with their transforms.
+    painted: Vec<(usize, Affine)>,
+}
+
+impl FlowPaintingContext {
+    /// Size of the [`Flow`].
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn child_count(&self) -> usize {
+        self.child_sizes.len()
+    }
+
+    pub fn child_size(&self, index: usize) -> Size {
+        self.child_sizes[index]
+    }
+
+    /// Paints the child at `index` with `transform`, relative to the top-left
+    /// corner of the [`Flow`]. Children painted later are painted on top.
+    ///
+    /// Panics if that child was already painted.
+    pub fn paint_child(&mut self, index: usize, transform: Affine) {
+        assert!(
+            index < self.child_count(),
+            "child index {index} is out of bounds of {} children",
+            self.child_count()
+        );
+        assert!(
+            !self.painted.iter().any(|(i, _)| *i == index),
+            "child {index} was already painted"
+        );
+
+        self.painted.push((index, transform));
+    }
+}
+
+/// Lays out its children once and positions them with transforms computed by
+/// a [`FlowDelegate`] during paint.
+///
+/// Since positions don't affect the layout, animating them (e.g. opening a
+/// radial menu) only repaints the children. Pointer events are mapped through
+/// the same transforms children were painted with.
+#[derive(MultiChildWidget)]
+pub struct Flow<D: FlowDelegate, WL: WidgetList> {
+    pub delegate: D,
+    pub children: WL,
+}
+
+impl<D: FlowDelegate, WL: WidgetList> Flow<D, WL> {
+    pub fn new(delegate: D, children: WL) -> Self {
+        Self { delegate, children }
+    }
+
+    /// Transform relative to the whole window, given the `offset` of this
+    /// widget.
+    fn global_transform(transform: Affine, offset: Offset) -> Affine {
+        let origin = Vec2::new(offset.x, offset.y);
+
+        Affine::translate(origin) * transform * Affine::translate(-origin)
+    }
+}
+
+#[doc(hidden)]
+pub struct FlowRenderState<D> {
+    /// Delegate which computed `painted`.
+    delegate: Option<D>,
+    child_sizes: Vec<Size>,
+    painted: Vec<(usize, Affine)>,
+}
+
+impl<D: FlowDelegate, WL: WidgetList> RenderState for Flow<D, WL> {
+    type State = RefCell<FlowRenderState<D>>;
+
+    fn create_state(&self) -> Self::State {
+        RefCell::new(FlowRenderState {
+            delegate: None,
+            child_sizes: Vec::new(),
+            painted: Vec::new(),
+        })
+    }
+}
+
+impl<D: FlowDelegate, WL: WidgetList> MultiChildWidget for Flow<D, WL> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        self.children.get()
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let child_sizes = ctx
+            .children()
+            .enumerate()
+            .map(|(n, mut child)| child.layout(self.delegate.child_constraints(n, constraints)))
+            .collect::<Vec<_>>();
+
+        let rstate = ctx.rstate();
+        let mut state = rstate.borrow_mut();
+
+        if state.child_sizes != child_sizes {
+            // Transforms may depend on the sizes of children.
+            state.child_sizes = child_sizes;
+            state.delegate = None;
+        }
+
+        self.delegate.size(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let painted = {
+            let rstate = ctx.rstate();
+            let mut state = rstate.borrow_mut();
+
+            let outdated = match &state.delegate {
+                Some(old) => self.delegate.should_repaint(old),
+                None => true,
+            };
+
+            if outdated {
+                let mut flow_ctx = FlowPaintingContext {
+                    size: ctx.size(),
+                    child_sizes: std::mem::take(&mut state.child_sizes),
+                    painted: Vec::new(),
+                };
+
+                self.delegate.paint_children(&mut flow_ctx);
+
+                state.child_sizes = flow_ctx.child_sizes;
+                state.painted = flow_ctx.painted;
+                state.delegate = Some(self.delegate.clone());
+            }
+
+            state.painted.clone()
+        };
+
+        let mut children = ctx.children().collect::<Vec<_>>();
+
+        for (index, transform) in painted {
+            canvas.with_transform(transform, |canvas| children[index].paint(canvas));
+        }
+    }
+}
+
+impl<D: FlowDelegate, WL: WidgetList> WidgetEvent for Flow<D, WL> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        let painted = ctx.rstate().borrow().painted.clone();
+        let offset = ctx.offset();
+
+        let mut children = ctx.children().collect::<Vec<_>>();
+
+        for (index, transform) in painted {
+            if transform.determinant() == 0. {
+                // Child is collapsed and can't be hit.
+                continue;
+            }
+
+            let transform = Self::global_transform(transform, offset).inverse();
+
+            children[index].handle_event(&event.transform(transform));
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Radial {
+        radius: f64,
+    }
+
+    impl FlowDelegate for Radial {
+        fn paint_children(&self, ctx: &mut FlowPaintingContext) {
+            let count = ctx.child_count();
+
+            // Topmost child is painted last.
+            for n in (0..count).rev() {
+                let angle = std::f64::consts::TAU * n as f64 / count as f64;
+                let offset = Vec2::from_angle(angle) * self.radius;
+
+                ctx.paint_child(n, Affine::translate(offset));
+            }
+        }
+
+        fn should_repaint(&self, old: &Self) -> bool {
+            self.radius != old.radius
+        }
+    }
+
+    #[test]
+    fn children_are_painted_in_delegate_order() {
+        let mut ctx = FlowPaintingContext {
+            size: Size::new(100., 100.),
+            child_sizes: vec![Size::new(10., 10.); 4],
+            painted: Vec::new(),
+        };
+
+        Radial { radius: 10. }.paint_children(&mut ctx);
+
+        let order = ctx.painted.iter().map(|(n, _)| *n).collect::<Vec<_>>();
+        assert_eq!(order, vec![3, 2, 1, 0]);
+
+        let origin = ctx.painted[3].1 * Point::ZERO;
+        assert!((origin - Point::new(10., 0.)).hypot() < 1e-9);
+    }
+
+    #[test]
+    #[should_panic(expected = "already painted")]
+    fn child_can_be_painted_once() {
+        let mut ctx = FlowPaintingContext {
+            size: Size::new(100., 100.),
+            child_sizes: vec![Size::new(10., 10.)],
+            painted: Vec::new(),
+        };
+
+        ctx.paint_child(0, Affine::default());
+        ctx.paint_child(0, Affine::default());
+    }
+}
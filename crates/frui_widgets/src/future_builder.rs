@@ -0,0 +1,170 @@
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    rc::Rc,
+};
+
+use frui::prelude::*;
+
+/// State of an asynchronous computation, as seen by a [`FutureBuilder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsyncSnapshot<T, E> {
+    /// Computation didn't complete yet.
+    Loading,
+    Data(T),
+    Error(E),
+}
+
+impl<T, E> AsyncSnapshot<T, E> {
+    pub fn is_loading(&self) -> bool {
+        matches!(self, AsyncSnapshot::Loading)
+    }
+
+    pub fn data(&self) -> Option<&T> {
+        match self {
+            AsyncSnapshot::Data(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn error(&self) -> Option<&E> {
+        match self {
+            AsyncSnapshot::Error(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<T, E> From<Result<T, E>> for AsyncSnapshot<T, E> {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(data) => AsyncSnapshot::Data(data),
+            Err(error) => AsyncSnapshot::Error(error),
+        }
+    }
+}
+
+/// Builds its child with `builder` from the [`AsyncSnapshot`] of `future`,
+/// building it again once the future completes.
+///
+/// ```
+/// # use frui::prelude::*;
+/// # use frui_widgets::*;
+/// # struct Profile { name: String }
+/// # async fn load_profile() -> Result<Profile, std::io::Error> {
+/// #     Ok(Profile { name: String::new() })
+/// # }
+/// FutureBuilder::new(load_profile(), |snapshot| match snapshot {
+///     AsyncSnapshot::Loading => Text::new("Loading...".to_string()),
+///     AsyncSnapshot::Data(profile) => Text::new(profile.name.clone()),
+///     AsyncSnapshot::Error(e) => Text::new(e.to_string()),
+/// });
+/// ```
+///
+/// Future is polled on the UI thread by the run loop (see `spawn_local`), so it
+/// doesn't have to be `Send`. If the widget is removed before the future
+/// completes, the future is dropped. If the widget is rebuilt with a new
+/// future, the previous one is dropped and the snapshot is `Loading` again
+/// until the new one completes.
+#[derive(ViewWidget)]
+pub struct FutureBuilder<T, E, Fut, W, B>
+where
+    T: 'static,
+    E: 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    W: Widget,
+    B: Fn(&AsyncSnapshot<T, E>) -> W,
+{
+    /// Taken once the future is spawned.
+    future: Cell<Option<Fut>>,
+    builder: B,
+}
+
+impl<T, E, Fut, W, B> FutureBuilder<T, E, Fut, W, B>
+where
+    T: 'static,
+    E: 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    W: Widget,
+    B: Fn(&AsyncSnapshot<T, E>) -> W,
+{
+    pub fn new(future: Fut, builder: B) -> Self {
+        Self {
+            future: Cell::new(Some(future)),
+            builder,
+        }
+    }
+}
+
+pub struct FutureBuilderState<T, E> {
+    snapshot: Rc<RefCell<AsyncSnapshot<T, E>>>,
+    /// Dropping it cancels the future.
+    task: Option<Task>,
+}
+
+impl<T, E, Fut, W, B> WidgetState for FutureBuilder<T, E, Fut, W, B>
+where
+    T: 'static,
+    E: 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    W: Widget,
+    B: Fn(&AsyncSnapshot<T, E>) -> W,
+{
+    type State = FutureBuilderState<T, E>;
+
+    fn create_state(&self) -> Self::State {
+        FutureBuilderState {
+            snapshot: Rc::new(RefCell::new(AsyncSnapshot::Loading)),
+            task: None,
+        }
+    }
+
+    fn mount(&self, ctx: BuildContext<Self>) {
+        // Updated widget is mounted again, after its new future replaced the
+        // previous one in `did_update_widget`.
+        if ctx.state().task.is_some() {
+            return;
+        }
+
+        if let Some(future) = self.future.take() {
+            let snapshot = ctx.state().snapshot.clone();
+            let listener = ctx.listener();
+
+            ctx.state_mut().task = Some(spawn_local(async move {
+                let result = future.await;
+
+                *snapshot.borrow_mut() = result.into();
+                listener.notify();
+            }));
+        }
+    }
+
+    fn did_update_widget(&self, ctx: BuildContext<Self>) {
+        let mut state = ctx.state_mut();
+
+        // Task of the previous future is cancelled and a new snapshot is
+        // created, so that the previous task can't overwrite it.
+        state.task = None;
+        state.snapshot = Rc::new(RefCell::new(AsyncSnapshot::Loading));
+    }
+
+    fn dispose(&self, ctx: BuildContext<Self>) {
+        ctx.state_mut().task = None;
+    }
+}
+
+impl<T, E, Fut, W, B> ViewWidget for FutureBuilder<T, E, Fut, W, B>
+where
+    T: 'static,
+    E: 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    W: Widget,
+    B: Fn(&AsyncSnapshot<T, E>) -> W,
+{
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        let snapshot = ctx.state().snapshot.clone();
+        let snapshot = snapshot.borrow();
+
+        (self.builder)(&snapshot)
+    }
+}
@@ -0,0 +1,275 @@
+use std::{
+    cell::{Cell, RefCell},
+    time::Duration,
+};
+
+use frui::{
+    api::events::{Event, WidgetEvent},
+    prelude::*,
+};
+
+use crate::{AnimationController, Curve, Enabled, RoundedRectangleBorder, ShapeBorder};
+
+const DEFAULT_SPLASH_COLOR: Color = Color::rgba8(255, 255, 255, 60);
+const DEFAULT_SPLASH_DURATION: Duration = Duration::from_millis(500);
+
+/// Whether ripples of an [`InkWell`] are painted above or below its child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InkPosition {
+    #[default]
+    Foreground,
+    Background,
+}
+
+/// Responds to taps with a ripple: a circle expanding from the pointer
+/// position, fading out while it grows to cover the whole widget.
+///
+/// Ripples are clipped to the bounds of the widget, rounded by
+/// `border_radius`. Every press starts a new ripple, so that ripples of rapid
+/// taps overlap. `on_tap` is called once a press within the widget turns out to
+/// be a tap, which it doesn't if e.g. it scrolls an enclosing [`Scroll`].
+///
+/// Once disabled by an [`Enabled`] ancestor, presses don't start ripples and
+/// `on_tap` isn't called.
+///
+/// [`Scroll`]: crate::Scroll
+#[derive(SingleChildWidget)]
+pub struct InkWell<W: Widget, F: Fn()> {
+    pub child: W,
+    pub on_tap: F,
+    pub splash_color: Color,
+    pub shape: Box<dyn ShapeBorder>,
+    pub duration: Duration,
+    pub position: InkPosition,
+}
+
+impl<W: Widget, F: Fn()> InkWell<W, F> {
+    pub fn new(child: W, on_tap: F) -> Self {
+        Self {
+            child,
+            on_tap,
+            splash_color: DEFAULT_SPLASH_COLOR,
+            shape: Box::new(RoundedRectangleBorder::new(0.)),
+            duration: DEFAULT_SPLASH_DURATION,
+            position: InkPosition::default(),
+        }
+    }
+
+    pub fn splash_color(mut self, color: Color) -> Self {
+        self.splash_color = color;
+        self
+    }
+
+    /// Shape ripples are clipped to, which should match the shape of the
+    /// child.
+    pub fn shape(mut self, shape: impl ShapeBorder + 'static) -> Self {
+        self.shape = Box::new(shape);
+        self
+    }
+
+    /// Shorthand for a [`shape`](InkWell::shape) of [`RoundedRectangleBorder`].
+    #[track_caller]
+    pub fn border_radius(self, radius: f64) -> Self {
+        assert!(radius >= 0.0, "border_radius must be >= 0.0");
+        self.shape(RoundedRectangleBorder::new(radius))
+    }
+
+    /// Time it takes a ripple to expand and fade out.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn position(mut self, position: InkPosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    fn paint_ripples(&self, ctx: &RenderContext<Self>, canvas: &mut PaintContext) {
+        let state = ctx.wstate();
+        let ripples = state.ripples.borrow();
+
+        if ripples.is_empty() {
+            return;
+        }
+
+        let size = ctx.size();
+        let bounds = Rect::from_origin_size(Point::ORIGIN, size);
+        let (.., alpha) = self.splash_color.as_rgba();
+
+        canvas.with_clip(self.shape.path(bounds), |canvas| {
+            for ripple in ripples.iter() {
+                let t = ripple.animation.value();
+
+                let center = ripple.origin;
+                let radius = max_radius(size, ripple.origin) * Curve::EaseOut.transform(t);
+
+                let color = self.splash_color.clone().with_alpha(alpha * (1.0 - t));
+                let brush = &canvas.solid_brush(color);
+                PietRenderContext::fill(canvas, Circle::new(center, radius), brush);
+            }
+        });
+    }
+}
+
+/// Ripple started by a single press.
+struct Ripple {
+    /// Position of the press, relative to the widget.
+    origin: Point,
+    animation: AnimationController,
+}
+
+#[doc(hidden)]
+pub struct InkWellState {
+    ripples: RefCell<Vec<Ripple>>,
+    tap: RefCell<TapGestureRecognizer>,
+    /// Whether this widget is enabled, as of the last change of its
+    /// dependencies.
+    enabled: Cell<bool>,
+}
+
+impl<W: Widget, F: Fn()> WidgetState for InkWell<W, F> {
+    type State = InkWellState;
+
+    fn create_state(&self) -> Self::State {
+        InkWellState {
+            ripples: RefCell::new(Vec::new()),
+            tap: RefCell::default(),
+            enabled: Cell::new(true),
+        }
+    }
+
+    fn did_change_dependencies<'a>(&'a self, ctx: BuildContext<'a, Self>) {
+        let enabled = Enabled::of(ctx);
+        let state = ctx.state();
+
+        state.enabled.set(enabled);
+
+        // Press in progress doesn't turn into a tap.
+        if !enabled {
+            *state.tap.borrow_mut() = TapGestureRecognizer::default();
+        }
+    }
+}
+
+impl<W: Widget, F: Fn()> SingleChildWidget for InkWell<W, F> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        // Ripples which finished by now are removed before they are painted, so
+        // that the frame after they finished is painted without them.
+        let is_animating = remove_finished(&mut ctx.wstate().ripples.borrow_mut());
+
+        if let InkPosition::Background = self.position {
+            self.paint_ripples(&ctx, canvas);
+        }
+
+        ctx.child().paint(canvas);
+
+        if let InkPosition::Foreground = self.position {
+            self.paint_ripples(&ctx, canvas);
+        }
+
+        if is_animating {
+            ctx.mark_needs_paint();
+        }
+    }
+}
+
+impl<W: Widget, F: Fn()> WidgetEvent for InkWell<W, F> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        if !ctx.wstate().enabled.get() {
+            return false;
+        }
+
+        if let Event::MouseDown(e) = event {
+            if e.button == MouseButton::Left && ctx.point_in_layout_bounds(e.pos) {
+                let mut animation = AnimationController::new(self.duration);
+                animation.forward();
+
+                let offset = ctx.offset();
+                let state = ctx.wstate();
+                state.tap.borrow_mut().add_pointer(e.pos);
+                state.ripples.borrow_mut().push(Ripple {
+                    origin: Point::new(e.pos.x - offset.x, e.pos.y - offset.y),
+                    animation,
+                });
+
+                drop(state);
+                ctx.mark_needs_paint();
+            }
+        }
+
+        let is_tap = ctx.wstate().tap.borrow_mut().handle_event(event);
+
+        if is_tap {
+            (self.on_tap)();
+        }
+
+        false
+    }
+}
+
+/// Radius at which a ripple from `origin` covers the whole box of `size`, i.e.
+/// the distance from `origin` to the farthest corner.
+fn max_radius(size: Size, origin: Point) -> f64 {
+    let dx = origin.x.max(size.width - origin.x);
+    let dy = origin.y.max(size.height - origin.y);
+
+    dx.hypot(dy)
+}
+
+/// Removes ripples which finished animating, returning whether some are still
+/// animating.
+fn remove_finished(ripples: &mut Vec<Ripple>) -> bool {
+    ripples.retain(|ripple| ripple.animation.is_animating());
+
+    !ripples.is_empty()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ripple(duration: Duration) -> Ripple {
+        let mut animation = AnimationController::new(duration);
+        animation.forward();
+
+        Ripple {
+            origin: Point::ZERO,
+            animation,
+        }
+    }
+
+    #[test]
+    fn ripple_reaches_farthest_corner() {
+        let size = Size::new(30., 40.);
+
+        assert_eq!(max_radius(size, Point::new(0., 0.)), 50.);
+        assert_eq!(max_radius(size, Point::new(30., 40.)), 50.);
+        assert_eq!(max_radius(size, Point::new(15., 20.)), 25.);
+    }
+
+    #[test]
+    fn only_finished_ripples_are_removed() {
+        let mut ripples = vec![
+            ripple(Duration::ZERO),
+            ripple(Duration::from_secs(60)),
+            ripple(Duration::ZERO),
+        ];
+
+        assert!(remove_finished(&mut ripples));
+        assert_eq!(ripples.len(), 1);
+        assert!(ripples[0].animation.is_animating());
+
+        ripples[0].animation.stop();
+        assert!(!remove_finished(&mut ripples));
+        assert!(ripples.is_empty());
+    }
+}
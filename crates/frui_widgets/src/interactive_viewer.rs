@@ -0,0 +1,223 @@
+use frui::{
+    api::events::{Event, WidgetEvent},
+    prelude::*,
+};
+
+use crate::Transform;
+
+/// Zoom factor applied per pixel of mouse wheel delta.
+const ZOOM_SENSITIVITY: f64 = 0.002;
+
+/// Lets the user pan its child by dragging it and zoom it with the mouse wheel.
+///
+/// Zooming is anchored at the pointer position and the scale is kept between
+/// `min_scale` and `max_scale`. If `constrained` is `true`, panning is limited
+/// so that the child can't be moved out of the viewport.
+///
+/// The child is laid out with loosened constraints of this widget, which fills
+/// all the available space.
+#[derive(SingleChildWidget)]
+pub struct InteractiveViewer<W: Widget> {
+    pub child: W,
+    pub min_scale: f64,
+    pub max_scale: f64,
+    pub constrained: bool,
+}
+
+impl<W: Widget> InteractiveViewer<W> {
+    pub fn new(child: W) -> Self {
+        Self {
+            child,
+            min_scale: 0.8,
+            max_scale: 2.5,
+            constrained: true,
+        }
+    }
+
+    #[track_caller]
+    pub fn scale_range(mut self, min_scale: f64, max_scale: f64) -> Self {
+        assert!(
+            0. < min_scale && min_scale <= max_scale,
+            "scale range should satisfy 0 < min_scale (is {min_scale}) <= max_scale (is {max_scale})"
+        );
+
+        self.min_scale = min_scale;
+        self.max_scale = max_scale;
+        self
+    }
+
+    pub fn constrained(mut self, constrained: bool) -> Self {
+        self.constrained = constrained;
+        self
+    }
+}
+
+#[doc(hidden)]
+pub struct InteractiveViewerState {
+    /// Transform of the child, consisting only of a uniform scale and a
+    /// translation.
+    transform: Affine,
+    /// Last pointer position of the ongoing pan.
+    pan: Option<Point>,
+}
+
+impl<W: Widget> WidgetState for InteractiveViewer<W> {
+    type State = InteractiveViewerState;
+
+    fn create_state(&self) -> Self::State {
+        InteractiveViewerState {
+            transform: Affine::default(),
+            pan: None,
+        }
+    }
+}
+
+impl<W: Widget> SingleChildWidget for InteractiveViewer<W> {
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        Transform::new(ctx.state().transform, &self.child)
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let child_size = ctx.child().layout(constraints.loosen());
+
+        let size = constraints.biggest();
+
+        if size.is_finite() {
+            size
+        } else {
+            constraints.constrain(child_size)
+        }
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let bounds = Rect::from_origin_size(Point::ORIGIN, ctx.size());
+
+        canvas.with_clip(bounds, |canvas| {
+            ctx.child().paint(canvas);
+        });
+    }
+}
+
+impl<W: Widget> WidgetEvent for InteractiveViewer<W> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        let (transform, pan) = {
+            let state = ctx.wstate();
+            (state.transform, state.pan)
+        };
+
+        let new_transform = match event {
+            Event::MouseDown(e) => {
+                if let MouseButton::Left = e.button {
+                    if ctx.point_in_layout_bounds(e.pos) {
+                        ctx.wstate_mut().pan = Some(e.pos);
+                        ctx.capture_pointer();
+                    }
+                }
+                None
+            }
+            Event::MouseMove(e) => pan.map(|last| {
+                ctx.wstate_mut().pan = Some(e.pos);
+                Affine::translate(e.pos - last) * transform
+            }),
+            Event::MouseUp(e) => {
+                if let MouseButton::Left = e.button {
+                    ctx.wstate_mut().pan = None;
+                }
+                None
+            }
+            Event::MouseWheel(e) => {
+                if ctx.point_in_layout_bounds(e.pos) {
+                    let offset = ctx.offset();
+                    let anchor = e.pos - Vec2::new(offset.x, offset.y);
+                    let factor = (-e.wheel_delta.y * ZOOM_SENSITIVITY).exp();
+
+                    Some(zoom(
+                        transform,
+                        anchor,
+                        factor,
+                        self.min_scale,
+                        self.max_scale,
+                    ))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+
+        if let Some(mut new_transform) = new_transform {
+            if self.constrained {
+                new_transform = constrain(new_transform, ctx.child().size(), ctx.size());
+            }
+
+            if new_transform != transform {
+                ctx.wstate_mut().transform = new_transform;
+            }
+
+            // Don't let the child react to the pointer while it's being moved.
+            return true;
+        }
+
+        false
+    }
+}
+
+fn scale_of(transform: Affine) -> f64 {
+    transform.as_coeffs()[0]
+}
+
+fn translation_of(transform: Affine) -> Vec2 {
+    let coeffs = transform.as_coeffs();
+    Vec2::new(coeffs[4], coeffs[5])
+}
+
+/// Scales `transform` by `factor` around `anchor`, keeping the resulting scale
+/// in range `min_scale..=max_scale`.
+fn zoom(transform: Affine, anchor: Point, factor: f64, min_scale: f64, max_scale: f64) -> Affine {
+    let scale = scale_of(transform);
+    let factor = (scale * factor).clamp(min_scale, max_scale) / scale;
+
+    let anchor = anchor.to_vec2();
+
+    Affine::translate(anchor) * Affine::scale(factor) * Affine::translate(-anchor) * transform
+}
+
+/// Limits translation of `transform` so that the child of `child_size` either
+/// covers the whole viewport of `size` or stays fully inside of it.
+fn constrain(transform: Affine, child_size: Size, size: Size) -> Affine {
+    let scale = scale_of(transform);
+    let translation = translation_of(transform);
+
+    let clamp = |translation: f64, child_extent: f64, extent: f64| {
+        let free_space = extent - child_extent * scale;
+        translation.clamp(free_space.min(0.), free_space.max(0.))
+    };
+
+    let x = clamp(translation.x, child_size.width, size.width);
+    let y = clamp(translation.y, child_size.height, size.height);
+
+    Affine::translate((x, y)) * Affine::scale(scale)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn zoom_is_clamped_and_anchored() {
+        let anchor = Point::new(10., 20.);
+        let transform = zoom(Affine::default(), anchor, 10., 0.5, 2.);
+
+        assert_eq!(scale_of(transform), 2.);
+        assert_eq!(transform * anchor, anchor);
+    }
+
+    #[test]
+    fn constrained_child_stays_in_viewport() {
+        let transform = Affine::translate((-500., 50.)) * Affine::scale(2.);
+        let transform = constrain(transform, Size::new(100., 10.), Size::new(100., 100.));
+
+        // Child is wider than the viewport and covers it, but fits vertically.
+        assert_eq!(translation_of(transform), Vec2::new(-100., 50.));
+    }
+}
@@ -0,0 +1,43 @@
+use std::any::TypeId;
+
+use frui::{macro_exports::WidgetDerive, prelude::*};
+
+/// Keeps the state of `child` alive in widgets which otherwise unmount their
+/// hidden children.
+///
+/// Widget honoring this marker (like [`TabView`] with `keep_alive` disabled)
+/// keeps a [`KeepAlive`] child mounted, but doesn't lay it out or paint it
+/// while it's hidden. For that to work, [`KeepAlive`] must be the direct child
+/// given to such widget. Everywhere else [`KeepAlive`] does nothing, e.g.
+/// [`ListView`] never unmounts children scrolled out of view (it only skips
+/// their layout and paint), so their state is kept either way.
+///
+/// # Memory
+///
+/// Whole subtree of a kept-alive child (including the state of every widget in
+/// it) stays in memory for as long as its parent is mounted. Use it for children
+/// whose state is expensive or impossible to recreate, not for every child.
+///
+/// [`TabView`]: crate::TabView
+/// [`ListView`]: crate::ListView
+#[derive(SingleChildWidget)]
+pub struct KeepAlive<W: Widget> {
+    pub child: W,
+}
+
+impl<W: Widget> KeepAlive<W> {
+    pub fn new(child: W) -> Self {
+        Self { child }
+    }
+}
+
+impl<W: Widget> SingleChildWidget for KeepAlive<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+}
+
+/// Whether `widget` is a [`KeepAlive`].
+pub(crate) fn is_kept_alive(widget: &dyn Widget) -> bool {
+    widget.unique_type() == TypeId::of::<<KeepAlive<()> as WidgetDerive>::UniqueTypeId>()
+}
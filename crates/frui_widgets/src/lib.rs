@@ -1,19 +1,107 @@
 #![feature(type_alias_impl_trait)]
 
+mod animated_list;
+mod animated_switcher;
+mod animation;
+mod auto_size_text;
+mod backdrop_filter;
+mod banner;
+mod capturable;
+mod color_filtered;
+mod conditional;
+mod constant;
+mod constraints_transform;
 mod container;
+mod context_menu;
+mod custom_layout;
+mod directionality;
+mod edge_insets;
+mod enabled;
 mod event_detectors;
 mod flex;
+mod flow;
+mod future_builder;
+mod ink_well;
+mod interactive_viewer;
+mod keep_alive;
+mod list_view;
+mod listenable_builder;
+mod min_touch_target;
+mod mnemonic_text;
+mod notification_listener;
+mod page_view;
+mod physical_model;
+mod pointer_behavior;
+mod progress;
+mod radio;
+mod reorderable_list;
+mod responsive_builder;
+mod rich_text;
 mod scroll;
+mod scroll_physics;
+mod selectable_text;
+mod shape_border;
+mod stream_builder;
+mod tabs;
 mod testing;
 mod text;
+mod transform;
+mod transitions;
+mod undo;
 mod widget_list;
 
+pub use self::animated_list::*;
+pub use self::animated_switcher::*;
+pub use self::animation::*;
+pub use self::auto_size_text::*;
+pub use self::backdrop_filter::*;
+pub use self::banner::*;
+pub use self::capturable::*;
+pub use self::color_filtered::*;
+pub use self::conditional::*;
+pub use self::constant::*;
+pub use self::constraints_transform::*;
 pub use self::container::*;
+pub use self::context_menu::*;
+pub use self::custom_layout::*;
+pub use self::directionality::*;
+pub use self::edge_insets::*;
+pub use self::enabled::*;
+pub use self::event_detectors::clipboard::*;
+pub use self::event_detectors::gesture::*;
 pub use self::event_detectors::keyboard::*;
+pub use self::event_detectors::shortcuts::*;
+pub use self::event_detectors::size::*;
 pub use self::flex::*;
+pub use self::flow::*;
+pub use self::future_builder::*;
+pub use self::ink_well::*;
+pub use self::interactive_viewer::*;
+pub use self::keep_alive::*;
+pub use self::list_view::*;
+pub use self::listenable_builder::*;
+pub use self::min_touch_target::*;
+pub use self::mnemonic_text::*;
+pub use self::notification_listener::*;
+pub use self::page_view::*;
+pub use self::physical_model::*;
+pub use self::pointer_behavior::*;
+pub use self::progress::*;
+pub use self::radio::*;
+pub use self::reorderable_list::*;
+pub use self::responsive_builder::*;
+pub use self::rich_text::*;
 pub use self::scroll::*;
+pub use self::scroll_physics::*;
+pub use self::selectable_text::*;
+pub use self::shape_border::*;
+pub use self::stream_builder::*;
+pub use self::tabs::*;
 pub use self::testing::*;
 pub use self::text::*;
+pub use self::transform::*;
+pub use self::transitions::*;
+pub use self::undo::*;
 pub use self::widget_list::*;
 
 #[doc(hidden)]
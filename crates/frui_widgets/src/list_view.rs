@@ -0,0 +1,584 @@
+use std::ops::Range;
+
+use frui::{
+    api::events::{Event, WidgetEvent},
+    prelude::*,
+};
+
+use crate::{
+    widget_list::WidgetList, ClampingScrollPhysics, ScrollDirection, ScrollPhysics, ScrollState,
+};
+
+/// Distance before and after the viewport of a [`ListView`] within which
+/// children are laid out ahead of being scrolled into view.
+pub const CACHE_EXTENT: f64 = 250.;
+
+/// Scrollable list of children laid out one after another along its scroll
+/// direction.
+///
+/// Only children which are visible, or within [`CACHE_EXTENT`] of the
+/// viewport, are laid out and painted. Children which weren't laid out yet are
+/// assumed to be as large as the first child which was, until they are
+/// scrolled into view. Extents of children are cached between frames, so
+/// that the cost of a scroll frame grows only logarithmically with the number
+/// of children, and a child which changes size shifts the offsets of the
+/// children after it without laying them out.
+///
+/// Every child stays mounted while it's scrolled out of view, so it keeps
+/// its state without being wrapped in [`KeepAlive`](crate::KeepAlive).
+///
+/// Within constraints unbounded along its scroll direction (e.g. in a
+/// [`Column`](crate::Column)), there is no viewport, so every child is laid out
+/// and the list is as large as all of them.
+#[derive(MultiChildWidget)]
+pub struct ListView<T: WidgetList, P: ScrollPhysics> {
+    pub children: T,
+    pub scroll_direction: ScrollDirection,
+    /// How the content responds to dragging it and keeps moving after a fling.
+    pub physics: P,
+}
+
+impl ListView<(), ClampingScrollPhysics> {
+    pub fn builder() -> Self {
+        ListView {
+            children: (),
+            scroll_direction: ScrollDirection::Vertical,
+            physics: ClampingScrollPhysics,
+        }
+    }
+}
+
+impl<T: WidgetList, P: ScrollPhysics> ListView<T, P> {
+    /// See [`WidgetList`] for all the types that you can use as `children`.
+    pub fn children(self, children: impl WidgetList) -> ListView<impl WidgetList, P> {
+        ListView {
+            children,
+            scroll_direction: self.scroll_direction,
+            physics: self.physics,
+        }
+    }
+
+    /// Children collected from `children`, e.g. an iterator mapping data to
+    /// widgets. Use [`BoxedWidget::boxed`] for children of different types.
+    pub fn children_iter<W: Widget>(
+        self,
+        children: impl IntoIterator<Item = W>,
+    ) -> ListView<impl WidgetList, P> {
+        self.children(children.into_iter().collect::<Vec<_>>())
+    }
+
+    pub fn scroll_direction(mut self, scroll_direction: ScrollDirection) -> Self {
+        self.scroll_direction = scroll_direction;
+        self
+    }
+
+    pub fn physics<P2: ScrollPhysics>(self, physics: P2) -> ListView<T, P2> {
+        ListView {
+            children: self.children,
+            scroll_direction: self.scroll_direction,
+            physics,
+        }
+    }
+
+    /// Size of all children laid out one after another within `size`.
+    fn content_size(&self, extents: &ListExtents, size: Size) -> Size {
+        match self.scroll_direction {
+            ScrollDirection::Horizontal => Size::new(extents.total_extent(), size.height),
+            ScrollDirection::Vertical => Size::new(size.width, extents.total_extent()),
+        }
+    }
+
+    /// Offset of a child `offset` away from the start of the list.
+    fn child_offset(&self, offset: f64) -> Offset {
+        match self.scroll_direction {
+            ScrollDirection::Horizontal => Offset { x: offset, y: 0. },
+            ScrollDirection::Vertical => Offset { x: 0., y: offset },
+        }
+    }
+
+    /// Whether children visible at `scroll_offset` weren't all laid out, e.g.
+    /// after scrolling further than the cache extent.
+    fn needs_layout(&self, state: &ListViewRenderState, scroll_offset: Vec2, size: Size) -> bool {
+        let direction = self.scroll_direction;
+        let visible = state
+            .extents
+            .visible(direction.along(scroll_offset), direction.extent(size));
+
+        !visible.is_empty()
+            && (visible.start < state.laid_out.start || visible.end > state.laid_out.end)
+    }
+}
+
+/// Extents of children of a [`ListView`] along its scroll direction, along
+/// with their offsets computed from them.
+///
+/// Extents are kept in a Fenwick tree, so that both changing an extent and
+/// computing an offset takes `O(log n)`.
+#[derive(Debug, Default)]
+struct ListExtents {
+    /// Extent of every child, or `None` if it wasn't laid out yet.
+    extents: Vec<Option<f64>>,
+    /// Fenwick tree of extents, where children which weren't laid out yet
+    /// take the `estimate`.
+    tree: Vec<f64>,
+    /// Extent of the first child which was laid out.
+    estimate: Option<f64>,
+}
+
+impl ListExtents {
+    fn len(&self) -> usize {
+        self.extents.len()
+    }
+
+    fn extent(&self, index: usize) -> f64 {
+        self.extents[index].or(self.estimate).unwrap_or(0.)
+    }
+
+    /// Changes the number of children, keeping extents of the first ones.
+    fn resize(&mut self, len: usize) {
+        if len != self.len() {
+            self.extents.resize(len, None);
+            self.rebuild();
+        }
+    }
+
+    /// Builds the tree from scratch, in which (1-based) node `k` stored at
+    /// `tree[k - 1]` is the sum of extents of the `k & -k` children up to it.
+    fn rebuild(&mut self) {
+        self.tree = (0..self.len()).map(|n| self.extent(n)).collect();
+
+        for k in 1..=self.len() {
+            let parent = k + lowest_bit(k);
+
+            if parent <= self.len() {
+                self.tree[parent - 1] += self.tree[k - 1];
+            }
+        }
+    }
+
+    /// Records extent of the child at `index`, shifting offsets of the
+    /// children after it if it changed.
+    fn set(&mut self, index: usize, extent: f64) {
+        if self.extents[index] == Some(extent) {
+            return;
+        }
+
+        let delta = extent - self.extent(index);
+        self.extents[index] = Some(extent);
+
+        if self.estimate.is_none() {
+            self.estimate = Some(extent);
+            self.rebuild();
+            return;
+        }
+
+        let mut k = index + 1;
+
+        while k <= self.len() {
+            self.tree[k - 1] += delta;
+            k += lowest_bit(k);
+        }
+    }
+
+    /// Offset of the child at `index` from the start of the list, i.e. sum of
+    /// extents of the children before it.
+    fn offset(&self, index: usize) -> f64 {
+        let mut offset = 0.;
+        let mut k = index;
+
+        while k > 0 {
+            offset += self.tree[k - 1];
+            k -= lowest_bit(k);
+        }
+
+        offset
+    }
+
+    fn total_extent(&self) -> f64 {
+        self.offset(self.len())
+    }
+
+    /// Number of children whose offset satisfies `pred`, which has to hold
+    /// for offsets up to some point and not after it.
+    fn partition_point(&self, pred: impl Fn(f64) -> bool) -> usize {
+        if self.len() == 0 || !pred(0.) {
+            return 0;
+        }
+
+        // Greatest number of leading children whose total extent satisfies
+        // `pred`.
+        let (mut count, mut offset) = (0, 0.);
+        let mut step = self.len().next_power_of_two();
+
+        while step > 0 {
+            if count + step <= self.len() && pred(offset + self.tree[count + step - 1]) {
+                count += step;
+                offset += self.tree[count - 1];
+            }
+
+            step /= 2;
+        }
+
+        (count + 1).min(self.len())
+    }
+
+    /// Indices of children within `viewport` of the list scrolled to
+    /// `position`.
+    fn visible(&self, position: f64, viewport: f64) -> Range<usize> {
+        let start = self.partition_point(|o| o <= position);
+        let end = self.partition_point(|o| o < position + viewport);
+
+        start.saturating_sub(1)..end.max(start.saturating_sub(1))
+    }
+}
+
+fn lowest_bit(k: usize) -> usize {
+    k & k.wrapping_neg()
+}
+
+#[doc(hidden)]
+pub struct ListViewRenderState {
+    extents: ListExtents,
+    /// Children laid out during the last layout.
+    laid_out: Range<usize>,
+}
+
+impl<T: WidgetList, P: ScrollPhysics> WidgetState for ListView<T, P> {
+    type State = ScrollState;
+
+    fn create_state(&self) -> Self::State {
+        ScrollState::new()
+    }
+}
+
+impl<T: WidgetList, P: ScrollPhysics> RenderState for ListView<T, P> {
+    type State = ListViewRenderState;
+
+    fn create_state(&self) -> Self::State {
+        ListViewRenderState {
+            extents: ListExtents::default(),
+            laid_out: 0..0,
+        }
+    }
+}
+
+impl<T: WidgetList, P: ScrollPhysics> MultiChildWidget for ListView<T, P> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        self.children.get()
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let direction = self.scroll_direction;
+        let child_constraints = match direction {
+            ScrollDirection::Horizontal => Constraints {
+                min_width: 0.,
+                max_width: f64::INFINITY,
+                ..constraints
+            },
+            ScrollDirection::Vertical => Constraints {
+                min_height: 0.,
+                max_height: f64::INFINITY,
+                ..constraints
+            },
+        };
+
+        let len = ctx.children().len();
+        ctx.rstate_mut().extents.resize(len);
+
+        let max = constraints.max();
+        let viewport = direction.extent(max);
+        let position = direction.along(ctx.wstate().scroll_offset());
+
+        // Laying out children replaces their estimated extents, which may
+        // bring other children into the viewport.
+        let mut laid_out = 0..0;
+        let mut cross_extent = 0f64;
+
+        loop {
+            let state = ctx.rstate();
+            let mut range = match viewport.is_finite() {
+                true => state
+                    .extents
+                    .visible(position - CACHE_EXTENT, viewport + 2. * CACHE_EXTENT),
+                false => 0..len,
+            };
+
+            // Until some child is laid out, there is nothing to estimate
+            // extents of the others with.
+            if state.extents.estimate.is_none() {
+                range.end = range.end.min(range.start + 1);
+            }
+
+            drop(state);
+
+            if range == laid_out {
+                break;
+            }
+
+            // Layout of children which didn't change is cached, so laying out
+            // the same children again is cheap.
+            let sizes = ctx
+                .children()
+                .skip(range.start)
+                .take(range.len())
+                .map(|mut child| child.layout(child_constraints))
+                .collect::<Vec<_>>();
+
+            let extents = &mut ctx.rstate_mut().extents;
+            cross_extent = 0.;
+
+            for (n, size) in range.clone().zip(sizes) {
+                extents.set(n, direction.extent(size));
+                cross_extent = cross_extent.max(match direction {
+                    ScrollDirection::Horizontal => size.height,
+                    ScrollDirection::Vertical => size.width,
+                });
+            }
+
+            laid_out = range;
+        }
+
+        // Along unbounded axes, the list is as large as its content.
+        let total_extent = ctx.rstate().extents.total_extent();
+        let content = match direction {
+            ScrollDirection::Horizontal => Size::new(total_extent, cross_extent),
+            ScrollDirection::Vertical => Size::new(cross_extent, total_extent),
+        };
+        let size = constraints.constrain(Size::new(
+            if max.width.is_finite() {
+                max.width
+            } else {
+                content.width
+            },
+            if max.height.is_finite() {
+                max.height
+            } else {
+                content.height
+            },
+        ));
+
+        let mut state = ctx.rstate_mut();
+        state.laid_out = laid_out;
+
+        let content_size = self.content_size(&state.extents, size);
+        drop(state);
+
+        ctx.wstate().clamp(direction, size, content_size);
+
+        size
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let size = ctx.size();
+        let direction = self.scroll_direction;
+        let content_size = self.content_size(&ctx.rstate().extents, size);
+
+        let is_animating = ctx.wstate().advance(direction, size, content_size);
+        let scroll_offset = ctx.wstate().scroll_offset();
+
+        // Fling went past the children laid out ahead of it, which are laid
+        // out during the next frame.
+        if self.needs_layout(&ctx.rstate(), scroll_offset, size) {
+            ctx.mark_needs_layout();
+        }
+
+        let children = {
+            let state = ctx.rstate();
+            let visible = state
+                .extents
+                .visible(direction.along(scroll_offset), direction.extent(size));
+            let start = visible.start.max(state.laid_out.start);
+            let end = visible.end.min(state.laid_out.end);
+
+            (start..end.max(start))
+                .map(|n| (n, state.extents.offset(n)))
+                .collect::<Vec<_>>()
+        };
+
+        canvas.with_clip(Rect::from_origin_size(Point::ORIGIN, size), |canvas| {
+            canvas.transform(Affine::translate(-scroll_offset));
+
+            if let Some(&(first, _)) = children.first() {
+                let visible = ctx.children().skip(first).zip(&children);
+
+                for (mut child, (_, child_offset)) in visible {
+                    child.paint_at(canvas, &self.child_offset(*child_offset));
+                }
+            }
+        });
+
+        if is_animating {
+            ctx.mark_needs_paint();
+        }
+    }
+}
+
+impl<T: WidgetList, P: ScrollPhysics> WidgetEvent for ListView<T, P> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        let size = ctx.size();
+        let direction = self.scroll_direction;
+        let viewport = Rect::from_origin_size(ctx.offset(), size);
+
+        ctx.wstate().press(event, viewport);
+
+        let scroll_offset = ctx.wstate().scroll_offset();
+
+        if let Some(event) = event.transform_scroll(scroll_offset, viewport) {
+            // Only visible children can be hit.
+            let visible = {
+                let state = ctx.rstate();
+                let visible = state
+                    .extents
+                    .visible(direction.along(scroll_offset), direction.extent(size));
+
+                visible.start.max(state.laid_out.start)..visible.end.min(state.laid_out.end)
+            };
+
+            for mut child in ctx.children().skip(visible.start).take(visible.len()) {
+                child.handle_event(&event);
+            }
+        }
+
+        let content_size = self.content_size(&ctx.rstate().extents, size);
+        let needs_paint =
+            ctx.wstate()
+                .handle_event(event, &self.physics, direction, viewport, content_size);
+
+        if needs_paint {
+            let scroll_offset = ctx.wstate().scroll_offset();
+            let needs_layout = self.needs_layout(&ctx.rstate(), scroll_offset, size);
+
+            match needs_layout {
+                true => ctx.mark_needs_layout(),
+                false => ctx.mark_needs_paint(),
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc};
+
+    use frui::{
+        app::testing::WidgetTester,
+        druid_shell::{Modifiers, MouseButton, MouseButtons, MouseEvent},
+    };
+
+    use super::*;
+
+    fn extents(extents: &[f64]) -> ListExtents {
+        let mut cache = ListExtents::default();
+        cache.resize(extents.len());
+
+        for (n, extent) in extents.iter().enumerate() {
+            cache.set(n, *extent);
+        }
+
+        cache
+    }
+
+    #[test]
+    fn changed_extent_shifts_only_following_offsets() {
+        let mut cache = extents(&[10.; 100]);
+        assert_eq!(cache.total_extent(), 1000.);
+        assert_eq!(cache.visible(95., 20.), 9..12);
+
+        cache.set(3, 30.);
+        assert_eq!((cache.offset(3), cache.offset(4)), (30., 60.));
+        assert_eq!(cache.total_extent(), 1020.);
+        assert_eq!(cache.visible(95., 20.), 7..10);
+
+        // Scrolled past the end, the last child is still considered visible.
+        assert_eq!(cache.visible(2000., 20.), 99..100);
+
+        cache.resize(2);
+        assert_eq!(cache.total_extent(), 20.);
+        assert_eq!(extents(&[]).visible(0., 20.), 0..0);
+    }
+
+    #[test]
+    fn children_not_laid_out_take_extent_of_first_one() {
+        let mut cache = ListExtents::default();
+        cache.resize(100);
+        assert_eq!(cache.total_extent(), 0.);
+
+        cache.set(0, 20.);
+        assert_eq!(cache.total_extent(), 2000.);
+
+        cache.set(1, 10.);
+        assert_eq!((cache.offset(2), cache.total_extent()), (30., 1990.));
+    }
+
+    /// Child of a fixed height, counting its layouts.
+    #[derive(LeafWidget)]
+    struct Item {
+        height: f64,
+        layouts: Rc<Cell<usize>>,
+    }
+
+    impl LeafWidget for Item {
+        fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+            self.layouts.set(self.layouts.get() + 1);
+            constraints.constrain(Size::new(50., self.height))
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    fn list(len: usize, layouts: &Rc<Cell<usize>>) -> impl Widget {
+        let layouts = layouts.clone();
+
+        ListView::builder().children_iter((0..len).map(move |_| Item {
+            height: 10.,
+            layouts: layouts.clone(),
+        }))
+    }
+
+    fn wheel(delta: f64) -> Event {
+        Event::MouseWheel(MouseEvent {
+            pos: Point::new(50., 50.),
+            buttons: MouseButtons::new(),
+            mods: Modifiers::default(),
+            count: 0,
+            focus: false,
+            button: MouseButton::None,
+            wheel_delta: Vec2::new(0., delta),
+        })
+    }
+
+    #[test]
+    fn only_children_near_viewport_are_laid_out() {
+        let layouts = Rc::new(Cell::new(0));
+        let mut tester = WidgetTester::new(list(1000, &layouts), Size::new(100., 100.));
+
+        // Viewport and the cache extent after it.
+        assert_eq!(layouts.get(), 35);
+        assert_eq!(tester.size(), Size::new(100., 100.));
+
+        tester.handle_event(wheel(500.));
+        tester.pump();
+
+        // Children from the cache extent before the viewport, of which 35
+        // were laid out already.
+        assert_eq!(layouts.get(), 85);
+    }
+
+    #[test]
+    fn list_is_as_large_as_its_content_within_unbounded_constraints() {
+        let layouts = Rc::new(Cell::new(0));
+        let mut tester = WidgetTester::new(list(100, &layouts), Size::new(100., 100.));
+
+        tester.layout(Constraints {
+            min_width: 0.,
+            max_width: 100.,
+            min_height: 0.,
+            max_height: f64::INFINITY,
+        });
+
+        assert_eq!(tester.size(), Size::new(100., 1000.));
+        assert_eq!(layouts.get(), 100);
+    }
+}
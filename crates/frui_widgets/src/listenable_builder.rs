@@ -0,0 +1,52 @@
+use frui::prelude::*;
+
+/// Builds its child with `builder`, building it again whenever `listenable`
+/// notifies its listeners.
+///
+/// ```
+/// # use frui::prelude::*;
+/// # use frui_widgets::*;
+/// let count = ValueNotifier::new(0);
+///
+/// ListenableBuilder::new(count.clone(), move || Text::new(count.get().to_string()));
+/// ```
+///
+/// Widget listens to `listenable` while it is mounted. If it is rebuilt with a
+/// different listenable, it stops listening to the previous one.
+#[derive(ViewWidget)]
+pub struct ListenableBuilder<L: Listenable, W: Widget, F: Fn() -> W> {
+    pub listenable: L,
+    pub builder: F,
+}
+
+impl<L: Listenable, W: Widget, F: Fn() -> W> ListenableBuilder<L, W, F> {
+    pub fn new(listenable: L, builder: F) -> Self {
+        Self {
+            listenable,
+            builder,
+        }
+    }
+}
+
+impl<L: Listenable, W: Widget, F: Fn() -> W> WidgetState for ListenableBuilder<L, W, F> {
+    type State = ();
+
+    fn create_state(&self) -> Self::State {}
+
+    // Updated widget is unmounted with its previous configuration and mounted
+    // with the new one, which moves the listener to the new listenable.
+
+    fn mount(&self, ctx: BuildContext<Self>) {
+        self.listenable.add_listener(ctx.listener());
+    }
+
+    fn unmount(&self, ctx: BuildContext<Self>) {
+        self.listenable.remove_listener(&ctx.listener());
+    }
+}
+
+impl<L: Listenable, W: Widget, F: Fn() -> W> ViewWidget for ListenableBuilder<L, W, F> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        (self.builder)()
+    }
+}
@@ -0,0 +1,143 @@
+use frui::{
+    api::events::{Event, WidgetEvent},
+    prelude::*,
+};
+
+/// Minimal size of a touch target recommended by most platform guidelines.
+pub const MIN_TOUCH_TARGET_SIZE: Size = Size {
+    width: 44.,
+    height: 44.,
+};
+
+/// Makes its child hittable within an area of at least `min_size`, centering
+/// the child in it if the child is smaller.
+///
+/// Only ever inflates: a child larger than `min_size` keeps its size and this
+/// widget takes it. The extra area doesn't exceed the incoming constraints and
+/// isn't painted, but pointer events within it are delivered to the child at
+/// the closest point of its bounds, so that e.g. a small checkbox is easier to
+/// tap.
+#[derive(SingleChildWidget)]
+pub struct MinTouchTarget<W: Widget> {
+    pub min_size: Size,
+    pub child: W,
+}
+
+impl<W: Widget> MinTouchTarget<W> {
+    pub fn new(child: W) -> Self {
+        Self {
+            min_size: MIN_TOUCH_TARGET_SIZE,
+            child,
+        }
+    }
+
+    pub fn min_size(mut self, min_size: Size) -> Self {
+        self.min_size = min_size;
+        self
+    }
+}
+
+impl<W: Widget> SingleChildWidget for MinTouchTarget<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let child_size = ctx.child().layout(constraints.loosen());
+        let (size, child_offset) = inflate(self.min_size, constraints, child_size);
+
+        ctx.child().set_offset(child_offset);
+
+        size
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
+    }
+}
+
+impl<W: Widget> WidgetEvent for MinTouchTarget<W> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        let bounds = Rect::from_origin_size(ctx.offset(), ctx.size());
+        let child_bounds =
+            Rect::from_origin_size(ctx.offset() + ctx.child().offset(), ctx.child().size());
+
+        let event = match event.pointer_pos() {
+            Some(pos) if bounds.contains(pos) => {
+                let target = closest_point(child_bounds, pos);
+                event.transform(Affine::translate(target - pos))
+            }
+            _ => event.clone(),
+        };
+
+        ctx.child().handle_event(&event);
+
+        true
+    }
+}
+
+/// Size of a touch target of at least `min_size` around a child of
+/// `child_size`, and the offset of the child centered within it.
+fn inflate(min_size: Size, constraints: Constraints, child_size: Size) -> (Size, Offset) {
+    let size = Size {
+        width: child_size
+            .width
+            .max(min_size.width.min(constraints.max_width)),
+        height: child_size
+            .height
+            .max(min_size.height.min(constraints.max_height)),
+    };
+    let size = constraints.constrain(size);
+
+    let offset = Offset {
+        x: (size.width - child_size.width) / 2.,
+        y: (size.height - child_size.height) / 2.,
+    };
+
+    (size, offset)
+}
+
+/// Point within `rect` closest to `pos`.
+///
+/// Right and bottom edges don't belong to a rectangle (see [`Rect::contains`]),
+/// so points past them are moved just before them.
+fn closest_point(rect: Rect, pos: Point) -> Point {
+    let clamp = |v: f64, min: f64, max: f64| v.clamp(min, max.next_down().max(min));
+
+    Point::new(
+        clamp(pos.x, rect.x0, rect.x1),
+        clamp(pos.y, rect.y0, rect.y1),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn small_child_is_centered_in_inflated_hit_area() {
+        let loose = Constraints::loose(Size::new(100., 100.));
+
+        let (size, Offset { x, y }) = inflate(MIN_TOUCH_TARGET_SIZE, loose, Size::new(20., 50.));
+        assert_eq!(size, Size::new(44., 50.));
+        assert_eq!((x, y), (12., 0.));
+
+        // Never inflates past the incoming constraints.
+        let narrow = Constraints::loose(Size::new(30., 100.));
+        let (size, _) = inflate(MIN_TOUCH_TARGET_SIZE, narrow, Size::new(20., 20.));
+        assert_eq!(size, Size::new(30., 44.));
+
+        // Pointer in the extra area hits the child at its closest point.
+        let child = Rect::new(12., 12., 32., 32.);
+        assert!(child.contains(closest_point(child, Point::new(40., 20.))));
+        assert!(child.contains(closest_point(child, Point::new(0., 0.))));
+        assert_eq!(
+            closest_point(child, Point::new(20., 0.)),
+            Point::new(20., 12.)
+        );
+        assert_eq!(
+            closest_point(child, Point::new(20., 20.)),
+            Point::new(20., 20.)
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use frui::{
+    app::listeners::{
+        keyboard::KeyboardState,
+        shortcuts::{ShortcutScope, ShortcutScopeKey, SHORTCUT_SCOPES},
+    },
+    prelude::*,
+};
+
+use crate::{Enabled, Text, TextRenderState};
+
+/// Label with a keyboard mnemonic, e.g. of a menu item or a button.
+///
+/// Character following the first `&` in `label` is the mnemonic, and `&&` is
+/// displayed as a single `&`:
+///
+/// ```
+/// # use frui::prelude::*;
+/// # use frui_widgets::*;
+/// # fn open_file_menu() {}
+/// # fn exit() {}
+/// MnemonicText::new("&File", || open_file_menu());
+/// MnemonicText::new("Save && E&xit", || exit());
+/// ```
+///
+/// While Alt is held, the mnemonic is underlined. Pressing Alt along with it
+/// (regardless of its case) calls `on_activate`, even if nothing is focused.
+/// If several mounted labels share a mnemonic, the most recently mounted one is
+/// activated, so that mnemonics of a newly opened menu win over those behind
+/// it. Mnemonics of labels disabled by [`Enabled`] are ignored.
+#[derive(LeafWidget)]
+pub struct MnemonicText<F: Fn()> {
+    text: Text<String>,
+    /// Byte offset of the mnemonic in the displayed text, and the mnemonic.
+    mnemonic: Option<(usize, char)>,
+    on_activate: F,
+}
+
+impl<F: Fn()> MnemonicText<F> {
+    pub fn new(label: impl AsRef<str>, on_activate: F) -> Self {
+        let (text, mnemonic) = parse_mnemonic(label.as_ref());
+
+        Self {
+            text: Text::new(text),
+            mnemonic,
+            on_activate,
+        }
+    }
+
+    pub fn size(mut self, size: f64) -> Self {
+        self.text = self.text.size(size);
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.text = self.text.color(color);
+        self
+    }
+
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.text = self.text.weight(weight);
+        self
+    }
+
+    pub fn font(mut self, font: FontFamily) -> Self {
+        self.text = self.text.font(font);
+        self
+    }
+}
+
+/// Text of `label` with `&` markers removed, along with the position of the
+/// first marked character in that text and the character itself.
+fn parse_mnemonic(label: &str) -> (String, Option<(usize, char)>) {
+    let mut text = String::with_capacity(label.len());
+    let mut mnemonic = None;
+    let mut chars = label.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek().copied()) {
+            ('&', Some('&')) => {
+                chars.next();
+                text.push('&');
+            }
+            ('&', Some(marked)) if !marked.is_whitespace() => {
+                chars.next();
+
+                if mnemonic.is_none() {
+                    mnemonic = Some((text.len(), marked));
+                }
+
+                text.push(marked);
+            }
+            // `&` followed by whitespace or at the end doesn't mark anything.
+            (c, _) => text.push(c),
+        }
+    }
+
+    (text, mnemonic)
+}
+
+impl<F: Fn()> ShortcutScope for MnemonicText<F> {
+    fn mnemonic(&self) -> Option<char> {
+        self.mnemonic.map(|(_, mnemonic)| mnemonic)
+    }
+
+    fn activate_mnemonic(&self) {
+        (self.on_activate)()
+    }
+}
+
+impl<F: Fn()> WidgetState for MnemonicText<F> {
+    type State = Option<ShortcutScopeKey>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+
+    // Label is registered only while it's enabled, which is first checked
+    // after the first build.
+
+    fn did_update_widget(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = *ctx.state() {
+            SHORTCUT_SCOPES.with(|scopes| unsafe {
+                scopes.borrow_mut().update(&key, self as &dyn ShortcutScope)
+            });
+        }
+    }
+
+    fn did_change_dependencies(&self, ctx: BuildContext<Self>) {
+        let enabled = Enabled::of(ctx);
+        let mut key = ctx.state_mut();
+
+        match (enabled, *key) {
+            (true, None) => {
+                *key = Some(SHORTCUT_SCOPES.with(|scopes| unsafe {
+                    scopes.borrow_mut().register(self as &dyn ShortcutScope)
+                }));
+            }
+            (false, Some(registered)) => {
+                SHORTCUT_SCOPES.with(|scopes| scopes.borrow_mut().unregister(&registered));
+                *key = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn dispose(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = ctx.state_mut().take() {
+            SHORTCUT_SCOPES.with(|scopes| scopes.borrow_mut().unregister(&key));
+        }
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl<F: Fn()> RenderState for MnemonicText<F> {
+    type State = TextRenderState;
+
+    fn create_state(&self) -> Self::State {
+        TextRenderState::empty()
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl<F: Fn()> LeafWidget for MnemonicText<F> {
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.depend_on_font(&self.text.font_family);
+
+        let state = self
+            .text
+            .layout_lines(self.text.font_size, constraints.max().width);
+        let size = state.size(constraints);
+
+        *ctx.rstate_mut() = state;
+
+        size
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let state = ctx.rstate();
+        state.paint(ctx.size(), canvas);
+
+        if let Some((start, mnemonic)) = self.mnemonic {
+            if KeyboardState::with(KeyboardState::is_alt_held) {
+                let thickness = (self.text.font_size / 14.).max(1.);
+                let range = start..start + mnemonic.len_utf8();
+
+                let underline = state.underline(range, thickness);
+                let brush = &canvas.solid_brush(self.text.font_color.clone());
+
+                PietRenderContext::fill(canvas, underline, brush);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "miri")]
+impl<F: Fn()> LeafWidget for MnemonicText<F> {
+    fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+        constraints.biggest()
+    }
+
+    fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn first_marked_char_is_mnemonic_and_double_ampersand_is_literal() {
+        assert_eq!(parse_mnemonic("&File"), ("File".into(), Some((0, 'F'))));
+        assert_eq!(
+            parse_mnemonic("Save && E&xit"),
+            ("Save & Exit".into(), Some((8, 'x')))
+        );
+        // Only the first marked char is the mnemonic.
+        assert_eq!(parse_mnemonic("&a&b"), ("ab".into(), Some((0, 'a'))));
+        assert_eq!(
+            parse_mnemonic("Zapisz &żółć"),
+            ("Zapisz żółć".into(), Some((7, 'ż')))
+        );
+
+        assert_eq!(parse_mnemonic("&&"), ("&".into(), None));
+        assert_eq!(parse_mnemonic("A & B&"), ("A & B&".into(), None));
+    }
+}
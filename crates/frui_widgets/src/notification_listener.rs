@@ -0,0 +1,124 @@
+use std::{any::Any, marker::PhantomData};
+
+use frui::{
+    api::notifications::{Notification, WidgetNotification},
+    prelude::*,
+};
+
+/// Calls `on_notification` for every notification of type `N` dispatched by
+/// its descendants.
+///
+/// If `on_notification` returns `true`, the notification stops bubbling and
+/// won't reach listeners further up the tree. Notifications of other types are
+/// passed through.
+#[derive(SingleChildWidget)]
+pub struct NotificationListener<N: Notification, F: Fn(&N) -> bool, W: Widget> {
+    pub on_notification: F,
+    pub child: W,
+    _p: PhantomData<N>,
+}
+
+impl<N: Notification, F: Fn(&N) -> bool, W: Widget> NotificationListener<N, F, W> {
+    pub fn new(on_notification: F, child: W) -> Self {
+        Self {
+            on_notification,
+            child,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<N, F, W> WidgetNotification for NotificationListener<N, F, W>
+where
+    N: Notification,
+    F: Fn(&N) -> bool,
+    W: Widget,
+{
+    fn handle_notification(&self, notification: &dyn Any) -> bool {
+        match notification.downcast_ref::<N>() {
+            Some(notification) => (self.on_notification)(notification),
+            None => false,
+        }
+    }
+}
+
+impl<N, F, W> SingleChildWidget for NotificationListener<N, F, W>
+where
+    N: Notification,
+    F: Fn(&N) -> bool,
+    W: Widget,
+{
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use frui::app::testing::WidgetTester;
+
+    use super::*;
+
+    struct Ping;
+
+    impl Notification for Ping {}
+
+    struct Other;
+
+    impl Notification for Other {}
+
+    /// Dispatches [`Ping`] whenever it's laid out.
+    #[derive(LeafWidget)]
+    struct Pinger;
+
+    impl LeafWidget for Pinger {
+        fn layout(&self, ctx: RenderContext<Self>, _: Constraints) -> Size {
+            ctx.dispatch_notification(Ping);
+            Size::default()
+        }
+
+        fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+    }
+
+    /// Names of the listeners which received a [`Ping`] dispatched below all
+    /// of them, in the order they received it.
+    fn listeners_reached(middle_stops: bool) -> Vec<&'static str> {
+        let reached = Rc::new(RefCell::new(Vec::new()));
+
+        let listener = |name, stops| {
+            let reached = reached.clone();
+
+            move |_: &Ping| {
+                reached.borrow_mut().push(name);
+                stops
+            }
+        };
+
+        let tree = NotificationListener::new(
+            listener("outer", false),
+            NotificationListener::new(
+                listener("middle", middle_stops),
+                NotificationListener::new(
+                    |_: &Other| -> bool { panic!("received notification of another type") },
+                    NotificationListener::new(listener("inner", false), Pinger),
+                ),
+            ),
+        );
+
+        WidgetTester::new(tree, Size::new(100., 100.));
+
+        reached.take()
+    }
+
+    #[test]
+    fn notification_bubbles_through_every_ancestor() {
+        assert_eq!(listeners_reached(false), ["inner", "middle", "outer"]);
+    }
+
+    #[test]
+    fn handled_notification_stops_bubbling() {
+        assert_eq!(listeners_reached(true), ["inner", "middle"]);
+    }
+}
@@ -0,0 +1,549 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use frui::{
+    api::events::{Event, WidgetEvent},
+    prelude::*,
+};
+
+use crate::{widget_list::WidgetList, AnimationController, Curve};
+
+const SETTLE_DURATION: Duration = Duration::from_millis(300);
+
+/// Horizontal velocity (in pixels per second) above which releasing a drag
+/// advances to the next page regardless of the dragged distance.
+const FLING_VELOCITY: f64 = 400.0;
+
+/// Distance the pointer can travel before a press becomes a drag.
+const DRAG_SLOP: f64 = 8.0;
+
+struct PageDrag {
+    start_x: f64,
+    start_position: f64,
+    velocity: VelocityTracker,
+    is_dragging: bool,
+}
+
+struct Settle {
+    from: f64,
+    animation: AnimationController,
+}
+
+struct PageState {
+    /// Page that the view is showing or settling to. Not wrapped if looping.
+    page: isize,
+    /// Currently displayed position in pages.
+    position: f64,
+    settle: Option<Settle>,
+    drag: Option<PageDrag>,
+    /// Page reported through `on_page_changed` most recently.
+    reported_page: usize,
+    /// Number of pages as of the last layout.
+    page_count: usize,
+    looping: bool,
+}
+
+impl PageState {
+    fn current_page(&self) -> usize {
+        self.wrap(self.page)
+    }
+
+    fn wrap(&self, page: isize) -> usize {
+        if self.page_count == 0 {
+            0
+        } else {
+            page.rem_euclid(self.page_count as isize) as usize
+        }
+    }
+
+    fn clamp_page(&self, page: isize) -> isize {
+        // Number of pages is not known until the first layout, which clamps
+        // the page again.
+        if self.looping || self.page_count == 0 {
+            page
+        } else {
+            page.clamp(0, self.page_count.saturating_sub(1) as isize)
+        }
+    }
+
+    fn clamp_position(&self, position: f64) -> f64 {
+        if self.looping || self.page_count == 0 {
+            position
+        } else {
+            position.clamp(0., self.page_count.saturating_sub(1) as f64)
+        }
+    }
+
+    fn settle_to(&mut self, page: isize) {
+        self.page = self.clamp_page(page);
+        self.drag = None;
+
+        let mut animation = AnimationController::new(SETTLE_DURATION);
+        animation.forward();
+
+        self.settle = Some(Settle {
+            from: self.position,
+            animation,
+        });
+    }
+
+    /// Advances the settle animation. Returns `true` if it's still running.
+    fn update_position(&mut self) -> bool {
+        if let Some(settle) = &self.settle {
+            let t = Curve::EaseOut.transform(settle.animation.value());
+            let is_animating = settle.animation.is_animating();
+
+            self.position = settle.from + (self.page as f64 - settle.from) * t;
+
+            if !is_animating {
+                self.position = self.page as f64;
+                self.settle = None;
+            }
+
+            is_animating
+        } else {
+            false
+        }
+    }
+}
+
+/// Controls which page is displayed by a [`PageView`].
+///
+/// Controller is [`Listenable`], notifying its listeners whenever the current
+/// page of its page view changes.
+#[derive(Clone)]
+pub struct PageController {
+    inner: Rc<RefCell<PageState>>,
+    listeners: Listeners,
+}
+
+impl PageController {
+    pub fn new(initial_page: usize) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(PageState {
+                page: initial_page as isize,
+                position: initial_page as f64,
+                settle: None,
+                drag: None,
+                reported_page: initial_page,
+                page_count: 0,
+                looping: false,
+            })),
+            listeners: Listeners::new(),
+        }
+    }
+
+    /// Page that is displayed or that the view is currently settling to.
+    pub fn current_page(&self) -> usize {
+        self.inner.borrow().current_page()
+    }
+
+    /// Currently displayed position in pages. It's fractional while the view
+    /// is being dragged or is settling to a page.
+    pub fn position(&self) -> f64 {
+        self.inner.borrow().position
+    }
+
+    /// Animates to the page `n`.
+    ///
+    /// The page view should be repainted after this call, e.g. by calling it in
+    /// a callback which updates some state.
+    pub fn animate_to_page(&self, n: usize) {
+        let mut inner = self.inner.borrow_mut();
+
+        // When looping, take the shortest path to the target page.
+        let target = if inner.looping && inner.page_count > 0 {
+            let count = inner.page_count as isize;
+            let delta = (n as isize - inner.current_page() as isize).rem_euclid(count);
+            let delta = if delta > count / 2 {
+                delta - count
+            } else {
+                delta
+            };
+            inner.page + delta
+        } else {
+            n as isize
+        };
+
+        inner.settle_to(target);
+    }
+
+    /// Changes the page to `n` without animating.
+    pub fn jump_to_page(&self, n: usize) {
+        let mut inner = self.inner.borrow_mut();
+
+        inner.page = inner.clamp_page(n as isize);
+        inner.position = inner.page as f64;
+        inner.settle = None;
+        inner.drag = None;
+    }
+}
+
+impl Listenable for PageController {
+    fn add_listener(&self, listener: Listener) {
+        self.listeners.add(listener)
+    }
+
+    fn remove_listener(&self, listener: &Listener) {
+        self.listeners.remove(listener)
+    }
+}
+
+impl Default for PageController {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Displays one of its children at a time, each filling the whole viewport.
+///
+/// Pages can be changed by dragging them horizontally or through the provided
+/// [`PageController`]. When released, the view snaps to the nearest page, or to
+/// the adjacent page in the direction of the drag if it was fast enough.
+///
+/// Only pages close to the current one are laid out, and only the visible ones
+/// are painted.
+#[derive(MultiChildWidget)]
+pub struct PageView<T: WidgetList, F: Fn(usize)> {
+    pub controller: PageController,
+    pub children: T,
+    pub on_page_changed: F,
+    /// Whether the last page should be followed by the first one.
+    pub looping: bool,
+}
+
+impl<T: WidgetList> PageView<T, fn(usize)> {
+    pub fn new(controller: PageController, children: T) -> Self {
+        Self {
+            controller,
+            children,
+            on_page_changed: |_| {},
+            looping: false,
+        }
+    }
+}
+
+impl<T: WidgetList, F: Fn(usize)> PageView<T, F> {
+    /// Called with the index of the new page after it has been changed.
+    pub fn on_page_changed<F2: Fn(usize)>(self, on_page_changed: F2) -> PageView<T, F2> {
+        PageView {
+            controller: self.controller,
+            children: self.children,
+            on_page_changed,
+            looping: self.looping,
+        }
+    }
+
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Indices of pages visible at the current position with their horizontal
+    /// offsets in pages relative to the viewport.
+    fn visible_pages(&self) -> Vec<(usize, f64)> {
+        self.pages_within(1.)
+    }
+
+    /// Indices of pages closer than `distance` pages to the current position,
+    /// with their horizontal offsets in pages relative to the viewport.
+    fn pages_within(&self, distance: f64) -> Vec<(usize, f64)> {
+        let inner = self.controller.inner.borrow();
+
+        if inner.page_count == 0 {
+            return Vec::new();
+        }
+
+        let first = inner.position.floor() as isize;
+        let pages = distance.ceil() as isize;
+
+        (first - pages..=first + pages)
+            .filter(|page| inner.looping || (0..inner.page_count as isize).contains(page))
+            .map(|page| (inner.wrap(page), page as f64 - inner.position))
+            .filter(|(_, offset)| offset.abs() < distance)
+            .collect()
+    }
+}
+
+#[doc(hidden)]
+pub struct PageViewRenderState {
+    /// Pages laid out during the last layout.
+    laid_out: Vec<usize>,
+}
+
+impl<T: WidgetList, F: Fn(usize)> RenderState for PageView<T, F> {
+    type State = PageViewRenderState;
+
+    fn create_state(&self) -> Self::State {
+        PageViewRenderState {
+            laid_out: Vec::new(),
+        }
+    }
+}
+
+impl<T: WidgetList, F: Fn(usize)> MultiChildWidget for PageView<T, F> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        self.children.get()
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let size = constraints.biggest();
+
+        {
+            let mut inner = self.controller.inner.borrow_mut();
+
+            inner.looping = self.looping;
+            inner.page_count = ctx.children().len();
+
+            // Number of pages could have changed.
+            let page = inner.clamp_page(inner.page);
+            if page != inner.page {
+                inner.page = page;
+                inner.position = inner.clamp_position(inner.position);
+            }
+
+            inner.update_position();
+        }
+
+        // Pages which can become visible before the next layout, since paint
+        // moves pages on its own while they are dragged or settle.
+        let mut pages = self
+            .pages_within(2.)
+            .into_iter()
+            .map(|(page, _)| page)
+            .collect::<Vec<_>>();
+
+        // Make sure the current page is laid out even if it's not visible yet,
+        // so that it can be painted right away.
+        pages.push(self.controller.current_page());
+
+        for (n, mut child) in ctx.children().enumerate() {
+            if pages.contains(&n) {
+                child.layout(Constraints::tight(size));
+            }
+        }
+
+        ctx.rstate_mut().laid_out = pages;
+
+        size
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let width = ctx.size().width;
+
+        let is_animating = self.controller.inner.borrow_mut().update_position();
+
+        let visible_pages = self.visible_pages();
+        let laid_out = ctx.rstate().laid_out.clone();
+
+        let bounds = Rect::from_origin_size(Point::ORIGIN, ctx.size());
+
+        canvas.with_clip(bounds, |canvas| {
+            for &(page, page_offset) in &visible_pages {
+                // Page which wasn't laid out is painted once it is.
+                if !laid_out.contains(&page) {
+                    continue;
+                }
+
+                if let Some(mut child) = ctx.children().nth(page) {
+                    let x = page_offset * width;
+                    child.paint_at(canvas, &Offset { x, y: 0. });
+                }
+            }
+        });
+
+        let changed_page = {
+            let mut inner = self.controller.inner.borrow_mut();
+            let current_page = inner.current_page();
+
+            if inner.reported_page != current_page {
+                inner.reported_page = current_page;
+                Some(current_page)
+            } else {
+                None
+            }
+        };
+
+        if let Some(page) = changed_page {
+            (self.on_page_changed)(page);
+            self.controller.listeners.notify();
+        }
+
+        // Pages are laid out again only once another page comes close enough
+        // to the viewport, otherwise they are just moved while painting.
+        let pages_changed = visible_pages
+            .iter()
+            .any(|(page, _)| !laid_out.contains(page));
+
+        if pages_changed {
+            ctx.mark_needs_layout();
+        } else if is_animating {
+            ctx.mark_needs_paint();
+        }
+    }
+}
+
+impl<T: WidgetList, F: Fn(usize)> WidgetEvent for PageView<T, F> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        let width = ctx.size().width;
+        let mut inner = self.controller.inner.borrow_mut();
+
+        match event {
+            Event::MouseDown(e) => {
+                if let MouseButton::Left = e.button {
+                    if ctx.point_in_layout_bounds(e.pos) {
+                        let mut velocity = VelocityTracker::new();
+                        velocity.add_position(Instant::now(), e.pos);
+
+                        inner.drag = Some(PageDrag {
+                            start_x: e.pos.x,
+                            start_position: inner.position,
+                            velocity,
+                            is_dragging: false,
+                        });
+                        ctx.capture_pointer();
+                    }
+                }
+            }
+            Event::MouseMove(e) => {
+                if let Some(drag) = &mut inner.drag {
+                    drag.velocity.add_position(Instant::now(), e.pos);
+
+                    if (e.pos.x - drag.start_x).abs() > DRAG_SLOP {
+                        drag.is_dragging = true;
+                    }
+
+                    if drag.is_dragging && width > 0. {
+                        let position = drag.start_position - (e.pos.x - drag.start_x) / width;
+
+                        inner.position = inner.clamp_position(position);
+                        inner.settle = None;
+
+                        drop(inner);
+                        ctx.mark_needs_paint();
+                        return true;
+                    }
+                }
+            }
+            Event::MouseUp(e) => {
+                if let MouseButton::Left = e.button {
+                    if let Some(drag) = inner.drag.take() {
+                        if drag.is_dragging {
+                            let start_page = drag.start_position.round() as isize;
+
+                            let velocity = drag.velocity.velocity(Instant::now()).x;
+
+                            let target = if velocity <= -FLING_VELOCITY {
+                                start_page + 1
+                            } else if velocity >= FLING_VELOCITY {
+                                start_page - 1
+                            } else {
+                                inner.position.round() as isize
+                            };
+
+                            inner.settle_to(target);
+
+                            drop(inner);
+                            ctx.mark_needs_paint();
+                            return true;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        // Don't let pages react to the pointer while they are dragged.
+        matches!(&inner.drag, Some(drag) if drag.is_dragging)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use frui::app::testing::WidgetTester;
+
+    use super::*;
+
+    /// Passes its constraints to its child, counting its layouts.
+    #[derive(SingleChildWidget)]
+    struct Layouts<W: Widget> {
+        count: Rc<Cell<usize>>,
+        child: W,
+    }
+
+    impl<W: Widget> SingleChildWidget for Layouts<W> {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+            &self.child
+        }
+
+        fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+            self.count.set(self.count.get() + 1);
+            ctx.child().layout(constraints)
+        }
+    }
+
+    fn controller(page_count: usize, looping: bool) -> PageController {
+        let controller = PageController::new(0);
+        controller.inner.borrow_mut().page_count = page_count;
+        controller.inner.borrow_mut().looping = looping;
+        controller
+    }
+
+    #[test]
+    fn pages_are_clamped_without_looping() {
+        let controller = controller(3, false);
+
+        controller.animate_to_page(10);
+        assert_eq!(controller.current_page(), 2);
+
+        controller.jump_to_page(1);
+        assert_eq!(controller.current_page(), 1);
+        assert_eq!(controller.inner.borrow().position, 1.);
+    }
+
+    #[test]
+    fn looping_takes_shortest_path() {
+        let controller = controller(4, true);
+
+        controller.animate_to_page(3);
+        assert_eq!(controller.current_page(), 3);
+        assert_eq!(controller.inner.borrow().page, -1);
+    }
+
+    #[test]
+    fn settling_to_adjacent_page_only_repaints() {
+        let controller = PageController::new(0);
+        let layouts = Rc::new(Cell::new(0));
+
+        let pages = PageView::new(controller.clone(), ((), (), ()));
+        let mut tester = WidgetTester::new(
+            Layouts {
+                count: layouts.clone(),
+                child: pages,
+            },
+            Size::new(100., 100.),
+        );
+        tester.paint();
+
+        let laid_out = layouts.get();
+        controller.animate_to_page(1);
+
+        let settled = (0..100).any(|_| {
+            std::thread::sleep(Duration::from_millis(10));
+
+            tester.take_scheduled_frame();
+            tester.pump();
+            tester.paint();
+            !tester.take_scheduled_frame()
+        });
+
+        assert!(settled);
+        assert_eq!(controller.position(), 1.);
+        assert_eq!(layouts.get(), laid_out);
+    }
+}
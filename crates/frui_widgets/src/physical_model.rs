@@ -0,0 +1,153 @@
+use frui::prelude::*;
+
+use crate::{RoundedRectangleBorder, ShapeBorder};
+
+const DEFAULT_SHADOW_COLOR: Color = Color::rgba8(0, 0, 0, 0x60);
+/// Number of increasingly larger copies of the shape its blurred shadow is
+/// made of.
+const SHADOW_LAYERS: usize = 6;
+
+/// Shadow cast by a [`PhysicalModel`] raised to some elevation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+    /// Offset of the shadow from the shape casting it.
+    pub offset: Vec2,
+    pub blur_radius: f64,
+}
+
+impl Shadow {
+    /// Shadow cast at `elevation` by a light above the top of the window, or
+    /// `None` if the widget isn't raised.
+    ///
+    /// Both the offset and the blur grow with the elevation, so that widgets
+    /// raised higher cast softer shadows farther below them.
+    pub fn for_elevation(elevation: f64) -> Option<Shadow> {
+        (elevation > 0.).then(|| Shadow {
+            offset: Vec2::new(0., elevation / 2.),
+            blur_radius: elevation,
+        })
+    }
+}
+
+/// Paints its child on a `color` background in the shape of `shape`, raised to
+/// `elevation` and casting a shadow of that shape below it. Child is clipped
+/// to that shape.
+///
+/// ```
+/// # use frui::prelude::*;
+/// # use frui_widgets::*;
+/// # let (content, icon) = (Text::new("Content"), Text::new("+"));
+/// let card = PhysicalModel::new(8., Color::WHITE, content).border_radius(4.);
+/// let button = PhysicalModel::new(6., Color::BLUE, icon).shape(CircleBorder::default());
+/// ```
+///
+/// Shadow is painted right before the widget, so overlapping widgets are
+/// layered in the order they are painted. In a [`Stack`](crate::Stack), list
+/// children with higher elevation last to have them (and their shadows) above
+/// the lower ones. Zero elevation paints no shadow.
+#[derive(SingleChildWidget)]
+pub struct PhysicalModel<W: Widget> {
+    pub elevation: f64,
+    pub color: Color,
+    pub shadow_color: Color,
+    pub shape: Box<dyn ShapeBorder>,
+    pub child: W,
+}
+
+impl<W: Widget> PhysicalModel<W> {
+    #[track_caller]
+    pub fn new(elevation: f64, color: Color, child: W) -> Self {
+        assert!(elevation >= 0.0, "elevation must be >= 0.0");
+
+        Self {
+            elevation,
+            color,
+            shadow_color: DEFAULT_SHADOW_COLOR,
+            shape: Box::new(RoundedRectangleBorder::new(0.)),
+            child,
+        }
+    }
+
+    pub fn shadow_color(mut self, color: Color) -> Self {
+        self.shadow_color = color;
+        self
+    }
+
+    pub fn shape(mut self, shape: impl ShapeBorder + 'static) -> Self {
+        self.shape = Box::new(shape);
+        self
+    }
+
+    /// Shorthand for a [`shape`](PhysicalModel::shape) of
+    /// [`RoundedRectangleBorder`].
+    #[track_caller]
+    pub fn border_radius(self, radius: f64) -> Self {
+        assert!(radius >= 0.0, "border_radius must be >= 0.0");
+        self.shape(RoundedRectangleBorder::new(radius))
+    }
+
+    /// Paints the shadow of the shape filling `bounds`.
+    ///
+    /// Blur is approximated by translucent copies of the shape spread over the
+    /// blur radius around its edge, so that the shadow keeps the shape of any border.
+    fn paint_shadow(&self, canvas: &mut PaintContext, bounds: Rect, shadow: Shadow) {
+        let (.., alpha) = self.shadow_color.as_rgba();
+        let color = self
+            .shadow_color
+            .clone()
+            .with_alpha(alpha / SHADOW_LAYERS as f64);
+        let brush = &canvas.solid_brush(color);
+
+        let bounds = bounds + shadow.offset;
+
+        for layer in 0..SHADOW_LAYERS {
+            let spread = shadow.blur_radius * ((layer as f64 + 0.5) / SHADOW_LAYERS as f64 - 0.5);
+            let path = self.shape.path(bounds.inflate(spread, spread));
+
+            PietRenderContext::fill(canvas, path, brush);
+        }
+    }
+}
+
+impl<W: Widget> SingleChildWidget for PhysicalModel<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let bounds = Rect::from_origin_size(Point::ORIGIN, ctx.size());
+        let shape = self.shape.path(bounds);
+
+        if let Some(shadow) = Shadow::for_elevation(self.elevation) {
+            self.paint_shadow(canvas, bounds, shadow);
+        }
+
+        let brush = &canvas.solid_brush(self.color.clone());
+        PietRenderContext::fill(canvas, &shape, brush);
+
+        self.shape.paint(canvas, bounds);
+
+        canvas.with_clip(shape, |canvas| ctx.child().paint(canvas));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn higher_elevation_casts_softer_farther_shadow() {
+        assert_eq!(Shadow::for_elevation(0.), None);
+
+        let low = Shadow::for_elevation(2.).unwrap();
+        let high = Shadow::for_elevation(8.).unwrap();
+
+        assert!(high.offset.y > low.offset.y && low.offset.y > 0.);
+        assert!(high.blur_radius > low.blur_radius && low.blur_radius > 0.);
+        assert_eq!(high.offset.x, 0.);
+    }
+}
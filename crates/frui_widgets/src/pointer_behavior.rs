@@ -0,0 +1,92 @@
+use frui::prelude::*;
+
+/// Makes its child invisible to pointer events, which are delivered to the
+/// widgets behind it instead. The child is still laid out and painted.
+///
+/// Doesn't affect the keyboard events.
+#[derive(SingleChildWidget)]
+pub struct IgnorePointer<W: Widget> {
+    /// Whether pointer events are ignored. Allows toggling the behavior
+    /// without rebuilding the child under a different widget.
+    pub ignoring: bool,
+    pub child: W,
+}
+
+impl<W: Widget> IgnorePointer<W> {
+    pub fn new(child: W) -> Self {
+        Self {
+            ignoring: true,
+            child,
+        }
+    }
+
+    pub fn ignoring(mut self, ignoring: bool) -> Self {
+        self.ignoring = ignoring;
+        self
+    }
+}
+
+impl<W: Widget> SingleChildWidget for IgnorePointer<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.set_pointer_behavior(match self.ignoring {
+            true => PointerBehavior::Ignore,
+            false => PointerBehavior::Pass,
+        });
+
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
+    }
+}
+
+/// Prevents its child and the widgets behind it from receiving pointer events
+/// within its bounds. The child is still laid out and painted.
+///
+/// Unlike [`IgnorePointer`], widgets behind [`AbsorbPointer`] don't receive
+/// events either, which makes it useful e.g. for disabling a whole form while
+/// it's being submitted.
+#[derive(SingleChildWidget)]
+pub struct AbsorbPointer<W: Widget> {
+    /// Whether pointer events are absorbed.
+    pub absorbing: bool,
+    pub child: W,
+}
+
+impl<W: Widget> AbsorbPointer<W> {
+    pub fn new(child: W) -> Self {
+        Self {
+            absorbing: true,
+            child,
+        }
+    }
+
+    pub fn absorbing(mut self, absorbing: bool) -> Self {
+        self.absorbing = absorbing;
+        self
+    }
+}
+
+impl<W: Widget> SingleChildWidget for AbsorbPointer<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.set_pointer_behavior(match self.absorbing {
+            true => PointerBehavior::Absorb,
+            false => PointerBehavior::Pass,
+        });
+
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
+    }
+}
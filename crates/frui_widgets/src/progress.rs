@@ -0,0 +1,316 @@
+use std::{f64::consts::PI, time::Duration};
+
+use frui::prelude::*;
+
+use crate::AnimationController;
+
+const DEFAULT_COLOR: Color = Color::rgb8(255, 144, 54);
+const DEFAULT_TRACK_COLOR: Color = Color::rgba8(255, 255, 255, 40);
+
+const INDETERMINATE_LINEAR_PERIOD: Duration = Duration::from_millis(1500);
+const INDETERMINATE_CIRCULAR_PERIOD: Duration = Duration::from_millis(1200);
+
+/// Computes value displayed by a progress indicator, starting the indeterminate
+/// animation if `value` is `None`. Returns `None` in indeterminate mode.
+fn resolve_value(animation: &mut AnimationController, value: Option<f64>) -> Option<f64> {
+    match value {
+        Some(value) => {
+            if animation.is_animating() {
+                animation.stop();
+            }
+
+            Some(value.clamp(0.0, 1.0))
+        }
+        None => {
+            animation.repeat();
+            None
+        }
+    }
+}
+
+/// A horizontal bar indicating progress of some operation.
+///
+/// If `value` is `Some` it must be in range `0.0..=1.0` and the indicator fills
+/// that fraction of its track. If `value` is `None`, the indicator animates
+/// continuously to show that the progress is unknown.
+#[derive(LeafWidget)]
+pub struct LinearProgressIndicator {
+    pub value: Option<f64>,
+    pub color: Color,
+    pub track_color: Color,
+    pub stroke_width: f64,
+}
+
+impl LinearProgressIndicator {
+    pub fn new(value: Option<f64>) -> Self {
+        Self {
+            value,
+            color: DEFAULT_COLOR,
+            track_color: DEFAULT_TRACK_COLOR,
+            stroke_width: 4.0,
+        }
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn track_color(mut self, color: Color) -> Self {
+        self.track_color = color;
+        self
+    }
+
+    #[track_caller]
+    pub fn stroke_width(mut self, width: f64) -> Self {
+        assert!(width >= 0.0, "stroke_width must be >= 0.0");
+        self.stroke_width = width;
+        self
+    }
+}
+
+impl RenderState for LinearProgressIndicator {
+    type State = AnimationController;
+
+    fn create_state(&self) -> Self::State {
+        AnimationController::new(INDETERMINATE_LINEAR_PERIOD)
+    }
+}
+
+impl LeafWidget for LinearProgressIndicator {
+    fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+        let width = if constraints.has_bounded_width() {
+            constraints.max_width
+        } else {
+            200.0
+        };
+
+        constraints.constrain(Size::new(width, self.stroke_width))
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let (value, t, is_animating) = {
+            let mut animation = ctx.rstate_mut();
+            let value = resolve_value(&mut animation, self.value);
+            (value, animation.value(), animation.is_animating())
+        };
+
+        let Size { width, height } = ctx.size();
+
+        let track = Rect::from_origin_size(Point::ORIGIN, (width, height));
+        let brush = &canvas.solid_brush(self.track_color.clone());
+        PietRenderContext::fill(canvas, track, brush);
+
+        let (start, end) = match value {
+            Some(value) => (0.0, width * value),
+            None => {
+                // Segment of 40% of the track sliding from left to right.
+                let start = width * (t * 1.4 - 0.4);
+                (start.max(0.0), (start + width * 0.4).min(width))
+            }
+        };
+
+        if end > start {
+            let bar = Rect::new(start, 0., end, height);
+            let brush = &canvas.solid_brush(self.color.clone());
+            PietRenderContext::fill(canvas, bar, brush);
+        }
+
+        if is_animating {
+            ctx.mark_needs_paint();
+        }
+    }
+}
+
+/// A circular arc indicating progress of some operation.
+///
+/// See [`LinearProgressIndicator`] for the meaning of `value`.
+#[derive(LeafWidget)]
+pub struct CircularProgressIndicator {
+    pub value: Option<f64>,
+    pub color: Color,
+    pub track_color: Color,
+    pub stroke_width: f64,
+}
+
+impl CircularProgressIndicator {
+    pub fn new(value: Option<f64>) -> Self {
+        Self {
+            value,
+            color: DEFAULT_COLOR,
+            track_color: DEFAULT_TRACK_COLOR,
+            stroke_width: 4.0,
+        }
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn track_color(mut self, color: Color) -> Self {
+        self.track_color = color;
+        self
+    }
+
+    #[track_caller]
+    pub fn stroke_width(mut self, width: f64) -> Self {
+        assert!(width >= 0.0, "stroke_width must be >= 0.0");
+        self.stroke_width = width;
+        self
+    }
+}
+
+impl RenderState for CircularProgressIndicator {
+    type State = AnimationController;
+
+    fn create_state(&self) -> Self::State {
+        AnimationController::new(INDETERMINATE_CIRCULAR_PERIOD)
+    }
+}
+
+impl LeafWidget for CircularProgressIndicator {
+    fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+        constraints.constrain(Size::new(36.0, 36.0))
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let (value, t, is_animating) = {
+            let mut animation = ctx.rstate_mut();
+            let value = resolve_value(&mut animation, self.value);
+            (value, animation.value(), animation.is_animating())
+        };
+
+        let Size { width, height } = ctx.size();
+
+        let center = Point::new(width / 2., height / 2.);
+        let radius = ((width.min(height) - self.stroke_width) / 2.).max(0.0);
+
+        let brush = &canvas.solid_brush(self.track_color.clone());
+        canvas.stroke(Circle::new(center, radius), brush, self.stroke_width);
+
+        // Angles start at the top of the circle and go clockwise.
+        let (start_angle, sweep_angle) = match value {
+            Some(value) => (-PI / 2., 2. * PI * value),
+            None => (-PI / 2. + 2. * PI * t, PI * 1.5),
+        };
+
+        if sweep_angle > 0.0 {
+            let arc = Arc {
+                center,
+                radii: Vec2::new(radius, radius),
+                start_angle,
+                sweep_angle,
+                x_rotation: 0.0,
+            };
+
+            let brush = &canvas.solid_brush(self.color.clone());
+            canvas.stroke(arc, brush, self.stroke_width);
+        }
+
+        if is_animating {
+            ctx.mark_needs_paint();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc};
+
+    use frui::app::testing::WidgetTester;
+
+    use super::*;
+    use crate::{ListenableBuilder, Maybe};
+
+    /// Passes its constraints to its child, counting its layouts.
+    #[derive(SingleChildWidget)]
+    struct Layouts<W: Widget> {
+        count: Rc<Cell<usize>>,
+        child: W,
+    }
+
+    impl<W: Widget> SingleChildWidget for Layouts<W> {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+            &self.child
+        }
+
+        fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+            self.count.set(self.count.get() + 1);
+            ctx.child().layout(constraints)
+        }
+    }
+
+    /// Lays out and paints a frame, returning whether another one was
+    /// scheduled.
+    fn frame(tester: &mut WidgetTester) -> bool {
+        tester.take_scheduled_frame();
+        tester.pump();
+        tester.paint();
+        tester.take_scheduled_frame()
+    }
+
+    /// Rebuilds the indicator built by `build` whenever `config` notifies.
+    fn tester<T: Clone + 'static, W: Widget + 'static>(
+        config: &ValueNotifier<T>,
+        layouts: &Rc<Cell<usize>>,
+        build: impl Fn(T) -> W + 'static,
+    ) -> WidgetTester {
+        let (listenable, config, count) = (config.clone(), config.clone(), layouts.clone());
+
+        let tree = ListenableBuilder::new(listenable, move || Layouts {
+            count: count.clone(),
+            child: build(config.get()),
+        });
+
+        WidgetTester::new(tree, Size::new(100., 100.))
+    }
+
+    #[test]
+    fn indeterminate_animation_only_repaints() {
+        let value = ValueNotifier::new(None);
+        let layouts = Rc::new(Cell::new(0));
+
+        let mut tester = tester(&value, &layouts, LinearProgressIndicator::new);
+        frame(&mut tester);
+
+        let laid_out = layouts.get();
+        assert!(frame(&mut tester));
+        assert!(frame(&mut tester));
+        assert_eq!(layouts.get(), laid_out);
+    }
+
+    #[test]
+    fn determinate_value_stops_animation() {
+        let value = ValueNotifier::new(None);
+        let layouts = Rc::new(Cell::new(0));
+
+        let mut tester = tester(&value, &layouts, LinearProgressIndicator::new);
+        assert!(frame(&mut tester));
+
+        value.set(Some(0.5));
+        frame(&mut tester);
+
+        let laid_out = layouts.get();
+        assert!(!frame(&mut tester));
+        assert_eq!(layouts.get(), laid_out);
+    }
+
+    #[test]
+    fn unmounted_indicator_stops_requesting_frames() {
+        let shown = ValueNotifier::new(true);
+        let layouts = Rc::new(Cell::new(0));
+
+        let mut tester = tester(&shown, &layouts, |shown| {
+            Maybe::some_or_empty(shown.then(|| CircularProgressIndicator::new(None)))
+        });
+        assert!(frame(&mut tester));
+
+        shown.set(false);
+        frame(&mut tester);
+
+        let laid_out = layouts.get();
+        assert!(!frame(&mut tester));
+        assert_eq!(layouts.get(), laid_out);
+    }
+}
@@ -0,0 +1,196 @@
+use std::cell::Cell;
+
+use frui::{
+    api::events::{Event, WidgetEvent},
+    prelude::*,
+};
+
+use crate::Enabled;
+
+const SIZE: f64 = 20.0;
+const RING_WIDTH: f64 = 2.0;
+const DOT_RADIUS: f64 = 5.0;
+
+/// A single choice in a group of mutually exclusive options.
+///
+/// Radio is rendered as selected when its `value` equals `group_value`. To form
+/// a group, give every [`Radio`] the same `group_value` and `on_changed`, and
+/// update `group_value` in `on_changed`.
+///
+/// Tapping a radio that is already selected doesn't call `on_changed`. Radio
+/// disabled by an [`Enabled`] ancestor is rendered greyed out and can't be
+/// tapped.
+#[derive(LeafWidget)]
+pub struct Radio<T: PartialEq + Clone, F: Fn(T)> {
+    pub value: T,
+    pub group_value: T,
+    pub on_changed: F,
+    pub color: Color,
+}
+
+impl<T: PartialEq + Clone, F: Fn(T)> Radio<T, F> {
+    pub fn new(value: T, group_value: T, on_changed: F) -> Self {
+        Self {
+            value,
+            group_value,
+            on_changed,
+            color: Color::rgb8(255, 144, 54),
+        }
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn is_selected(&self) -> bool {
+        self.value == self.group_value
+    }
+}
+
+impl<T: PartialEq + Clone, F: Fn(T)> WidgetState for Radio<T, F> {
+    /// Whether this radio is enabled, as of the last change of its
+    /// dependencies.
+    type State = Cell<bool>;
+
+    fn create_state(&self) -> Self::State {
+        Cell::new(true)
+    }
+
+    fn did_change_dependencies<'a>(&'a self, ctx: BuildContext<'a, Self>) {
+        // State can't stay borrowed while depending on `Enabled`.
+        let enabled = Enabled::of(ctx);
+        ctx.state().set(enabled);
+    }
+}
+
+#[doc(hidden)]
+pub struct RadioRenderState {
+    is_pressed: Cell<bool>,
+}
+
+impl<T: PartialEq + Clone, F: Fn(T)> RenderState for Radio<T, F> {
+    type State = RadioRenderState;
+
+    fn create_state(&self) -> Self::State {
+        RadioRenderState {
+            is_pressed: Cell::new(false),
+        }
+    }
+}
+
+impl<T: PartialEq + Clone, F: Fn(T)> LeafWidget for Radio<T, F> {
+    fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+        constraints.constrain(Size::new(SIZE, SIZE))
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let size = ctx.size();
+        let center = Point::new(size.width / 2., size.height / 2.);
+        let radius = (size.width.min(size.height) - RING_WIDTH) / 2.;
+
+        let color = match (ctx.wstate().get(), self.is_selected()) {
+            (false, _) => Color::grey8(200),
+            (true, true) => self.color.clone(),
+            (true, false) => Color::grey8(160),
+        };
+
+        let brush = &canvas.solid_brush(color);
+
+        canvas.stroke(Circle::new(center, radius), brush, RING_WIDTH);
+
+        if self.is_selected() {
+            PietRenderContext::fill(canvas, Circle::new(center, DOT_RADIUS), brush);
+        }
+    }
+}
+
+impl<T: PartialEq + Clone, F: Fn(T)> WidgetEvent for Radio<T, F> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        if !ctx.wstate().get() {
+            ctx.rstate().is_pressed.set(false);
+            return false;
+        }
+
+        match event {
+            Event::MouseDown(e) => {
+                if let MouseButton::Left = e.button {
+                    if ctx.point_in_layout_bounds(e.pos) {
+                        ctx.rstate().is_pressed.set(true);
+                    }
+                }
+            }
+            Event::MouseUp(e) => {
+                if let MouseButton::Left = e.button {
+                    let was_pressed = ctx.rstate().is_pressed.replace(false);
+
+                    if was_pressed && ctx.point_in_layout_bounds(e.pos) && !self.is_selected() {
+                        (self.on_changed)(self.value.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use frui::{
+        app::testing::WidgetTester,
+        druid_shell::{Modifiers, MouseButtons, MouseEvent},
+    };
+
+    use super::*;
+
+    fn mouse() -> MouseEvent {
+        MouseEvent {
+            pos: Point::new(SIZE / 2., SIZE / 2.),
+            buttons: MouseButtons::new(),
+            mods: Modifiers::default(),
+            count: 1,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        }
+    }
+
+    /// Taps a radio built by `radio` with a callback counting its calls,
+    /// returning the number of calls.
+    fn tap<W: Widget + 'static>(radio: impl FnOnce(Box<dyn Fn(u8)>) -> W) -> usize {
+        let changes = Rc::new(Cell::new(0));
+
+        let on_changed = {
+            let changes = changes.clone();
+            Box::new(move |_| changes.set(changes.get() + 1))
+        };
+
+        let mut tester = WidgetTester::new(radio(on_changed), Size::new(100., 100.));
+
+        tester.handle_event(Event::MouseDown(mouse()));
+        tester.handle_event(Event::MouseUp(mouse()));
+        tester.pump();
+
+        changes.get()
+    }
+
+    #[test]
+    fn tapping_unselected_radio_changes_value() {
+        assert_eq!(tap(|on_changed| Radio::new(1, 0, on_changed)), 1);
+    }
+
+    #[test]
+    fn tapping_selected_radio_does_nothing() {
+        assert_eq!(tap(|on_changed| Radio::new(1, 1, on_changed)), 0);
+    }
+
+    #[test]
+    fn disabled_radio_ignores_taps() {
+        let radio = |on_changed| Enabled::new(false, Radio::new(1, 0, on_changed));
+        assert_eq!(tap(radio), 0);
+    }
+}
@@ -0,0 +1,486 @@
+use std::time::{Duration, Instant};
+
+use frui::{
+    api::events::{Event, WidgetEvent},
+    prelude::*,
+};
+
+use crate::widget_list::WidgetList;
+
+const DEFAULT_LONG_PRESS_DELAY: Duration = Duration::from_millis(500);
+
+/// Distance the pointer can travel before a press stops being a long press.
+const PRESS_SLOP: f64 = 8.0;
+
+/// Time constant (in seconds) of the animation moving items out of the way.
+const SHIFT_TIME_CONSTANT: f64 = 0.06;
+
+/// A vertical list whose items can be reordered by dragging.
+///
+/// To start dragging an item, press and hold it for `long_press_delay`, then
+/// move the pointer. When the item is dropped, `on_reorder` is called with its
+/// old and new index, which should be used to reorder the data the children
+/// were built from. It's not called if the item was dropped in its original
+/// position. Dropped item then moves from where it was released to its new
+/// slot.
+///
+/// Every child should be wrapped in a [`LocalKey`] which identifies the item, so
+/// that the state of that item follows it to its new position.
+#[derive(MultiChildWidget)]
+pub struct ReorderableList<T: WidgetList, F: Fn(usize, usize)> {
+    pub children: T,
+    pub on_reorder: F,
+    pub long_press_delay: Duration,
+}
+
+impl<T: WidgetList, F: Fn(usize, usize)> ReorderableList<T, F> {
+    pub fn new(children: T, on_reorder: F) -> Self {
+        Self {
+            children,
+            on_reorder,
+            long_press_delay: DEFAULT_LONG_PRESS_DELAY,
+        }
+    }
+
+    pub fn long_press_delay(mut self, delay: Duration) -> Self {
+        self.long_press_delay = delay;
+        self
+    }
+}
+
+struct Press {
+    index: usize,
+    time: Instant,
+    pos: Point,
+}
+
+struct Drag {
+    index: usize,
+    start_y: f64,
+    pointer_y: f64,
+}
+
+#[doc(hidden)]
+pub struct ReorderableListRenderState {
+    press: Option<Press>,
+    drag: Option<Drag>,
+    /// Heights of children computed during the last layout.
+    heights: Vec<f64>,
+    /// Vertical offsets of children relative to the list, as of the last paint
+    /// (which advances their animation).
+    offsets: Vec<f64>,
+    /// Index of the dropped item while it's moving to its slot.
+    dropped: Option<usize>,
+    last_frame: Instant,
+    is_settled: bool,
+}
+
+impl ReorderableListRenderState {
+    /// Vertical offset of the dragged item relative to the list.
+    fn dragged_offset(&self, drag: &Drag) -> f64 {
+        let natural: f64 = self.heights[..drag.index].iter().sum();
+        natural + drag.pointer_y - drag.start_y
+    }
+
+    /// Index at which the dragged item would land if dropped now.
+    fn drop_index(&self, drag: &Drag) -> usize {
+        let center = self.dragged_offset(drag) + self.heights[drag.index] / 2.;
+        drop_index(&self.heights, drag.index, center)
+    }
+
+    /// Moves the dragged item to `new_index`, the way children are reordered
+    /// after the drop, so that every item keeps moving from where it's
+    /// displayed (the dropped one from where it was released).
+    fn finish_drag(&mut self, drag: &Drag, new_index: usize) {
+        let dragged_offset = self.dragged_offset(drag);
+
+        self.offsets.remove(drag.index);
+        self.offsets.insert(new_index, dragged_offset);
+
+        let height = self.heights.remove(drag.index);
+        self.heights.insert(new_index, height);
+
+        self.dropped = Some(new_index);
+        self.is_settled = false;
+    }
+
+    /// Moves displayed offsets of children towards their slots. They are moved
+    /// there right away unless an item is dragged or dropped.
+    fn advance(&mut self) {
+        let targets = match &self.drag {
+            Some(drag) => {
+                let drop_index = self.drop_index(drag);
+                slot_offsets(&self.heights, drag.index, drop_index)
+            }
+            None => slot_offsets(&self.heights, 0, 0),
+        };
+
+        let now = animation_now();
+        let dt = now.duration_since(self.last_frame).as_secs_f64();
+        self.last_frame = now;
+
+        let is_moving = self.drag.is_some() || self.dropped.is_some();
+
+        if self.offsets.len() != targets.len() || !is_moving {
+            self.offsets = targets;
+            self.is_settled = true;
+        } else {
+            let t = 1. - (-dt / SHIFT_TIME_CONSTANT).exp();
+
+            self.is_settled = true;
+
+            for (offset, target) in self.offsets.iter_mut().zip(targets) {
+                *offset += (target - *offset) * t;
+
+                if (target - *offset).abs() > 0.5 {
+                    self.is_settled = false;
+                } else {
+                    *offset = target;
+                }
+            }
+        }
+
+        if self.is_settled {
+            self.dropped = None;
+        }
+    }
+}
+
+impl<T: WidgetList, F: Fn(usize, usize)> RenderState for ReorderableList<T, F> {
+    type State = ReorderableListRenderState;
+
+    fn create_state(&self) -> Self::State {
+        ReorderableListRenderState {
+            press: None,
+            drag: None,
+            heights: Vec::new(),
+            offsets: Vec::new(),
+            dropped: None,
+            last_frame: animation_now(),
+            is_settled: true,
+        }
+    }
+}
+
+impl<T: WidgetList, F: Fn(usize, usize)> MultiChildWidget for ReorderableList<T, F> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        self.children.get()
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let child_constraints = Constraints {
+            min_width: 0.,
+            max_width: constraints.max_width,
+            min_height: 0.,
+            max_height: f64::INFINITY,
+        };
+
+        let mut size = Size::default();
+        let mut heights = Vec::with_capacity(ctx.children().len());
+
+        for mut child in ctx.children() {
+            let child_size = child.layout(child_constraints);
+
+            size.width = size.width.max(child_size.width);
+            size.height += child_size.height;
+            heights.push(child_size.height);
+        }
+
+        let state = &mut *ctx.rstate_mut();
+
+        // Number of children changed, so the drag can't continue.
+        if state.heights.len() != heights.len() {
+            state.press = None;
+            state.drag = None;
+            state.dropped = None;
+            state.offsets = slot_offsets(&heights, 0, 0);
+        }
+
+        state.heights = heights;
+
+        constraints.constrain(size)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.rstate_mut().advance();
+
+        let (offsets, lifted) = {
+            let state = ctx.rstate();
+            let mut offsets = state.offsets.clone();
+
+            let lifted = match &state.drag {
+                Some(drag) => {
+                    offsets[drag.index] = state.dragged_offset(drag);
+                    Some(drag.index)
+                }
+                None => state.dropped,
+            };
+
+            (offsets, lifted)
+        };
+
+        for (n, mut child) in ctx.children().enumerate() {
+            if lifted != Some(n) {
+                let y = offsets[n];
+                child.paint_at(canvas, &Offset { x: 0., y });
+            }
+        }
+
+        // Paint dragged (or dropped) item last, so that it is above other items.
+        if let Some(n) = lifted {
+            if let Some(mut child) = ctx.children().nth(n) {
+                let y = offsets[n];
+                child.paint_at(canvas, &Offset { x: 0., y });
+            }
+        }
+
+        if !ctx.rstate().is_settled {
+            ctx.mark_needs_paint();
+        }
+    }
+}
+
+impl<T: WidgetList, F: Fn(usize, usize)> WidgetEvent for ReorderableList<T, F> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        match event {
+            Event::MouseDown(e) => {
+                if let MouseButton::Left = e.button {
+                    if ctx.point_in_layout_bounds(e.pos) {
+                        let y = e.pos.y - ctx.offset().y;
+                        let mut state = ctx.rstate_mut();
+
+                        let index = state
+                            .offsets
+                            .iter()
+                            .zip(&state.heights)
+                            .position(|(offset, height)| y >= *offset && y < offset + height);
+
+                        state.press = index.map(|index| Press {
+                            index,
+                            time: Instant::now(),
+                            pos: e.pos,
+                        });
+
+                        if state.press.is_some() {
+                            drop(state);
+                            ctx.capture_pointer();
+                        }
+                    }
+                }
+            }
+            Event::MouseMove(e) => {
+                let mut state = ctx.rstate_mut();
+
+                if let Some(drag) = &mut state.drag {
+                    drag.pointer_y = e.pos.y;
+                    drop(state);
+                    ctx.mark_needs_paint();
+                    return true;
+                }
+
+                if let Some(press) = &state.press {
+                    if press.time.elapsed() >= self.long_press_delay {
+                        state.drag = Some(Drag {
+                            index: press.index,
+                            start_y: press.pos.y,
+                            pointer_y: e.pos.y,
+                        });
+                        state.press = None;
+                        drop(state);
+                        ctx.mark_needs_paint();
+                        return true;
+                    } else if (e.pos - press.pos).hypot() > PRESS_SLOP {
+                        state.press = None;
+                    }
+                }
+            }
+            Event::MouseUp(e) => {
+                if let MouseButton::Left = e.button {
+                    let mut state = ctx.rstate_mut();
+
+                    state.press = None;
+
+                    if let Some(drag) = state.drag.take() {
+                        let new_index = state.drop_index(&drag);
+                        state.finish_drag(&drag, new_index);
+                        drop(state);
+
+                        if new_index != drag.index {
+                            (self.on_reorder)(drag.index, new_index);
+                        }
+
+                        ctx.mark_needs_paint();
+                        return true;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        // Don't let children react to the pointer while an item is dragged.
+        ctx.rstate().drag.is_some()
+    }
+}
+
+/// Computes the index at which the item `dragged` lands when its center is at
+/// `center`. The result is always a valid index in the list.
+fn drop_index(heights: &[f64], dragged: usize, center: f64) -> usize {
+    let mut top = 0.;
+    let mut index = 0;
+
+    for (n, height) in heights.iter().enumerate() {
+        if n == dragged {
+            continue;
+        }
+
+        if top + height / 2. < center {
+            index += 1;
+        }
+
+        top += height;
+    }
+
+    index
+}
+
+/// Computes offsets of items when `dragged` item is moved to `drop_index`.
+/// Offset of the dragged item itself is its offset in the new position.
+fn slot_offsets(heights: &[f64], dragged: usize, drop_index: usize) -> Vec<f64> {
+    let mut offsets = vec![0.; heights.len()];
+
+    // Heights of items in the order they will have after the drop.
+    let mut order = (0..heights.len())
+        .filter(|n| *n != dragged)
+        .collect::<Vec<_>>();
+    order.insert(drop_index.min(order.len()), dragged);
+
+    let mut top = 0.;
+
+    for n in order {
+        offsets[n] = top;
+        top += heights[n];
+    }
+
+    offsets
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use frui::{
+        app::testing::WidgetTester,
+        druid_shell::{Modifiers, MouseButtons, MouseEvent},
+    };
+
+    use super::*;
+    use crate::ListenableBuilder;
+
+    #[test]
+    fn drop_index_is_clamped() {
+        let heights = [10., 10., 10.];
+
+        assert_eq!(drop_index(&heights, 1, -100.), 0);
+        assert_eq!(drop_index(&heights, 1, 15.), 1);
+        assert_eq!(drop_index(&heights, 1, 100.), 2);
+        assert_eq!(drop_index(&heights, 0, 12.), 1);
+    }
+
+    #[test]
+    fn slots_make_room_for_dragged_item() {
+        let heights = [10., 20., 30.];
+
+        assert_eq!(slot_offsets(&heights, 0, 0), vec![0., 10., 30.]);
+        assert_eq!(slot_offsets(&heights, 0, 2), vec![50., 0., 20.]);
+        assert_eq!(slot_offsets(&heights, 2, 0), vec![30., 40., 0.]);
+    }
+
+    /// Item of a fixed height, recording its offset whenever it's painted.
+    #[derive(LeafWidget)]
+    struct Item {
+        id: usize,
+        painted: Rc<RefCell<Vec<(usize, f64)>>>,
+    }
+
+    impl LeafWidget for Item {
+        fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+            constraints.constrain(Size::new(50., 10.))
+        }
+
+        fn paint(&self, ctx: RenderContext<Self>, _: &mut PaintContext) {
+            self.painted.borrow_mut().push((self.id, ctx.offset().y));
+        }
+    }
+
+    fn mouse(y: f64) -> MouseEvent {
+        MouseEvent {
+            pos: Point::new(20., y),
+            buttons: MouseButtons::new(),
+            mods: Modifiers::default(),
+            count: 1,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        }
+    }
+
+    #[test]
+    fn dropped_item_moves_to_its_slot_in_new_order() {
+        let order = ValueNotifier::new(vec![0, 1, 2]);
+        let painted = Rc::new(RefCell::new(Vec::new()));
+
+        let list = {
+            let (listenable, order, painted) = (order.clone(), order.clone(), painted.clone());
+
+            ListenableBuilder::new(listenable, move || {
+                let items = order.get().into_iter().map(|id| {
+                    let painted = painted.clone();
+                    LocalKey::new(id, Item { id, painted })
+                });
+
+                let order = order.clone();
+
+                ReorderableList::new(items.collect::<Vec<_>>(), move |from, to| {
+                    order.update(|order| {
+                        let id = order.remove(from);
+                        order.insert(to, id);
+                    })
+                })
+                .long_press_delay(Duration::ZERO)
+            })
+        };
+
+        let mut tester = WidgetTester::new(list, Size::new(100., 100.));
+
+        let frame = |tester: &mut WidgetTester| {
+            painted.borrow_mut().clear();
+            tester.pump();
+            tester.paint();
+            painted.borrow().clone()
+        };
+
+        assert_eq!(frame(&mut tester), [(0, 0.), (1, 10.), (2, 20.)]);
+
+        // First item is dragged below the end of the list and dropped there.
+        tester.handle_event(Event::MouseDown(mouse(5.)));
+        tester.handle_event(Event::MouseMove(mouse(5.)));
+        tester.handle_event(Event::MouseMove(mouse(95.)));
+        assert_eq!(frame(&mut tester).last(), Some(&(0, 90.)));
+
+        tester.handle_event(Event::MouseUp(mouse(95.)));
+        assert_eq!(order.get(), [1, 2, 0]);
+
+        // Dropped item starts moving from where it was released and is painted
+        // above the others until it reaches its slot.
+        let (id, y) = *frame(&mut tester).last().unwrap();
+        assert!(id == 0 && y > 20. && y < 90., "dropped item is at {y}");
+
+        let settled = (0..100).find_map(|_| {
+            std::thread::sleep(Duration::from_millis(10));
+            let painted = frame(&mut tester);
+            (painted.last() == Some(&(0, 20.))).then_some(painted)
+        });
+
+        assert_eq!(settled.unwrap(), [(1, 0.), (2, 10.), (0, 20.)]);
+    }
+}
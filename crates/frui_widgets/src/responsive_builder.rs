@@ -0,0 +1,152 @@
+use frui::prelude::*;
+
+type BreakpointBuilder = Box<dyn Fn() -> Box<dyn Widget>>;
+
+struct Breakpoint {
+    name: &'static str,
+    min_width: f64,
+    builder: BreakpointBuilder,
+}
+
+/// Builds its child with the builder of the breakpoint matching the width
+/// available to it.
+///
+/// Active breakpoint is the one with the greatest `min_width` not exceeding the
+/// maximal width of incoming constraints. If that width is smaller than every
+/// breakpoint, the smallest breakpoint is used.
+///
+/// ```
+/// # use frui::prelude::*;
+/// # use frui_widgets::*;
+/// # fn mobile_layout() -> impl Widget { Text::new("Mobile") }
+/// # fn tablet_layout() -> impl Widget { Text::new("Tablet") }
+/// # fn desktop_layout() -> impl Widget { Text::new("Desktop") }
+/// ResponsiveBuilder::new()
+///     .breakpoint("mobile", 0.0, || mobile_layout())
+///     .breakpoint("tablet", 600.0, || tablet_layout())
+///     .breakpoint("desktop", 1024.0, || desktop_layout());
+/// ```
+///
+/// Child is rebuilt only when the active breakpoint changes, not on every
+/// change of constraints, so resizing the window within a breakpoint only lays
+/// the child out again. Since constraints are known only after the child was
+/// built, the first frame (and the frame in which the active breakpoint
+/// changes) is laid out with the child of the previous breakpoint, and the
+/// child of the new one is built in the following frame.
+#[derive(SingleChildWidget)]
+pub struct ResponsiveBuilder {
+    breakpoints: Vec<Breakpoint>,
+}
+
+impl ResponsiveBuilder {
+    pub fn new() -> Self {
+        Self {
+            breakpoints: Vec::new(),
+        }
+    }
+
+    /// Adds a breakpoint called `name`, active from `min_width` up to the next
+    /// greater breakpoint, whose child is built by `builder`.
+    pub fn breakpoint<W, F>(mut self, name: &'static str, min_width: f64, builder: F) -> Self
+    where
+        W: Widget + 'static,
+        F: Fn() -> W + 'static,
+    {
+        let n = self
+            .breakpoints
+            .partition_point(|breakpoint| breakpoint.min_width <= min_width);
+
+        self.breakpoints.insert(
+            n,
+            Breakpoint {
+                name,
+                min_width,
+                builder: Box::new(move || builder().boxed()),
+            },
+        );
+
+        self
+    }
+
+    /// Name of the breakpoint active for `width`, or `None` if there are no
+    /// breakpoints.
+    pub fn active_breakpoint(&self, width: f64) -> Option<&'static str> {
+        let n = self
+            .breakpoints
+            .partition_point(|breakpoint| breakpoint.min_width <= width);
+
+        self.breakpoints.get(n.saturating_sub(1)).map(|b| b.name)
+    }
+}
+
+impl Default for ResponsiveBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WidgetState for ResponsiveBuilder {
+    /// Name of the active breakpoint, as of the last layout.
+    type State = Option<&'static str>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+}
+
+impl SingleChildWidget for ResponsiveBuilder {
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        let active = *ctx.state();
+
+        let breakpoint = self
+            .breakpoints
+            .iter()
+            .find(|breakpoint| Some(breakpoint.name) == active)
+            .or_else(|| self.breakpoints.first());
+
+        match breakpoint {
+            Some(breakpoint) => (breakpoint.builder)(),
+            None => ().boxed(),
+        }
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let active = self.active_breakpoint(constraints.max_width);
+
+        // Changing state rebuilds this widget, so it is changed only once the
+        // active breakpoint changes.
+        if *ctx.wstate() != active {
+            *ctx.wstate_mut() = active;
+        }
+
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn breakpoint_is_picked_by_width() {
+        let builder = ResponsiveBuilder::new()
+            .breakpoint("desktop", 1024., || ())
+            .breakpoint("mobile", 0., || ())
+            .breakpoint("tablet", 600., || ());
+
+        assert_eq!(builder.active_breakpoint(320.), Some("mobile"));
+        assert_eq!(builder.active_breakpoint(599.9), Some("mobile"));
+        assert_eq!(builder.active_breakpoint(600.), Some("tablet"));
+        assert_eq!(builder.active_breakpoint(f64::INFINITY), Some("desktop"));
+
+        // Width below the smallest breakpoint uses the first one.
+        let builder = ResponsiveBuilder::new().breakpoint("compact", 200., || ());
+        assert_eq!(builder.active_breakpoint(100.), Some("compact"));
+
+        assert_eq!(ResponsiveBuilder::new().active_breakpoint(100.), None);
+    }
+}
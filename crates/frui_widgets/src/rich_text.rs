@@ -0,0 +1,641 @@
+use std::{ops::Range, rc::Rc};
+
+use druid_shell::piet::{
+    LineMetric, PietTextLayout, Text as TextExt, TextAttribute, TextLayout, TextLayoutBuilder,
+};
+use frui::{app::TEXT_FACTORY, prelude::*};
+
+/// Style of a [`TextSpan`]. Fields which are `None` are inherited from the
+/// parent span.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextStyle {
+    pub color: Option<Color>,
+    pub size: Option<f64>,
+    pub weight: Option<FontWeight>,
+    pub font: Option<FontFamily>,
+}
+
+impl TextStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn size(mut self, size: f64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    pub fn font(mut self, font: FontFamily) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// This style with fields which are `None` taken from `parent`.
+    fn inherit(&self, parent: &TextStyle) -> TextStyle {
+        TextStyle {
+            color: self.color.clone().or_else(|| parent.color.clone()),
+            size: self.size.or(parent.size),
+            weight: self.weight.or(parent.weight),
+            font: self.font.clone().or_else(|| parent.font.clone()),
+        }
+    }
+}
+
+/// Ranges of the flattened text of a span tree, with their resolved styles.
+type Runs = Vec<(Range<usize>, TextStyle)>;
+
+/// Vertical alignment of a [`WidgetSpan`] relative to the line it is in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlaceholderAlignment {
+    /// Baseline of the child (see [`WidgetSpan::baseline`]) is aligned with
+    /// the baseline of the text.
+    Baseline,
+    /// Bottom edge of the child is aligned with the baseline of the text.
+    AboveBaseline,
+    /// Top edge of the child is aligned with the baseline of the text.
+    BelowBaseline,
+    /// Top edge of the child is aligned with the top of the line.
+    Top,
+    /// Bottom edge of the child is aligned with the bottom of the line.
+    #[default]
+    Bottom,
+    /// Child is centered vertically within the line.
+    Middle,
+}
+
+/// A widget embedded in the text, e.g. an inline icon.
+///
+/// Space of the size of the child is reserved in the text, which wraps around
+/// it like around a single word. The child is laid out with the width of the
+/// [`RichText`] as its maximum width.
+#[derive(Clone)]
+pub struct WidgetSpan {
+    pub child: Rc<dyn Widget>,
+    pub alignment: PlaceholderAlignment,
+    /// Distance from the top of the child to its baseline, used with
+    /// [`PlaceholderAlignment::Baseline`]. Defaults to the height of the child.
+    pub baseline: Option<f64>,
+}
+
+impl WidgetSpan {
+    pub fn new(child: impl Widget + 'static) -> Self {
+        Self {
+            child: Rc::new(child),
+            alignment: PlaceholderAlignment::default(),
+            baseline: None,
+        }
+    }
+
+    pub fn alignment(mut self, alignment: PlaceholderAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn baseline(mut self, baseline: f64) -> Self {
+        self.baseline = Some(baseline);
+        self
+    }
+}
+
+impl std::fmt::Debug for WidgetSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WidgetSpan")
+            .field("alignment", &self.alignment)
+            .field("baseline", &self.baseline)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for WidgetSpan {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.child, &other.child)
+            && self.alignment == other.alignment
+            && self.baseline == other.baseline
+    }
+}
+
+/// Child of a [`TextSpan`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum InlineSpan {
+    Text(TextSpan),
+    Widget(WidgetSpan),
+}
+
+impl From<TextSpan> for InlineSpan {
+    fn from(span: TextSpan) -> Self {
+        InlineSpan::Text(span)
+    }
+}
+
+impl From<WidgetSpan> for InlineSpan {
+    fn from(span: WidgetSpan) -> Self {
+        InlineSpan::Widget(span)
+    }
+}
+
+/// A run of text with an optional style, followed by its children.
+///
+/// Children inherit style of their parent, overriding only fields set in their
+/// own style.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextSpan {
+    pub text: String,
+    pub style: Option<TextStyle>,
+    pub children: Vec<InlineSpan>,
+}
+
+impl TextSpan {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            style: None,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn style(mut self, style: TextStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub fn child(mut self, child: impl Into<InlineSpan>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    pub fn children<S: Into<InlineSpan>>(mut self, children: Vec<S>) -> Self {
+        self.children.extend(children.into_iter().map(Into::into));
+        self
+    }
+
+    /// Appends text of this span tree to `text`, pushing the resolved style of
+    /// every non-empty run to `runs`. Text of widget spans is appended by
+    /// `placeholder`, which receives the style of their parent.
+    fn flatten(
+        &self,
+        parent: &TextStyle,
+        text: &mut String,
+        runs: &mut Runs,
+        placeholder: &mut dyn FnMut(&WidgetSpan, &TextStyle, &mut String, &mut Runs),
+    ) {
+        let style = match &self.style {
+            Some(style) => style.inherit(parent),
+            None => parent.clone(),
+        };
+
+        if !self.text.is_empty() {
+            let start = text.len();
+            text.push_str(&self.text);
+            runs.push((start..text.len(), style.clone()));
+        }
+
+        for child in self.children.iter() {
+            match child {
+                InlineSpan::Text(child) => child.flatten(&style, text, runs, placeholder),
+                InlineSpan::Widget(child) => placeholder(child, &style, text, runs),
+            }
+        }
+    }
+
+    /// Pushes widget spans of this span tree to `spans`, in order of the text.
+    fn widget_spans<'a>(&'a self, spans: &mut Vec<&'a WidgetSpan>) {
+        for child in self.children.iter() {
+            match child {
+                InlineSpan::Text(child) => child.widget_spans(spans),
+                InlineSpan::Widget(child) => spans.push(child),
+            }
+        }
+    }
+}
+
+/// Vertical metrics and the width of a space of the font used for
+/// placeholders of widget spans, per unit of font size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PlaceholderMetrics {
+    ascent: f64,
+    descent: f64,
+    space_width: f64,
+}
+
+impl PlaceholderMetrics {
+    /// Font size used to measure the metrics.
+    const PROBE_SIZE: f64 = 100.;
+
+    #[cfg(not(feature = "miri"))]
+    fn measure() -> Self {
+        let layout = TEXT_FACTORY.with(|f| {
+            f.get()
+                .new_text_layout(NO_BREAK_SPACE.to_string())
+                .font(FontFamily::default(), Self::PROBE_SIZE)
+                .build()
+                .unwrap()
+        });
+
+        let line = layout.line_metric(0).unwrap_or_default();
+        let space_width = layout
+            .hit_test_text_position(NO_BREAK_SPACE.len_utf8())
+            .point
+            .x;
+
+        let metrics = PlaceholderMetrics {
+            ascent: line.baseline / Self::PROBE_SIZE,
+            descent: (line.height - line.baseline) / Self::PROBE_SIZE,
+            space_width: space_width / Self::PROBE_SIZE,
+        };
+
+        if metrics.ascent > 0. && metrics.descent > 0. && metrics.space_width > 0. {
+            metrics
+        } else {
+            // No usable font, but we still want to produce some layout.
+            PlaceholderMetrics {
+                ascent: 0.8,
+                descent: 0.2,
+                space_width: 0.25,
+            }
+        }
+    }
+}
+
+/// Characters of the placeholder of a widget span.
+///
+/// Text layouts of piet can't reserve space of a given size, so placeholders
+/// are made of text instead: no-break spaces, which can be scaled to exactly
+/// the width of the child, surrounded by zero-width spaces, which make the
+/// line tall enough and allow it to break around the placeholder.
+const NO_BREAK_SPACE: char = '\u{a0}';
+const ZERO_WIDTH_SPACE: char = '\u{200b}';
+
+/// Font sizes of the placeholder of a widget span.
+#[derive(Debug, PartialEq)]
+struct Reservation {
+    /// Font size of zero-width spaces around the placeholder.
+    height_font_size: f64,
+    /// Number of no-break spaces reserving the width of the placeholder.
+    spaces: usize,
+    space_font_size: f64,
+}
+
+impl Reservation {
+    fn new(span: &WidgetSpan, size: Size, metrics: PlaceholderMetrics) -> Self {
+        let PlaceholderMetrics {
+            ascent,
+            descent,
+            space_width,
+        } = metrics;
+
+        let height = size.height;
+
+        let height_font_size = match span.alignment {
+            PlaceholderAlignment::Baseline => {
+                let baseline = span.baseline.unwrap_or(height).clamp(0., height);
+                (baseline / ascent).max((height - baseline) / descent)
+            }
+            PlaceholderAlignment::AboveBaseline => height / ascent,
+            PlaceholderAlignment::BelowBaseline => height / descent,
+            _ => height / (ascent + descent),
+        };
+
+        if size.width <= 0. {
+            return Reservation {
+                height_font_size,
+                spaces: 0,
+                space_font_size: 0.,
+            };
+        }
+
+        // Spaces can't be of a bigger font size, or they would make the line
+        // taller than needed.
+        let max_font_size = height_font_size.max(1.);
+        let spaces = (size.width / (space_width * max_font_size)).ceil() as usize;
+
+        Reservation {
+            height_font_size,
+            spaces,
+            space_font_size: size.width / (spaces as f64 * space_width),
+        }
+    }
+
+    /// Appends the placeholder to `text`, returning the index of its first
+    /// no-break space.
+    fn push(&self, style: &TextStyle, text: &mut String, runs: &mut Runs) -> usize {
+        let mut push_run = |text: &mut String, s: &str, size: f64| {
+            let start = text.len();
+            text.push_str(s);
+
+            let style = TextStyle {
+                size: Some(size),
+                font: Some(FontFamily::default()),
+                ..style.clone()
+            };
+
+            runs.push((start..text.len(), style));
+        };
+
+        push_run(text, &ZERO_WIDTH_SPACE.to_string(), self.height_font_size);
+        let start = text.len();
+
+        if self.spaces > 0 {
+            let spaces = NO_BREAK_SPACE.to_string().repeat(self.spaces);
+            push_run(text, &spaces, self.space_font_size);
+        }
+
+        push_run(text, &ZERO_WIDTH_SPACE.to_string(), self.height_font_size);
+
+        start
+    }
+}
+
+/// Position of the top edge of a widget span of `height`, within `line`.
+fn placeholder_top(span: &WidgetSpan, height: f64, line: &LineMetric) -> f64 {
+    let line_baseline = line.y_offset + line.baseline;
+
+    match span.alignment {
+        PlaceholderAlignment::Baseline => line_baseline - span.baseline.unwrap_or(height),
+        PlaceholderAlignment::AboveBaseline => line_baseline - height,
+        PlaceholderAlignment::BelowBaseline => line_baseline,
+        PlaceholderAlignment::Top => line.y_offset,
+        PlaceholderAlignment::Bottom => line.y_offset + line.height - height,
+        PlaceholderAlignment::Middle => line.y_offset + (line.height - height) / 2.,
+    }
+}
+
+/// Paragraph made of [`TextSpan`]s of different styles, which can embed
+/// widgets through [`WidgetSpan`]s.
+///
+/// All spans are laid out together as a single paragraph, so lines wrap
+/// across style boundaries like in a plain [`Text`](crate::Text).
+#[derive(MultiChildWidget)]
+pub struct RichText {
+    pub span: TextSpan,
+}
+
+impl RichText {
+    pub fn new(span: TextSpan) -> Self {
+        Self { span }
+    }
+
+    /// Builds the text layout, reserving placeholders of the given `sizes` of
+    /// widget spans. Returns the layout and the indices of placeholders.
+    /// Families of fonts the text is laid out with are appended to `fonts`.
+    #[cfg(not(feature = "miri"))]
+    fn build_layout(
+        &self,
+        max_width: f64,
+        sizes: &[Size],
+        fonts: &mut Vec<FontFamily>,
+    ) -> (PietTextLayout, Vec<usize>) {
+        // Defaults of the root span, same as those of `Text`.
+        let root = TextStyle {
+            color: Some(Color::WHITE),
+            size: Some(16.),
+            weight: Some(FontWeight::default()),
+            font: Some(FontFamily::default()),
+        };
+
+        let metrics = match sizes.is_empty() {
+            true => None,
+            false => Some(PlaceholderMetrics::measure()),
+        };
+
+        let mut text = String::new();
+        let mut runs = Vec::new();
+        let mut placeholders = Vec::with_capacity(sizes.len());
+
+        self.span.flatten(
+            &root,
+            &mut text,
+            &mut runs,
+            &mut |span, style, text, runs| {
+                let size = sizes[placeholders.len()];
+                let reservation = Reservation::new(span, size, metrics.unwrap());
+
+                placeholders.push(reservation.push(style, text, runs));
+            },
+        );
+
+        let layout = TEXT_FACTORY.with(|f| {
+            let mut builder = f.get().new_text_layout(text).max_width(max_width);
+
+            // Every field is set, since all spans inherit from `root`.
+            for (range, style) in runs {
+                let TextStyle {
+                    color,
+                    size,
+                    weight,
+                    font,
+                } = style;
+
+                let font = font.unwrap();
+                fonts.push(font.clone());
+
+                builder = builder
+                    .range_attribute(range.clone(), TextAttribute::TextColor(color.unwrap()))
+                    .range_attribute(range.clone(), TextAttribute::FontSize(size.unwrap()))
+                    .range_attribute(range.clone(), TextAttribute::Weight(weight.unwrap()))
+                    .range_attribute(range, TextAttribute::FontFamily(font));
+            }
+
+            builder.build().unwrap()
+        });
+
+        (layout, placeholders)
+    }
+}
+
+#[doc(hidden)]
+pub struct RichTextRenderState {
+    layout: PietTextLayout,
+}
+
+#[cfg(not(feature = "miri"))]
+impl RenderState for RichText {
+    type State = RichTextRenderState;
+
+    fn create_state(&self) -> Self::State {
+        RichTextRenderState {
+            layout: TEXT_FACTORY.with(|f| f.get().new_text_layout("").build().unwrap()),
+        }
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl MultiChildWidget for RichText {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        let mut spans = Vec::new();
+        self.span.widget_spans(&mut spans);
+
+        spans.into_iter().map(|span| &*span.child).collect()
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let max_width = constraints.max().width;
+        let child_constraints = Constraints::loose(Size::new(max_width, f64::INFINITY));
+
+        let sizes = ctx
+            .children()
+            .map(|mut child| child.layout(child_constraints))
+            .collect::<Vec<_>>();
+
+        let mut fonts = Vec::new();
+        let (layout, placeholders) = self.build_layout(max_width, &sizes, &mut fonts);
+
+        for font in fonts {
+            ctx.depend_on_font(&font);
+        }
+
+        let mut spans = Vec::new();
+        self.span.widget_spans(&mut spans);
+
+        let offsets = spans
+            .iter()
+            .zip(sizes.iter())
+            .zip(placeholders)
+            .map(|((span, size), idx)| {
+                let position = layout.hit_test_text_position(idx);
+                let line = layout.line_metric(position.line).unwrap_or_default();
+
+                Offset {
+                    x: position.point.x,
+                    y: placeholder_top(span, size.height, &line),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Size of the wrapped layout, which may not include all of the widget
+        // spans, e.g. those aligned below the baseline of the last line.
+        let text_size = layout.size();
+        let height = offsets
+            .iter()
+            .zip(sizes.iter())
+            .fold(text_size.height, |height, (offset, size)| {
+                height.max(offset.y + size.height)
+            });
+
+        for (mut child, offset) in ctx.children().zip(offsets) {
+            child.set_offset(offset);
+        }
+
+        *ctx.rstate_mut() = RichTextRenderState { layout };
+
+        constraints.constrain(Size {
+            width: text_size.width,
+            height,
+        })
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        PietRenderContext::draw_text(canvas, &ctx.rstate().layout, Point::ORIGIN);
+
+        for mut child in ctx.children() {
+            child.paint(canvas);
+        }
+    }
+}
+
+#[cfg(feature = "miri")]
+impl MultiChildWidget for RichText {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        let mut spans = Vec::new();
+        self.span.widget_spans(&mut spans);
+
+        spans.into_iter().map(|span| &*span.child).collect()
+    }
+
+    fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+        constraints.biggest()
+    }
+
+    fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn children_inherit_style() {
+        let span = TextSpan::new("a")
+            .style(TextStyle::new().size(20.).color(Color::BLACK))
+            .child(TextSpan::new("bc").style(TextStyle::new().color(Color::WHITE)))
+            .child(TextSpan::new("d"));
+
+        let mut text = String::new();
+        let mut runs = Vec::new();
+        span.flatten(
+            &TextStyle::new().size(10.),
+            &mut text,
+            &mut runs,
+            &mut |_, _, _, _| {},
+        );
+
+        assert_eq!(text, "abcd");
+        assert_eq!(runs[1].0, 1..3);
+        assert_eq!(runs[1].1, TextStyle::new().size(20.).color(Color::WHITE));
+        assert_eq!(runs[2].1, TextStyle::new().size(20.).color(Color::BLACK));
+    }
+    const METRICS: PlaceholderMetrics = PlaceholderMetrics {
+        ascent: 0.75,
+        descent: 0.25,
+        space_width: 0.25,
+    };
+
+    #[test]
+    fn placeholder_reserves_size_of_widget() {
+        let span = WidgetSpan::new(()).alignment(PlaceholderAlignment::Baseline);
+        let reservation =
+            Reservation::new(&span.clone().baseline(15.), Size::new(30., 20.), METRICS);
+
+        // Fits 15 above and 5 below the baseline.
+        assert_eq!(reservation.height_font_size, 20.);
+        assert_eq!(reservation.spaces, 6);
+        assert_eq!(
+            reservation.spaces as f64 * reservation.space_font_size * 0.25,
+            30.
+        );
+
+        let mut text = String::new();
+        let mut runs = Vec::new();
+        let start = reservation.push(&TextStyle::new(), &mut text, &mut runs);
+
+        assert_eq!(
+            &text[start..text.len() - ZERO_WIDTH_SPACE.len_utf8()],
+            "\u{a0}".repeat(6)
+        );
+        assert_eq!(runs.len(), 3);
+    }
+
+    #[test]
+    fn placeholder_is_aligned_within_line() {
+        let line = LineMetric {
+            baseline: 15.,
+            height: 20.,
+            y_offset: 40.,
+            ..Default::default()
+        };
+
+        let top =
+            |alignment| placeholder_top(&WidgetSpan::new(()).alignment(alignment), 10., &line);
+
+        assert_eq!(top(PlaceholderAlignment::Baseline), 45.);
+        assert_eq!(top(PlaceholderAlignment::AboveBaseline), 45.);
+        assert_eq!(top(PlaceholderAlignment::BelowBaseline), 55.);
+        assert_eq!(top(PlaceholderAlignment::Top), 40.);
+        assert_eq!(top(PlaceholderAlignment::Bottom), 50.);
+        assert_eq!(top(PlaceholderAlignment::Middle), 45.);
+
+        let span = WidgetSpan::new(())
+            .alignment(PlaceholderAlignment::Baseline)
+            .baseline(8.);
+        assert_eq!(placeholder_top(&span, 10., &line), 47.);
+    }
+}
@@ -1,37 +1,74 @@
 //! This is a bad prototype.
 
+use std::{
+    cell::{Cell, RefCell},
+    time::Instant,
+};
+
 use frui::{
     api::events::{Event, WidgetEvent},
     prelude::*,
 };
 
-#[derive(Debug, Clone, Copy)]
+use crate::{ClampingScrollPhysics, ScrollMetrics, ScrollPhysics, Simulation};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ScrollDirection {
     Horizontal,
     Vertical,
     // Todo: All,
 }
 
+impl ScrollDirection {
+    /// Component of `v` along this direction.
+    pub(crate) fn along(self, v: Vec2) -> f64 {
+        match self {
+            ScrollDirection::Horizontal => v.x,
+            ScrollDirection::Vertical => v.y,
+        }
+    }
+
+    /// Length of `size` along this direction.
+    pub(crate) fn extent(self, size: Size) -> f64 {
+        match self {
+            ScrollDirection::Horizontal => size.width,
+            ScrollDirection::Vertical => size.height,
+        }
+    }
+
+    /// `v` with its component along this direction replaced by `value`.
+    pub(crate) fn with_along(self, v: Vec2, value: f64) -> Vec2 {
+        match self {
+            ScrollDirection::Horizontal => Vec2::new(value, v.y),
+            ScrollDirection::Vertical => Vec2::new(v.x, value),
+        }
+    }
+}
+
 #[derive(SingleChildWidget)]
-pub struct Scroll<W: Widget> {
+pub struct Scroll<W: Widget, P: ScrollPhysics> {
     pub child: W,
     pub scroll_direction: ScrollDirection,
+    /// How the content responds to dragging it and keeps moving after a fling.
+    pub physics: P,
 }
 
-impl Scroll<()> {
-    pub fn builder() -> Scroll<()> {
+impl Scroll<(), ClampingScrollPhysics> {
+    pub fn builder() -> Scroll<(), ClampingScrollPhysics> {
         Scroll {
             child: (),
             scroll_direction: ScrollDirection::Vertical,
+            physics: ClampingScrollPhysics,
         }
     }
 }
 
-impl<W: Widget> Scroll<W> {
-    pub fn child(self, child: impl Widget) -> Scroll<impl Widget> {
+impl<W: Widget, P: ScrollPhysics> Scroll<W, P> {
+    pub fn child(self, child: impl Widget) -> Scroll<impl Widget, P> {
         Scroll {
             child,
             scroll_direction: self.scroll_direction,
+            physics: self.physics,
         }
     }
 
@@ -39,24 +76,185 @@ impl<W: Widget> Scroll<W> {
         self.scroll_direction = scroll_direction;
         self
     }
+
+    pub fn physics<P2: ScrollPhysics>(self, physics: P2) -> Scroll<W, P2> {
+        Scroll {
+            child: self.child,
+            scroll_direction: self.scroll_direction,
+            physics,
+        }
+    }
+}
+
+/// Metrics of content of `content_size` scrolled by `scroll_offset` within a
+/// viewport of `size`.
+fn metrics(
+    direction: ScrollDirection,
+    scroll_offset: Vec2,
+    size: Size,
+    content_size: Size,
+) -> ScrollMetrics {
+    let viewport = direction.extent(size);
+
+    ScrollMetrics {
+        position: direction.along(scroll_offset),
+        min: 0.,
+        max: (direction.extent(content_size) - viewport).max(0.),
+        viewport,
+    }
+}
+
+/// Scrolling which continues after a drag was released.
+struct Ballistic {
+    simulation: Box<dyn Simulation>,
+    start: Instant,
+}
+
+impl Ballistic {
+    fn start(simulation: Box<dyn Simulation>) -> Self {
+        Ballistic {
+            simulation,
+            start: animation_now(),
+        }
+    }
 }
 
 #[doc(hidden)]
 pub struct ScrollState {
-    scroll_offset: Vec2,
+    scroll_offset: Cell<Vec2>,
+    drag: RefCell<DragGestureRecognizer>,
+    ballistic: RefCell<Option<Ballistic>>,
 }
 
-impl<W: Widget> WidgetState for Scroll<W> {
+impl ScrollState {
+    pub(crate) fn new() -> Self {
+        ScrollState {
+            scroll_offset: Cell::new(Vec2::new(0., 0.)),
+            drag: RefCell::default(),
+            ballistic: RefCell::new(None),
+        }
+    }
+
+    pub(crate) fn scroll_offset(&self) -> Vec2 {
+        self.scroll_offset.get()
+    }
+
+    /// Moves the content after a fling, returning whether it keeps moving.
+    pub(crate) fn advance(
+        &self,
+        direction: ScrollDirection,
+        size: Size,
+        content_size: Size,
+    ) -> bool {
+        let mut ballistic = self.ballistic.borrow_mut();
+
+        match ballistic.as_ref() {
+            Some(Ballistic { simulation, start }) => {
+                let time = (animation_now() - *start).as_secs_f64();
+                let is_done = simulation.is_done(time);
+                let mut position = simulation.x(time);
+
+                if is_done {
+                    let metrics = metrics(direction, self.scroll_offset.get(), size, content_size);
+                    position = position.clamp(metrics.min, metrics.max);
+                    *ballistic = None;
+                }
+
+                let scroll_offset = direction.with_along(self.scroll_offset.get(), position);
+                self.scroll_offset.set(scroll_offset);
+
+                !is_done
+            }
+            None => false,
+        }
+    }
+
+    /// Brings the content back within range once the viewport or the content
+    /// was resized, unless it is being dragged or flung.
+    pub(crate) fn clamp(&self, direction: ScrollDirection, size: Size, content_size: Size) {
+        if self.ballistic.borrow().is_some() || self.drag.borrow().is_dragging() {
+            return;
+        }
+
+        let metrics = metrics(direction, self.scroll_offset.get(), size, content_size);
+        let position = metrics.position.clamp(metrics.min, metrics.max);
+
+        self.scroll_offset
+            .set(direction.with_along(self.scroll_offset.get(), position));
+    }
+
+    /// Starts tracking a press of the content, stopping it. Called before the
+    /// content receives `event`, so that a tap of the content wins the gesture
+    /// if neither is claimed.
+    pub(crate) fn press(&self, event: &Event, viewport: Rect) {
+        if let Event::MouseDown(e) = event {
+            if e.button == MouseButton::Left && viewport.winding(e.pos) != 0 {
+                *self.ballistic.borrow_mut() = None;
+                self.drag.borrow_mut().add_pointer(e.pos);
+            }
+        }
+    }
+
+    /// Scrolls the content by a wheel, drag or fling `event`, returning whether
+    /// it needs to be painted again. Called after the content received `event`.
+    pub(crate) fn handle_event<P: ScrollPhysics>(
+        &self,
+        event: &Event,
+        physics: &P,
+        direction: ScrollDirection,
+        viewport: Rect,
+        content_size: Size,
+    ) -> bool {
+        let drag = self.drag.borrow_mut().handle_event(event);
+
+        let scroll_offset = self.scroll_offset.get();
+        let size = Size::from(viewport.size());
+        let metrics = metrics(direction, scroll_offset, size, content_size);
+
+        let mut ballistic = self.ballistic.borrow_mut();
+
+        match (event, drag) {
+            (Event::MouseWheel(event), _) if viewport.winding(event.pos) != 0 => {
+                let mut scroll_offset = scroll_offset;
+                scroll(&mut scroll_offset, content_size, event.wheel_delta, size);
+
+                self.scroll_offset.set(scroll_offset);
+                *ballistic = None;
+            }
+            // Content follows the pointer.
+            (_, Some(DragEvent::Update { delta })) => {
+                let position = physics.apply_user_offset(metrics, -direction.along(delta));
+
+                self.scroll_offset
+                    .set(direction.with_along(scroll_offset, position));
+            }
+            (_, Some(DragEvent::End { velocity })) => {
+                *ballistic = physics
+                    .create_ballistic_simulation(metrics, -direction.along(velocity))
+                    .map(Ballistic::start);
+            }
+            // Content pressed while it was out of range is released in place.
+            (Event::MouseUp(_), None) if ballistic.is_none() && metrics.overscroll() > 0. => {
+                *ballistic = physics
+                    .create_ballistic_simulation(metrics, 0.)
+                    .map(Ballistic::start);
+            }
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+impl<W: Widget, P: ScrollPhysics> WidgetState for Scroll<W, P> {
     type State = ScrollState;
 
     fn create_state(&self) -> Self::State {
-        ScrollState {
-            scroll_offset: Vec2::new(0., 0.),
-        }
+        ScrollState::new()
     }
 }
 
-impl<W: Widget> SingleChildWidget for Scroll<W> {
+impl<W: Widget, P: ScrollPhysics> SingleChildWidget for Scroll<W, P> {
     fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
         &self.child
     }
@@ -80,48 +278,55 @@ impl<W: Widget> SingleChildWidget for Scroll<W> {
         constraints.max()
     }
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, offset: &Offset) {
-        if let Err(e) = canvas.save() {
-            log::error!("saving render context failed: {:?}", e);
-            return;
-        }
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let size = ctx.size();
+        let child_size = ctx.child().size();
 
-        let viewport = Rect::from_origin_size(offset, ctx.size());
-        canvas.clip(viewport);
-        canvas.transform(Affine::translate(-ctx.wstate().scroll_offset));
+        let is_animating = ctx
+            .wstate()
+            .advance(self.scroll_direction, size, child_size);
 
-        ctx.child().paint(canvas, offset);
+        let scroll_offset = ctx.wstate().scroll_offset();
 
-        if let Err(e) = canvas.restore() {
-            log::error!("restoring render context failed: {:?}", e);
+        canvas.with_clip(Rect::from_origin_size(Point::ORIGIN, size), |canvas| {
+            canvas.transform(Affine::translate(-scroll_offset));
+
+            ctx.child().paint(canvas);
+        });
+
+        if is_animating {
+            ctx.mark_needs_paint();
         }
 
         // Todo: Draw scroll bar.
     }
 }
 
-impl<W: Widget> WidgetEvent for Scroll<W> {
+impl<W: Widget, P: ScrollPhysics> WidgetEvent for Scroll<W, P> {
     fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
         // Todo: Transform event into child coordinates.
 
         let viewport = Rect::from_origin_size(ctx.offset(), ctx.size());
 
-        let event = event
-            .transform_scroll(ctx.wstate().scroll_offset, viewport)
-            .unwrap();
+        ctx.wstate().press(event, viewport);
 
-        ctx.child().handle_event(&event);
+        let scroll_offset = ctx.wstate().scroll_offset();
+
+        if let Some(event) = event.transform_scroll(scroll_offset, viewport) {
+            ctx.child().handle_event(&event);
+        }
 
-        if let Event::MouseWheel(event) = event {
-            let size = ctx.size();
-            let child_size = ctx.child().size();
+        let child_size = ctx.child().size();
+        let needs_paint = ctx.wstate().handle_event(
+            event,
+            &self.physics,
+            self.scroll_direction,
+            viewport,
+            child_size,
+        );
 
-            scroll(
-                &mut ctx.wstate_mut().scroll_offset,
-                child_size,
-                event.wheel_delta,
-                size,
-            );
+        if needs_paint {
+            ctx.mark_needs_paint();
         }
 
         true
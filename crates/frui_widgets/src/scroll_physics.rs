@@ -0,0 +1,407 @@
+/// Drag coefficient of [`FrictionSimulation`]s of scroll physics: fraction of
+/// the velocity left after one second.
+const SCROLL_FRICTION: f64 = 0.135;
+
+/// Release velocity (in pixels per second) below which scrolling stops right
+/// away, if it is within range.
+const MIN_FLING_VELOCITY: f64 = 50.;
+
+/// Velocity (in pixels per second) at which a simulation is considered done.
+const VELOCITY_TOLERANCE: f64 = 10.;
+
+/// Distance (in pixels) from which a spring is considered settled.
+const DISTANCE_TOLERANCE: f64 = 0.5;
+
+/// Angular frequency of the springs of [`BouncingScrollPhysics`].
+const SPRING_OMEGA: f64 = 14.;
+
+/// Motion of a value over time, in seconds since its start.
+pub trait Simulation {
+    /// Value at `time`.
+    fn x(&self, time: f64) -> f64;
+
+    /// Velocity of the value at `time`, per second.
+    fn dx(&self, time: f64) -> f64;
+
+    /// Whether the value stopped moving by `time`.
+    fn is_done(&self, time: f64) -> bool;
+}
+
+/// Value moving at an initial velocity, slowed down by friction proportional
+/// to its velocity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrictionSimulation {
+    /// Fraction of the velocity left after one second.
+    drag: f64,
+    position: f64,
+    velocity: f64,
+}
+
+impl FrictionSimulation {
+    pub fn new(drag: f64, position: f64, velocity: f64) -> Self {
+        Self {
+            drag,
+            position,
+            velocity,
+        }
+    }
+
+    /// Value at which this simulation eventually stops.
+    pub fn final_x(&self) -> f64 {
+        self.position - self.velocity / self.drag.ln()
+    }
+
+    /// Time at which the value reaches `x`, if it ever does.
+    fn time_at_x(&self, x: f64) -> Option<f64> {
+        let at_end = (x - self.position) / (self.final_x() - self.position);
+
+        (0.0..1.0)
+            .contains(&at_end)
+            .then(|| (1. - at_end).ln() / self.drag.ln())
+    }
+}
+
+impl Simulation for FrictionSimulation {
+    fn x(&self, time: f64) -> f64 {
+        self.position + self.velocity * (self.drag.powf(time) - 1.) / self.drag.ln()
+    }
+
+    fn dx(&self, time: f64) -> f64 {
+        self.velocity * self.drag.powf(time)
+    }
+
+    fn is_done(&self, time: f64) -> bool {
+        self.dx(time).abs() < VELOCITY_TOLERANCE
+    }
+}
+
+/// Critically damped spring pulling a value towards `target`, which it
+/// approaches without oscillating.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpringSimulation {
+    target: f64,
+    /// Displacement from the target at the start.
+    c1: f64,
+    c2: f64,
+    omega: f64,
+}
+
+impl SpringSimulation {
+    pub fn new(omega: f64, position: f64, velocity: f64, target: f64) -> Self {
+        let c1 = position - target;
+
+        Self {
+            target,
+            c1,
+            c2: velocity + omega * c1,
+            omega,
+        }
+    }
+}
+
+impl Simulation for SpringSimulation {
+    fn x(&self, time: f64) -> f64 {
+        self.target + (self.c1 + self.c2 * time) * (-self.omega * time).exp()
+    }
+
+    fn dx(&self, time: f64) -> f64 {
+        (self.c2 - self.omega * (self.c1 + self.c2 * time)) * (-self.omega * time).exp()
+    }
+
+    fn is_done(&self, time: f64) -> bool {
+        (self.x(time) - self.target).abs() < DISTANCE_TOLERANCE
+            && self.dx(time).abs() < VELOCITY_TOLERANCE
+    }
+}
+
+/// Scroll position along with the range it is normally kept in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollMetrics {
+    pub position: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Length of the viewport along the scroll direction.
+    pub viewport: f64,
+}
+
+impl ScrollMetrics {
+    /// Distance by which the position is outside of its range.
+    pub fn overscroll(&self) -> f64 {
+        (self.min - self.position)
+            .max(self.position - self.max)
+            .max(0.)
+    }
+}
+
+/// How a scroll position responds to dragging and continues moving once a drag
+/// was released.
+pub trait ScrollPhysics {
+    /// New position after the content was dragged by `delta`.
+    fn apply_user_offset(&self, metrics: ScrollMetrics, delta: f64) -> f64;
+
+    /// Motion of the position after a drag was released with the position
+    /// changing at `velocity` (in pixels per second), or `None` if it should
+    /// stay where it is.
+    fn create_ballistic_simulation(
+        &self,
+        metrics: ScrollMetrics,
+        velocity: f64,
+    ) -> Option<Box<dyn Simulation>>;
+}
+
+/// Keeps the scroll position within its range, stopping a fling once it hits
+/// either end.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClampingScrollPhysics;
+
+impl ScrollPhysics for ClampingScrollPhysics {
+    fn apply_user_offset(&self, metrics: ScrollMetrics, delta: f64) -> f64 {
+        (metrics.position + delta).clamp(metrics.min, metrics.max.max(metrics.min))
+    }
+
+    fn create_ballistic_simulation(
+        &self,
+        metrics: ScrollMetrics,
+        velocity: f64,
+    ) -> Option<Box<dyn Simulation>> {
+        if velocity.abs() < MIN_FLING_VELOCITY {
+            return None;
+        }
+
+        Some(Box::new(ClampedSimulation {
+            friction: FrictionSimulation::new(SCROLL_FRICTION, metrics.position, velocity),
+            min: metrics.min,
+            max: metrics.max.max(metrics.min),
+        }))
+    }
+}
+
+/// Friction simulation which stops at either end of its range.
+struct ClampedSimulation {
+    friction: FrictionSimulation,
+    min: f64,
+    max: f64,
+}
+
+impl Simulation for ClampedSimulation {
+    fn x(&self, time: f64) -> f64 {
+        self.friction.x(time).clamp(self.min, self.max)
+    }
+
+    fn dx(&self, time: f64) -> f64 {
+        match self.x(time) == self.friction.x(time) {
+            true => self.friction.dx(time),
+            false => 0.,
+        }
+    }
+
+    fn is_done(&self, time: f64) -> bool {
+        self.friction.is_done(time) || self.dx(time) == 0.
+    }
+}
+
+/// Lets the scroll position go past either end of its range, with increasing
+/// resistance, and springs it back once released.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BouncingScrollPhysics;
+
+impl BouncingScrollPhysics {
+    /// Fraction of a drag applied to the position overscrolled by `fraction` of
+    /// the viewport.
+    fn friction_factor(fraction: f64) -> f64 {
+        0.52 * (1. - fraction.min(1.)).powi(2)
+    }
+}
+
+impl ScrollPhysics for BouncingScrollPhysics {
+    fn apply_user_offset(&self, metrics: ScrollMetrics, delta: f64) -> f64 {
+        let ScrollMetrics { position, min, .. } = metrics;
+        let max = metrics.max.max(min);
+
+        // Moving back into range isn't resisted.
+        let is_leaving = (position <= min && delta < 0.) || (position >= max && delta > 0.);
+
+        if !is_leaving && metrics.overscroll() == 0. {
+            // Drag starting in range may end out of it, the rest is resisted.
+            let target = position + delta;
+            let in_range = target.clamp(min, max);
+            let outside = target - in_range;
+
+            return in_range + outside * Self::friction_factor(0.);
+        }
+
+        if !is_leaving {
+            return position + delta;
+        }
+
+        let fraction = metrics.overscroll() / metrics.viewport.max(1.);
+
+        position + delta * Self::friction_factor(fraction)
+    }
+
+    fn create_ballistic_simulation(
+        &self,
+        metrics: ScrollMetrics,
+        velocity: f64,
+    ) -> Option<Box<dyn Simulation>> {
+        let ScrollMetrics { position, min, .. } = metrics;
+        let max = metrics.max.max(min);
+
+        if metrics.overscroll() > 0. {
+            let target = position.clamp(min, max);
+            return Some(Box::new(SpringSimulation::new(
+                SPRING_OMEGA,
+                position,
+                velocity,
+                target,
+            )));
+        }
+
+        if velocity.abs() < MIN_FLING_VELOCITY {
+            return None;
+        }
+
+        let friction = FrictionSimulation::new(SCROLL_FRICTION, position, velocity);
+        let bound = if velocity > 0. { max } else { min };
+
+        Some(Box::new(BouncingSimulation {
+            friction,
+            bounce: friction.time_at_x(bound).map(|time| {
+                let spring = SpringSimulation::new(SPRING_OMEGA, bound, friction.dx(time), bound);
+                (time, spring)
+            }),
+        }))
+    }
+}
+
+/// Friction simulation which changes to a spring back to the end of the range
+/// once it goes past it.
+struct BouncingSimulation {
+    friction: FrictionSimulation,
+    /// Time at which the friction reaches an end of the range, and the spring
+    /// which continues from there.
+    bounce: Option<(f64, SpringSimulation)>,
+}
+
+impl Simulation for BouncingSimulation {
+    fn x(&self, time: f64) -> f64 {
+        match self.bounce {
+            Some((start, spring)) if time >= start => spring.x(time - start),
+            _ => self.friction.x(time),
+        }
+    }
+
+    fn dx(&self, time: f64) -> f64 {
+        match self.bounce {
+            Some((start, spring)) if time >= start => spring.dx(time - start),
+            _ => self.friction.dx(time),
+        }
+    }
+
+    fn is_done(&self, time: f64) -> bool {
+        match self.bounce {
+            Some((start, spring)) if time >= start => spring.is_done(time - start),
+            Some(_) => false,
+            None => self.friction.is_done(time),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const METRICS: ScrollMetrics = ScrollMetrics {
+        position: 100.,
+        min: 0.,
+        max: 10_000.,
+        viewport: 500.,
+    };
+
+    /// Runs `simulation` until it is done (for at most `max_time` seconds),
+    /// returning the final value.
+    fn settle(simulation: &dyn Simulation, max_time: f64) -> f64 {
+        let mut time = 0.;
+
+        while !simulation.is_done(time) && time < max_time {
+            time += 1. / 60.;
+        }
+
+        simulation.x(time)
+    }
+
+    fn fling(physics: &dyn ScrollPhysics, metrics: ScrollMetrics, velocity: f64) -> f64 {
+        physics
+            .create_ballistic_simulation(metrics, velocity)
+            .map_or(metrics.position, |simulation| settle(&*simulation, 10.))
+    }
+
+    #[test]
+    fn fast_fling_travels_farther() {
+        for physics in [
+            &ClampingScrollPhysics as &dyn ScrollPhysics,
+            &BouncingScrollPhysics,
+        ] {
+            let slow = fling(physics, METRICS, 500.);
+            let fast = fling(physics, METRICS, 3000.);
+
+            assert!(METRICS.position < slow && slow < fast, "{slow} {fast}");
+
+            // Too slow to fling at all.
+            assert_eq!(fling(physics, METRICS, 10.), METRICS.position);
+        }
+    }
+
+    #[test]
+    fn flings_end_within_range() {
+        let metrics = ScrollMetrics {
+            max: 300.,
+            ..METRICS
+        };
+
+        assert_eq!(fling(&ClampingScrollPhysics, metrics, 5000.), 300.);
+        assert_eq!(fling(&ClampingScrollPhysics, metrics, -5000.), 0.);
+
+        // Goes past the end, then springs back to it.
+        let simulation = BouncingScrollPhysics
+            .create_ballistic_simulation(metrics, 5000.)
+            .unwrap();
+        let peak = (0..120)
+            .map(|frame| simulation.x(frame as f64 / 60.))
+            .fold(f64::MIN, f64::max);
+
+        assert!(peak > 300.);
+        assert!((settle(&*simulation, 10.) - 300.).abs() < DISTANCE_TOLERANCE);
+
+        // Released while overscrolled, even without velocity.
+        let overscrolled = ScrollMetrics {
+            position: -40.,
+            ..metrics
+        };
+        let back = fling(&BouncingScrollPhysics, overscrolled, 0.);
+        assert!(back.abs() < DISTANCE_TOLERANCE);
+    }
+
+    #[test]
+    fn dragging_past_the_end_is_resisted() {
+        let at_start = ScrollMetrics {
+            position: 0.,
+            ..METRICS
+        };
+
+        assert_eq!(ClampingScrollPhysics.apply_user_offset(at_start, -50.), 0.);
+
+        let bounced = BouncingScrollPhysics.apply_user_offset(at_start, -50.);
+        assert!((-50. ..0.).contains(&bounced), "{bounced}");
+
+        // Dragging back into range isn't resisted.
+        let overscrolled = ScrollMetrics {
+            position: -20.,
+            ..METRICS
+        };
+        assert_eq!(
+            BouncingScrollPhysics.apply_user_offset(overscrolled, 10.),
+            -10.
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use std::{
+    cell::{Cell, RefCell},
+    ops::Range,
+    rc::Rc,
+};
+
+use druid_shell::{
+    piet::{PietTextLayout, Text as TextExt, TextLayout, TextLayoutBuilder},
+    Application,
+};
+use frui::{
+    api::events::{Event, WidgetEvent},
+    app::{
+        listeners::clipboard::{ClipboardTarget, ClipboardTargetKey, CLIPBOARD_TARGETS},
+        TEXT_FACTORY,
+    },
+    prelude::*,
+};
+
+use crate::{Enabled, Text};
+
+/// Text which can be selected with the pointer and copied with Ctrl+C (Cmd+C
+/// on macOS).
+///
+/// Pressing the pointer over the text collapses the selection at that point
+/// and dragging extends it, also beyond the bounds of the text. It also
+/// focuses the text, so that it receives the copy shortcut (see
+/// [`ClipboardListener`](crate::ClipboardListener)). Pressing the pointer
+/// anywhere else clears the selection and unfocuses the text. Selection always starts and
+/// ends at grapheme boundaries, as reported by hit testing of the text layout.
+#[derive(LeafWidget)]
+pub struct SelectableText<S: AsRef<str>> {
+    text: Text<S>,
+    selection_color: Color,
+}
+
+impl<S: AsRef<str>> SelectableText<S> {
+    pub fn new(string: S) -> Self {
+        Self {
+            text: Text::new(string),
+            selection_color: Color::rgba8(51, 153, 255, 128),
+        }
+    }
+
+    pub fn size(mut self, size: f64) -> Self {
+        self.text = self.text.size(size);
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.text = self.text.color(color);
+        self
+    }
+
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.text = self.text.weight(weight);
+        self
+    }
+
+    pub fn font(mut self, font: FontFamily) -> Self {
+        self.text = self.text.font(font);
+        self
+    }
+
+    pub fn selection_color(mut self, color: Color) -> Self {
+        self.selection_color = color;
+        self
+    }
+}
+
+/// Selected range of text, in bytes.
+#[derive(Default)]
+struct Selection {
+    /// Copy of the text as of the last layout, used for copying the selection.
+    text: String,
+    anchor: usize,
+    focus: usize,
+    is_dragging: bool,
+}
+
+impl Selection {
+    fn range(&self) -> Range<usize> {
+        self.anchor.min(self.focus)..self.anchor.max(self.focus)
+    }
+
+    fn selected_text(&self) -> Option<&str> {
+        let range = self.range();
+
+        if range.is_empty() {
+            None
+        } else {
+            self.text.get(range)
+        }
+    }
+
+    fn collapse(&mut self, idx: usize) {
+        self.anchor = idx;
+        self.focus = idx;
+    }
+
+    /// Updates the copy of the text, keeping the selection within it.
+    fn set_text(&mut self, text: &str) {
+        if self.text != text {
+            self.text = text.to_owned();
+
+            if self.text.get(self.range()).is_none() {
+                self.collapse(0);
+            }
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct SelectableTextState {
+    selection: Rc<RefCell<Selection>>,
+    /// Key of this state, registered as a clipboard target until disposed.
+    target_key: Cell<Option<ClipboardTargetKey>>,
+}
+
+impl ClipboardTarget for SelectableTextState {
+    fn copy(&self) {
+        if let Some(text) = self.selection.borrow().selected_text() {
+            if let Some(app) = Application::try_global() {
+                app.clipboard().put_string(text);
+            }
+        }
+    }
+}
+
+impl<S: AsRef<str>> WidgetState for SelectableText<S> {
+    type State = SelectableTextState;
+
+    fn create_state(&self) -> Self::State {
+        SelectableTextState {
+            selection: Rc::new(RefCell::new(Selection::default())),
+            target_key: Cell::new(None),
+        }
+    }
+
+    fn mount(&self, ctx: BuildContext<Self>) {
+        let state = ctx.state();
+
+        // State is registered (not the widget), so it stays registered and
+        // focused when this widget is updated.
+        if state.target_key.get().is_none() {
+            let key = CLIPBOARD_TARGETS.with(|targets| unsafe {
+                targets
+                    .borrow_mut()
+                    .register(&*state as &dyn ClipboardTarget)
+            });
+
+            state.target_key.set(Some(key));
+        }
+    }
+
+    /// Selection is cleared and focus dropped once an [`Enabled`] ancestor
+    /// disables this widget.
+    fn did_change_dependencies(&self, ctx: BuildContext<Self>) {
+        if Enabled::of(ctx) {
+            return;
+        }
+
+        let state = ctx.state();
+
+        if let Some(key) = state.target_key.get() {
+            CLIPBOARD_TARGETS.with(|targets| targets.borrow_mut().unfocus(&key));
+        }
+
+        let mut selection = state.selection.borrow_mut();
+        selection.collapse(0);
+        selection.is_dragging = false;
+    }
+
+    fn dispose(&self, ctx: BuildContext<Self>) {
+        if let Some(key) = ctx.state().target_key.take() {
+            CLIPBOARD_TARGETS.with(|targets| targets.borrow_mut().unregister(&key));
+        }
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl<S: AsRef<str>> RenderState for SelectableText<S> {
+    type State = PietTextLayout;
+
+    fn create_state(&self) -> Self::State {
+        TEXT_FACTORY.with(|f| f.get().new_text_layout("").build().unwrap())
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl<S: AsRef<str>> LeafWidget for SelectableText<S> {
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.depend_on_font(&self.text.font_family);
+        *ctx.rstate_mut() = self.text.build_layout(constraints.max().width);
+
+        ctx.wstate()
+            .selection
+            .borrow_mut()
+            .set_text(self.text.as_str());
+
+        let text_size = ctx.rstate().size();
+
+        constraints.constrain(Size {
+            width: text_size.width,
+            height: text_size.height,
+        })
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let range = ctx.wstate().selection.borrow().range();
+        let layout = ctx.rstate();
+
+        if !range.is_empty() {
+            let brush = &canvas.solid_brush(self.selection_color.clone());
+
+            for rect in layout.rects_for_range(range) {
+                PietRenderContext::fill(canvas, rect, brush);
+            }
+        }
+
+        PietRenderContext::draw_text(canvas, &layout, Point::ORIGIN);
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl<S: AsRef<str>> WidgetEvent for SelectableText<S> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        let offset = ctx.offset();
+
+        // Index of the grapheme boundary closest to `pos`.
+        let hit_test = |ctx: &RenderContext<Self>, pos: Point| {
+            let pos = Point::new(pos.x - offset.x, pos.y - offset.y);
+            ctx.rstate().hit_test_point(pos).idx
+        };
+
+        let changed = match event {
+            Event::MouseDown(e) if e.button == MouseButton::Left => {
+                let idx = ctx
+                    .point_in_layout_bounds(e.pos)
+                    .then(|| hit_test(&ctx, e.pos));
+
+                let state = ctx.wstate();
+
+                if let Some(key) = state.target_key.get() {
+                    CLIPBOARD_TARGETS.with(|targets| match idx {
+                        Some(_) => targets.borrow_mut().focus(&key),
+                        None => targets.borrow_mut().unfocus(&key),
+                    });
+                }
+
+                let mut selection = state.selection.borrow_mut();
+                let had_selection = !selection.range().is_empty();
+
+                selection.collapse(idx.unwrap_or(0));
+                selection.is_dragging = idx.is_some();
+
+                had_selection
+            }
+            Event::MouseMove(e) => {
+                let is_dragging = ctx.wstate().selection.borrow().is_dragging;
+
+                if is_dragging {
+                    let idx = hit_test(&ctx, e.pos);
+
+                    let state = ctx.wstate();
+                    let mut selection = state.selection.borrow_mut();
+
+                    let changed = selection.focus != idx;
+                    selection.focus = idx;
+                    changed
+                } else {
+                    false
+                }
+            }
+            Event::MouseUp(e) if e.button == MouseButton::Left => {
+                ctx.wstate().selection.borrow_mut().is_dragging = false;
+                false
+            }
+            _ => false,
+        };
+
+        if changed {
+            ctx.mark_needs_paint();
+        }
+
+        false
+    }
+}
+
+#[cfg(feature = "miri")]
+impl<S: AsRef<str>> LeafWidget for SelectableText<S> {
+    fn layout(&self, _: RenderContext<Self>, constraints: Constraints) -> Size {
+        constraints.biggest()
+    }
+
+    fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn selection_is_kept_within_text() {
+        let mut selection = Selection::default();
+        selection.set_text("zażółć");
+
+        selection.anchor = 6;
+        selection.focus = 2;
+        assert_eq!(selection.selected_text(), Some("żó"));
+
+        // Shorter text can't contain the old selection.
+        selection.set_text("za");
+        assert_eq!(selection.range(), 0..0);
+        assert_eq!(selection.selected_text(), None);
+    }
+}
@@ -0,0 +1,363 @@
+use frui::prelude::*;
+
+/// Accuracy of curves of shapes converted to paths.
+const TOLERANCE: f64 = 0.1;
+
+/// Outline painted along the edge of a [`ShapeBorder`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorderSide {
+    pub color: Color,
+    pub width: f64,
+}
+
+impl BorderSide {
+    /// Side which isn't painted.
+    pub const NONE: BorderSide = BorderSide {
+        color: Color::rgba8(0, 0, 0, 0),
+        width: 0.,
+    };
+
+    pub fn new(color: Color, width: f64) -> Self {
+        Self { color, width }
+    }
+
+    fn lerp(&self, other: &BorderSide, t: f64) -> BorderSide {
+        BorderSide {
+            color: lerp_color(&self.color, &other.color, t),
+            width: lerp(self.width, other.width, t),
+        }
+    }
+}
+
+impl Default for BorderSide {
+    fn default() -> Self {
+        BorderSide::NONE
+    }
+}
+
+/// Shape of a widget, used to paint its background and outline, to clip its
+/// child, or to cast its shadow.
+///
+/// Shapes are sized by the rect they are given, so the same shape can be used
+/// by widgets of any size.
+pub trait ShapeBorder {
+    /// Outline of the shape filling `rect`, for filling or clipping to it.
+    fn path(&self, rect: Rect) -> BezPath;
+
+    /// Outline painted by [`paint`](ShapeBorder::paint).
+    fn border_side(&self) -> BorderSide {
+        BorderSide::NONE
+    }
+
+    /// Path along the middle of the outline of the shape filling `rect`, so
+    /// that stroking it with the width of the [`border_side`](ShapeBorder::border_side)
+    /// stays within that shape.
+    fn stroke_path(&self, rect: Rect) -> BezPath {
+        self.path(rect.inset(-self.border_side().width / 2.))
+    }
+
+    /// Paints the outline of the shape filling `rect`.
+    fn paint(&self, canvas: &mut PaintContext, rect: Rect) {
+        let side = self.border_side();
+
+        if side.width > 0. {
+            let brush = &canvas.solid_brush(side.color);
+            canvas.stroke(self.stroke_path(rect), brush, side.width);
+        }
+    }
+
+    /// This shape as a [`RectangularBorder`], if it is one, which makes it
+    /// interpolatable with other rectangular shapes.
+    fn rectangular(&self) -> Option<RectangularBorder> {
+        None
+    }
+}
+
+/// Shape derived from the rect it fills: a rectangle with rounded or beveled
+/// corners, which at most make its shorter side a half circle. It's what all
+/// of [`RoundedRectangleBorder`], [`BeveledRectangleBorder`],
+/// [`StadiumBorder`] and [`CircleBorder`] are, which makes them interpolatable
+/// with [`RectangularBorder::lerp`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RectangularBorder {
+    /// Radius of the corners.
+    pub radius: f64,
+    /// Radius of the corners added to `radius`, relative to the shorter side
+    /// of the rect.
+    pub relative_radius: f64,
+    /// How far the rect is shrunk towards the square at its center, from `0`
+    /// (filling the whole rect) to `1` (filling just the square).
+    pub squareness: f64,
+    /// Whether the corners are cut straight instead of rounded.
+    pub beveled: bool,
+    pub side: BorderSide,
+}
+
+impl RectangularBorder {
+    /// Shape between `a` (at `0`) and `b` (at `1`), e.g. to animate a circle
+    /// into a rounded rectangle. It's `None` if either of them isn't
+    /// rectangular, or only one of them is beveled.
+    pub fn lerp(a: &dyn ShapeBorder, b: &dyn ShapeBorder, t: f64) -> Option<RectangularBorder> {
+        let (a, b) = (a.rectangular()?, b.rectangular()?);
+
+        if a.beveled != b.beveled {
+            return None;
+        }
+
+        Some(RectangularBorder {
+            radius: lerp(a.radius, b.radius, t),
+            relative_radius: lerp(a.relative_radius, b.relative_radius, t),
+            squareness: lerp(a.squareness, b.squareness, t),
+            beveled: a.beveled,
+            side: a.side.lerp(&b.side, t),
+        })
+    }
+
+    /// Rect filled by this shape within `rect`, along with its corner radius.
+    fn bounds(&self, rect: Rect) -> (Rect, f64) {
+        let rect = rect.abs();
+        let shorter = rect.width().min(rect.height());
+
+        let square = Rect::from_center_size(rect.center(), (shorter, shorter));
+        let bounds = Rect::new(
+            lerp(rect.x0, square.x0, self.squareness),
+            lerp(rect.y0, square.y0, self.squareness),
+            lerp(rect.x1, square.x1, self.squareness),
+            lerp(rect.y1, square.y1, self.squareness),
+        );
+
+        let radius = self.radius + self.relative_radius * shorter;
+        (bounds, radius.clamp(0., shorter / 2.))
+    }
+}
+
+impl ShapeBorder for RectangularBorder {
+    fn path(&self, rect: Rect) -> BezPath {
+        let (rect, radius) = self.bounds(rect);
+
+        if !self.beveled {
+            return RoundedRect::from_rect(rect, radius).to_path(TOLERANCE);
+        }
+
+        let mut path = BezPath::new();
+        path.move_to((rect.x0 + radius, rect.y0));
+        path.line_to((rect.x1 - radius, rect.y0));
+        path.line_to((rect.x1, rect.y0 + radius));
+        path.line_to((rect.x1, rect.y1 - radius));
+        path.line_to((rect.x1 - radius, rect.y1));
+        path.line_to((rect.x0 + radius, rect.y1));
+        path.line_to((rect.x0, rect.y1 - radius));
+        path.line_to((rect.x0, rect.y0 + radius));
+        path.close_path();
+        path
+    }
+
+    fn border_side(&self) -> BorderSide {
+        self.side.clone()
+    }
+
+    fn rectangular(&self) -> Option<RectangularBorder> {
+        Some(self.clone())
+    }
+}
+
+/// Rectangle with corners rounded by `radius`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RoundedRectangleBorder {
+    pub radius: f64,
+    pub side: BorderSide,
+}
+
+impl RoundedRectangleBorder {
+    pub fn new(radius: f64) -> Self {
+        Self {
+            radius,
+            side: BorderSide::NONE,
+        }
+    }
+
+    pub fn side(mut self, side: BorderSide) -> Self {
+        self.side = side;
+        self
+    }
+}
+
+/// Rectangle with corners cut straight `radius` from each of their edges.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BeveledRectangleBorder {
+    pub radius: f64,
+    pub side: BorderSide,
+}
+
+impl BeveledRectangleBorder {
+    pub fn new(radius: f64) -> Self {
+        Self {
+            radius,
+            side: BorderSide::NONE,
+        }
+    }
+
+    pub fn side(mut self, side: BorderSide) -> Self {
+        self.side = side;
+        self
+    }
+}
+
+/// Rectangle with its shorter sides replaced by half circles.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StadiumBorder {
+    pub side: BorderSide,
+}
+
+/// Circle at the center of the rect, as large as fits within it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CircleBorder {
+    pub side: BorderSide,
+}
+
+impl ShapeBorder for RoundedRectangleBorder {
+    fn path(&self, rect: Rect) -> BezPath {
+        self.rectangular().unwrap().path(rect)
+    }
+
+    fn border_side(&self) -> BorderSide {
+        self.side.clone()
+    }
+
+    fn rectangular(&self) -> Option<RectangularBorder> {
+        Some(RectangularBorder {
+            radius: self.radius,
+            relative_radius: 0.,
+            squareness: 0.,
+            beveled: false,
+            side: self.side.clone(),
+        })
+    }
+}
+
+impl ShapeBorder for BeveledRectangleBorder {
+    fn path(&self, rect: Rect) -> BezPath {
+        self.rectangular().unwrap().path(rect)
+    }
+
+    fn border_side(&self) -> BorderSide {
+        self.side.clone()
+    }
+
+    fn rectangular(&self) -> Option<RectangularBorder> {
+        Some(RectangularBorder {
+            radius: self.radius,
+            relative_radius: 0.,
+            squareness: 0.,
+            beveled: true,
+            side: self.side.clone(),
+        })
+    }
+}
+
+impl ShapeBorder for StadiumBorder {
+    fn path(&self, rect: Rect) -> BezPath {
+        self.rectangular().unwrap().path(rect)
+    }
+
+    fn border_side(&self) -> BorderSide {
+        self.side.clone()
+    }
+
+    fn rectangular(&self) -> Option<RectangularBorder> {
+        Some(RectangularBorder {
+            radius: 0.,
+            relative_radius: 0.5,
+            squareness: 0.,
+            beveled: false,
+            side: self.side.clone(),
+        })
+    }
+}
+
+impl ShapeBorder for CircleBorder {
+    fn path(&self, rect: Rect) -> BezPath {
+        let rect = rect.abs();
+        let radius = rect.width().min(rect.height()) / 2.;
+
+        Circle::new(rect.center(), radius).to_path(TOLERANCE)
+    }
+
+    fn border_side(&self) -> BorderSide {
+        self.side.clone()
+    }
+
+    fn rectangular(&self) -> Option<RectangularBorder> {
+        Some(RectangularBorder {
+            radius: 0.,
+            relative_radius: 0.5,
+            squareness: 1.,
+            beveled: false,
+            side: self.side.clone(),
+        })
+    }
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+fn lerp_color(a: &Color, b: &Color, t: f64) -> Color {
+    let (r0, g0, b0, a0) = a.as_rgba();
+    let (r1, g1, b1, a1) = b.as_rgba();
+
+    Color::rgba(
+        lerp(r0, r1, t),
+        lerp(g0, g1, t),
+        lerp(b0, b1, t),
+        lerp(a0, a1, t),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn circle_morphs_into_rounded_rectangle() {
+        let rect = Rect::new(0., 0., 200., 100.);
+        let (circle, rounded) = (CircleBorder::default(), RoundedRectangleBorder::new(10.));
+
+        let start = RectangularBorder::lerp(&circle, &rounded, 0.).unwrap();
+        assert_eq!(start.bounds(rect), (Rect::new(50., 0., 150., 100.), 50.));
+
+        let middle = RectangularBorder::lerp(&circle, &rounded, 0.5).unwrap();
+        assert_eq!(middle.bounds(rect), (Rect::new(25., 0., 175., 100.), 30.));
+
+        let end = RectangularBorder::lerp(&circle, &rounded, 1.).unwrap();
+        assert_eq!(end.bounds(rect), (rect, 10.));
+
+        // Circle is as large as fits, like its rectangular form.
+        assert_eq!(
+            circle.path(rect).bounding_box(),
+            Rect::new(50., 0., 150., 100.)
+        );
+
+        // There is no sensible shape between rounded and beveled corners.
+        assert!(
+            RectangularBorder::lerp(&rounded, &BeveledRectangleBorder::new(10.), 0.5).is_none()
+        );
+    }
+
+    #[test]
+    fn corner_radius_is_at_most_half_of_shorter_side() {
+        let rect = Rect::new(0., 0., 200., 100.);
+
+        assert_eq!(
+            RoundedRectangleBorder::new(80.)
+                .rectangular()
+                .unwrap()
+                .bounds(rect),
+            (rect, 50.)
+        );
+        assert_eq!(
+            StadiumBorder::default().rectangular().unwrap().bounds(rect),
+            (rect, 50.)
+        );
+    }
+}
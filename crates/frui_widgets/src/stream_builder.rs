@@ -0,0 +1,269 @@
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use frui::prelude::*;
+use futures_core::Stream;
+
+/// Number of items a [`StreamBuilder`] takes from a stream which keeps
+/// yielding, before it lets other tasks and frames run.
+const MAX_ITEMS_PER_POLL: usize = 64;
+
+/// Whether a stream of a [`StreamBuilder`] is still yielding items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Stream didn't yield any item yet.
+    Waiting,
+    /// Stream yielded an item and may yield more.
+    Active,
+    /// Stream ended.
+    Done,
+}
+
+/// Latest item of a stream, as seen by a [`StreamBuilder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamSnapshot<T> {
+    state: ConnectionState,
+    data: Option<T>,
+}
+
+impl<T> StreamSnapshot<T> {
+    fn new() -> Self {
+        Self {
+            state: ConnectionState::Waiting,
+            data: None,
+        }
+    }
+
+    pub fn state(&self) -> ConnectionState {
+        self.state
+    }
+
+    /// Latest item yielded by the stream, kept after the stream ends.
+    pub fn data(&self) -> Option<&T> {
+        self.data.as_ref()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.state == ConnectionState::Active
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.state == ConnectionState::Done
+    }
+}
+
+/// Builds its child with `builder` from the [`StreamSnapshot`] of `stream`,
+/// building it again whenever the stream yields an item or ends.
+///
+/// ```
+/// # use frui::prelude::*;
+/// # use frui_widgets::*;
+/// # let (_sender, messages) = futures_channel::mpsc::unbounded::<String>();
+/// StreamBuilder::new(messages, |snapshot| match snapshot.data() {
+///     Some(message) => Text::new(message.clone()),
+///     None => Text::new("No messages yet".to_string()),
+/// });
+/// ```
+///
+/// Stream is polled on the UI thread by the run loop (see `spawn_local`). If
+/// it yields several items between frames, the widget is built only with the
+/// latest one. If the widget is removed, the stream is dropped. If the widget
+/// is rebuilt with a new stream, the previous one is dropped and the snapshot
+/// is `Waiting` again until the new one yields.
+#[derive(ViewWidget)]
+pub struct StreamBuilder<S, W, B>
+where
+    S: Stream + 'static,
+    S::Item: 'static,
+    W: Widget,
+    B: Fn(&StreamSnapshot<S::Item>) -> W,
+{
+    /// Taken once the stream is subscribed to.
+    stream: Cell<Option<S>>,
+    builder: B,
+}
+
+impl<S, W, B> StreamBuilder<S, W, B>
+where
+    S: Stream + 'static,
+    S::Item: 'static,
+    W: Widget,
+    B: Fn(&StreamSnapshot<S::Item>) -> W,
+{
+    pub fn new(stream: S, builder: B) -> Self {
+        Self {
+            stream: Cell::new(Some(stream)),
+            builder,
+        }
+    }
+}
+
+pub struct StreamBuilderState<T> {
+    snapshot: Rc<RefCell<StreamSnapshot<T>>>,
+    /// Dropping it cancels the subscription.
+    task: Option<Task>,
+}
+
+impl<S, W, B> WidgetState for StreamBuilder<S, W, B>
+where
+    S: Stream + 'static,
+    S::Item: 'static,
+    W: Widget,
+    B: Fn(&StreamSnapshot<S::Item>) -> W,
+{
+    type State = StreamBuilderState<S::Item>;
+
+    fn create_state(&self) -> Self::State {
+        StreamBuilderState {
+            snapshot: Rc::new(RefCell::new(StreamSnapshot::new())),
+            task: None,
+        }
+    }
+
+    fn mount(&self, ctx: BuildContext<Self>) {
+        // Updated widget is mounted again, after its new stream replaced the
+        // previous one in `did_update_widget`.
+        if ctx.state().task.is_some() {
+            return;
+        }
+
+        if let Some(stream) = self.stream.take() {
+            let listener = ctx.listener();
+
+            ctx.state_mut().task = Some(spawn_local(Subscription {
+                stream: Box::pin(stream),
+                snapshot: ctx.state().snapshot.clone(),
+                on_change: Box::new(move || listener.notify()),
+            }));
+        }
+    }
+
+    fn did_update_widget(&self, ctx: BuildContext<Self>) {
+        let mut state = ctx.state_mut();
+
+        // Previous subscription is cancelled and a new snapshot is created, so
+        // that the previous subscription can't overwrite it.
+        state.task = None;
+        state.snapshot = Rc::new(RefCell::new(StreamSnapshot::new()));
+    }
+
+    fn dispose(&self, ctx: BuildContext<Self>) {
+        ctx.state_mut().task = None;
+    }
+}
+
+impl<S, W, B> ViewWidget for StreamBuilder<S, W, B>
+where
+    S: Stream + 'static,
+    S::Item: 'static,
+    W: Widget,
+    B: Fn(&StreamSnapshot<S::Item>) -> W,
+{
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        let snapshot = ctx.state().snapshot.clone();
+        let snapshot = snapshot.borrow();
+
+        (self.builder)(&snapshot)
+    }
+}
+
+/// Future which stores items of `stream` in `snapshot`, calling `on_change`
+/// once it changes.
+struct Subscription<S: Stream> {
+    stream: Pin<Box<S>>,
+    snapshot: Rc<RefCell<StreamSnapshot<S::Item>>>,
+    on_change: Box<dyn Fn()>,
+}
+
+impl<S: Stream> Future for Subscription<S> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let mut changed = false;
+
+        for _ in 0..MAX_ITEMS_PER_POLL {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    let mut snapshot = this.snapshot.borrow_mut();
+                    snapshot.state = ConnectionState::Active;
+                    snapshot.data = Some(item);
+                    changed = true;
+                }
+                Poll::Ready(None) => {
+                    this.snapshot.borrow_mut().state = ConnectionState::Done;
+                    (this.on_change)();
+                    return Poll::Ready(());
+                }
+                Poll::Pending => {
+                    if changed {
+                        (this.on_change)();
+                    }
+                    return Poll::Pending;
+                }
+            }
+        }
+
+        // Stream keeps yielding, so it is polled again after other tasks.
+        cx.waker().wake_by_ref();
+        (this.on_change)();
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::task::Waker;
+
+    use super::*;
+
+    #[test]
+    fn each_item_rebuilds_with_latest_value() {
+        let (sender, receiver) = futures_channel::mpsc::unbounded();
+        let snapshot = Rc::new(RefCell::new(StreamSnapshot::new()));
+        let rebuilds = Rc::new(Cell::new(0));
+
+        let mut subscription = Subscription {
+            stream: Box::pin(receiver),
+            snapshot: snapshot.clone(),
+            on_change: Box::new({
+                let rebuilds = rebuilds.clone();
+                move || rebuilds.set(rebuilds.get() + 1)
+            }),
+        };
+
+        let mut poll = || Pin::new(&mut subscription).poll(&mut Context::from_waker(Waker::noop()));
+
+        assert!(poll().is_pending());
+        assert_eq!(snapshot.borrow().state(), ConnectionState::Waiting);
+        assert_eq!(rebuilds.get(), 0);
+
+        for n in 1..=3 {
+            sender.unbounded_send(n).unwrap();
+
+            assert!(poll().is_pending());
+            assert_eq!(rebuilds.get(), n);
+            assert!(snapshot.borrow().is_active());
+            assert_eq!(snapshot.borrow().data(), Some(&n));
+        }
+
+        // Items yielded between polls rebuild once with the latest one.
+        sender.unbounded_send(4).unwrap();
+        sender.unbounded_send(5).unwrap();
+        assert!(poll().is_pending());
+        assert_eq!(rebuilds.get(), 4);
+        assert_eq!(snapshot.borrow().data(), Some(&5));
+
+        // Ended stream keeps its latest item.
+        drop(sender);
+        assert!(poll().is_ready());
+        assert_eq!(rebuilds.get(), 5);
+        assert!(snapshot.borrow().is_done());
+        assert_eq!(snapshot.borrow().data(), Some(&5));
+    }
+}
@@ -0,0 +1,373 @@
+use std::cell::Cell;
+
+use frui::{
+    api::events::{Event, WidgetEvent},
+    prelude::*,
+};
+
+use crate::{keep_alive::is_kept_alive, widget_list::WidgetList, PageController, PageView};
+
+const TAB_PADDING: f64 = 16.0;
+const INDICATOR_HEIGHT: f64 = 2.0;
+
+/// Coordinates the active tab between a [`TabBar`] and a [`TabView`].
+///
+/// Both widgets should be given clones of the same controller. Controller is
+/// [`Listenable`], notifying its listeners whenever the active tab changes.
+#[derive(Clone)]
+pub struct TabController {
+    length: usize,
+    pages: PageController,
+}
+
+impl TabController {
+    pub fn new(length: usize) -> Self {
+        Self {
+            length,
+            pages: PageController::new(0),
+        }
+    }
+
+    /// Number of tabs.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Index of the active tab.
+    pub fn index(&self) -> usize {
+        self.pages.current_page()
+    }
+
+    /// Makes tab at `index` active, animating both the indicator and the content.
+    #[track_caller]
+    pub fn animate_to(&self, index: usize) {
+        assert!(
+            index < self.length,
+            "tab index (is {index}) should be < length (is {})",
+            self.length
+        );
+
+        self.pages.animate_to_page(index);
+    }
+
+    /// Position of the active tab, fractional while switching between tabs.
+    fn position(&self) -> f64 {
+        self.pages.position()
+    }
+}
+
+impl Listenable for TabController {
+    fn add_listener(&self, listener: Listener) {
+        self.pages.add_listener(listener)
+    }
+
+    fn remove_listener(&self, listener: &Listener) {
+        self.pages.remove_listener(listener)
+    }
+}
+
+/// A row of tab labels with an indicator under the active tab.
+///
+/// Tapping a label makes its tab active.
+#[derive(MultiChildWidget)]
+pub struct TabBar<T: WidgetList> {
+    pub controller: TabController,
+    pub labels: T,
+    pub indicator_color: Color,
+}
+
+impl<T: WidgetList> TabBar<T> {
+    pub fn new(controller: TabController, labels: T) -> Self {
+        Self {
+            controller,
+            labels,
+            indicator_color: Color::rgb8(255, 144, 54),
+        }
+    }
+
+    pub fn indicator_color(mut self, color: Color) -> Self {
+        self.indicator_color = color;
+        self
+    }
+}
+
+#[doc(hidden)]
+pub struct TabBarRenderState {
+    /// Horizontal offset and width of every tab, relative to the bar.
+    tabs: Vec<(f64, f64)>,
+    pressed_tab: Cell<Option<usize>>,
+}
+
+impl TabBarRenderState {
+    fn tab_at(&self, x: f64) -> Option<usize> {
+        self.tabs
+            .iter()
+            .position(|(tab_x, width)| x >= *tab_x && x < tab_x + width)
+    }
+
+    /// Horizontal offset and width of the indicator at `position`, which is
+    /// interpolated between the two closest tabs.
+    fn indicator(&self, position: f64) -> Option<(f64, f64)> {
+        let last = self.tabs.len().checked_sub(1)?;
+        let position = position.clamp(0., last as f64);
+
+        let (a, b) = (position.floor() as usize, position.ceil() as usize);
+        let t = position - a as f64;
+
+        let (a, b) = (self.tabs[a], self.tabs[b]);
+
+        Some((a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t))
+    }
+}
+
+impl<T: WidgetList> RenderState for TabBar<T> {
+    type State = TabBarRenderState;
+
+    fn create_state(&self) -> Self::State {
+        TabBarRenderState {
+            tabs: Vec::new(),
+            pressed_tab: Cell::new(None),
+        }
+    }
+}
+
+impl<T: WidgetList> MultiChildWidget for TabBar<T> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Vec<Self::Widget<'w>> {
+        self.labels.get()
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let mut tabs = Vec::with_capacity(ctx.children().len());
+        let mut size = Size::default();
+
+        for mut child in ctx.children() {
+            let label_size = child.layout(constraints.loosen());
+            let width = label_size.width + TAB_PADDING * 2.;
+
+            tabs.push((size.width, width));
+
+            size.width += width;
+            size.height = size.height.max(label_size.height);
+        }
+
+        size.height += TAB_PADDING + INDICATOR_HEIGHT;
+
+        let size = constraints.constrain(size);
+
+        for ((tab_x, width), mut child) in tabs.iter().zip(ctx.children()) {
+            let label = child.size();
+
+            child.set_offset(Offset {
+                x: tab_x + (width - label.width) / 2.,
+                y: (size.height - INDICATOR_HEIGHT - label.height) / 2.,
+            });
+        }
+
+        ctx.rstate_mut().tabs = tabs;
+
+        size
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        let height = ctx.size().height;
+
+        for mut child in ctx.children() {
+            child.paint(canvas);
+        }
+
+        let indicator = ctx.rstate().indicator(self.controller.position());
+
+        if let Some((x, width)) = indicator {
+            let rect = Rect::new(x, height - INDICATOR_HEIGHT, x + width, height);
+
+            let brush = &canvas.solid_brush(self.indicator_color.clone());
+            PietRenderContext::fill(canvas, rect, brush);
+        }
+    }
+}
+
+impl<T: WidgetList> WidgetEvent for TabBar<T> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        match event {
+            Event::MouseDown(e) => {
+                if let MouseButton::Left = e.button {
+                    if ctx.point_in_layout_bounds(e.pos) {
+                        let state = ctx.rstate();
+                        state
+                            .pressed_tab
+                            .set(state.tab_at(e.pos.x - ctx.offset().x));
+                    }
+                }
+            }
+            Event::MouseUp(e) => {
+                if let MouseButton::Left = e.button {
+                    let pressed_tab = ctx.rstate().pressed_tab.take();
+
+                    if ctx.point_in_layout_bounds(e.pos) {
+                        let tab = ctx.rstate().tab_at(e.pos.x - ctx.offset().x);
+
+                        if let Some(tab) = tab.filter(|tab| Some(*tab) == pressed_tab) {
+                            if tab < self.controller.length() {
+                                self.controller.animate_to(tab);
+                                ctx.mark_needs_paint();
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        false
+    }
+}
+
+/// Displays content of the active tab of a [`TabController`].
+///
+/// Content can be swiped horizontally to switch to the adjacent tab.
+///
+/// By default contents of all tabs are kept in the tree, preserving their state
+/// while switching tabs. If `keep_alive` is `false`, only the active tab and
+/// its neighbours (which become visible while swiping) stay mounted, along with
+/// children wrapped in [`KeepAlive`](crate::KeepAlive).
+#[derive(ViewWidget)]
+pub struct TabView<T: WidgetList> {
+    pub controller: TabController,
+    pub children: T,
+    pub keep_alive: bool,
+}
+
+impl<T: WidgetList> TabView<T> {
+    pub fn new(controller: TabController, children: T) -> Self {
+        Self {
+            controller,
+            children,
+            keep_alive: true,
+        }
+    }
+
+    pub fn keep_alive(mut self, keep_alive: bool) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+}
+
+impl<T: WidgetList> WidgetState for TabView<T> {
+    /// Index of the tab this view was built for.
+    type State = usize;
+
+    fn create_state(&self) -> Self::State {
+        self.controller.index()
+    }
+}
+
+impl<T: WidgetList> ViewWidget for TabView<T> {
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        let active = *ctx.state();
+
+        let children = self
+            .children
+            .get()
+            .into_iter()
+            .enumerate()
+            .map(|(n, child)| {
+                if self.keep_alive || n.abs_diff(active) <= 1 || is_kept_alive(child) {
+                    child
+                } else {
+                    &() as &dyn Widget
+                }
+            })
+            .collect::<Vec<_>>();
+
+        PageView::new(self.controller.pages.clone(), children).on_page_changed(move |page| {
+            if *ctx.state() != page {
+                *ctx.state_mut() = page;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use frui::app::testing::WidgetTester;
+
+    use super::*;
+    use crate::KeepAlive;
+
+    #[test]
+    fn indicator_interpolates_between_tabs() {
+        let state = TabBarRenderState {
+            tabs: vec![(0., 10.), (10., 30.)],
+            pressed_tab: Cell::new(None),
+        };
+
+        assert_eq!(state.indicator(0.), Some((0., 10.)));
+        assert_eq!(state.indicator(0.5), Some((5., 20.)));
+        assert_eq!(state.indicator(5.), Some((10., 30.)));
+        assert_eq!(state.tab_at(15.), Some(1));
+    }
+
+    /// Tab content recording its id whenever its state is created.
+    #[derive(ViewWidget)]
+    struct Content {
+        id: usize,
+        created: Rc<RefCell<Vec<usize>>>,
+    }
+
+    impl WidgetState for Content {
+        type State = ();
+
+        fn create_state(&self) -> Self::State {
+            self.created.borrow_mut().push(self.id);
+        }
+    }
+
+    impl ViewWidget for Content {
+        fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {}
+    }
+
+    #[test]
+    fn only_neighbours_of_active_tab_and_kept_alive_tabs_stay_mounted() {
+        let controller = TabController::new(4);
+        let created = Rc::new(RefCell::new(Vec::new()));
+
+        let content = |id| Content {
+            id,
+            created: created.clone(),
+        };
+
+        let view = TabView::new(
+            controller.clone(),
+            (
+                content(0),
+                content(1),
+                content(2),
+                KeepAlive::new(content(3)),
+            ),
+        )
+        .keep_alive(false);
+
+        let mut tester = WidgetTester::new(view, Size::new(100., 100.));
+        assert_eq!(*created.borrow(), [0, 1, 3]);
+
+        let mut switch_to = |tab| {
+            controller.pages.jump_to_page(tab);
+
+            // Jumping doesn't invalidate layout of the pages, so it's forced
+            // by different constraints. Page change is then reported during
+            // paint and rebuilds the view.
+            tester.layout(Constraints::tight(Size::new(100., 100.)));
+            tester.paint();
+            tester.pump();
+        };
+
+        switch_to(2);
+        assert_eq!(*created.borrow(), [0, 1, 3, 2]);
+
+        // Tab 0 was unmounted, while the kept-alive tab 3 never is.
+        switch_to(0);
+        assert_eq!(*created.borrow(), [0, 1, 3, 2, 0]);
+    }
+}
@@ -24,10 +24,10 @@ impl<W: Widget> SingleChildWidget for DebugContainer<W> {
         ctx.child().layout(constraints)
     }
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, offset: &Offset) {
-        ctx.child().paint(canvas, offset);
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.child().paint(canvas);
 
-        let rect = Rect::from_origin_size(*offset, ctx.child().size());
+        let rect = Rect::from_origin_size(Point::ORIGIN, ctx.child().size());
         let brush = &canvas.solid_brush(Color::GREEN);
 
         canvas.stroke_styled(rect, brush, 2., &StrokeStyle::new().line_cap(LineCap::Butt));
@@ -1,17 +1,35 @@
 use frui::{app::TEXT_FACTORY, prelude::*};
 
 use druid_shell::piet::{
-    kurbo::Point, Color, FontFamily, FontWeight, PietTextLayout, Text as TextExt, TextLayout,
-    TextLayoutBuilder,
+    kurbo::Point, Color, FontFamily, FontWeight, LineMetric, PietTextLayout, PietTextLayoutBuilder,
+    Text as TextExt, TextAttribute, TextLayout, TextLayoutBuilder,
 };
 
-#[derive(LeafWidget)]
+/// Width of the faded part of the last visible line, in multiples of font size.
+const FADE_WIDTH: f64 = 3.;
+
+/// How text which doesn't fit in `max_lines` (or in its box) is displayed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextOverflow {
+    /// Cut off the overflowing text at the bounds of the widget.
+    #[default]
+    Clip,
+    /// Truncate the last visible line and append "…". If not even the ellipsis
+    /// fits on that line, the text is clipped instead.
+    Ellipsis,
+    /// Fade out the end of the last visible line.
+    Fade,
+}
+
+#[derive(LeafWidget, Clone)]
 pub struct Text<S: AsRef<str>> {
     text: S,
-    font_size: f64,
-    font_color: Color,
+    pub(crate) font_size: f64,
+    pub(crate) font_color: Color,
     font_weight: FontWeight,
-    font_family: FontFamily,
+    pub(crate) font_family: FontFamily,
+    max_lines: Option<usize>,
+    overflow: TextOverflow,
 }
 
 impl<S: AsRef<str>> Text<S> {
@@ -22,6 +40,8 @@ impl<S: AsRef<str>> Text<S> {
             font_color: Color::WHITE,
             font_weight: FontWeight::default(),
             font_family: FontFamily::default(),
+            max_lines: None,
+            overflow: TextOverflow::default(),
         }
     }
 
@@ -44,50 +64,259 @@ impl<S: AsRef<str>> Text<S> {
         self.font_family = font;
         self
     }
+
+    /// Maximum number of lines to display. Text doesn't fitting in them is
+    /// handled according to [`overflow`](Self::overflow).
+    #[track_caller]
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        assert!(max_lines > 0, "max_lines should be > 0");
+        self.max_lines = Some(max_lines);
+        self
+    }
+
+    pub fn overflow(mut self, overflow: TextOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    #[cfg(not(feature = "miri"))]
+    fn layout_builder(
+        &self,
+        text: String,
+        font_size: f64,
+        max_width: f64,
+    ) -> PietTextLayoutBuilder {
+        TEXT_FACTORY.with(|f| {
+            f.get()
+                .new_text_layout(text)
+                .font(self.font_family.clone(), font_size)
+                .text_color(self.font_color.clone())
+                .range_attribute(.., self.font_weight)
+                .max_width(max_width)
+        })
+    }
+
+    #[cfg(not(feature = "miri"))]
+    pub(crate) fn build_layout(&self, max_width: f64) -> PietTextLayout {
+        self.build_layout_at(self.font_size, max_width)
+    }
+
+    /// Same as [`build_layout`](Self::build_layout), but with `font_size`
+    /// instead of the font size of this text.
+    #[cfg(not(feature = "miri"))]
+    pub(crate) fn build_layout_at(&self, font_size: f64, max_width: f64) -> PietTextLayout {
+        self.layout_builder(self.text.as_ref().to_owned(), font_size, max_width)
+            .build()
+            .unwrap()
+    }
+
+    /// Whether the whole text laid out at `font_size` fits in `constraints`,
+    /// without being cut off at `max_lines`.
+    #[cfg(not(feature = "miri"))]
+    pub(crate) fn fits(&self, font_size: f64, constraints: Constraints) -> bool {
+        let max = constraints.max();
+        let layout = self.build_layout_at(font_size, max.width);
+        let size = layout.size();
+
+        self.max_lines.is_none_or(|n| layout.line_count() <= n)
+            && size.width <= max.width
+            && size.height <= max.height
+    }
+
+    /// Lays out the text at `font_size`, handling lines beyond `max_lines`
+    /// according to `overflow`.
+    #[cfg(not(feature = "miri"))]
+    pub(crate) fn layout_lines(&self, font_size: f64, max_width: f64) -> TextRenderState {
+        let mut layout = self.build_layout_at(font_size, max_width);
+        let mut visible_height = layout.size().height;
+
+        if let Some(max_lines) = self.max_lines {
+            if layout.line_count() > max_lines {
+                let last = layout.line_metric(max_lines - 1).unwrap();
+                visible_height = last.y_offset + last.height;
+
+                match self.overflow {
+                    TextOverflow::Clip => {}
+                    TextOverflow::Ellipsis => {
+                        if let Some(ellipsized) =
+                            self.ellipsized_layout(&last, font_size, max_width)
+                        {
+                            layout = ellipsized;
+                        }
+                    }
+                    TextOverflow::Fade => {
+                        layout = self.faded_layout(&layout, &last, font_size, max_width)
+                    }
+                }
+            }
+        }
+
+        TextRenderState {
+            layout,
+            visible_height,
+        }
+    }
+
+    /// Layout of the text truncated at the end of the line `last` with "…"
+    /// appended, or `None` if not even the ellipsis fits on that line.
+    #[cfg(not(feature = "miri"))]
+    fn ellipsized_layout(
+        &self,
+        last: &LineMetric,
+        font_size: f64,
+        max_width: f64,
+    ) -> Option<PietTextLayout> {
+        let text = self.text.as_ref();
+        let max_lines = self.max_lines.unwrap();
+
+        let layout = |end: usize| {
+            let truncated = format!("{}\u{2026}", text[..end].trim_end());
+            self.layout_builder(truncated, font_size, max_width)
+                .build()
+                .unwrap()
+        };
+
+        let boundaries = (last.start_offset..=last.end_offset)
+            .filter(|idx| text.is_char_boundary(*idx))
+            .collect::<Vec<_>>();
+
+        let end = longest_fitting(&boundaries, |end| layout(end).line_count() <= max_lines)?;
+
+        Some(layout(end))
+    }
+
+    /// Layout of the whole text with the end of the line `last` faded out.
+    #[cfg(not(feature = "miri"))]
+    fn faded_layout(
+        &self,
+        layout: &PietTextLayout,
+        last: &LineMetric,
+        font_size: f64,
+        max_width: f64,
+    ) -> PietTextLayout {
+        let text = self.text.as_ref();
+        let line_end = last.end_offset - last.trailing_whitespace;
+        let line_width = layout.hit_test_text_position(line_end).point.x;
+        let fade_width = font_size * FADE_WIDTH;
+
+        let mut builder = self.layout_builder(text.to_owned(), font_size, max_width);
+
+        for (idx, c) in text[last.start_offset..line_end].char_indices() {
+            let start = last.start_offset + idx;
+            let x = layout.hit_test_text_position(start).point.x;
+            let alpha = fade_alpha(x, line_width, fade_width);
+
+            if alpha < 1. {
+                let (_, _, _, a) = self.font_color.as_rgba();
+                let color = self.font_color.clone().with_alpha(a * alpha);
+
+                builder = builder
+                    .range_attribute(start..start + c.len_utf8(), TextAttribute::TextColor(color));
+            }
+        }
+
+        builder.build().unwrap()
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        self.text.as_ref()
+    }
+}
+
+/// Largest of sorted `boundaries` for which `fits` returns `true`, assuming
+/// that it returns `true` for all boundaries smaller than that.
+fn longest_fitting(boundaries: &[usize], fits: impl Fn(usize) -> bool) -> Option<usize> {
+    let n = boundaries.partition_point(|end| fits(*end));
+    n.checked_sub(1).map(|n| boundaries[n])
+}
+
+/// Opacity of a glyph at `x` on a line of `line_width` which fades out over
+/// the last `fade_width` of it.
+fn fade_alpha(x: f64, line_width: f64, fade_width: f64) -> f64 {
+    ((line_width - x) / fade_width).clamp(0., 1.)
+}
+
+#[doc(hidden)]
+pub struct TextRenderState {
+    layout: PietTextLayout,
+    /// Height of lines which are displayed.
+    visible_height: f64,
+}
+
+#[cfg(not(feature = "miri"))]
+impl TextRenderState {
+    pub(crate) fn empty() -> Self {
+        TextRenderState {
+            layout: TEXT_FACTORY.with(|f| f.get().new_text_layout("").build().unwrap()),
+            visible_height: 0.,
+        }
+    }
+
+    /// Size of the displayed text, constrained by `constraints`.
+    pub(crate) fn size(&self, constraints: Constraints) -> Size {
+        constraints.constrain(Size {
+            width: self.layout.size().width,
+            height: self.visible_height,
+        })
+    }
+
+    /// Bounds of a line of `thickness` drawn under the text in `range`, which
+    /// must be within a single line.
+    pub(crate) fn underline(&self, range: std::ops::Range<usize>, thickness: f64) -> Rect {
+        let start = self.layout.hit_test_text_position(range.start);
+        let end = self.layout.hit_test_text_position(range.end);
+
+        // Points of hit tests lie on the baseline.
+        let y = start.point.y + thickness;
+
+        Rect::new(start.point.x, y, end.point.x, y + thickness)
+    }
+
+    /// Paints the text in a box of `size`, clipping parts that overflow it.
+    pub(crate) fn paint(&self, size: Size, canvas: &mut PaintContext) {
+        let layout_size = self.layout.size();
+        let overflows = layout_size.width > size.width
+            || layout_size.height > size.height.min(self.visible_height);
+
+        let draw = |canvas: &mut PaintContext| {
+            PietRenderContext::draw_text(canvas, &self.layout, Point::ORIGIN)
+        };
+
+        if overflows {
+            let height = size.height.min(self.visible_height);
+            let bounds = Rect::from_origin_size(Point::ORIGIN, Size::new(size.width, height));
+
+            canvas.with_clip(bounds, draw);
+        } else {
+            draw(canvas);
+        }
+    }
 }
 
 #[cfg(not(feature = "miri"))]
 impl<S: AsRef<str>> RenderState for Text<S> {
-    type State = PietTextLayout;
+    type State = TextRenderState;
 
     fn create_state(&self) -> Self::State {
-        TEXT_FACTORY.with(|f| f.get().new_text_layout("").build().unwrap())
+        TextRenderState::empty()
     }
 }
 
 #[cfg(not(feature = "miri"))]
 impl<S: AsRef<str>> LeafWidget for Text<S> {
     fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
-        let max_width = constraints.max().width;
+        ctx.depend_on_font(&self.font_family);
 
-        *ctx.rstate_mut() = TEXT_FACTORY.with(|f| {
-            f.get()
-                .new_text_layout(self.text.as_ref().to_owned())
-                .font(self.font_family.clone(), self.font_size)
-                .text_color(self.font_color.clone())
-                .range_attribute(.., self.font_weight)
-                .max_width(max_width)
-                .build()
-                .unwrap()
-        });
+        let state = self.layout_lines(self.font_size, constraints.max().width);
+        let size = state.size(constraints);
 
-        let text_size = ctx.rstate().size();
+        *ctx.rstate_mut() = state;
 
-        Size {
-            width: text_size.width,
-            height: text_size.height,
-        }
+        size
     }
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, offset: &Offset) {
-        PietRenderContext::draw_text(
-            canvas,
-            &ctx.rstate(),
-            Point {
-                x: offset.x,
-                y: offset.y,
-            },
-        );
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        ctx.rstate().paint(ctx.size(), canvas);
     }
 }
 
@@ -100,5 +329,23 @@ impl<S: AsRef<str>> LeafWidget for Text<S> {
         }
     }
 
-    fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext, _: &Offset) {}
+    fn paint(&self, _: RenderContext<Self>, _: &mut PaintContext) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn longest_fitting_boundary_is_found() {
+        let boundaries = [0, 1, 3, 4, 6];
+
+        assert_eq!(longest_fitting(&boundaries, |end| end <= 4), Some(4));
+        assert_eq!(longest_fitting(&boundaries, |_| true), Some(6));
+        // Not even the first boundary fits.
+        assert_eq!(longest_fitting(&boundaries, |_| false), None);
+
+        assert_eq!(fade_alpha(0., 100., 30.), 1.);
+        assert_eq!(fade_alpha(85., 100., 30.), 0.5);
+    }
 }
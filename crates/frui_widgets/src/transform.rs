@@ -0,0 +1,236 @@
+use frui::{
+    api::events::{Event, WidgetEvent},
+    prelude::*,
+};
+
+/// Applies `transform` to its child when painting.
+///
+/// The transform is relative to the top-left corner of this widget. Pointer
+/// events are mapped into the child's coordinate space, so that hit testing
+/// follows the painted child. Layout is not affected.
+#[derive(SingleChildWidget)]
+pub struct Transform<W: Widget> {
+    pub transform: Affine,
+    pub child: W,
+}
+
+impl<W: Widget> Transform<W> {
+    pub fn new(transform: Affine, child: W) -> Self {
+        Self { transform, child }
+    }
+
+    pub fn translate(offset: Vec2, child: W) -> Self {
+        Self::new(Affine::translate(offset), child)
+    }
+
+    /// Scales the child around its top-left corner.
+    pub fn scale(scale: f64, child: W) -> Self {
+        Self::new(Affine::scale(scale), child)
+    }
+
+    /// Transform relative to the whole window, given the `offset` of this
+    /// widget.
+    fn global_transform(&self, offset: Offset) -> Affine {
+        let origin = Vec2::new(offset.x, offset.y);
+
+        Affine::translate(origin) * self.transform * Affine::translate(-origin)
+    }
+}
+
+impl<W: Widget> SingleChildWidget for Transform<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        if self.transform == Affine::default() {
+            return ctx.child().paint(canvas);
+        }
+
+        canvas.with_transform(self.transform, |canvas| {
+            ctx.child().paint(canvas);
+        });
+    }
+}
+
+impl<W: Widget> WidgetEvent for Transform<W> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        if self.transform.determinant() == 0. {
+            // Child is collapsed and can't be hit.
+            return true;
+        }
+
+        let transform = self.global_transform(ctx.offset()).inverse();
+
+        ctx.child().handle_event(&event.transform(transform));
+
+        true
+    }
+}
+
+/// Rotates its child clockwise by `quarter_turns` quarters of a full turn.
+///
+/// Unlike [`Transform`], rotation affects layout: for odd numbers of quarter
+/// turns, the child is laid out with transposed constraints and its width
+/// becomes the height of this widget (and vice versa). Pointer events are
+/// rotated along with the child.
+#[derive(SingleChildWidget)]
+pub struct RotatedBox<W: Widget> {
+    pub quarter_turns: i32,
+    pub child: W,
+}
+
+impl<W: Widget> RotatedBox<W> {
+    pub fn new(quarter_turns: i32, child: W) -> Self {
+        Self {
+            quarter_turns,
+            child,
+        }
+    }
+
+    /// Number of quarter turns in range `0..4`.
+    fn turns(&self) -> i32 {
+        self.quarter_turns.rem_euclid(4)
+    }
+
+    /// Transform relative to the whole window, given the `offset` of this
+    /// widget and the size of its child.
+    fn global_transform(&self, offset: Offset, child_size: Size) -> Affine {
+        let origin = Vec2::new(offset.x, offset.y);
+
+        Affine::translate(origin)
+            * quarter_turns_transform(self.turns(), child_size)
+            * Affine::translate(-origin)
+    }
+}
+
+impl<W: Widget> SingleChildWidget for RotatedBox<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        if self.turns() % 2 == 0 {
+            return ctx.child().layout(constraints);
+        }
+
+        let size = ctx.child().layout(transpose(constraints));
+
+        Size::new(size.height, size.width)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        if self.turns() == 0 {
+            return ctx.child().paint(canvas);
+        }
+
+        let transform = quarter_turns_transform(self.turns(), ctx.child().size());
+
+        canvas.with_transform(transform, |canvas| {
+            ctx.child().paint(canvas);
+        });
+    }
+}
+
+impl<W: Widget> WidgetEvent for RotatedBox<W> {
+    fn handle_event(&self, ctx: RenderContext<Self>, event: &Event) -> bool {
+        let child_size = ctx.child().size();
+        let transform = self.global_transform(ctx.offset(), child_size).inverse();
+
+        ctx.child().handle_event(&event.transform(transform));
+
+        true
+    }
+}
+
+/// Constraints with width and height swapped.
+fn transpose(constraints: Constraints) -> Constraints {
+    Constraints {
+        min_width: constraints.min_height,
+        max_width: constraints.max_height,
+        min_height: constraints.min_width,
+        max_height: constraints.max_width,
+    }
+}
+
+/// Rotates a child of `size` clockwise by `turns` quarter turns (in range
+/// `0..4`) around its top-left corner and moves it back to the origin.
+///
+/// Coefficients are exact, so that the rotated child is aligned to pixels just
+/// like the unrotated one.
+fn quarter_turns_transform(turns: i32, size: Size) -> Affine {
+    let Size { width, height } = size;
+
+    match turns {
+        0 => Affine::default(),
+        // (x, y) -> (height - y, x)
+        1 => Affine::new([0., 1., -1., 0., height, 0.]),
+        // (x, y) -> (width - x, height - y)
+        2 => Affine::new([-1., 0., 0., -1., width, height]),
+        // (x, y) -> (y, width - x)
+        3 => Affine::new([0., -1., 1., 0., 0., width]),
+        _ => unreachable!("quarter turns must be in range 0..4"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rotated_child_lands_within_box() {
+        let child = Size::new(40., 10.);
+
+        let rotated = |quarter_turns: i32| {
+            let turns = RotatedBox::new(quarter_turns, ()).turns();
+            let transform = quarter_turns_transform(turns, child);
+
+            // Where the top-left and bottom-right corners of the child land.
+            let corners = [Point::ZERO, Point::new(child.width, child.height)]
+                .map(|corner| transform * corner)
+                .map(|p| (p.x, p.y));
+
+            // Compare with exact rotation.
+            let angle = turns as f64 * std::f64::consts::FRAC_PI_2;
+            let exact = Affine::rotate(angle) * Point::new(1., 0.);
+            let direction = transform * Point::new(1., 0.) - transform * Point::ZERO;
+            assert!((direction - exact.to_vec2()).hypot() < 1e-9);
+
+            (turns, corners)
+        };
+
+        assert_eq!(rotated(0), (0, [(0., 0.), (40., 10.)]));
+        assert_eq!(rotated(1), (1, [(10., 0.), (0., 40.)]));
+        assert_eq!(rotated(2), (2, [(40., 10.), (0., 0.)]));
+        assert_eq!(rotated(3), (3, [(0., 40.), (10., 0.)]));
+
+        // Turns wrap around in both directions.
+        assert_eq!(rotated(-1).0, 3);
+        assert_eq!(rotated(6).0, 2);
+    }
+
+    #[test]
+    fn constraints_are_transposed() {
+        let constraints = Constraints {
+            min_width: 1.,
+            max_width: 2.,
+            min_height: 3.,
+            max_height: 4.,
+        };
+
+        assert_eq!(
+            transpose(constraints),
+            Constraints {
+                min_width: 3.,
+                max_width: 4.,
+                min_height: 1.,
+                max_height: 2.,
+            }
+        );
+        assert_eq!(transpose(transpose(constraints)), constraints);
+    }
+}
@@ -0,0 +1,92 @@
+use frui::prelude::*;
+
+/// Reveals its child vertically, sizing itself to `size_factor` of the child's
+/// height and clipping the rest.
+#[derive(SingleChildWidget)]
+pub struct SizeTransition<W: Widget> {
+    pub size_factor: f64,
+    pub child: W,
+}
+
+impl<W: Widget> SizeTransition<W> {
+    pub fn new(size_factor: f64, child: W) -> Self {
+        Self {
+            size_factor: size_factor.clamp(0.0, 1.0),
+            child,
+        }
+    }
+}
+
+impl<W: Widget> SingleChildWidget for SizeTransition<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        let child_constraints = Constraints {
+            min_height: 0.,
+            max_height: f64::INFINITY,
+            ..constraints
+        };
+
+        let child_size = ctx.child().layout(child_constraints);
+
+        constraints.constrain(Size::new(
+            child_size.width,
+            child_size.height * self.size_factor,
+        ))
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        if self.size_factor >= 1.0 {
+            return ctx.child().paint(canvas);
+        }
+
+        let bounds = Rect::from_origin_size(Point::ORIGIN, ctx.size());
+
+        canvas.with_clip(bounds, |canvas| {
+            ctx.child().paint(canvas);
+        });
+    }
+}
+
+/// Scales its child around its center by `scale`.
+///
+/// Only painting is affected, the child is laid out at its full size.
+#[derive(SingleChildWidget)]
+pub struct ScaleTransition<W: Widget> {
+    pub scale: f64,
+    pub child: W,
+}
+
+impl<W: Widget> ScaleTransition<W> {
+    pub fn new(scale: f64, child: W) -> Self {
+        Self {
+            scale: scale.max(0.0),
+            child,
+        }
+    }
+}
+
+impl<W: Widget> SingleChildWidget for ScaleTransition<W> {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
+        ctx.child().layout(constraints)
+    }
+
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
+        if self.scale == 1.0 {
+            return ctx.child().paint(canvas);
+        }
+
+        let size = ctx.size();
+        let center = Vec2::new(size.width / 2., size.height / 2.);
+        let transform =
+            Affine::translate(center) * Affine::scale(self.scale) * Affine::translate(-center);
+
+        canvas.with_transform(transform, |canvas| ctx.child().paint(canvas));
+    }
+}
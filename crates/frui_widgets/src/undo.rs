@@ -0,0 +1,337 @@
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+use druid_shell::{KbKey, Modifiers};
+use frui::{api::contexts::build_ctx::InheritedState, prelude::*};
+
+use crate::{Actions, Intent, KeyCombination, Shortcuts};
+
+/// Number of edits an [`UndoController`] keeps by default.
+const DEFAULT_MAX_DEPTH: usize = 100;
+
+/// Change which can be reverted and then applied again.
+///
+/// Edits can either carry out a command and its inverse, or restore snapshots
+/// of the edited value (see [`Snapshot`]).
+pub trait UndoableEdit {
+    /// Reverts this edit.
+    fn undo(&mut self);
+
+    /// Applies this edit again after it was reverted.
+    fn redo(&mut self);
+}
+
+/// Edit which replaces a shared value with its snapshot from before or after
+/// the edit.
+pub struct Snapshot<T: Clone> {
+    target: Rc<RefCell<T>>,
+    before: T,
+    after: T,
+}
+
+impl<T: Clone> Snapshot<T> {
+    /// Records an edit of `target` which just changed it from `before` to its
+    /// current value.
+    pub fn new(target: &Rc<RefCell<T>>, before: T) -> Self {
+        Self {
+            target: target.clone(),
+            after: target.borrow().clone(),
+            before,
+        }
+    }
+}
+
+impl<T: Clone> UndoableEdit for Snapshot<T> {
+    fn undo(&mut self) {
+        *self.target.borrow_mut() = self.before.clone();
+    }
+
+    fn redo(&mut self) {
+        *self.target.borrow_mut() = self.after.clone();
+    }
+}
+
+/// History of edits which can be undone and redone.
+///
+/// Edits are pushed once they were applied. Pushing an edit discards the ones
+/// which were undone, and once there are more than `max_depth` edits, the
+/// oldest ones are discarded.
+///
+/// To rebuild widgets whenever the history changes (e.g. buttons enabled by
+/// [`can_undo`](Self::can_undo)), share it through an [`UndoScope`].
+pub struct UndoController {
+    undo: VecDeque<Box<dyn UndoableEdit>>,
+    redo: Vec<Box<dyn UndoableEdit>>,
+    max_depth: usize,
+}
+
+impl UndoController {
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            max_depth,
+        }
+    }
+
+    /// Records `edit`, which was just applied.
+    pub fn push(&mut self, edit: impl UndoableEdit + 'static) {
+        self.redo.clear();
+        self.undo.push_back(Box::new(edit));
+        self.trim();
+    }
+
+    /// Reverts the last applied edit, returning whether there was one.
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop_back() {
+            Some(mut edit) => {
+                edit.undo();
+                self.redo.push(edit);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Applies the last reverted edit again, returning whether there was one.
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some(mut edit) => {
+                edit.redo();
+                self.undo.push_back(edit);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Discards all edits.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Changes the number of kept edits, discarding the oldest ones if there
+    /// are more of them.
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        while self.undo.len() + self.redo.len() > self.max_depth {
+            match self.undo.pop_front() {
+                Some(_) => {}
+                None => drop(self.redo.remove(0)),
+            }
+        }
+    }
+}
+
+impl Default for UndoController {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_DEPTH)
+    }
+}
+
+/// Intent to undo the last edit of the closest [`UndoScope`].
+pub struct UndoIntent;
+
+impl Intent for UndoIntent {}
+
+/// Intent to redo the last undone edit of the closest [`UndoScope`].
+pub struct RedoIntent;
+
+impl Intent for RedoIntent {}
+
+/// Provides an [`UndoController`] to its subtree, accessed through
+/// [`UndoScope::of`]. Widgets which access it are rebuilt whenever its edits
+/// change, including when an edit is undone or redone.
+///
+/// Ctrl+Z and Ctrl+Shift+Z (Cmd+Z and Cmd+Shift+Z on macOS) pressed while the
+/// scope is focused produce [`UndoIntent`] and [`RedoIntent`], which it handles
+/// unless a nested [`Actions`] handles them first (see [`Shortcuts`]).
+#[derive(InheritedWidget)]
+pub struct UndoScope<W: Widget> {
+    max_depth: usize,
+    child: UndoShortcuts<W>,
+}
+
+impl<W: Widget> UndoScope<W> {
+    pub fn new(child: W) -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            child: UndoShortcuts { child },
+        }
+    }
+
+    /// Number of kept edits. The default is `100`.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+}
+
+impl UndoScope<()> {
+    /// Returns the edits of the closest `UndoScope` ancestor, rebuilding the
+    /// widget of `ctx` whenever they change.
+    pub fn of<'a, T>(ctx: BuildContext<'a, T>) -> Option<UndoHandle<'a>> {
+        let state = ctx.depend_on_inherited_widget::<Self>()?;
+
+        Some(UndoHandle { state })
+    }
+}
+
+impl<W: Widget> WidgetState for UndoScope<W> {
+    type State = UndoController;
+
+    fn create_state(&self) -> Self::State {
+        UndoController::new(self.max_depth)
+    }
+
+    fn did_update_widget<'a>(&'a self, ctx: BuildContext<'a, Self>) {
+        if ctx.state().max_depth() != self.max_depth {
+            ctx.state_mut().set_max_depth(self.max_depth);
+            ctx.notify_dependents();
+        }
+    }
+}
+
+impl<W: Widget> InheritedWidget for UndoScope<W> {
+    fn child<'w>(&'w self) -> &'w Self::Widget<'w> {
+        &self.child
+    }
+}
+
+/// Access to the [`UndoController`] of an [`UndoScope`], see [`UndoScope::of`].
+///
+/// Methods changing the edits consume the handle, so a new one should be
+/// obtained for every change.
+pub struct UndoHandle<'a> {
+    state: InheritedState<'a, UndoController>,
+}
+
+impl<'a> UndoHandle<'a> {
+    pub fn can_undo(&self) -> bool {
+        self.state.as_ref().can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.state.as_ref().can_redo()
+    }
+
+    /// See [`UndoController::push`].
+    pub fn push(self, edit: impl UndoableEdit + 'static) {
+        self.edit(|undo| undo.push(edit))
+    }
+
+    /// See [`UndoController::undo`].
+    pub fn undo(self) -> bool {
+        self.edit(UndoController::undo)
+    }
+
+    /// See [`UndoController::redo`].
+    pub fn redo(self) -> bool {
+        self.edit(UndoController::redo)
+    }
+
+    pub fn clear(self) {
+        self.edit(UndoController::clear)
+    }
+
+    /// Changes the edits, rebuilding widgets which depend on the scope.
+    fn edit<R>(self, f: impl FnOnce(&mut UndoController) -> R) -> R {
+        let mut state: InheritedState<UndoController> = self.state;
+        let r = f(&mut state.as_mut());
+        r
+    }
+}
+
+/// Binds undo and redo shortcuts of an [`UndoScope`] to its controller.
+#[doc(hidden)]
+#[derive(ViewWidget)]
+pub struct UndoShortcuts<W: Widget> {
+    child: W,
+}
+
+impl<W: Widget> ViewWidget for UndoShortcuts<W> {
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        let command = if cfg!(target_os = "macos") {
+            Modifiers::META
+        } else {
+            Modifiers::CONTROL
+        };
+        let z = || KbKey::Character("z".into());
+
+        Shortcuts::new(
+            Actions::new(&self.child)
+                .action(move |_: &UndoIntent| {
+                    if let Some(undo) = UndoScope::of(ctx) {
+                        undo.undo();
+                    }
+                })
+                .action(move |_: &RedoIntent| {
+                    if let Some(undo) = UndoScope::of(ctx) {
+                        undo.redo();
+                    }
+                }),
+        )
+        .shortcut(KeyCombination::new(z(), command), UndoIntent)
+        .shortcut(
+            KeyCombination::new(z(), command | Modifiers::SHIFT),
+            RedoIntent,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn edits_are_undone_and_redone_within_max_depth() {
+        let text = Rc::new(RefCell::new(String::new()));
+        let mut undo = UndoController::new(2);
+
+        let type_text = |undo: &mut UndoController, s: &str| {
+            let before = text.borrow().clone();
+            text.borrow_mut().push_str(s);
+            undo.push(Snapshot::new(&text, before));
+        };
+
+        type_text(&mut undo, "a");
+        type_text(&mut undo, "b");
+        type_text(&mut undo, "c");
+        assert_eq!(*text.borrow(), "abc");
+
+        // Only the last two edits are kept.
+        assert!(undo.undo() && undo.undo());
+        assert!(!undo.undo());
+        assert_eq!(*text.borrow(), "a");
+
+        assert!(undo.redo());
+        assert_eq!(*text.borrow(), "ab");
+        assert!(undo.can_undo() && undo.can_redo());
+
+        // New edit discards the undone one.
+        type_text(&mut undo, "d");
+        assert!(!undo.can_redo());
+        assert_eq!(*text.borrow(), "abd");
+
+        undo.set_max_depth(1);
+        assert!(undo.undo());
+        assert!(!undo.can_undo());
+        assert_eq!(*text.borrow(), "ab");
+    }
+}
@@ -45,6 +45,30 @@ pub use frui::prelude::Widget;
 /// and slices as [`WidgetList`]. Those come with their respectful constraints,
 /// like the fact that types of elements stored in these kinds of collections
 /// must be the same.
+///
+///
+/// ## Iterators
+///
+/// Children built programmatically can be passed as an iterator through
+/// `children_iter` of the widget (collecting them into a [`Vec`]). Boxing them
+/// lets that iterator produce children of different types:
+///
+/// ```
+/// # use frui::prelude::*;
+/// # use frui_widgets::*;
+/// # enum Item { Heading(String), Paragraph(String) }
+/// # let items = vec![Item::Heading("Title".into()), Item::Paragraph("Text".into())];
+/// Column::builder().children_iter(items.iter().map(|item| match item {
+///     Item::Heading(text) => Text::new(text).size(24.).boxed(),
+///     Item::Paragraph(text) => Text::new(text).boxed(),
+/// }));
+/// ```
+///
+/// Changing the number of children between rebuilds keeps the state of
+/// children which stay at the same position. Children which are inserted or
+/// removed in the middle of the list should be wrapped in a
+/// [`LocalKey`](frui::prelude::LocalKey), so that the ones after them keep
+/// their state too.
 pub trait WidgetList {
     fn get(&self) -> Vec<&dyn Widget>;
 }
@@ -76,3 +100,39 @@ impl<W: Widget, const N: usize> WidgetList for [W; N] {
         self.iter().map(|e| e as &dyn Widget).collect()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Text;
+
+    #[test]
+    fn tuple_of_twelve_widgets_keeps_their_order() {
+        let children = (
+            Text::new("0"),
+            (),
+            Text::new("2"),
+            (),
+            Text::new("4"),
+            (),
+            Text::new("6"),
+            (),
+            Text::new("8"),
+            (),
+            Text::new("10"),
+            (),
+        );
+
+        let widgets = children.get();
+        assert_eq!(widgets.len(), 12);
+
+        for (n, widget) in widgets.iter().enumerate() {
+            let expected = match n % 2 {
+                0 => children.0.unique_type(),
+                _ => ().unique_type(),
+            };
+
+            assert_eq!(widget.unique_type(), expected);
+        }
+    }
+}
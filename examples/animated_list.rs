@@ -0,0 +1,80 @@
+//! This example shows how to animate items inserted to and removed from a list
+//! using [`AnimatedList`].
+//!
+//! Items are stored in the state of the [`App`] alongside the controller of
+//! the list. Every change to the items is mirrored by a call to the controller.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::prelude::*;
+
+mod misc;
+use misc::Button;
+
+struct AppState {
+    items: Vec<usize>,
+    next_item: usize,
+    controller: AnimatedListController,
+}
+
+#[derive(ViewWidget)]
+struct App;
+
+impl WidgetState for App {
+    type State = AppState;
+
+    fn create_state(&self) -> Self::State {
+        AppState {
+            items: vec![0, 1, 2],
+            next_item: 3,
+            controller: AnimatedListController::new(3),
+        }
+    }
+}
+
+fn item(n: usize, t: f64) -> impl Widget {
+    Text::new(format!("Item {}", n))
+        .size(30.0)
+        .color(Color::WHITE.with_alpha(t))
+}
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        let controller = ctx.state().controller.clone();
+
+        Column::builder()
+            .space_between(20.0)
+            .cross_axis_alignment(CrossAxisAlignment::Center)
+            .children((
+                Row::builder().space_between(10.0).children((
+                    Button {
+                        label: Text::new("+").size(30.),
+                        on_click: || {
+                            let mut state = ctx.state_mut();
+                            let n = state.next_item;
+                            state.next_item += 1;
+                            state.items.insert(0, n);
+                            state.controller.insert_item(0);
+                        },
+                    },
+                    Button {
+                        label: Text::new("-").size(30.),
+                        on_click: || {
+                            let mut state = ctx.state_mut();
+                            if !state.items.is_empty() {
+                                let n = state.items.remove(0);
+                                state.controller.remove_item(0, move |t| item(n, t));
+                            }
+                        },
+                    },
+                )),
+                AnimatedList::new(controller, move |index, t| {
+                    item(ctx.state().items[index], t)
+                }),
+            ))
+    }
+}
+
+fn main() {
+    run_app(App);
+}
@@ -0,0 +1,38 @@
+//! This example shows how to transition between children of [`AnimatedSwitcher`].
+//!
+//! Press any key to increment the counter. Since every number is wrapped in a
+//! [`LocalKey`] of a different value, [`AnimatedSwitcher`] treats each of them
+//! as a new child and animates between the old and the new number.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::prelude::*;
+
+#[derive(ViewWidget)]
+struct App;
+
+impl WidgetState for App {
+    type State = usize;
+
+    fn create_state(&self) -> Self::State {
+        0
+    }
+}
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        let count = *ctx.state();
+
+        KeyboardEventDetector {
+            on_event: |_| *ctx.state_mut() += 1,
+            child: Center::child(AnimatedSwitcher::new(LocalKey::new(
+                count,
+                Text::new(count.to_string()).size(100.0),
+            ))),
+        }
+    }
+}
+
+fn main() {
+    run_app(App);
+}
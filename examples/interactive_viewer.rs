@@ -0,0 +1,29 @@
+//! This example shows how to let the user pan and zoom a widget with an
+//! [`InteractiveViewer`].
+//!
+//! Drag to pan and use the mouse wheel to zoom. The child can't be moved out of
+//! the window.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::prelude::*;
+
+#[derive(ViewWidget)]
+struct App;
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, _: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        InteractiveViewer::new(
+            Container::builder()
+                .width(400.)
+                .height(300.)
+                .color(Color::rgb8(84, 110, 255))
+                .child(Center::child(Text::new("Drag me or zoom me"))),
+        )
+        .scale_range(0.5, 4.)
+    }
+}
+
+fn main() {
+    run_app(App);
+}
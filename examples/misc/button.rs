@@ -21,7 +21,7 @@ impl<L: Widget, F: Fn()> SingleChildWidget for Button<L, F> {
         Center { child: &self.label }
     }
 
-    fn layout(&self, ctx: RenderContext<Self>, _: Constraints) -> Size {
+    fn layout(&self, ctx: RenderContext<Self>, constraints: Constraints) -> Size {
         ctx.child().layout(Constraints {
             min_width: 0.,
             max_width: WIDTH,
@@ -29,13 +29,13 @@ impl<L: Widget, F: Fn()> SingleChildWidget for Button<L, F> {
             max_height: HEIGHT,
         });
 
-        Size {
+        constraints.constrain(Size {
             width: WIDTH,
             height: HEIGHT,
-        }
+        })
     }
 
-    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext, offset: &Offset) {
+    fn paint(&self, ctx: RenderContext<Self>, canvas: &mut PaintContext) {
         let color;
         let hover_shade = 0.2;
 
@@ -54,13 +54,9 @@ impl<L: Widget, F: Fn()> SingleChildWidget for Button<L, F> {
 
         let brush = &canvas.solid_brush(color);
 
-        PietRenderContext::fill(
-            canvas,
-            RoundedRect::new(offset.x, offset.y, offset.x + WIDTH, offset.y + HEIGHT, 15.),
-            brush,
-        );
+        PietRenderContext::fill(canvas, RoundedRect::new(0., 0., WIDTH, HEIGHT, 15.), brush);
 
-        ctx.child().paint(canvas, offset)
+        ctx.child().paint(canvas)
     }
 }
 
@@ -102,12 +98,12 @@ impl<L: Widget, F: Fn()> WidgetEvent for Button<L, F> {
                 if ctx.point_in_layout_bounds(e.pos) {
                     if !ctx.rstate().is_hovered.replace(true) {
                         // Repaint only if the hover state changed.
-                        ctx.schedule_layout();
+                        ctx.mark_needs_paint();
                     }
                 } else {
                     if ctx.rstate().is_hovered.replace(false) {
                         // Repaint only if the hover state changed.
-                        ctx.schedule_layout();
+                        ctx.mark_needs_paint();
                     }
                 }
             }
@@ -0,0 +1,53 @@
+//! This example shows how to build a group of mutually exclusive [`Radio`]
+//! buttons.
+//!
+//! Every [`Radio`] in the group shares the same `group_value` (which is stored
+//! in the state of the [`App`]) and the same `on_changed` callback, which
+//! updates that state.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Fruit {
+    Apple,
+    Banana,
+    Cherry,
+}
+
+#[derive(ViewWidget)]
+struct App;
+
+impl WidgetState for App {
+    type State = Fruit;
+
+    fn create_state(&self) -> Self::State {
+        Fruit::Apple
+    }
+}
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        let selected = *ctx.state();
+        let on_changed = |fruit| *ctx.state_mut() = fruit;
+
+        Center::child(
+            Column::builder()
+                .space_between(20.0)
+                .cross_axis_alignment(CrossAxisAlignment::Center)
+                .children((
+                    Text::new(format!("{:?}", selected)).size(30.0),
+                    Row::builder().space_between(20.0).children((
+                        Radio::new(Fruit::Apple, selected, on_changed),
+                        Radio::new(Fruit::Banana, selected, on_changed),
+                        Radio::new(Fruit::Cherry, selected, on_changed),
+                    )),
+                )),
+        )
+    }
+}
+
+fn main() {
+    run_app(App);
+}
@@ -0,0 +1,52 @@
+//! This example shows how to use [`ReorderableList`].
+//!
+//! Press and hold any item, then drag it to a new position. Every item is
+//! annotated with [`LocalKey`], so the random number displayed by it (which is
+//! stored in its state) follows the item after it has been moved.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::prelude::*;
+
+mod misc;
+use misc::RandomState;
+
+#[derive(ViewWidget)]
+struct App;
+
+impl WidgetState for App {
+    type State = Vec<&'static str>;
+
+    fn create_state(&self) -> Self::State {
+        vec!["Apple", "Banana", "Cherry", "Date"]
+    }
+}
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        let children = ctx
+            .state()
+            .iter()
+            .map(|name| {
+                LocalKey::new(
+                    *name,
+                    Container::builder().width(300.).height(60.).child(
+                        Row::builder()
+                            .space_between(20.)
+                            .children((Text::new(*name).size(30.), RandomState)),
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        Center::child(ReorderableList::new(children, |old_index, new_index| {
+            let mut items = ctx.state_mut();
+            let item = items.remove(old_index);
+            items.insert(new_index, item);
+        }))
+    }
+}
+
+fn main() {
+    run_app(App);
+}
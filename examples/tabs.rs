@@ -0,0 +1,55 @@
+//! This example shows how to coordinate a [`TabBar`] with a [`TabView`] using
+//! a shared [`TabController`].
+//!
+//! Tap a label or swipe the content to switch tabs. Each tab displays a random
+//! number stored in its state, which is preserved while switching tabs.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::prelude::*;
+
+mod misc;
+use misc::RandomState;
+
+#[derive(ViewWidget)]
+struct App;
+
+impl WidgetState for App {
+    type State = TabController;
+
+    fn create_state(&self) -> Self::State {
+        TabController::new(3)
+    }
+}
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, ctx: BuildContext<'w, Self>) -> Self::Widget<'w> {
+        let controller = ctx.state().clone();
+
+        Column::builder()
+            .main_axis_size(MainAxisSize::Max)
+            .cross_axis_size(CrossAxisSize::Max)
+            .children((
+                TabBar::new(
+                    controller.clone(),
+                    (
+                        Text::new("Home"),
+                        Text::new("Notifications"),
+                        Text::new("Settings"),
+                    ),
+                ),
+                Container::builder().height(400.).child(TabView::new(
+                    controller,
+                    (
+                        Center::child(RandomState),
+                        Center::child(RandomState),
+                        Center::child(RandomState),
+                    ),
+                )),
+            ))
+    }
+}
+
+fn main() {
+    run_app(App);
+}